@@ -0,0 +1,25 @@
+-- Record of every state-changing admin action, written in the same transaction as the change
+CREATE TYPE admin_action AS ENUM (
+    'verify_user',
+    'delete_final_match',
+    'trigger_final_matching',
+    'create_scheduled_matches',
+    'cancel_scheduled_match'
+);
+
+CREATE TABLE admin_audit_log (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Identity of the admin, taken from the access gateway header (or the system task name)
+    actor VARCHAR(255) NOT NULL,
+    action admin_action NOT NULL,
+    -- Ids of the rows touched by the action (users, final matches, scheduled matches)
+    target_ids UUID[] NOT NULL DEFAULT '{}',
+    payload JSONB,
+    status_before user_status,
+    status_after user_status,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_admin_audit_log_created_at ON admin_audit_log(created_at DESC);
+CREATE INDEX idx_admin_audit_log_target_ids ON admin_audit_log USING GIN(target_ids);
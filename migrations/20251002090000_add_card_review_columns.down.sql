@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS idx_users_card_reviewed_at;
+
+ALTER TABLE users
+    DROP COLUMN IF EXISTS card_purged_at,
+    DROP COLUMN IF EXISTS card_reviewed_at,
+    DROP COLUMN IF EXISTS card_verified_at;
@@ -0,0 +1,20 @@
+-- Track card review decisions so that card photos can be purged after a retention period
+ALTER TABLE users
+    ADD COLUMN card_verified_at TIMESTAMPTZ,   -- last time an admin verified the card, kept after purge
+    ADD COLUMN card_reviewed_at TIMESTAMPTZ,   -- last admin decision (verified or rejected), starts the retention clock
+    ADD COLUMN card_purged_at TIMESTAMPTZ;     -- when the card photo was deleted by the retention job
+
+-- Backfill cards that were already reviewed before this migration
+UPDATE users
+SET card_verified_at = updated_at, card_reviewed_at = updated_at
+WHERE card_photo_filename IS NOT NULL
+  AND status IN ('verified', 'form_completed', 'matched', 'confirmed');
+
+UPDATE users
+SET card_reviewed_at = updated_at
+WHERE card_photo_filename IS NOT NULL
+  AND status = 'unverified';
+
+-- Index for the retention job looking for reviewed cards that still have a photo
+CREATE INDEX idx_users_card_reviewed_at ON users(card_reviewed_at)
+WHERE card_photo_filename IS NOT NULL;
@@ -0,0 +1,41 @@
+-- PostgreSQL cannot drop enum values, so both enum types are rebuilt without them
+UPDATE users
+SET status = COALESCE(status_before_suspension, 'unverified')
+WHERE status = 'suspended';
+
+ALTER TABLE users
+    DROP COLUMN IF EXISTS status_before_suspension,
+    DROP COLUMN IF EXISTS suspended_until,
+    DROP COLUMN IF EXISTS suspension_reason;
+
+DELETE FROM admin_audit_log WHERE action IN ('suspend_user', 'lift_suspension');
+UPDATE admin_audit_log SET status_before = NULL WHERE status_before = 'suspended';
+UPDATE admin_audit_log SET status_after = NULL WHERE status_after = 'suspended';
+
+ALTER TYPE user_status RENAME TO user_status_old;
+CREATE TYPE user_status AS ENUM (
+    'unverified',
+    'verification_pending',
+    'verified',
+    'form_completed',
+    'matched',
+    'confirmed'
+);
+ALTER TABLE users ALTER COLUMN status DROP DEFAULT;
+ALTER TABLE users ALTER COLUMN status TYPE user_status USING status::text::user_status;
+ALTER TABLE users ALTER COLUMN status SET DEFAULT 'unverified';
+ALTER TABLE admin_audit_log
+    ALTER COLUMN status_before TYPE user_status USING status_before::text::user_status,
+    ALTER COLUMN status_after TYPE user_status USING status_after::text::user_status;
+DROP TYPE user_status_old;
+
+ALTER TYPE admin_action RENAME TO admin_action_old;
+CREATE TYPE admin_action AS ENUM (
+    'verify_user',
+    'delete_final_match',
+    'trigger_final_matching',
+    'create_scheduled_matches',
+    'cancel_scheduled_match'
+);
+ALTER TABLE admin_audit_log ALTER COLUMN action TYPE admin_action USING action::text::admin_action;
+DROP TYPE admin_action_old;
@@ -0,0 +1,11 @@
+-- Allow admins to take misbehaving users out of the event
+-- (new enum values cannot be used in the same transaction that adds them)
+ALTER TYPE user_status ADD VALUE IF NOT EXISTS 'suspended';
+
+ALTER TABLE users
+    ADD COLUMN suspension_reason TEXT,
+    ADD COLUMN suspended_until TIMESTAMPTZ,          -- NULL means suspended until lifted by an admin
+    ADD COLUMN status_before_suspension user_status; -- status restored when the suspension is lifted
+
+ALTER TYPE admin_action ADD VALUE IF NOT EXISTS 'suspend_user';
+ALTER TYPE admin_action ADD VALUE IF NOT EXISTS 'lift_suspension';
@@ -0,0 +1,27 @@
+-- PostgreSQL cannot drop enum values, so the enum type is rebuilt without them
+UPDATE users SET status = 'form_completed' WHERE status IN ('paused', 'withdrawn');
+UPDATE users SET status_before_suspension = 'form_completed'
+WHERE status_before_suspension IN ('paused', 'withdrawn');
+
+UPDATE admin_audit_log SET status_before = NULL WHERE status_before IN ('paused', 'withdrawn');
+UPDATE admin_audit_log SET status_after = NULL WHERE status_after IN ('paused', 'withdrawn');
+
+ALTER TYPE user_status RENAME TO user_status_old;
+CREATE TYPE user_status AS ENUM (
+    'unverified',
+    'verification_pending',
+    'verified',
+    'form_completed',
+    'matched',
+    'confirmed',
+    'suspended'
+);
+ALTER TABLE users ALTER COLUMN status DROP DEFAULT;
+ALTER TABLE users
+    ALTER COLUMN status TYPE user_status USING status::text::user_status,
+    ALTER COLUMN status_before_suspension TYPE user_status USING status_before_suspension::text::user_status;
+ALTER TABLE users ALTER COLUMN status SET DEFAULT 'unverified';
+ALTER TABLE admin_audit_log
+    ALTER COLUMN status_before TYPE user_status USING status_before::text::user_status,
+    ALTER COLUMN status_after TYPE user_status USING status_after::text::user_status;
+DROP TYPE user_status_old;
@@ -0,0 +1,3 @@
+-- Let users with a completed form leave the matching pool without losing their form
+ALTER TYPE user_status ADD VALUE IF NOT EXISTS 'paused';
+ALTER TYPE user_status ADD VALUE IF NOT EXISTS 'withdrawn';
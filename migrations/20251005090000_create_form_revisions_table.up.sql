@@ -0,0 +1,29 @@
+-- Every form submission or edit is kept as a numbered revision
+CREATE TABLE form_revisions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+
+    -- Snapshot of the form at this revision (see the forms table)
+    gender gender NOT NULL,
+    familiar_tags TEXT[] NOT NULL,
+    aspirational_tags TEXT[] NOT NULL,
+    recent_topics TEXT NOT NULL,
+    self_traits TEXT[] NOT NULL,
+    ideal_traits TEXT[] NOT NULL,
+    physical_boundary SMALLINT NOT NULL,
+    self_intro TEXT NOT NULL,
+    profile_photo_filename VARCHAR(255),
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE (user_id, revision)
+);
+
+-- Existing forms become their first revision
+INSERT INTO form_revisions (user_id, revision, gender, familiar_tags, aspirational_tags,
+                            recent_topics, self_traits, ideal_traits, physical_boundary,
+                            self_intro, profile_photo_filename, created_at)
+SELECT user_id, 1, gender, familiar_tags, aspirational_tags, recent_topics, self_traits,
+       ideal_traits, physical_boundary, self_intro, profile_photo_filename, updated_at
+FROM forms;
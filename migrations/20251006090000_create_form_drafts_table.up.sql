@@ -0,0 +1,12 @@
+-- Autosaved, partially filled forms; promoted to the forms table on final submission
+CREATE TABLE form_drafts (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    data JSONB NOT NULL,
+
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER set_timestamp_form_drafts
+BEFORE UPDATE ON form_drafts
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
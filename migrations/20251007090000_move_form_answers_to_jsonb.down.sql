@@ -0,0 +1,56 @@
+-- Answers to questions that are not part of the original questionnaire are lost
+ALTER TABLE forms
+    ADD COLUMN familiar_tags TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN aspirational_tags TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN recent_topics TEXT NOT NULL DEFAULT '',
+    ADD COLUMN self_traits TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN ideal_traits TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN physical_boundary SMALLINT NOT NULL DEFAULT 1,
+    ADD COLUMN self_intro TEXT NOT NULL DEFAULT '';
+
+ALTER TABLE form_revisions
+    ADD COLUMN familiar_tags TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN aspirational_tags TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN recent_topics TEXT NOT NULL DEFAULT '',
+    ADD COLUMN self_traits TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN ideal_traits TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN physical_boundary SMALLINT NOT NULL DEFAULT 1,
+    ADD COLUMN self_intro TEXT NOT NULL DEFAULT '';
+
+UPDATE forms SET
+    familiar_tags = ARRAY(SELECT jsonb_array_elements_text(COALESCE(answers->'familiar_tags', '[]'))),
+    aspirational_tags = ARRAY(SELECT jsonb_array_elements_text(COALESCE(answers->'aspirational_tags', '[]'))),
+    recent_topics = COALESCE(answers->>'recent_topics', ''),
+    self_traits = ARRAY(SELECT jsonb_array_elements_text(COALESCE(answers->'self_traits', '[]'))),
+    ideal_traits = ARRAY(SELECT jsonb_array_elements_text(COALESCE(answers->'ideal_traits', '[]'))),
+    physical_boundary = COALESCE((answers->>'physical_boundary')::SMALLINT, 1),
+    self_intro = COALESCE(answers->>'self_intro', '');
+
+UPDATE form_revisions SET
+    familiar_tags = ARRAY(SELECT jsonb_array_elements_text(COALESCE(answers->'familiar_tags', '[]'))),
+    aspirational_tags = ARRAY(SELECT jsonb_array_elements_text(COALESCE(answers->'aspirational_tags', '[]'))),
+    recent_topics = COALESCE(answers->>'recent_topics', ''),
+    self_traits = ARRAY(SELECT jsonb_array_elements_text(COALESCE(answers->'self_traits', '[]'))),
+    ideal_traits = ARRAY(SELECT jsonb_array_elements_text(COALESCE(answers->'ideal_traits', '[]'))),
+    physical_boundary = COALESCE((answers->>'physical_boundary')::SMALLINT, 1),
+    self_intro = COALESCE(answers->>'self_intro', '');
+
+ALTER TABLE forms
+    ALTER COLUMN familiar_tags DROP DEFAULT,
+    ALTER COLUMN aspirational_tags DROP DEFAULT,
+    ALTER COLUMN recent_topics DROP DEFAULT,
+    ALTER COLUMN self_traits DROP DEFAULT,
+    ALTER COLUMN ideal_traits DROP DEFAULT,
+    ALTER COLUMN physical_boundary DROP DEFAULT,
+    ALTER COLUMN self_intro DROP DEFAULT,
+    DROP COLUMN answers;
+
+ALTER TABLE form_revisions
+    ALTER COLUMN familiar_tags DROP DEFAULT,
+    ALTER COLUMN aspirational_tags DROP DEFAULT,
+    ALTER COLUMN recent_topics DROP DEFAULT,
+    ALTER COLUMN self_traits DROP DEFAULT,
+    ALTER COLUMN ideal_traits DROP DEFAULT,
+    ALTER COLUMN physical_boundary DROP DEFAULT,
+    ALTER COLUMN self_intro DROP DEFAULT,
+    DROP COLUMN answers;
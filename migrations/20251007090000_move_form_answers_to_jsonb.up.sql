@@ -0,0 +1,47 @@
+-- Questionnaire answers are defined by form_schema.json and stored as JSONB keyed
+-- by question id. Gender and the profile photo stay core columns.
+ALTER TABLE forms ADD COLUMN answers JSONB NOT NULL DEFAULT '{}';
+ALTER TABLE form_revisions ADD COLUMN answers JSONB NOT NULL DEFAULT '{}';
+
+UPDATE forms SET answers = jsonb_build_object(
+    'familiar_tags', to_jsonb(familiar_tags),
+    'aspirational_tags', to_jsonb(aspirational_tags),
+    'recent_topics', recent_topics,
+    'self_traits', to_jsonb(self_traits),
+    'ideal_traits', to_jsonb(ideal_traits),
+    'physical_boundary', physical_boundary,
+    'self_intro', self_intro
+);
+
+UPDATE form_revisions SET answers = jsonb_build_object(
+    'familiar_tags', to_jsonb(familiar_tags),
+    'aspirational_tags', to_jsonb(aspirational_tags),
+    'recent_topics', recent_topics,
+    'self_traits', to_jsonb(self_traits),
+    'ideal_traits', to_jsonb(ideal_traits),
+    'physical_boundary', physical_boundary,
+    'self_intro', self_intro
+);
+
+ALTER TABLE forms
+    ALTER COLUMN answers DROP DEFAULT,
+    DROP COLUMN familiar_tags,
+    DROP COLUMN aspirational_tags,
+    DROP COLUMN recent_topics,
+    DROP COLUMN self_traits,
+    DROP COLUMN ideal_traits,
+    DROP COLUMN physical_boundary,
+    DROP COLUMN self_intro;
+
+ALTER TABLE form_revisions
+    ALTER COLUMN answers DROP DEFAULT,
+    DROP COLUMN familiar_tags,
+    DROP COLUMN aspirational_tags,
+    DROP COLUMN recent_topics,
+    DROP COLUMN self_traits,
+    DROP COLUMN ideal_traits,
+    DROP COLUMN physical_boundary,
+    DROP COLUMN self_intro;
+
+-- Unanswered questions are omitted from drafts rather than stored as null
+UPDATE form_drafts SET data = jsonb_strip_nulls(data);
@@ -0,0 +1,18 @@
+DROP TABLE IF EXISTS event_phases;
+DROP TYPE IF EXISTS event_phase;
+
+-- PostgreSQL cannot drop enum values, so the enum type is rebuilt without them
+DELETE FROM admin_audit_log WHERE action IN ('create_event_phases', 'cancel_event_phase');
+
+ALTER TYPE admin_action RENAME TO admin_action_old;
+CREATE TYPE admin_action AS ENUM (
+    'verify_user',
+    'delete_final_match',
+    'trigger_final_matching',
+    'create_scheduled_matches',
+    'cancel_scheduled_match',
+    'suspend_user',
+    'lift_suspension'
+);
+ALTER TABLE admin_audit_log ALTER COLUMN action TYPE admin_action USING action::text::admin_action;
+DROP TYPE admin_action_old;
@@ -0,0 +1,19 @@
+-- Admin-configured event timeline; the phase with the latest start time in the past is current
+CREATE TYPE event_phase AS ENUM (
+    'registration',
+    'form_filling',
+    'preview_veto',
+    'final_match',
+    'confirmation'
+);
+
+CREATE TABLE event_phases (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    phase event_phase NOT NULL,
+    starts_at TIMESTAMPTZ NOT NULL UNIQUE,
+    entered_at TIMESTAMPTZ,                 -- set by the scheduler once the phase has started
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TYPE admin_action ADD VALUE IF NOT EXISTS 'create_event_phases';
+ALTER TYPE admin_action ADD VALUE IF NOT EXISTS 'cancel_event_phase';
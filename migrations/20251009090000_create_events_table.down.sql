@@ -0,0 +1,54 @@
+-- Only the current event survives, as the single event of the deployment
+DELETE FROM events WHERE NOT is_current;
+
+ALTER TABLE event_phases
+    DROP COLUMN event_id,
+    ADD UNIQUE (starts_at);
+
+ALTER TABLE scheduled_final_matches
+    DROP COLUMN event_id,
+    ADD UNIQUE (scheduled_time);
+
+DROP INDEX IF EXISTS idx_final_matches_event_id;
+ALTER TABLE final_matches DROP COLUMN event_id;
+
+ALTER TABLE vetoes
+    DROP COLUMN event_id,
+    ADD UNIQUE (vetoer_id, vetoed_id);
+
+ALTER TABLE match_previews
+    DROP COLUMN event_id,
+    ADD UNIQUE (user_id);
+
+ALTER TABLE form_revisions
+    DROP COLUMN event_id,
+    ADD UNIQUE (user_id, revision);
+
+ALTER TABLE form_drafts
+    DROP COLUMN event_id,
+    ADD PRIMARY KEY (user_id);
+
+ALTER TABLE forms
+    DROP COLUMN event_id,
+    ADD PRIMARY KEY (user_id);
+
+DROP FUNCTION IF EXISTS current_event_id();
+DROP TABLE IF EXISTS events;
+
+-- PostgreSQL cannot drop enum values, so the enum type is rebuilt without them
+DELETE FROM admin_audit_log WHERE action IN ('create_event', 'activate_event');
+
+ALTER TYPE admin_action RENAME TO admin_action_old;
+CREATE TYPE admin_action AS ENUM (
+    'verify_user',
+    'delete_final_match',
+    'trigger_final_matching',
+    'create_scheduled_matches',
+    'cancel_scheduled_match',
+    'suspend_user',
+    'lift_suspension',
+    'create_event_phases',
+    'cancel_event_phase'
+);
+ALTER TABLE admin_audit_log ALTER COLUMN action TYPE admin_action USING action::text::admin_action;
+DROP TYPE admin_action_old;
@@ -0,0 +1,68 @@
+CREATE TABLE events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    slug VARCHAR(64) NOT NULL UNIQUE,
+    name VARCHAR(255) NOT NULL,
+
+    -- NULL falls back to the deployment settings (ALLOWED_DOMAINS, ALLOWED_GRADES, tags.json, traits.json)
+    allowed_domains TEXT[],
+    allowed_grades TEXT[],
+    tags JSONB,
+    traits JSONB,
+
+    -- Exactly one event is current: users sign up, fill in forms and get matched in it
+    is_current BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_events_current ON events(is_current) WHERE is_current;
+
+-- Everything created before events existed belongs to the default event
+INSERT INTO events (slug, name, is_current) VALUES ('default', 'Default event', TRUE);
+
+CREATE FUNCTION current_event_id() RETURNS UUID AS $$
+    SELECT id FROM events WHERE is_current
+$$ LANGUAGE sql STABLE;
+
+-- Event-scoped rows default to the current event
+ALTER TABLE forms
+    ADD COLUMN event_id UUID NOT NULL DEFAULT current_event_id() REFERENCES events(id) ON DELETE CASCADE,
+    DROP CONSTRAINT forms_pkey,
+    ADD PRIMARY KEY (event_id, user_id);
+
+ALTER TABLE form_drafts
+    ADD COLUMN event_id UUID NOT NULL DEFAULT current_event_id() REFERENCES events(id) ON DELETE CASCADE,
+    DROP CONSTRAINT form_drafts_pkey,
+    ADD PRIMARY KEY (event_id, user_id);
+
+ALTER TABLE form_revisions
+    ADD COLUMN event_id UUID NOT NULL DEFAULT current_event_id() REFERENCES events(id) ON DELETE CASCADE,
+    DROP CONSTRAINT form_revisions_user_id_revision_key,
+    ADD UNIQUE (event_id, user_id, revision);
+
+ALTER TABLE match_previews
+    ADD COLUMN event_id UUID NOT NULL DEFAULT current_event_id() REFERENCES events(id) ON DELETE CASCADE,
+    DROP CONSTRAINT match_previews_user_id_key,
+    ADD UNIQUE (event_id, user_id);
+
+ALTER TABLE vetoes
+    ADD COLUMN event_id UUID NOT NULL DEFAULT current_event_id() REFERENCES events(id) ON DELETE CASCADE,
+    DROP CONSTRAINT vetoes_vetoer_id_vetoed_id_key,
+    ADD UNIQUE (event_id, vetoer_id, vetoed_id);
+
+ALTER TABLE final_matches
+    ADD COLUMN event_id UUID NOT NULL DEFAULT current_event_id() REFERENCES events(id) ON DELETE CASCADE;
+
+CREATE INDEX idx_final_matches_event_id ON final_matches(event_id);
+
+ALTER TABLE scheduled_final_matches
+    ADD COLUMN event_id UUID NOT NULL DEFAULT current_event_id() REFERENCES events(id) ON DELETE CASCADE,
+    DROP CONSTRAINT scheduled_final_matches_scheduled_time_key,
+    ADD UNIQUE (event_id, scheduled_time);
+
+ALTER TABLE event_phases
+    ADD COLUMN event_id UUID NOT NULL DEFAULT current_event_id() REFERENCES events(id) ON DELETE CASCADE,
+    DROP CONSTRAINT event_phases_starts_at_key,
+    ADD UNIQUE (event_id, starts_at);
+
+ALTER TYPE admin_action ADD VALUE IF NOT EXISTS 'create_event';
+ALTER TYPE admin_action ADD VALUE IF NOT EXISTS 'activate_event';
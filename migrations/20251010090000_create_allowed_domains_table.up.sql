@@ -0,0 +1,35 @@
+CREATE TABLE allowed_domains (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Lowercase domain part of email addresses, e.g. mails.tsinghua.edu.cn
+    domain VARCHAR(253) NOT NULL UNIQUE CHECK (domain = LOWER(domain) AND domain <> ''),
+    display_name VARCHAR(255) NOT NULL,
+    -- Also allow any subdomain, e.g. cs.example.edu for example.edu
+    include_subdomains BOOLEAN NOT NULL DEFAULT FALSE,
+    allowed_grades TEXT[] NOT NULL CHECK (CARDINALITY(allowed_grades) > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON allowed_domains
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+-- Running servers reload the allowed domains when notified
+CREATE FUNCTION notify_allowed_domains_changed() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('allowed_domains_changed', '');
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER notify_allowed_domains_changed
+AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON allowed_domains
+FOR EACH STATEMENT
+EXECUTE PROCEDURE notify_allowed_domains_changed();
+
+-- Domains of the ALLOWED_DOMAINS and ALLOWED_GRADES env vars are seeded at startup
+
+ALTER TYPE admin_action ADD VALUE IF NOT EXISTS 'create_allowed_domain';
+ALTER TYPE admin_action ADD VALUE IF NOT EXISTS 'update_allowed_domain';
+ALTER TYPE admin_action ADD VALUE IF NOT EXISTS 'delete_allowed_domain';
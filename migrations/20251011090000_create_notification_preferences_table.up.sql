@@ -0,0 +1,15 @@
+-- Users without a row receive every notification
+CREATE TABLE notification_preferences (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    final_match_created BOOLEAN NOT NULL DEFAULT TRUE,
+    partner_accepted BOOLEAN NOT NULL DEFAULT TRUE,
+    match_rejected BOOLEAN NOT NULL DEFAULT TRUE,
+    match_auto_confirmed BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON notification_preferences
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
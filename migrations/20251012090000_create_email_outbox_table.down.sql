@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS email_outbox;
+DROP FUNCTION IF EXISTS notify_email_outbox();
+DROP TYPE IF EXISTS email_status;
+DROP TYPE IF EXISTS email_kind;
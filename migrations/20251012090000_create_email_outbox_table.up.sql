@@ -0,0 +1,41 @@
+CREATE TYPE email_kind AS ENUM ('verification_code', 'notification');
+CREATE TYPE email_status AS ENUM ('pending', 'failed');
+
+-- Emails waiting for delivery; delivered emails are deleted
+CREATE TABLE email_outbox (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind email_kind NOT NULL,
+    recipient VARCHAR(255) NOT NULL,
+    subject TEXT NOT NULL,
+    -- Verification code or notification message, formatted by the email service
+    body TEXT NOT NULL,
+    -- Failed emails are dead letters that are no longer retried
+    status email_status NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Emails that are useless afterwards, e.g. expired verification codes, are not sent late
+    expires_at TIMESTAMPTZ,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_email_outbox_due ON email_outbox (next_attempt_at) WHERE status = 'pending';
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON email_outbox
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+-- Wake up the delivery workers of all running servers
+CREATE FUNCTION notify_email_outbox() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('email_outbox', '');
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER notify_email_outbox
+AFTER INSERT ON email_outbox
+FOR EACH STATEMENT
+EXECUTE PROCEDURE notify_email_outbox();
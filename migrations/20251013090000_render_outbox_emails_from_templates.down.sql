@@ -0,0 +1,22 @@
+CREATE TYPE email_kind AS ENUM ('verification_code', 'notification');
+
+ALTER TABLE email_outbox
+    ADD COLUMN kind email_kind,
+    ADD COLUMN body TEXT;
+
+UPDATE email_outbox
+SET kind = 'verification_code', body = variables->>'code'
+WHERE template = 'verification_code';
+
+UPDATE email_outbox
+SET kind = 'notification', body = COALESCE(variables->>'message', '')
+WHERE template <> 'verification_code';
+
+ALTER TABLE email_outbox
+    ALTER COLUMN kind SET NOT NULL,
+    ALTER COLUMN body SET NOT NULL,
+    DROP COLUMN template,
+    DROP COLUMN locale,
+    DROP COLUMN variables;
+
+DROP TYPE email_template;
@@ -0,0 +1,36 @@
+CREATE TYPE email_template AS ENUM (
+    'verification_code',
+    'notification',
+    'final_match_created',
+    'partner_accepted',
+    'match_rejected',
+    'match_dissolved',
+    'match_auto_confirmed',
+    'match_reminder'
+);
+
+-- Emails are rendered from `email_templates.json` on delivery
+ALTER TABLE email_outbox
+    ADD COLUMN template email_template,
+    ADD COLUMN locale VARCHAR(16) NOT NULL DEFAULT 'en',
+    ADD COLUMN variables JSONB NOT NULL DEFAULT '{}';
+
+-- Keep the content of emails queued before templates existed
+UPDATE email_outbox
+SET template = 'verification_code',
+    variables = jsonb_build_object('code', body, 'expiry_minutes', '5')
+WHERE kind = 'verification_code';
+
+UPDATE email_outbox
+SET template = 'notification',
+    variables = jsonb_build_object('subject', subject, 'message', body)
+WHERE kind = 'notification';
+
+ALTER TABLE email_outbox
+    ALTER COLUMN template SET NOT NULL,
+    ALTER COLUMN locale DROP DEFAULT,
+    ALTER COLUMN variables DROP DEFAULT,
+    DROP COLUMN kind,
+    DROP COLUMN body;
+
+DROP TYPE email_kind;
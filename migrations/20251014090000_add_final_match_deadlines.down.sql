@@ -0,0 +1,11 @@
+ALTER TABLE notification_preferences DROP COLUMN IF EXISTS match_reminder;
+
+DROP INDEX IF EXISTS idx_final_matches_auto_accept_at;
+ALTER TABLE final_matches
+    DROP COLUMN IF EXISTS auto_accept_at,
+    DROP COLUMN IF EXISTS reminder_at,
+    DROP COLUMN IF EXISTS reminded_at;
+
+ALTER TABLE scheduled_final_matches
+    DROP COLUMN IF EXISTS auto_accept_hours,
+    DROP COLUMN IF EXISTS reminder_hours;
@@ -0,0 +1,26 @@
+-- Each final matching round sets how long users have to respond and when they are
+-- reminded; NULL uses the defaults of the server
+ALTER TABLE scheduled_final_matches
+    ADD COLUMN auto_accept_hours INTEGER CHECK (auto_accept_hours > 0),
+    ADD COLUMN reminder_hours INTEGER CHECK (reminder_hours > 0),
+    ADD CHECK (reminder_hours < auto_accept_hours);
+
+-- Deadlines are fixed when the match is created, so changing the defaults does not
+-- affect matches awaiting a response
+ALTER TABLE final_matches
+    ADD COLUMN auto_accept_at TIMESTAMPTZ,
+    ADD COLUMN reminder_at TIMESTAMPTZ,
+    ADD COLUMN reminded_at TIMESTAMPTZ;
+
+UPDATE final_matches SET
+    auto_accept_at = created_at + INTERVAL '24 hours',
+    reminder_at = created_at + INTERVAL '18 hours';
+
+ALTER TABLE final_matches
+    ALTER COLUMN auto_accept_at SET NOT NULL,
+    ALTER COLUMN reminder_at SET NOT NULL;
+
+CREATE INDEX idx_final_matches_auto_accept_at ON final_matches(auto_accept_at);
+
+ALTER TABLE notification_preferences
+    ADD COLUMN match_reminder BOOLEAN NOT NULL DEFAULT TRUE;
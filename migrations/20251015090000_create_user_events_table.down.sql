@@ -0,0 +1,7 @@
+DROP TRIGGER IF EXISTS record_previews_updated ON match_previews;
+DROP FUNCTION IF EXISTS record_previews_updated();
+DROP TRIGGER IF EXISTS record_user_status_change ON users;
+DROP FUNCTION IF EXISTS record_user_status_change();
+DROP TABLE IF EXISTS user_events;
+DROP FUNCTION IF EXISTS notify_user_event();
+DROP TYPE IF EXISTS user_event_kind;
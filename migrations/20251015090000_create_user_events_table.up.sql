@@ -0,0 +1,60 @@
+-- Events pushed to users over the event stream, kept for a while so that
+-- reconnecting clients can resume where they left off
+CREATE TYPE user_event_kind AS ENUM (
+    'status_changed',
+    'previews_updated',
+    'partner_accepted',
+    'next_match_time_changed'
+);
+
+CREATE TABLE user_events (
+    id BIGSERIAL PRIMARY KEY,
+    -- NULL for events addressed to every user
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    kind user_event_kind NOT NULL,
+    data JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_user_events_user_id ON user_events(user_id, id);
+CREATE INDEX idx_user_events_created_at ON user_events(created_at);
+
+-- Running servers push new events to the connected users when notified
+CREATE FUNCTION notify_user_event() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('user_events', row_to_json(NEW)::text);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER notify_user_event
+AFTER INSERT ON user_events
+FOR EACH ROW
+EXECUTE PROCEDURE notify_user_event();
+
+-- Status changes are recorded wherever they happen
+CREATE FUNCTION record_user_status_change() RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO user_events (user_id, kind, data)
+    VALUES (NEW.id, 'status_changed', jsonb_build_object('status', NEW.status));
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER record_user_status_change
+AFTER UPDATE OF status ON users
+FOR EACH ROW
+WHEN (OLD.status IS DISTINCT FROM NEW.status)
+EXECUTE PROCEDURE record_user_status_change();
+
+CREATE FUNCTION record_previews_updated() RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO user_events (user_id, kind) VALUES (NEW.user_id, 'previews_updated');
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER record_previews_updated
+AFTER INSERT OR UPDATE ON match_previews
+FOR EACH ROW
+EXECUTE PROCEDURE record_previews_updated();
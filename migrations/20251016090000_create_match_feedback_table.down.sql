@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS match_feedback;
+
+ALTER TABLE final_matches
+    DROP COLUMN IF EXISTS tag_overlap,
+    DROP COLUMN IF EXISTS algorithm_version,
+    DROP COLUMN IF EXISTS confirmed_at;
@@ -0,0 +1,27 @@
+-- What each final match was based on, recorded when it is created so that feedback
+-- can be compared across scores, tag overlap and matching configurations. NULL for
+-- matches created before this was recorded
+ALTER TABLE final_matches
+    ADD COLUMN tag_overlap INTEGER,
+    ADD COLUMN algorithm_version TEXT,
+    -- Set once both users confirmed the match, opens the feedback window
+    ADD COLUMN confirmed_at TIMESTAMPTZ;
+
+UPDATE final_matches fm SET confirmed_at = LEAST(fm.auto_accept_at, NOW())
+FROM users ua, users ub
+WHERE ua.id = fm.user_a_id AND ub.id = fm.user_b_id
+  AND ua.status = 'confirmed' AND ub.status = 'confirmed';
+
+-- Feedback of a user on their confirmed final match, one per user and match
+CREATE TABLE match_feedback (
+    final_match_id UUID NOT NULL REFERENCES final_matches(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    rating SMALLINT NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    met BOOLEAN NOT NULL,
+    comment TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (final_match_id, user_id)
+);
+
+CREATE INDEX idx_match_feedback_user_id ON match_feedback(user_id);
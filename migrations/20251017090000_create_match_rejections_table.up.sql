@@ -0,0 +1,23 @@
+-- Final matches are deleted when rejected; they are kept here with the reason given,
+-- so that admins can spot systematic scoring problems. The reason is never shown to
+-- the partner
+CREATE TABLE match_rejections (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    -- Id the final match had, the match itself is deleted
+    final_match_id UUID NOT NULL UNIQUE,
+    rejected_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    partner_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    score DOUBLE PRECISION NOT NULL,
+    tag_overlap INTEGER,
+    algorithm_version TEXT,
+    -- One of the configured rejection reasons, NULL if none was given
+    reason TEXT,
+    comment TEXT,
+    matched_at TIMESTAMPTZ NOT NULL,
+    rejected_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_match_rejections_event_id ON match_rejections(event_id, rejected_at);
+CREATE INDEX idx_match_rejections_rejected_by ON match_rejections(rejected_by);
+CREATE INDEX idx_match_rejections_partner_id ON match_rejections(partner_id);
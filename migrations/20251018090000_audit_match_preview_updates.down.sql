@@ -0,0 +1,22 @@
+-- PostgreSQL cannot drop enum values, so the enum type is rebuilt without them
+DELETE FROM admin_audit_log WHERE action = 'update_match_previews';
+
+ALTER TYPE admin_action RENAME TO admin_action_old;
+CREATE TYPE admin_action AS ENUM (
+    'verify_user',
+    'delete_final_match',
+    'trigger_final_matching',
+    'create_scheduled_matches',
+    'cancel_scheduled_match',
+    'suspend_user',
+    'lift_suspension',
+    'create_event_phases',
+    'cancel_event_phase',
+    'create_event',
+    'activate_event',
+    'create_allowed_domain',
+    'update_allowed_domain',
+    'delete_allowed_domain'
+);
+ALTER TABLE admin_audit_log ALTER COLUMN action TYPE admin_action USING action::text::admin_action;
+DROP TYPE admin_action_old;
@@ -0,0 +1 @@
+ALTER TYPE admin_action ADD VALUE IF NOT EXISTS 'update_match_previews';
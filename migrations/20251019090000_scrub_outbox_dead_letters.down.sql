@@ -0,0 +1 @@
+-- Scrubbed verification codes cannot be restored
@@ -0,0 +1,4 @@
+-- Dead letters no longer keep the verification codes they were sent with
+UPDATE email_outbox
+SET variables = variables - 'code'
+WHERE status = 'failed';
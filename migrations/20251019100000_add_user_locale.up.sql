@@ -0,0 +1,3 @@
+-- Locale users are emailed in, negotiated from the language of their browser
+ALTER TABLE users
+    ADD COLUMN locale VARCHAR(16) NOT NULL DEFAULT 'en';
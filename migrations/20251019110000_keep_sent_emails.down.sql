@@ -0,0 +1,15 @@
+-- PostgreSQL cannot drop enum values, so the enum type is rebuilt without them
+DELETE FROM email_outbox WHERE status = 'sent';
+
+ALTER TABLE email_outbox
+    DROP COLUMN provider,
+    DROP COLUMN sent_at;
+
+DROP INDEX idx_email_outbox_due;
+ALTER TABLE email_outbox ALTER COLUMN status DROP DEFAULT;
+ALTER TYPE email_status RENAME TO email_status_old;
+CREATE TYPE email_status AS ENUM ('pending', 'failed');
+ALTER TABLE email_outbox ALTER COLUMN status TYPE email_status USING status::text::email_status;
+ALTER TABLE email_outbox ALTER COLUMN status SET DEFAULT 'pending';
+DROP TYPE email_status_old;
+CREATE INDEX idx_email_outbox_due ON email_outbox (next_attempt_at) WHERE status = 'pending';
@@ -0,0 +1,7 @@
+-- Delivered emails are kept, along with the provider that accepted them, until
+-- they are purged
+ALTER TYPE email_status ADD VALUE IF NOT EXISTS 'sent';
+
+ALTER TABLE email_outbox
+    ADD COLUMN provider TEXT,
+    ADD COLUMN sent_at TIMESTAMPTZ;
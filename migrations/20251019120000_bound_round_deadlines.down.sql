@@ -0,0 +1,2 @@
+ALTER TABLE scheduled_final_matches
+    DROP CONSTRAINT scheduled_final_matches_auto_accept_hours_max;
@@ -0,0 +1,7 @@
+-- Rounds that allowed more than 30 days to respond fall back to the defaults of the server
+UPDATE scheduled_final_matches SET auto_accept_hours = NULL, reminder_hours = NULL
+WHERE auto_accept_hours > 720;
+
+-- Deadlines far in the future cannot be scheduled
+ALTER TABLE scheduled_final_matches
+    ADD CONSTRAINT scheduled_final_matches_auto_accept_hours_max CHECK (auto_accept_hours <= 720);
@@ -0,0 +1,7 @@
+DROP TRIGGER record_previews_updated ON match_previews;
+DROP TRIGGER record_previews_inserted ON match_previews;
+
+CREATE TRIGGER record_previews_updated
+AFTER INSERT OR UPDATE ON match_previews
+FOR EACH ROW
+EXECUTE PROCEDURE record_previews_updated();
@@ -0,0 +1,13 @@
+-- Previews are refreshed periodically, users are only told when their candidates changed
+DROP TRIGGER record_previews_updated ON match_previews;
+
+CREATE TRIGGER record_previews_inserted
+AFTER INSERT ON match_previews
+FOR EACH ROW
+EXECUTE PROCEDURE record_previews_updated();
+
+CREATE TRIGGER record_previews_updated
+AFTER UPDATE ON match_previews
+FOR EACH ROW
+WHEN (OLD.candidate_ids IS DISTINCT FROM NEW.candidate_ids)
+EXECUTE PROCEDURE record_previews_updated();
@@ -0,0 +1,10 @@
+CREATE OR REPLACE FUNCTION notify_user_event() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('user_events', row_to_json(NEW)::text);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP INDEX idx_user_events_position;
+
+ALTER TABLE user_events DROP COLUMN xact_id;
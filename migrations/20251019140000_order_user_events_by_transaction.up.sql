@@ -0,0 +1,16 @@
+-- Ids are taken when events are recorded, not when their transaction commits, so a
+-- lower id can become visible after a higher one. Streams only send the events of
+-- transactions older than every running one, in the order of their transactions,
+-- so that resuming after an event never skips one committed later.
+ALTER TABLE user_events
+    ADD COLUMN xact_id BIGINT NOT NULL DEFAULT pg_current_xact_id()::text::bigint;
+
+CREATE INDEX idx_user_events_position ON user_events(xact_id, id);
+
+-- Listeners load the events from the table, which also keeps the payload small
+CREATE OR REPLACE FUNCTION notify_user_event() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('user_events', NEW.id::text);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,6 @@
+DELETE FROM match_rejections WHERE rejected_by IS NULL;
+
+ALTER TABLE match_rejections
+    DROP CONSTRAINT match_rejections_rejected_or_deleted,
+    DROP COLUMN deleted_by,
+    ALTER COLUMN rejected_by SET NOT NULL;
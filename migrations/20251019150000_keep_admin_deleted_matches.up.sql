@@ -0,0 +1,9 @@
+-- Final matches deleted by an admin are kept as well, so that rejection rates are
+-- still computed against every final match created. They have no rejecter; the
+-- audit log records their users
+ALTER TABLE match_rejections
+    ALTER COLUMN rejected_by DROP NOT NULL,
+    -- Admin who deleted the match, NULL for matches rejected by a user
+    ADD COLUMN deleted_by TEXT,
+    ADD CONSTRAINT match_rejections_rejected_or_deleted
+        CHECK ((rejected_by IS NULL) <> (deleted_by IS NULL));
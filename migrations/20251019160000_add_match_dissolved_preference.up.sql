@@ -0,0 +1,3 @@
+-- Partners of suspended users can opt out of being told their match was dissolved
+ALTER TABLE notification_preferences
+    ADD COLUMN match_dissolved BOOLEAN NOT NULL DEFAULT TRUE;
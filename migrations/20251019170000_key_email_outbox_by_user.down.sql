@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_email_outbox_user_id;
+ALTER TABLE email_outbox DROP COLUMN IF EXISTS user_id;
@@ -0,0 +1,12 @@
+-- Emails belong to the account they were sent to, so they are exported with it and
+-- removed along with it rather than passed on to a later account of the same address
+ALTER TABLE email_outbox
+    ADD COLUMN user_id UUID REFERENCES users(id) ON DELETE CASCADE;
+
+-- Only emails sent since the current account was created belong to it
+UPDATE email_outbox o
+SET user_id = u.id
+FROM users u
+WHERE u.email = o.recipient AND o.created_at >= u.created_at;
+
+CREATE INDEX idx_email_outbox_user_id ON email_outbox (user_id);
@@ -0,0 +1,593 @@
+//! # Account Handler
+//!
+//! This module implements endpoints that let users take their data with them
+//! or leave the event entirely.
+//!
+//! # Deletion
+//!
+//! Deleting an account removes the user row; forms, previews, vetoes, refresh tokens
+//! and final matches of all events are removed through `ON DELETE CASCADE`. A partner
+//! in an active final match of the current event is reverted to 'form_completed' so
+//! they can be matched again. Uploaded card photos and profile photos (including
+//! thumbnails) are removed afterwards.
+//!
+//! # Export
+//!
+//! The export is a ZIP archive containing `data.json` with the database records
+//! of the user, grouped by event, plus their files under `card_photos/` and
+//! `profile_photos/`. Reasons and comments of rejections are only included for
+//! the user who gave them, as they are never shown to the partner.
+
+use std::{
+    io::{Cursor, Write},
+    path::Path,
+    sync::Arc,
+};
+
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use serde::Serialize;
+use time::OffsetDateTime;
+use tokio::fs;
+use tracing::{debug, error, info, instrument, trace, warn};
+use uuid::Uuid;
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+use crate::{
+    error::{AppError, AppResult},
+    middleware::AuthUser,
+    models::{
+        AppState, EmailStatus, Form, FormAnswers, FormRevision, Gender, MatchFeedback,
+        NotificationPreferences, TemplateId, UserEventKind, UserStatus,
+    },
+    services::{event::EventService, notification::NotificationService},
+    utils::{file::FileManager, static_object::UPLOAD_DIR},
+};
+
+/// Upload subdirectories that may contain files owned by a user
+const USER_FILE_DIRS: [&str; 2] = ["card_photos", "profile_photos"];
+
+/// Response for a successful account deletion
+#[derive(Debug, Serialize)]
+pub struct DeleteAccountResponse {
+    pub success: bool,
+    pub message: String,
+    pub files_removed: usize,
+}
+
+/// User record included in the data export
+#[derive(Debug, Serialize)]
+struct ExportedUser {
+    id: Uuid,
+    email: String,
+    status: UserStatus,
+    wechat_id: Option<String>,
+    grade: Option<String>,
+    card_photo_filename: Option<String>,
+    locale: String,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    updated_at: OffsetDateTime,
+}
+
+/// Veto record included in the data export
+#[derive(Debug, Serialize)]
+struct ExportedVeto {
+    vetoed_id: Uuid,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+/// Final match record included in the data export
+#[derive(Debug, Serialize)]
+struct ExportedFinalMatch {
+    id: Uuid,
+    partner_id: Uuid,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+/// Rejected final match record included in the data export
+#[derive(Debug, Serialize)]
+struct ExportedRejection {
+    final_match_id: Uuid,
+    /// Whether the user rejected the match, rather than their partner
+    rejected_by_user: bool,
+    /// `None` if the partner deleted their account
+    partner_id: Option<Uuid>,
+    /// Only included if the user rejected the match
+    reason: Option<String>,
+    /// Only included if the user rejected the match
+    comment: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    matched_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    rejected_at: OffsetDateTime,
+}
+
+/// Records of the user in one event included in the data export
+#[derive(Debug, Serialize)]
+struct ExportedEvent {
+    event_id: Uuid,
+    slug: String,
+    form: Option<Form>,
+    form_draft: Option<serde_json::Value>,
+    vetoes: Vec<ExportedVeto>,
+    final_match: Option<ExportedFinalMatch>,
+    rejections: Vec<ExportedRejection>,
+    /// Feedback the user gave on their final matches
+    feedback: Vec<MatchFeedback>,
+}
+
+impl ExportedEvent {
+    fn is_empty(&self) -> bool {
+        self.form.is_none()
+            && self.form_draft.is_none()
+            && self.vetoes.is_empty()
+            && self.final_match.is_none()
+            && self.rejections.is_empty()
+            && self.feedback.is_empty()
+    }
+}
+
+/// Notification preferences included in the data export
+#[derive(Debug, Serialize)]
+struct ExportedNotificationPreferences {
+    #[serde(flatten)]
+    preferences: NotificationPreferences,
+    /// When the user first changed their preferences, `None` if they never did
+    #[serde(with = "time::serde::rfc3339::option")]
+    created_at: Option<OffsetDateTime>,
+    /// When the user last changed their preferences, `None` if they never did
+    #[serde(with = "time::serde::rfc3339::option")]
+    updated_at: Option<OffsetDateTime>,
+}
+
+/// Event pushed to the user over the event stream included in the data export
+#[derive(Debug, Serialize)]
+struct ExportedUserEvent {
+    kind: UserEventKind,
+    data: serde_json::Value,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+/// Email sent or queued for the user included in the data export
+#[derive(Debug, Serialize)]
+struct ExportedEmail {
+    template: TemplateId,
+    locale: String,
+    subject: String,
+    status: EmailStatus,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    sent_at: Option<OffsetDateTime>,
+}
+
+/// Contents of `data.json` in the data export
+#[derive(Debug, Serialize)]
+struct AccountExport {
+    #[serde(with = "time::serde::rfc3339")]
+    exported_at: OffsetDateTime,
+    user: ExportedUser,
+    form_revisions: Vec<FormRevision>,
+    /// Events the user took part in, most recent first
+    events: Vec<ExportedEvent>,
+    notification_preferences: ExportedNotificationPreferences,
+    /// Events addressed to the user that are still kept, oldest first
+    user_events: Vec<ExportedUserEvent>,
+    /// Emails sent to the account that are still kept, oldest first
+    emails: Vec<ExportedEmail>,
+}
+
+/// Permanently deletes the authenticated user's account.
+///
+/// DELETE /api/account
+///
+/// Removes the user, all dependent records and the emails queued or sent to the
+/// user's address in one transaction. If the user has an active final match, the
+/// partner is reverted to 'form_completed'. Only the final match of the current
+/// event can be active, since activating an event resets the statuses of all
+/// participants.
+/// Uploaded files are removed after the transaction commits; failures there are
+/// logged but do not fail the request since the account is already gone.
+///
+/// # Returns
+///
+/// - `200 OK` with `DeleteAccountResponse` - Account deleted successfully
+/// - `401 Unauthorized` - Missing or invalid authentication token
+/// - `404 Not Found` - User not found in database
+/// - `500 Internal Server Error` - Database error
+#[instrument(
+    skip_all,
+    fields(
+        user_id = %user.user_id,
+        request_id = %uuid::Uuid::new_v4()
+    )
+)]
+pub async fn delete_account(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<impl IntoResponse> {
+    debug!("Processing account deletion request");
+
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
+    let mut tx = state.db_pool.begin().await?;
+
+    // Lock the user row so that no concurrent status change slips in
+    let user_row = sqlx::query!(
+        r#"SELECT email, status as "status: UserStatus" FROM users WHERE id = $1 FOR UPDATE"#,
+        user.user_id
+    )
+    .fetch_optional(tx.as_mut())
+    .await?
+    .ok_or_else(|| {
+        warn!("User not found in database");
+        AppError::NotFound("User not found")
+    })?;
+
+    // Release the partner of an active final match before the match row cascades away
+    let final_match = sqlx::query!(
+        r#"
+        SELECT id, user_a_id, user_b_id
+        FROM final_matches
+        WHERE event_id = $1 AND (user_a_id = $2 OR user_b_id = $2)
+        "#,
+        event_id,
+        user.user_id
+    )
+    .fetch_optional(tx.as_mut())
+    .await?;
+
+    if let Some(final_match) = final_match {
+        let partner_id = if final_match.user_a_id == user.user_id {
+            final_match.user_b_id
+        } else {
+            final_match.user_a_id
+        };
+
+        let partner_result = sqlx::query!(
+            "UPDATE users SET status = 'form_completed' WHERE id = $1 AND (status = 'matched' OR status = 'confirmed')",
+            partner_id
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        // A partner who is no longer matched or confirmed has nothing to revert
+        if partner_result.rows_affected() == 0 {
+            debug!(final_match_id = %final_match.id, %partner_id, "Partner not in an active match, left unchanged");
+        } else {
+            info!(final_match_id = %final_match.id, %partner_id, "Partner reverted due to account deletion");
+        }
+    }
+
+    sqlx::query!("DELETE FROM users WHERE id = $1", user.user_id)
+        .execute(tx.as_mut())
+        .await?;
+
+    // Emails to the account went with it, but those sent before it existed, e.g. the
+    // first verification code, are only known by address and would still be delivered
+    let emails_removed = sqlx::query!(
+        "DELETE FROM email_outbox WHERE recipient = $1",
+        user_row.email
+    )
+    .execute(tx.as_mut())
+    .await?
+    .rows_affected();
+
+    tx.commit().await?;
+    info!(previous_status = %user_row.status, emails_removed, "User account deleted");
+
+    // Remove uploaded files (non-critical - log errors but don't fail)
+    let mut files_removed = 0;
+    for dir in USER_FILE_DIRS {
+        let dir_path = Path::new(UPLOAD_DIR.as_str()).join(dir);
+        match FileManager::remove_user_files(&dir_path, user.user_id).await {
+            Ok(count) => files_removed += count,
+            Err(e) => error!(error = %e, dir, "Failed to remove user files"),
+        }
+    }
+
+    debug!(files_removed, "User files removed");
+    Ok((
+        StatusCode::OK,
+        Json(DeleteAccountResponse {
+            success: true,
+            message: "Account deleted successfully".to_string(),
+            files_removed,
+        }),
+    ))
+}
+
+/// Exports all data of the authenticated user as a ZIP archive.
+///
+/// GET /api/account/export
+///
+/// The archive contains `data.json` (user record, form revisions, notification
+/// preferences, user events, emails, and the form with its draft, vetoes, final
+/// match, rejections and feedback of each event) and all uploaded images of the
+/// user, including thumbnails.
+///
+/// # Returns
+///
+/// - `200 OK` with `application/zip` body - Export generated successfully
+/// - `401 Unauthorized` - Missing or invalid authentication token
+/// - `404 Not Found` - User not found in database
+/// - `500 Internal Server Error` - Database or file system error
+#[instrument(
+    skip_all,
+    fields(
+        user_id = %user.user_id,
+        request_id = %uuid::Uuid::new_v4()
+    )
+)]
+pub async fn export_account(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<impl IntoResponse> {
+    debug!("Processing account export request");
+
+    let export = collect_account_data(&state, user.user_id).await?;
+    let data_json = serde_json::to_vec_pretty(&export).map_err(|e| {
+        error!(error = %e, "Failed to serialize account export");
+        AppError::Internal
+    })?;
+
+    // Gather uploaded files as (archive path, contents)
+    let mut files = Vec::new();
+    for dir in USER_FILE_DIRS {
+        let dir_path = Path::new(UPLOAD_DIR.as_str()).join(dir);
+        let paths = FileManager::find_user_files(&dir_path, user.user_id)
+            .await
+            .map_err(|e| {
+                error!(error = %e, dir, "Failed to list user files");
+                AppError::Internal
+            })?;
+
+        for path in paths {
+            let Some(filename) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let data = fs::read(&path).await.map_err(|e| {
+                error!(error = %e, file_path = %path.display(), "Failed to read user file");
+                AppError::Internal
+            })?;
+            files.push((format!("{dir}/{filename}"), data));
+        }
+    }
+
+    let archive = build_archive(&data_json, &files).map_err(|e| {
+        error!(error = %e, "Failed to build export archive");
+        AppError::Internal
+    })?;
+
+    info!(
+        archive_size = archive.len(),
+        file_count = files.len(),
+        "Account export generated"
+    );
+
+    let content_disposition = format!("attachment; filename=\"hilo_export_{}.zip\"", user.user_id);
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (header::CONTENT_DISPOSITION, content_disposition),
+        ],
+        archive,
+    ))
+}
+
+/// Loads the database records that belong to the user.
+async fn collect_account_data(state: &AppState, user_id: Uuid) -> AppResult<AccountExport> {
+    let user = sqlx::query_as!(
+        ExportedUser,
+        r#"
+        SELECT id, email, status as "status: UserStatus", wechat_id, grade,
+               card_photo_filename, locale, created_at, updated_at
+        FROM users
+        WHERE id = $1
+        "#,
+        user_id
+    )
+    .fetch_optional(&state.db_pool)
+    .await?
+    .ok_or_else(|| {
+        warn!("User not found in database");
+        AppError::NotFound("User not found")
+    })?;
+
+    let form_revisions = FormRevision::list(&state.db_pool, &user_id, None).await?;
+
+    let mut events = Vec::new();
+    for event in EventService::get_all_events(&state.db_pool).await? {
+        let exported = collect_event_data(state, user_id, event.id, event.slug).await?;
+        if !exported.is_empty() {
+            events.push(exported);
+        }
+    }
+
+    let preferences = NotificationService::get_preferences(&state.db_pool, user_id).await?;
+    let preference_changes = sqlx::query!(
+        "SELECT created_at, updated_at FROM notification_preferences WHERE user_id = $1",
+        user_id
+    )
+    .fetch_optional(&state.db_pool)
+    .await?;
+    let notification_preferences = ExportedNotificationPreferences {
+        preferences,
+        created_at: preference_changes.as_ref().map(|row| row.created_at),
+        updated_at: preference_changes.as_ref().map(|row| row.updated_at),
+    };
+
+    let user_events = sqlx::query_as!(
+        ExportedUserEvent,
+        r#"
+        SELECT kind as "kind: UserEventKind", data, created_at
+        FROM user_events
+        WHERE user_id = $1
+        ORDER BY id
+        "#,
+        user_id
+    )
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    let emails = sqlx::query_as!(
+        ExportedEmail,
+        r#"
+        SELECT template as "template: TemplateId", locale, subject,
+               status as "status: EmailStatus", created_at, sent_at
+        FROM email_outbox
+        WHERE user_id = $1
+        ORDER BY created_at
+        "#,
+        user_id
+    )
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    trace!(
+        revision_count = form_revisions.len(),
+        event_count = events.len(),
+        user_event_count = user_events.len(),
+        email_count = emails.len(),
+        "Account data collected"
+    );
+
+    Ok(AccountExport {
+        exported_at: OffsetDateTime::now_utc(),
+        user,
+        form_revisions,
+        events,
+        notification_preferences,
+        user_events,
+        emails,
+    })
+}
+
+/// Loads the database records of the user in one event.
+async fn collect_event_data(
+    state: &AppState,
+    user_id: Uuid,
+    event_id: Uuid,
+    slug: String,
+) -> AppResult<ExportedEvent> {
+    let form = sqlx::query_as!(
+        Form,
+        r#"
+        SELECT user_id, gender as "gender: Gender",
+               answers as "answers: sqlx::types::Json<FormAnswers>", profile_photo_filename
+        FROM forms
+        WHERE event_id = $1 AND user_id = $2
+        "#,
+        event_id,
+        user_id
+    )
+    .fetch_optional(&state.db_pool)
+    .await?;
+
+    let form_draft = sqlx::query_scalar!(
+        "SELECT data FROM form_drafts WHERE event_id = $1 AND user_id = $2",
+        event_id,
+        user_id
+    )
+    .fetch_optional(&state.db_pool)
+    .await?;
+
+    let vetoes = sqlx::query_as!(
+        ExportedVeto,
+        r#"
+        SELECT vetoed_id, created_at
+        FROM vetoes
+        WHERE event_id = $1 AND vetoer_id = $2
+        ORDER BY created_at
+        "#,
+        event_id,
+        user_id
+    )
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    let final_match = sqlx::query_as!(
+        ExportedFinalMatch,
+        r#"
+        SELECT id,
+               CASE WHEN user_a_id = $1 THEN user_b_id ELSE user_a_id END as "partner_id!",
+               created_at
+        FROM final_matches
+        WHERE event_id = $2 AND (user_a_id = $1 OR user_b_id = $1)
+        "#,
+        user_id,
+        event_id
+    )
+    .fetch_optional(&state.db_pool)
+    .await?;
+
+    let rejections = sqlx::query_as!(
+        ExportedRejection,
+        r#"
+        SELECT final_match_id, rejected_by = $1 as "rejected_by_user!",
+               CASE WHEN rejected_by = $1 THEN partner_id ELSE rejected_by END as partner_id,
+               CASE WHEN rejected_by = $1 THEN reason END as reason,
+               CASE WHEN rejected_by = $1 THEN comment END as comment,
+               matched_at, rejected_at
+        FROM match_rejections
+        WHERE event_id = $2 AND (rejected_by = $1 OR partner_id = $1)
+        ORDER BY rejected_at
+        "#,
+        user_id,
+        event_id
+    )
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    let feedback = sqlx::query_as!(
+        MatchFeedback,
+        r#"
+        SELECT f.final_match_id, f.user_id, f.rating, f.met, f.comment, f.created_at,
+               f.updated_at
+        FROM match_feedback f
+        JOIN final_matches fm ON fm.id = f.final_match_id
+        WHERE fm.event_id = $2 AND f.user_id = $1
+        ORDER BY f.created_at
+        "#,
+        user_id,
+        event_id
+    )
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    Ok(ExportedEvent {
+        event_id,
+        slug,
+        form,
+        form_draft,
+        vetoes,
+        final_match,
+        rejections,
+        feedback,
+    })
+}
+
+/// Builds an in-memory ZIP archive from `data.json` and the user's files.
+fn build_archive(data_json: &[u8], files: &[(String, Vec<u8>)]) -> zip::result::ZipResult<Vec<u8>> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+
+    writer.start_file("data.json", options)?;
+    writer.write_all(data_json)?;
+
+    for (name, data) in files {
+        writer.start_file(name.as_str(), options)?;
+        writer.write_all(data)?;
+    }
+
+    Ok(writer.finish()?.into_inner())
+}
@@ -14,12 +14,19 @@
 //! - **Final Matching** - Executes the matching algorithm to create final pairs
 //! - **Match Previews** - Regenerates preview suggestions for all users
 //! - **User Verification** - Changes user status for verification workflow
+//! - **Suspension** - Suspends users and lifts suspensions
+//! - **Event Phases** - Configures the event timeline
+//! - **Events** - Creates events and switches the current event
+//! - **Allowed Domains** - Manages the email domains users can sign up with
+//!
+//! Every operation that changes state writes an audit entry in the same
+//! transaction as the change itself.
 
 use std::sync::Arc;
 
 use axum::{
     Json,
-    extract::{Path as AxumPath, State},
+    extract::{Path as AxumPath, Query, State},
     http::StatusCode,
     response::IntoResponse,
 };
@@ -27,12 +34,17 @@ use serde::{Deserialize, Serialize};
 use tracing::{error, info, instrument, warn};
 use uuid::Uuid;
 
-use super::{AdminState, get_user_id_by_email, get_user_status};
+use super::{AdminActor, AdminState, EventScope, get_user_id_by_email, get_user_status};
 use crate::{
     error::{AppError, AppResult},
-    models::{CreateScheduledMatchesRequest, UserStatus},
-    services::{matching::MatchingService, scheduler::SchedulerService},
-    utils::static_object::TAG_SYSTEM,
+    models::{
+        AdminAction, AllowedDomainRequest, CreateEventPhasesRequest, CreateEventRequest,
+        CreateScheduledMatchesRequest, NewAuditEntry, RoundDeadlines, UserStatus,
+    },
+    services::{
+        audit::AuditService, domain::DomainService, event::EventService, matching::MatchingService,
+        moderation::ModerationService, rejection::RejectionService, scheduler::SchedulerService,
+    },
 };
 
 #[derive(Debug, Serialize)]
@@ -50,25 +62,40 @@ pub struct ActionResponse {
 
 /// Executes the final matching algorithm to create user pairs.
 ///
-/// POST /api/admin/trigger-match
+/// POST /api/admin/trigger-match ?auto_accept_hours=&reminder_hours=
 ///
-/// This endpoint triggers the final matching algorithm and updates matched users'
-/// status to 'matched'. All vetoes and match previews are cleared after completion.
+/// This endpoint triggers the final matching algorithm in the current event and
+/// updates matched users' status to 'matched'. All vetoes and match previews of the
+/// event are cleared after completion, and the matched users are notified by email.
+/// The optional query parameters set when the new matches are auto-accepted and
+/// their users reminded.
 ///
 /// # Returns
 ///
 /// - `200 OK` with `TriggerMatchingResponse` - Final matching completed successfully
+/// - `400 Bad Request` - Invalid deadlines
 /// - `500 Internal Server Error` - Matching algorithm failure
 #[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
 pub async fn trigger_final_matching(
     State(state): State<Arc<AdminState>>,
+    AdminActor(actor): AdminActor,
+    Query(deadlines): Query<RoundDeadlines>,
 ) -> AppResult<impl IntoResponse> {
-    let matches_len = SchedulerService::execute_final_matching(&state.db_pool, &TAG_SYSTEM, false)
-        .await
-        .map_err(|e| {
-            error!("Final matching failed: {}", e);
-            AppError::Internal
-        })?;
+    deadlines.validate().map_err(AppError::BadRequest)?;
+
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
+    let matches_len = SchedulerService::execute_final_matching(
+        &state.db_pool,
+        event_id,
+        false,
+        deadlines,
+        &actor,
+    )
+    .await
+    .map_err(|e| {
+        error!("Final matching failed: {}", e);
+        AppError::Internal
+    })?;
 
     info!("Final matching completed: {} pairs", matches_len);
     Ok(Json(TriggerMatchingResponse {
@@ -82,7 +109,8 @@ pub async fn trigger_final_matching(
 ///
 /// POST /api/admin/dry-run-final
 ///
-/// This endpoint runs the final matching algorithm in dry run mode, which means:
+/// This endpoint runs the final matching algorithm of the current event in dry run
+/// mode, which means:
 /// - No database changes are made (no matches created, no status updates, no deletions)
 /// - Results are saved to a JSON file in UPLOAD_DIR with filename format:
 ///   `dry_run_matches_{timestamp}.json`
@@ -95,13 +123,23 @@ pub async fn trigger_final_matching(
 /// - `200 OK` with `TriggerMatchingResponse` - Dry run completed successfully
 /// - `500 Internal Server Error` - Matching algorithm or file write failure
 #[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
-pub async fn dry_run_final(State(state): State<Arc<AdminState>>) -> AppResult<impl IntoResponse> {
-    let matches_len = SchedulerService::execute_final_matching(&state.db_pool, &TAG_SYSTEM, true)
-        .await
-        .map_err(|e| {
-            error!("Final matching dry run failed: {}", e);
-            AppError::Internal
-        })?;
+pub async fn dry_run_final(
+    State(state): State<Arc<AdminState>>,
+    AdminActor(actor): AdminActor,
+) -> AppResult<impl IntoResponse> {
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
+    let matches_len = SchedulerService::execute_final_matching(
+        &state.db_pool,
+        event_id,
+        true,
+        RoundDeadlines::default(),
+        &actor,
+    )
+    .await
+    .map_err(|e| {
+        error!("Final matching dry run failed: {}", e);
+        AppError::Internal
+    })?;
 
     info!("Final matching dry run completed: {} pairs", matches_len);
     Ok(Json(TriggerMatchingResponse {
@@ -116,8 +154,9 @@ pub async fn dry_run_final(State(state): State<Arc<AdminState>>) -> AppResult<im
 /// POST /api/admin/update-previews
 ///
 /// This endpoint triggers regeneration of match preview suggestions for users
-/// with completed forms. Match previews are used to show potential matches
+/// with completed forms in the current event. Match previews are used to show potential matches
 /// before final matching occurs, allowing users to veto unwanted suggestions.
+/// The regeneration is audited in the same transaction as the stored previews.
 ///
 /// # Returns
 ///
@@ -126,14 +165,22 @@ pub async fn dry_run_final(State(state): State<Arc<AdminState>>) -> AppResult<im
 #[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
 pub async fn update_match_previews(
     State(state): State<Arc<AdminState>>,
+    AdminActor(actor): AdminActor,
 ) -> AppResult<impl IntoResponse> {
-    MatchingService::generate_match_previews(&state.db_pool, &TAG_SYSTEM)
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
+    let mut tx = state.db_pool.begin().await?;
+    MatchingService::generate_match_previews_in(&state.db_pool, &mut tx, event_id)
         .await
         .map_err(|e| {
             error!("Match previews update failed: {}", e);
             AppError::Internal
         })?;
 
+    let audit = NewAuditEntry::new(&actor, AdminAction::UpdateMatchPreviews, Vec::new())
+        .payload(serde_json::json!({ "event_id": event_id }));
+    AuditService::record(tx.as_mut(), &audit).await?;
+    tx.commit().await?;
+
     info!("Match previews update completed successfully");
     Ok(Json(ActionResponse {
         success: true,
@@ -142,7 +189,7 @@ pub async fn update_match_previews(
 }
 
 /// Request payload for admin user verification
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct VerifyUserRequest {
     /// User ID (takes priority if both id and email are provided)
     pub user_id: Option<Uuid>,
@@ -179,6 +226,7 @@ pub struct UserData {
 #[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
 pub async fn verify_user(
     State(state): State<Arc<AdminState>>,
+    AdminActor(actor): AdminActor,
     Json(payload): Json<VerifyUserRequest>,
 ) -> AppResult<impl IntoResponse> {
     // Validate target status
@@ -203,6 +251,13 @@ pub async fn verify_user(
     // Check current user status: should not be 'unverified'
     let current_status = get_user_status(&state.db_pool, &user_id).await?;
 
+    if current_status.is_suspended() {
+        warn!(%user_id, "Cannot change verification status of a suspended user");
+        return Err(AppError::BadRequest(
+            "Cannot change status of a suspended user",
+        ));
+    }
+
     if current_status == UserStatus::Unverified {
         warn!(
             %user_id,
@@ -215,14 +270,32 @@ pub async fn verify_user(
     }
 
     // Update user status and return updated user data
+    let mut tx = state.db_pool.begin().await?;
+
     let updated_user = sqlx::query!(
-        r#"UPDATE users SET status = $1 WHERE id = $2
+        r#"UPDATE users
+           SET status = $1,
+               card_reviewed_at = NOW(),
+               card_verified_at = CASE WHEN $1::user_status = 'verified' THEN NOW() ELSE card_verified_at END
+           WHERE id = $2 AND status = $3
            RETURNING id, email, status as "status: UserStatus", grade, card_photo_filename"#,
         payload.status as UserStatus,
-        user_id
+        user_id,
+        current_status as UserStatus
     )
-    .fetch_one(&state.db_pool)
-    .await?;
+    .fetch_optional(tx.as_mut())
+    .await?
+    .ok_or_else(|| {
+        error!(%user_id, "Data race detected while updating user status");
+        AppError::Internal
+    })?;
+
+    let audit = NewAuditEntry::new(&actor, AdminAction::VerifyUser, vec![user_id])
+        .payload(serde_json::to_value(&payload).unwrap_or_default())
+        .status_change(Some(current_status), payload.status);
+    AuditService::record(tx.as_mut(), &audit).await?;
+
+    tx.commit().await?;
 
     info!(
         %user_id,
@@ -239,22 +312,161 @@ pub async fn verify_user(
     }))
 }
 
+/// Request payload for suspending a user
+#[derive(Debug, Deserialize)]
+pub struct SuspendUserRequest {
+    /// User ID (takes priority if both id and email are provided)
+    pub user_id: Option<Uuid>,
+    /// User email (used if user_id is not provided)
+    pub email: Option<String>,
+    /// Reason shown to the user while suspended
+    pub reason: String,
+    /// When the suspension ends automatically; `None` means until lifted by an admin
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub suspended_until: Option<time::OffsetDateTime>,
+}
+
+/// Request payload for lifting a suspension
+#[derive(Debug, Deserialize)]
+pub struct LiftSuspensionRequest {
+    /// User ID (takes priority if both id and email are provided)
+    pub user_id: Option<Uuid>,
+    /// User email (used if user_id is not provided)
+    pub email: Option<String>,
+}
+
+/// Suspends a user, taking them out of the event.
+///
+/// POST /api/admin/suspend-user SuspendUserRequest
+///
+/// Any final match of the user is dissolved and the partner is returned to
+/// 'form_completed' and notified by email. The suspended user is rejected by
+/// every protected endpoint until the suspension is lifted or expires.
+///
+/// # Returns
+///
+/// - `200 OK` with `UserData` - User suspended successfully
+/// - `400 Bad Request` - Invalid request parameters or user already suspended
+/// - `404 Not Found` - User not found
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
+pub async fn suspend_user(
+    State(state): State<Arc<AdminState>>,
+    AdminActor(actor): AdminActor,
+    Json(payload): Json<SuspendUserRequest>,
+) -> AppResult<impl IntoResponse> {
+    let reason = payload.reason.trim();
+    if reason.is_empty() {
+        warn!("Empty suspension reason");
+        return Err(AppError::BadRequest("Suspension reason is required"));
+    }
+
+    if payload
+        .suspended_until
+        .is_some_and(|until| until <= time::OffsetDateTime::now_utc())
+    {
+        warn!("Suspension end is in the past");
+        return Err(AppError::BadRequest(
+            "suspended_until must be in the future",
+        ));
+    }
+
+    let user_id = resolve_user_id(&state, payload.user_id, payload.email.as_deref()).await?;
+
+    ModerationService::suspend_user(
+        &state.db_pool,
+        &actor,
+        user_id,
+        reason,
+        payload.suspended_until,
+    )
+    .await?;
+
+    Ok(Json(get_user_data(&state, user_id).await?))
+}
+
+/// Lifts a user's suspension and restores their previous status.
+///
+/// POST /api/admin/lift-suspension LiftSuspensionRequest
+///
+/// Users who were matched or confirmed when suspended come back as
+/// 'form_completed', since their final match was dissolved.
+///
+/// # Returns
+///
+/// - `200 OK` with `UserData` - Suspension lifted successfully
+/// - `400 Bad Request` - Invalid request parameters or user not suspended
+/// - `404 Not Found` - User not found
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
+pub async fn lift_suspension(
+    State(state): State<Arc<AdminState>>,
+    AdminActor(actor): AdminActor,
+    Json(payload): Json<LiftSuspensionRequest>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = resolve_user_id(&state, payload.user_id, payload.email.as_deref()).await?;
+
+    ModerationService::lift_suspension(&state.db_pool, &actor, user_id).await?;
+
+    Ok(Json(get_user_data(&state, user_id).await?))
+}
+
+/// Get user ID from the request (prioritize user_id over email)
+async fn resolve_user_id(
+    state: &AdminState,
+    user_id: Option<Uuid>,
+    email: Option<&str>,
+) -> AppResult<Uuid> {
+    if let Some(id) = user_id {
+        Ok(id)
+    } else if let Some(email) = email {
+        get_user_id_by_email(&state.db_pool, email).await
+    } else {
+        warn!("Neither user_id nor email provided");
+        Err(AppError::BadRequest("Must provide either user_id or email"))
+    }
+}
+
+/// Fetch the user data returned by status-changing actions
+async fn get_user_data(state: &AdminState, user_id: Uuid) -> AppResult<UserData> {
+    let user = sqlx::query!(
+        r#"SELECT id, email, status as "status: UserStatus", grade, card_photo_filename
+           FROM users WHERE id = $1"#,
+        user_id
+    )
+    .fetch_optional(&state.db_pool)
+    .await?
+    .ok_or(AppError::NotFound("User not found"))?;
+
+    Ok(UserData {
+        user_id: user.id,
+        email: user.email,
+        status: user.status,
+        grade: user.grade,
+        card_photo_filename: user.card_photo_filename,
+    })
+}
+
 /// Creates multiple scheduled final match triggers.
 ///
-/// POST /api/admin/scheduled-matches CreateScheduledMatchesRequest
+/// POST /api/admin/scheduled-matches?event_id= CreateScheduledMatchesRequest
 ///
 /// This endpoint allows administrators to schedule automatic final match
 /// executions at specified UTC timestamps. The scheduled matches will be
-/// executed automatically by the background scheduler service.
+/// executed automatically by the background scheduler service while their
+/// event is current. Each scheduled time may set when its matches are
+/// auto-accepted and their users reminded.
 ///
 /// # Returns
 ///
 /// - `201 Created` with `Vec<ScheduledFinalMatch>` - Scheduled matches created successfully
-/// - `400 Bad Request` - Invalid timestamps or timestamps in the past
+/// - `400 Bad Request` - Invalid timestamps or deadlines, or timestamps in the past
 /// - `500 Internal Server Error` - Database error
 #[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
 pub async fn create_scheduled_matches(
     State(state): State<Arc<AdminState>>,
+    AdminActor(actor): AdminActor,
+    EventScope(event_id): EventScope,
     Json(payload): Json<CreateScheduledMatchesRequest>,
 ) -> AppResult<impl IntoResponse> {
     if payload.scheduled_times.is_empty() {
@@ -263,14 +475,18 @@ pub async fn create_scheduled_matches(
         ));
     }
 
-    let scheduled_times: Vec<_> = payload
-        .scheduled_times
-        .into_iter()
-        .map(|req| req.scheduled_time)
-        .collect();
+    let mut tx = state.db_pool.begin().await?;
 
     let scheduled_matches =
-        SchedulerService::create_scheduled_matches(&state.db_pool, &scheduled_times).await?;
+        SchedulerService::create_scheduled_matches(tx.as_mut(), event_id, &payload.scheduled_times)
+            .await?;
+
+    let target_ids = scheduled_matches.iter().map(|m| m.id).collect();
+    let audit = NewAuditEntry::new(&actor, AdminAction::CreateScheduledMatches, target_ids)
+        .payload(serde_json::to_value(&payload).unwrap_or_default());
+    AuditService::record(tx.as_mut(), &audit).await?;
+
+    tx.commit().await?;
 
     info!(
         "Created {} scheduled final matches",
@@ -282,10 +498,10 @@ pub async fn create_scheduled_matches(
 
 /// Gets all scheduled final match triggers.
 ///
-/// GET /api/admin/scheduled-matches
+/// GET /api/admin/scheduled-matches?event_id=
 ///
-/// This endpoint returns all scheduled final match triggers, including
-/// pending, completed, and failed ones, ordered by scheduled time.
+/// This endpoint returns all scheduled final match triggers of the event,
+/// including pending, completed, and failed ones, ordered by scheduled time.
 ///
 /// # Returns
 ///
@@ -294,8 +510,10 @@ pub async fn create_scheduled_matches(
 #[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
 pub async fn get_scheduled_matches(
     State(state): State<Arc<AdminState>>,
+    EventScope(event_id): EventScope,
 ) -> AppResult<impl IntoResponse> {
-    let scheduled_matches = SchedulerService::get_all_scheduled_matches(&state.db_pool).await?;
+    let scheduled_matches =
+        SchedulerService::get_all_scheduled_matches(&state.db_pool, event_id).await?;
 
     Ok(Json(scheduled_matches))
 }
@@ -315,9 +533,12 @@ pub async fn get_scheduled_matches(
 #[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4(), match_id = %match_id))]
 pub async fn cancel_scheduled_match(
     State(state): State<Arc<AdminState>>,
+    AdminActor(actor): AdminActor,
     AxumPath(match_id): AxumPath<Uuid>,
 ) -> AppResult<impl IntoResponse> {
-    let cancelled = SchedulerService::cancel_scheduled_match(&state.db_pool, match_id).await?;
+    let mut tx = state.db_pool.begin().await?;
+
+    let cancelled = SchedulerService::cancel_scheduled_match(tx.as_mut(), match_id).await?;
 
     if !cancelled {
         return Err(AppError::NotFound(
@@ -325,6 +546,11 @@ pub async fn cancel_scheduled_match(
         ));
     }
 
+    let audit = NewAuditEntry::new(&actor, AdminAction::CancelScheduledMatch, vec![match_id]);
+    AuditService::record(tx.as_mut(), &audit).await?;
+
+    tx.commit().await?;
+
     info!(%match_id, "Cancelled scheduled final match");
 
     Ok(Json(ActionResponse {
@@ -333,14 +559,352 @@ pub async fn cancel_scheduled_match(
     }))
 }
 
+/// Creates or reschedules phases of the event timeline.
+///
+/// POST /api/admin/event-phases?event_id= CreateEventPhasesRequest
+///
+/// Each phase starts at the given UTC timestamp and lasts until the next one starts.
+/// A phase starting at the same time as an existing one replaces it. Phase
+/// transitions are processed by the background scheduler service.
+///
+/// # Returns
+///
+/// - `201 Created` with `Vec<EventPhaseEntry>` - Phases created successfully
+/// - `400 Bad Request` - No phases or start times in the past
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
+pub async fn create_event_phases(
+    State(state): State<Arc<AdminState>>,
+    AdminActor(actor): AdminActor,
+    EventScope(event_id): EventScope,
+    Json(payload): Json<CreateEventPhasesRequest>,
+) -> AppResult<impl IntoResponse> {
+    if payload.phases.is_empty() {
+        return Err(AppError::BadRequest("At least one phase is required"));
+    }
+
+    let phases: Vec<_> = payload
+        .phases
+        .iter()
+        .map(|req| (req.phase, req.starts_at))
+        .collect();
+
+    let mut tx = state.db_pool.begin().await?;
+
+    let entries = EventService::create_phases(tx.as_mut(), event_id, &phases).await?;
+
+    let target_ids = entries.iter().map(|entry| entry.id).collect();
+    let audit = NewAuditEntry::new(&actor, AdminAction::CreateEventPhases, target_ids)
+        .payload(serde_json::to_value(&payload).unwrap_or_default());
+    AuditService::record(tx.as_mut(), &audit).await?;
+
+    tx.commit().await?;
+
+    info!("Created {} event phases", entries.len());
+
+    Ok((StatusCode::CREATED, Json(entries)))
+}
+
+/// Gets the whole timeline of an event.
+///
+/// GET /api/admin/event-phases?event_id=
+///
+/// # Returns
+///
+/// - `200 OK` with `Vec<EventPhaseEntry>` - All phases ordered by start time
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
+pub async fn get_event_phases(
+    State(state): State<Arc<AdminState>>,
+    EventScope(event_id): EventScope,
+) -> AppResult<impl IntoResponse> {
+    let phases = EventService::get_all_phases(&state.db_pool, event_id).await?;
+
+    Ok(Json(phases))
+}
+
+/// Cancels a phase of the event timeline.
+///
+/// DELETE /api/admin/event-phases/{id}
+///
+/// Only phases that have not started yet can be cancelled.
+///
+/// # Returns
+///
+/// - `200 OK` with `ActionResponse` - Phase cancelled successfully
+/// - `404 Not Found` - Phase not found or already started
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4(), phase_id = %phase_id))]
+pub async fn cancel_event_phase(
+    State(state): State<Arc<AdminState>>,
+    AdminActor(actor): AdminActor,
+    AxumPath(phase_id): AxumPath<Uuid>,
+) -> AppResult<impl IntoResponse> {
+    let mut tx = state.db_pool.begin().await?;
+
+    if !EventService::cancel_phase(tx.as_mut(), phase_id).await? {
+        return Err(AppError::NotFound(
+            "Event phase not found or already started",
+        ));
+    }
+
+    let audit = NewAuditEntry::new(&actor, AdminAction::CancelEventPhase, vec![phase_id]);
+    AuditService::record(tx.as_mut(), &audit).await?;
+
+    tx.commit().await?;
+
+    info!(%phase_id, "Cancelled event phase");
+
+    Ok(Json(ActionResponse {
+        success: true,
+        message: "Event phase cancelled successfully",
+    }))
+}
+
+/// Creates an event.
+///
+/// POST /api/admin/events CreateEventRequest
+///
+/// The event does not become current until it is activated. Allowed domains,
+/// grades, tags and traits left out fall back to the deployment defaults.
+///
+/// # Returns
+///
+/// - `201 Created` with `Event` - Event created successfully
+/// - `400 Bad Request` - Empty slug, name or lists, or slug already taken
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
+pub async fn create_event(
+    State(state): State<Arc<AdminState>>,
+    AdminActor(actor): AdminActor,
+    Json(payload): Json<CreateEventRequest>,
+) -> AppResult<impl IntoResponse> {
+    let mut tx = state.db_pool.begin().await?;
+
+    let event = EventService::create_event(tx.as_mut(), &payload).await?;
+
+    let audit = NewAuditEntry::new(&actor, AdminAction::CreateEvent, vec![event.id]).payload(
+        serde_json::json!({
+            "slug": event.slug,
+            "name": event.name,
+            "allowed_domains": event.allowed_domains,
+            "allowed_grades": event.allowed_grades,
+            "custom_tags": payload.tags.is_some(),
+            "custom_traits": payload.traits.is_some(),
+        }),
+    );
+    AuditService::record(tx.as_mut(), &audit).await?;
+
+    tx.commit().await?;
+
+    info!(event_id = %event.id, slug = %event.slug, "Created event");
+
+    Ok((StatusCode::CREATED, Json(event)))
+}
+
+/// Gets all events.
+///
+/// GET /api/admin/events
+///
+/// # Returns
+///
+/// - `200 OK` with `Vec<Event>` - All events, newest first
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
+pub async fn get_events(State(state): State<Arc<AdminState>>) -> AppResult<impl IntoResponse> {
+    let events = EventService::get_all_events(&state.db_pool).await?;
+
+    Ok(Json(events))
+}
+
+/// Response of switching the current event
+#[derive(Debug, Serialize)]
+pub struct ActivateEventResponse {
+    pub success: bool,
+    pub message: &'static str,
+    pub users_reset: u64,
+}
+
+/// Makes an event the current event.
+///
+/// POST /api/admin/events/{id}/activate
+///
+/// Sign-up, forms, previews, vetoes and matching move to the new event. Every
+/// participant of the previous event goes back to 'verified' to fill in a new
+/// form; forms and matches of the previous event are kept.
+///
+/// # Returns
+///
+/// - `200 OK` with `ActivateEventResponse` - Event activated successfully
+/// - `400 Bad Request` - Event is already current, or was activated concurrently
+/// - `404 Not Found` - Event not found
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4(), event_id = %event_id))]
+pub async fn activate_event(
+    State(state): State<Arc<AdminState>>,
+    AdminActor(actor): AdminActor,
+    AxumPath(event_id): AxumPath<Uuid>,
+) -> AppResult<impl IntoResponse> {
+    let mut tx = state.db_pool.begin().await?;
+
+    let previous_event_id = EventService::lock_current_event(tx.as_mut()).await?;
+    if previous_event_id == event_id {
+        warn!("Event is already current");
+        return Err(AppError::BadRequest("Event is already current"));
+    }
+
+    let users_reset = EventService::activate_event(tx.as_mut(), event_id)
+        .await?
+        .ok_or(AppError::NotFound("Event not found"))?;
+
+    let audit = NewAuditEntry::new(&actor, AdminAction::ActivateEvent, vec![event_id]).payload(
+        serde_json::json!({
+            "previous_event_id": previous_event_id,
+            "users_reset": users_reset,
+        }),
+    );
+    AuditService::record(tx.as_mut(), &audit).await?;
+
+    tx.commit().await?;
+
+    info!(%event_id, %previous_event_id, users_reset, "Activated event");
+
+    Ok(Json(ActivateEventResponse {
+        success: true,
+        message: "Event activated successfully",
+        users_reset,
+    }))
+}
+
+/// Gets all allowed email domains.
+///
+/// GET /api/admin/domains
+///
+/// # Returns
+///
+/// - `200 OK` with `Vec<AllowedDomain>` - All allowed domains ordered by domain
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
+pub async fn get_allowed_domains(
+    State(state): State<Arc<AdminState>>,
+) -> AppResult<impl IntoResponse> {
+    let domains = DomainService::get_all_domains(&state.db_pool).await?;
+
+    Ok(Json(domains))
+}
+
+/// Allows a new email domain to sign up.
+///
+/// POST /api/admin/domains AllowedDomainRequest
+///
+/// Running servers pick up the change without a restart.
+///
+/// # Returns
+///
+/// - `201 Created` with `AllowedDomain` - Domain allowed successfully
+/// - `400 Bad Request` - Invalid domain, empty name or grades, or domain already allowed
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
+pub async fn create_allowed_domain(
+    State(state): State<Arc<AdminState>>,
+    AdminActor(actor): AdminActor,
+    Json(payload): Json<AllowedDomainRequest>,
+) -> AppResult<impl IntoResponse> {
+    let mut tx = state.db_pool.begin().await?;
+
+    let domain = DomainService::create_domain(tx.as_mut(), &payload).await?;
+
+    let audit = NewAuditEntry::new(&actor, AdminAction::CreateAllowedDomain, vec![domain.id])
+        .payload(serde_json::to_value(&payload).unwrap_or_default());
+    AuditService::record(tx.as_mut(), &audit).await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(domain)))
+}
+
+/// Updates an allowed email domain.
+///
+/// PUT /api/admin/domains/{id} AllowedDomainRequest
+///
+/// Grades only apply to new card uploads; verified users are not affected.
+///
+/// # Returns
+///
+/// - `200 OK` with `AllowedDomain` - Domain updated successfully
+/// - `400 Bad Request` - Invalid domain, empty name or grades, or domain already allowed
+/// - `404 Not Found` - Domain not found
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4(), domain_id = %domain_id))]
+pub async fn update_allowed_domain(
+    State(state): State<Arc<AdminState>>,
+    AdminActor(actor): AdminActor,
+    AxumPath(domain_id): AxumPath<Uuid>,
+    Json(payload): Json<AllowedDomainRequest>,
+) -> AppResult<impl IntoResponse> {
+    let mut tx = state.db_pool.begin().await?;
+
+    let (before, after) = DomainService::update_domain(tx.as_mut(), domain_id, &payload)
+        .await?
+        .ok_or(AppError::NotFound("Domain not found"))?;
+
+    let audit = NewAuditEntry::new(&actor, AdminAction::UpdateAllowedDomain, vec![domain_id])
+        .payload(serde_json::json!({ "before": before, "after": payload }));
+    AuditService::record(tx.as_mut(), &audit).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(after))
+}
+
+/// Stops allowing an email domain to sign up.
+///
+/// DELETE /api/admin/domains/{id}
+///
+/// Existing users of the domain keep their accounts and can still log in, but
+/// can no longer upload a student card.
+///
+/// # Returns
+///
+/// - `200 OK` with `ActionResponse` - Domain removed successfully
+/// - `404 Not Found` - Domain not found
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4(), domain_id = %domain_id))]
+pub async fn delete_allowed_domain(
+    State(state): State<Arc<AdminState>>,
+    AdminActor(actor): AdminActor,
+    AxumPath(domain_id): AxumPath<Uuid>,
+) -> AppResult<impl IntoResponse> {
+    let mut tx = state.db_pool.begin().await?;
+
+    let domain = DomainService::delete_domain(tx.as_mut(), domain_id)
+        .await?
+        .ok_or(AppError::NotFound("Domain not found"))?;
+
+    let audit = NewAuditEntry::new(&actor, AdminAction::DeleteAllowedDomain, vec![domain_id])
+        .payload(serde_json::to_value(&domain).unwrap_or_default());
+    AuditService::record(tx.as_mut(), &audit).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(ActionResponse {
+        success: true,
+        message: "Domain removed successfully",
+    }))
+}
+
 /// Deletes a final match and reverts both users' status to form_completed.
 ///
 /// DELETE /api/admin/final-matches/{id}
 ///
-/// This endpoint allows administrators to delete a final match by ID and
-/// revert both matched users back to 'form_completed' status. This is useful
+/// This endpoint allows administrators to delete a final match of the current
+/// event by ID and revert both matched users back to 'form_completed' status. This is useful
 /// for correcting matching errors or handling user requests to be rematched.
 ///
+/// The match is kept with the rejected matches, marked as deleted by the admin, so
+/// that rejection rates are still computed against every match created. One audit
+/// entry is recorded per user, with the status they had before.
+///
 /// # Returns
 ///
 /// - `200 OK` with `ActionResponse` - Match deleted and users reverted successfully
@@ -349,15 +913,27 @@ pub async fn cancel_scheduled_match(
 #[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4(), match_id = %match_id))]
 pub async fn delete_final_match(
     State(state): State<Arc<AdminState>>,
+    AdminActor(actor): AdminActor,
     AxumPath(match_id): AxumPath<Uuid>,
 ) -> AppResult<impl IntoResponse> {
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
+
     // Start a transaction to ensure atomicity
     let mut tx = state.db_pool.begin().await?;
 
-    // Fetch the final match to get user IDs
+    // Fetch the final match to get user IDs and their current statuses
     let final_match = sqlx::query!(
-        r#"SELECT user_a_id, user_b_id FROM final_matches WHERE id = $1"#,
-        match_id
+        r#"
+        SELECT fm.user_a_id, fm.user_b_id, fm.score,
+               ua.status as "user_a_status: UserStatus",
+               ub.status as "user_b_status: UserStatus"
+        FROM final_matches fm
+        JOIN users ua ON fm.user_a_id = ua.id
+        JOIN users ub ON fm.user_b_id = ub.id
+        WHERE fm.id = $1 AND fm.event_id = $2
+        "#,
+        match_id,
+        event_id
     )
     .fetch_optional(tx.as_mut())
     .await?;
@@ -371,10 +947,14 @@ pub async fn delete_final_match(
         }
     };
 
-    // Delete the final match
-    sqlx::query!(r#"DELETE FROM final_matches WHERE id = $1"#, match_id)
-        .execute(tx.as_mut())
-        .await?;
+    // Keep the deleted match for the rejection statistics
+    if RejectionService::record_deletion(tx.as_mut(), match_id, &actor)
+        .await?
+        .is_none()
+    {
+        warn!(%match_id, "Final match deleted concurrently");
+        return Err(AppError::NotFound("Final match not found"));
+    }
 
     // Revert both users' status to form_completed
     sqlx::query!(
@@ -391,6 +971,20 @@ pub async fn delete_final_match(
     .execute(tx.as_mut())
     .await?;
 
+    for (user_id, status_before) in [
+        (final_match.user_a_id, final_match.user_a_status),
+        (final_match.user_b_id, final_match.user_b_status),
+    ] {
+        let audit = NewAuditEntry::new(
+            &actor,
+            AdminAction::DeleteFinalMatch,
+            vec![match_id, user_id],
+        )
+        .payload(serde_json::json!({ "score": final_match.score }))
+        .status_change(Some(status_before), UserStatus::FormCompleted);
+        AuditService::record(tx.as_mut(), &audit).await?;
+    }
+
     // Commit the transaction
     tx.commit().await?;
 
@@ -9,20 +9,43 @@
 //! ## View Endpoints
 //! - **Users Overview** - Paginated list of all users
 //! - **User Details** - Detailed information for specific users
+//! - **Form Revisions** - Revision history of a user's form
 //! - **User Card Photos** - Serve student verification card photos
 //! - **Tag Statistics** - Tag usage statistics with IDF scores
-//! - **Final Matches** - View all final match results
+//! - **Final Matches** - View all final match results with the feedback of their users
+//! - **Match Feedback** - Feedback on confirmed matches by score bucket, tag overlap and algorithm version
+//! - **Rejections** - Rejected matches with their reasons, and rejection rates by reason, score bucket, tag overlap and algorithm version
 //! - **User Statistics** - Overall user and gender statistics
+//! - **Preview Exposure** - How often each user appears in other users' previews
+//! - **Audit Log** - Paginated, filterable history of admin actions
+//! - **Email Outbox** - Emails queued for delivery and failed deliveries
+//! - **Email Providers** - Circuit breaker state and delivery counts of each email provider
 //!
 //! ## Action Endpoints
 //! - **Trigger Final Matching** - Execute the final matching algorithm
 //! - **Update Match Previews** - Regenerate match preview suggestions
 //! - **Verify Users** - Change user verification status
+//! - **Suspend Users** - Suspend users and lift suspensions
+//! - **Event Phases** - Configure the event timeline that gates user actions
+//! - **Events** - Create events and switch the current event
+//! - **Allowed Domains** - Manage the email domains users can sign up with
 //!
 //! # Admin State
 //!
-//! All admin handlers use a shared `AdminState` containing database pool
-//! and tag system references for consistent access to application data.
+//! All admin handlers use a shared `AdminState` containing the database pool
+//! for consistent access to application data. Emails are written to the outbox
+//! and delivered by the main server; the admin state only shares its email
+//! service to report the health of the providers.
+//!
+//! # Event Scope
+//!
+//! Views and schedules of event-scoped data accept an optional `event_id` query
+//! parameter, resolved by [`EventScope`]. Without it they use the current event.
+//!
+//! # Audit
+//!
+//! Every state-changing action records an entry in `admin_audit_log` within the
+//! same transaction as the change. The acting admin is identified by [`AdminActor`].
 
 mod action;
 mod view;
@@ -31,20 +54,27 @@ use std::sync::Arc;
 
 use axum::{
     Router,
-    routing::{delete, get, post},
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+    routing::{delete, get, post, put},
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use tracing::warn;
 use uuid::Uuid;
 
 use self::{
     action::{
-        cancel_scheduled_match, create_scheduled_matches, delete_final_match, dry_run_final,
-        get_scheduled_matches, trigger_final_matching, update_match_previews, verify_user,
+        activate_event, cancel_event_phase, cancel_scheduled_match, create_allowed_domain,
+        create_event, create_event_phases, create_scheduled_matches, delete_allowed_domain,
+        delete_final_match, dry_run_final, get_allowed_domains, get_event_phases, get_events,
+        get_scheduled_matches, lift_suspension, suspend_user, trigger_final_matching,
+        update_allowed_domain, update_match_previews, verify_user,
     },
     view::{
-        get_final_matches, get_tags_with_stats, get_user_detail, get_user_stats,
+        get_audit_log, get_email_outbox, get_email_providers, get_feedback_summary,
+        get_final_matches, get_preview_exposure, get_rejection_summary, get_rejections,
+        get_tags_with_stats, get_user_detail, get_user_form_revisions, get_user_stats,
         get_users_overview, serve_user_card_photo,
     },
 };
@@ -52,22 +82,29 @@ use crate::{
     error::{AppError, AppResult},
     handlers::admin::view::serve_user_profile_photo,
     models::{TagNode, UserStatus},
+    services::{email::EmailService, event::EventService},
     utils::constant::IDF_MIN,
 };
 
 pub struct AdminState {
     pub db_pool: PgPool,
+    pub email_service: Arc<dyn EmailService>,
 }
 
 /// Create the admin router with admin-specific routes
-pub fn admin_router(db_pool: PgPool) -> Router {
-    let state = Arc::new(AdminState { db_pool });
+pub fn admin_router(db_pool: PgPool, email_service: Arc<dyn EmailService>) -> Router {
+    let state = Arc::new(AdminState {
+        db_pool,
+        email_service,
+    });
 
     Router::new()
         .route("/api/admin/trigger-match", post(trigger_final_matching))
         .route("/api/admin/dry-run-final", post(dry_run_final))
         .route("/api/admin/update-previews", post(update_match_previews))
         .route("/api/admin/verify-user", post(verify_user))
+        .route("/api/admin/suspend-user", post(suspend_user))
+        .route("/api/admin/lift-suspension", post(lift_suspension))
         .route(
             "/api/admin/scheduled-matches",
             post(create_scheduled_matches),
@@ -77,17 +114,104 @@ pub fn admin_router(db_pool: PgPool) -> Router {
             "/api/admin/scheduled-matches/{id}",
             delete(cancel_scheduled_match),
         )
+        .route("/api/admin/event-phases", post(create_event_phases))
+        .route("/api/admin/event-phases", get(get_event_phases))
+        .route("/api/admin/event-phases/{id}", delete(cancel_event_phase))
+        .route("/api/admin/events", post(create_event))
+        .route("/api/admin/events", get(get_events))
+        .route("/api/admin/events/{id}/activate", post(activate_event))
+        .route("/api/admin/domains", post(create_allowed_domain))
+        .route("/api/admin/domains", get(get_allowed_domains))
+        .route("/api/admin/domains/{id}", put(update_allowed_domain))
+        .route("/api/admin/domains/{id}", delete(delete_allowed_domain))
         .route("/api/admin/users", get(get_users_overview))
         .route("/api/admin/card/{filename}", get(serve_user_card_photo))
         .route("/api/admin/photo/{filename}", get(serve_user_profile_photo))
         .route("/api/admin/user/{user_id}", get(get_user_detail))
+        .route(
+            "/api/admin/user/{user_id}/form-revisions",
+            get(get_user_form_revisions),
+        )
         .route("/api/admin/tags", get(get_tags_with_stats))
         .route("/api/admin/matches", get(get_final_matches))
+        .route("/api/admin/feedback", get(get_feedback_summary))
+        .route("/api/admin/rejections", get(get_rejections))
+        .route("/api/admin/rejections/summary", get(get_rejection_summary))
         .route("/api/admin/final-matches/{id}", delete(delete_final_match))
         .route("/api/admin/stats", get(get_user_stats))
+        .route("/api/admin/stats/exposure", get(get_preview_exposure))
+        .route("/api/admin/audit", get(get_audit_log))
+        .route("/api/admin/emails", get(get_email_outbox))
+        .route("/api/admin/emails/providers", get(get_email_providers))
         .with_state(state)
 }
 
+/// Header set by Cloudflare Access with the email of the authenticated admin
+const ADMIN_IDENTITY_HEADER: &str = "cf-access-authenticated-user-email";
+
+/// Identity of the admin performing a request, recorded in the audit log.
+///
+/// The admin server is protected by Cloudflare Access, which forwards the
+/// authenticated identity in a request header. Requests without the header
+/// are rejected with `401 Unauthorized`, as no one could be held accountable
+/// for them.
+#[derive(Debug, Clone)]
+pub struct AdminActor(pub String);
+
+impl<S: Send + Sync> FromRequestParts<S> for AdminActor {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let actor = parts
+            .headers
+            .get(ADMIN_IDENTITY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| {
+                warn!("Admin request without an authenticated identity");
+                AppError::Unauthorized("Missing admin identity")
+            })?;
+
+        Ok(AdminActor(actor.to_string()))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct EventScopeQuery {
+    event_id: Option<Uuid>,
+}
+
+/// Event whose data an admin request reads or schedules.
+///
+/// Taken from the optional `event_id` query parameter and defaults to the
+/// current event. Unknown events are rejected with `404 Not Found`.
+#[derive(Debug, Clone, Copy)]
+pub struct EventScope(pub Uuid);
+
+impl FromRequestParts<Arc<AdminState>> for EventScope {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AdminState>,
+    ) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<EventScopeQuery>::from_request_parts(parts, state)
+            .await
+            .map_err(|_| AppError::BadRequest("Invalid event_id"))?;
+
+        match query.event_id {
+            Some(event_id) => {
+                EventService::get_event(&state.db_pool, event_id).await?;
+                Ok(EventScope(event_id))
+            }
+            None => Ok(EventScope(
+                EventService::current_event_id(&state.db_pool).await?,
+            )),
+        }
+    }
+}
+
 /// Get user ID by email
 async fn get_user_id_by_email(db_pool: &PgPool, email: &str) -> AppResult<Uuid> {
     match sqlx::query_scalar!("SELECT id FROM users WHERE email = $1", email)
@@ -14,6 +14,11 @@
 //!
 //! List endpoints support pagination with configurable page size (1-100 items)
 //! and include pagination metadata in responses.
+//!
+//! # Event Scope
+//!
+//! Forms, matches, feedback, rejections, tag statistics and exposure are read from the event given by
+//! the optional `event_id` query parameter, defaulting to the current event.
 
 use std::{path::Path, sync::Arc};
 
@@ -30,12 +35,25 @@ use tower_http::services::ServeFile;
 use tracing::{debug, error, instrument, warn};
 use uuid::Uuid;
 
-use super::{AdminState, convert_tags_to_stats};
+use super::{AdminState, EventScope, convert_tags_to_stats};
 use crate::{
     error::{AppError, AppResult},
-    models::{Form, Gender, UserStatus},
-    services::matching::MatchingService,
-    utils::static_object::{TAG_SYSTEM, TAG_TREE, UPLOAD_DIR},
+    models::{
+        AdminAction, EmailStatus, Form, FormAnswers, FormRevision, Gender, MatchFeedback,
+        UserStatus,
+    },
+    services::{
+        audit::{AuditFilter, AuditService},
+        event::EventService,
+        feedback::FeedbackService,
+        matching::MatchingService,
+        outbox::EmailOutboxService,
+        rejection::RejectionService,
+    },
+    utils::{
+        constant::DEFAULT_SCORE_BUCKET_WIDTH,
+        static_object::{CARD_PHOTO_RETENTION_DAYS, FORM_SCHEMA, UPLOAD_DIR},
+    },
 };
 
 /// Pagination query parameters
@@ -82,13 +100,13 @@ pub struct PaginationInfo {
 
 /// Gets a paginated overview of all users in the system.
 ///
-/// GET /api/admin/users ?page=1&limit=20&status=verification_pending&gender=male
+/// GET /api/admin/users ?page=1&limit=20&status=verification_pending&gender=male&event_id=
 ///
 /// This endpoint returns a paginated list of users with basic information
 /// (ID, email, status). Results are ordered by creation date (newest first).
 /// Supports pagination with configurable page size (1-100 items per page).
 /// Optionally filters users by status and/or gender. When gender filter is applied,
-/// only users who have submitted forms are included in the results.
+/// only users who have submitted forms in the event are included in the results.
 ///
 /// # Query Parameters
 ///
@@ -96,6 +114,7 @@ pub struct PaginationInfo {
 /// - `limit`: Items per page (default: 20, max: 100)
 /// - `status`: Optional status filter (e.g. "verification_pending")
 /// - `gender`: Optional gender filter ("male" or "female")
+/// - `event_id`: Event of the forms used by the gender filter (default: current event)
 ///
 /// # Returns
 ///
@@ -104,6 +123,7 @@ pub struct PaginationInfo {
 #[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
 pub async fn get_users_overview(
     State(state): State<Arc<AdminState>>,
+    EventScope(event_id): EventScope,
     Query(pagination): Query<PaginationQuery>,
 ) -> AppResult<impl IntoResponse> {
     // Validate and sanitize pagination parameters
@@ -116,7 +136,8 @@ pub async fn get_users_overview(
         (Some(status), Some(gender)) => {
             // Filter by both status and gender (requires JOIN with forms)
             sqlx::query_scalar!(
-                "SELECT COUNT(*) FROM users u JOIN forms f ON u.id = f.user_id WHERE u.status = $1 AND f.gender = $2",
+                "SELECT COUNT(*) FROM users u JOIN forms f ON u.id = f.user_id WHERE f.event_id = $1 AND u.status = $2 AND f.gender = $3",
+                event_id,
                 *status as UserStatus,
                 *gender as Gender
             )
@@ -137,7 +158,8 @@ pub async fn get_users_overview(
         (None, Some(gender)) => {
             // Filter by gender only (requires JOIN with forms)
             sqlx::query_scalar!(
-                "SELECT COUNT(*) FROM users u JOIN forms f ON u.id = f.user_id WHERE f.gender = $1",
+                "SELECT COUNT(*) FROM users u JOIN forms f ON u.id = f.user_id WHERE f.event_id = $1 AND f.gender = $2",
+                event_id,
                 *gender as Gender
             )
             .fetch_one(&state.db_pool)
@@ -163,10 +185,11 @@ pub async fn get_users_overview(
                 SELECT u.id, u.email, u.status as "status: UserStatus", u.wechat_id
                 FROM users u
                 JOIN forms f ON u.id = f.user_id
-                WHERE u.status = $1 AND f.gender = $2
+                WHERE f.event_id = $1 AND u.status = $2 AND f.gender = $3
                 ORDER BY u.created_at DESC
-                LIMIT $3 OFFSET $4
+                LIMIT $4 OFFSET $5
                 "#,
+                event_id,
                 *status as UserStatus,
                 *gender as Gender,
                 limit as i64,
@@ -201,10 +224,11 @@ pub async fn get_users_overview(
                 SELECT u.id, u.email, u.status as "status: UserStatus", u.wechat_id
                 FROM users u
                 JOIN forms f ON u.id = f.user_id
-                WHERE f.gender = $1
+                WHERE f.event_id = $1 AND f.gender = $2
                 ORDER BY u.created_at DESC
-                LIMIT $2 OFFSET $3
+                LIMIT $3 OFFSET $4
                 "#,
+                event_id,
                 *gender as Gender,
                 limit as i64,
                 offset as i64
@@ -336,6 +360,17 @@ pub struct UserDetailResponse {
     pub wechat_id: Option<String>,
     pub grade: Option<String>,
     pub card_photo_uri: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub card_verified_at: Option<OffsetDateTime>,
+    /// When the card photo will be deleted by the retention job, if it is still stored
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub card_purge_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub card_purged_at: Option<OffsetDateTime>,
+    pub suspension_reason: Option<String>,
+    /// When the suspension is lifted automatically; `None` if indefinite or not suspended
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub suspended_until: Option<OffsetDateTime>,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
     // Form info (if exists)
@@ -346,22 +381,17 @@ pub struct UserDetailResponse {
 #[derive(Debug, Serialize)]
 pub struct UserFormInfo {
     pub gender: Gender,
-    pub familiar_tags: Vec<String>,
-    pub aspirational_tags: Vec<String>,
-    pub recent_topics: String,
-    pub self_traits: Vec<String>,
-    pub ideal_traits: Vec<String>,
-    pub physical_boundary: i16,
-    pub self_intro: String,
+    #[serde(flatten)]
+    pub answers: FormAnswers,
     pub profile_photo_uri: Option<String>,
 }
 
 /// Gets detailed information for a specific user.
 ///
-/// GET /api/admin/user/{user_id}
+/// GET /api/admin/user/{user_id} ?event_id=
 ///
 /// This endpoint returns comprehensive user information including basic profile data,
-/// form responses (if submitted), and links to uploaded files. Used by admins for
+/// form responses in the event (if submitted), and links to uploaded files. Used by admins for
 /// detailed user review and verification processes.
 ///
 /// # Returns
@@ -372,17 +402,24 @@ pub struct UserFormInfo {
 #[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
 pub async fn get_user_detail(
     State(state): State<Arc<AdminState>>,
+    EventScope(event_id): EventScope,
     AxumPath(user_id): AxumPath<Uuid>,
 ) -> AppResult<impl IntoResponse> {
     // Get user basic info
     let user = sqlx::query!(
         r#"
         SELECT id, email, status as "status: UserStatus", wechat_id,
-               grade, card_photo_filename, created_at, updated_at
+               grade, card_photo_filename, card_verified_at, card_purged_at,
+               CASE WHEN card_photo_filename IS NOT NULL
+                    THEN card_reviewed_at + make_interval(days => $2)
+               END as card_purge_at,
+               suspension_reason, suspended_until,
+               created_at, updated_at
         FROM users
         WHERE id = $1
         "#,
-        user_id
+        user_id,
+        *CARD_PHOTO_RETENTION_DAYS
     )
     .fetch_optional(&state.db_pool)
     .await?
@@ -392,12 +429,12 @@ pub async fn get_user_detail(
     let form_result = sqlx::query_as!(
         Form,
         r#"
-        SELECT user_id, gender as "gender: Gender", familiar_tags, aspirational_tags,
-               recent_topics, self_traits, ideal_traits, physical_boundary,
-               self_intro, profile_photo_filename
+        SELECT user_id, gender as "gender: Gender",
+               answers as "answers: sqlx::types::Json<FormAnswers>", profile_photo_filename
         FROM forms
-        WHERE user_id = $1
+        WHERE event_id = $1 AND user_id = $2
         "#,
+        event_id,
         user_id
     )
     .fetch_optional(&state.db_pool)
@@ -406,13 +443,7 @@ pub async fn get_user_detail(
     let form_info = match form_result {
         Ok(Some(form)) => Some(UserFormInfo {
             gender: form.gender,
-            familiar_tags: form.familiar_tags,
-            aspirational_tags: form.aspirational_tags,
-            recent_topics: form.recent_topics,
-            self_traits: form.self_traits,
-            ideal_traits: form.ideal_traits,
-            physical_boundary: form.physical_boundary,
-            self_intro: form.self_intro,
+            answers: form.answers.0,
             profile_photo_uri: form
                 .profile_photo_filename
                 .map(|filename| format!("/api/admin/photo/{}", filename)),
@@ -433,6 +464,11 @@ pub async fn get_user_detail(
         card_photo_uri: user
             .card_photo_filename
             .map(|filename| format!("/api/admin/card/{}", filename)),
+        card_verified_at: user.card_verified_at,
+        card_purge_at: user.card_purge_at,
+        card_purged_at: user.card_purged_at,
+        suspension_reason: user.suspension_reason,
+        suspended_until: user.suspended_until,
         created_at: user.created_at,
         updated_at: user.updated_at,
         form: form_info,
@@ -441,12 +477,40 @@ pub async fn get_user_detail(
     Ok(Json(response))
 }
 
+/// Gets the revision history of a user's form.
+///
+/// GET /api/admin/user/{user_id}/form-revisions ?event_id=
+///
+/// Every form submission or edit is recorded as a numbered revision of the
+/// user's form in the event. Used by admins to review what a user changed after
+/// completing their form.
+///
+/// # Returns
+///
+/// - `200 OK` with `Vec<FormRevision>` - Revisions, oldest first (empty if no form)
+/// - `404 Not Found` - User not found
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
+pub async fn get_user_form_revisions(
+    State(state): State<Arc<AdminState>>,
+    EventScope(event_id): EventScope,
+    AxumPath(user_id): AxumPath<Uuid>,
+) -> AppResult<impl IntoResponse> {
+    // Ensure the user exists
+    UserStatus::query(&state.db_pool, &user_id).await?;
+
+    let revisions = FormRevision::list(&state.db_pool, &user_id, Some(event_id)).await?;
+    debug!(%user_id, count = revisions.len(), "Fetched form revisions");
+
+    Ok(Json(revisions))
+}
+
 /// Gets the tag system structure with usage statistics.
 ///
-/// GET /api/admin/tags
+/// GET /api/admin/tags ?event_id=
 ///
-/// This endpoint returns the complete tag hierarchy with user count and IDF
-/// (Inverse Document Frequency) scores for each tag. Used by admins to understand
+/// This endpoint returns the complete tag hierarchy of the event with user count
+/// and IDF (Inverse Document Frequency) scores for each tag. Used by admins to understand
 /// tag usage patterns and matching algorithm behavior.
 ///
 /// # Returns
@@ -456,16 +520,20 @@ pub async fn get_user_detail(
 #[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
 pub async fn get_tags_with_stats(
     State(state): State<Arc<AdminState>>,
+    EventScope(event_id): EventScope,
 ) -> AppResult<impl IntoResponse> {
+    let catalogs = EventService::catalogs(&state.db_pool, event_id).await?;
+
     // Get all forms to calculate tag statistics
     let forms = sqlx::query_as!(
         Form,
         r#"
-        SELECT user_id, gender as "gender: Gender", familiar_tags, aspirational_tags,
-               recent_topics, self_traits, ideal_traits, physical_boundary,
-               self_intro, profile_photo_filename
+        SELECT user_id, gender as "gender: Gender",
+               answers as "answers: sqlx::types::Json<FormAnswers>", profile_photo_filename
         FROM forms
-        "#
+        WHERE event_id = $1
+        "#,
+        event_id
     )
     .fetch_all(&state.db_pool)
     .await?;
@@ -474,10 +542,12 @@ pub async fn get_tags_with_stats(
 
     // Calculate tag frequencies using the same logic as matching algorithm
     // This ensures IDF scores shown match actual matching scores
-    let tag_frequencies = MatchingService::calculate_tag_frequencies(&forms, &TAG_SYSTEM);
+    let tag_frequencies =
+        MatchingService::calculate_tag_frequencies(&forms, &FORM_SCHEMA, &catalogs.tags);
 
     // Convert tag nodes to stats format
-    let tags_with_stats = convert_tags_to_stats(&TAG_TREE, &tag_frequencies, total_user_count);
+    let tags_with_stats =
+        convert_tags_to_stats(&catalogs.tag_tree, &tag_frequencies, total_user_count);
 
     Ok(Json(tags_with_stats))
 }
@@ -491,15 +561,28 @@ pub struct FinalMatchOverview {
     pub user_b_id: Uuid,
     pub user_b_email: String,
     pub score: f64,
+    /// Tags both users chose, `None` for matches created before it was recorded
+    pub tag_overlap: Option<i32>,
+    pub algorithm_version: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub auto_accept_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub reminded_at: Option<OffsetDateTime>,
+    /// When both users confirmed the match
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub confirmed_at: Option<OffsetDateTime>,
+    /// Feedback the users gave on the match
+    pub feedback: Vec<MatchFeedback>,
 }
 
 /// Gets a paginated overview of all final matches.
 ///
-/// GET /api/admin/matches ?page=1&limit=20
+/// GET /api/admin/matches ?page=1&limit=20&event_id=
 ///
 /// This endpoint returns a paginated list of final matches created by the matching
-/// algorithm, including match scores and participant email addresses. Results are
-/// ordered by match score (highest first). Used by admins to review match quality.
+/// algorithm in the event, including match scores, participant email addresses and the
+/// feedback of the participants. Results are ordered by match score (highest first). Used
+/// by admins to review match quality.
 ///
 /// # Returns
 ///
@@ -508,6 +591,7 @@ pub struct FinalMatchOverview {
 #[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
 pub async fn get_final_matches(
     State(state): State<Arc<AdminState>>,
+    EventScope(event_id): EventScope,
     Query(pagination): Query<PaginationQuery>,
 ) -> AppResult<impl IntoResponse> {
     // Validate and sanitize pagination parameters
@@ -516,10 +600,13 @@ pub async fn get_final_matches(
     let offset = (page - 1) * limit;
 
     // Get total count
-    let total = sqlx::query_scalar!("SELECT COUNT(*) FROM final_matches")
-        .fetch_one(&state.db_pool)
-        .await?
-        .unwrap_or(0) as u32;
+    let total = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM final_matches WHERE event_id = $1",
+        event_id
+    )
+    .fetch_one(&state.db_pool)
+    .await?
+    .unwrap_or(0) as u32;
 
     // Get final matches with user emails
     let matches = sqlx::query!(
@@ -529,20 +616,30 @@ pub async fn get_final_matches(
             fm.user_a_id,
             fm.user_b_id,
             fm.score,
+            fm.tag_overlap,
+            fm.algorithm_version,
+            fm.auto_accept_at,
+            fm.reminded_at,
+            fm.confirmed_at,
             ua.email as user_a_email,
             ub.email as user_b_email
         FROM final_matches fm
         JOIN users ua ON fm.user_a_id = ua.id
         JOIN users ub ON fm.user_b_id = ub.id
+        WHERE fm.event_id = $1
         ORDER BY fm.score DESC
-        LIMIT $1 OFFSET $2
+        LIMIT $2 OFFSET $3
         "#,
+        event_id,
         limit as i64,
         offset as i64
     )
     .fetch_all(&state.db_pool)
     .await?;
 
+    let final_match_ids: Vec<Uuid> = matches.iter().map(|row| row.id).collect();
+    let mut feedback = FeedbackService::list_for_matches(&state.db_pool, &final_match_ids).await?;
+
     let match_overviews: Vec<FinalMatchOverview> = matches
         .into_iter()
         .map(|row| FinalMatchOverview {
@@ -552,6 +649,14 @@ pub async fn get_final_matches(
             user_b_id: row.user_b_id,
             user_b_email: row.user_b_email,
             score: row.score,
+            tag_overlap: row.tag_overlap,
+            algorithm_version: row.algorithm_version,
+            auto_accept_at: row.auto_accept_at,
+            reminded_at: row.reminded_at,
+            confirmed_at: row.confirmed_at,
+            feedback: feedback
+                .extract_if(.., |feedback| feedback.final_match_id == row.id)
+                .collect(),
         })
         .collect();
 
@@ -580,18 +685,21 @@ pub struct UserStatsResponse {
 
 /// Gets overall user and gender statistics.
 ///
-/// GET /api/admin/stats
+/// GET /api/admin/stats ?event_id=
 ///
 /// This endpoint returns aggregate statistics about users, including total counts,
-/// gender distribution among users with completed forms, and unmatched user counts
-/// by gender. Used by admins for system monitoring and matching insights.
+/// gender distribution among users with completed forms in the event, and unmatched
+/// user counts by gender. Used by admins for system monitoring and matching insights.
 ///
 /// # Returns
 ///
 /// - `200 OK` with `UserStatsResponse` - User statistics retrieved successfully
 /// - `500 Internal Server Error` - Database error
 #[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
-pub async fn get_user_stats(State(state): State<Arc<AdminState>>) -> AppResult<impl IntoResponse> {
+pub async fn get_user_stats(
+    State(state): State<Arc<AdminState>>,
+    EventScope(event_id): EventScope,
+) -> AppResult<impl IntoResponse> {
     // Get total user count
     let total_users = sqlx::query_scalar!("SELECT COUNT(*) FROM users")
         .fetch_one(&state.db_pool)
@@ -606,9 +714,10 @@ pub async fn get_user_stats(State(state): State<Arc<AdminState>>) -> AppResult<i
             COUNT(*) as count
         FROM forms f
         JOIN users u ON f.user_id = u.id
-        WHERE u.status IN ('form_completed', 'matched', 'confirmed')
+        WHERE f.event_id = $1 AND u.status IN ('form_completed', 'matched', 'confirmed')
         GROUP BY f.gender
-        "#
+        "#,
+        event_id
     )
     .fetch_all(&state.db_pool)
     .await?;
@@ -631,9 +740,10 @@ pub async fn get_user_stats(State(state): State<Arc<AdminState>>) -> AppResult<i
             COUNT(*) as count
         FROM forms f
         JOIN users u ON f.user_id = u.id
-        WHERE u.status = 'form_completed'
+        WHERE f.event_id = $1 AND u.status = 'form_completed'
         GROUP BY f.gender
-        "#
+        "#,
+        event_id
     )
     .fetch_all(&state.db_pool)
     .await?;
@@ -658,3 +768,327 @@ pub async fn get_user_stats(State(state): State<Arc<AdminState>>) -> AppResult<i
 
     Ok(Json(response))
 }
+
+/// Feedback and rejection summary query parameters
+#[derive(Debug, Deserialize)]
+pub struct SummaryQuery {
+    /// Width of the score buckets
+    #[serde(default = "default_bucket_width")]
+    pub bucket_width: f64,
+}
+
+fn default_bucket_width() -> f64 {
+    DEFAULT_SCORE_BUCKET_WIDTH
+}
+
+/// Gets the feedback on the confirmed final matches of an event.
+///
+/// GET /api/admin/feedback ?bucket_width=5&event_id=
+///
+/// Ratings and meeting rates are aggregated overall and broken down by score bucket,
+/// number of shared tags and algorithm version, so admins can check whether higher
+/// scores produce better pairs and compare matching configurations.
+///
+/// # Returns
+///
+/// - `200 OK` with `FeedbackSummary` - Feedback aggregated successfully
+/// - `400 Bad Request` - Bucket width is not a positive number
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
+pub async fn get_feedback_summary(
+    State(state): State<Arc<AdminState>>,
+    EventScope(event_id): EventScope,
+    Query(query): Query<SummaryQuery>,
+) -> AppResult<impl IntoResponse> {
+    let summary = FeedbackService::summary(&state.db_pool, event_id, query.bucket_width).await?;
+    debug!(
+        matches = summary.overall.matches,
+        responses = summary.overall.responses,
+        "Aggregated match feedback"
+    );
+
+    Ok(Json(summary))
+}
+
+/// Gets a paginated list of the rejected final matches of an event.
+///
+/// GET /api/admin/rejections ?page=1&limit=20&event_id=
+///
+/// Each rejection comes with the reason and comment its rejecter gave and what the
+/// match was based on. Results are ordered by rejection time (latest first).
+///
+/// # Returns
+///
+/// - `200 OK` with `PaginatedResponse<MatchRejection>` - Rejections retrieved successfully
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
+pub async fn get_rejections(
+    State(state): State<Arc<AdminState>>,
+    EventScope(event_id): EventScope,
+    Query(pagination): Query<PaginationQuery>,
+) -> AppResult<impl IntoResponse> {
+    let limit = pagination.limit.clamp(1, 100);
+    let page = pagination.page.max(1);
+    let offset = (page - 1) * limit;
+
+    let (total, rejections) =
+        RejectionService::list(&state.db_pool, event_id, limit.into(), offset.into()).await?;
+    let total = total as u32;
+
+    Ok(Json(PaginatedResponse {
+        data: rejections,
+        pagination: PaginationInfo {
+            page,
+            limit,
+            total,
+            total_pages: total.div_ceil(limit),
+        },
+    }))
+}
+
+/// Gets the rejections of the final matches of an event.
+///
+/// GET /api/admin/rejections/summary ?bucket_width=5&event_id=
+///
+/// Rejections are counted by reason, with the average score of the rejected matches,
+/// and compared to all final matches created by score bucket, number of shared tags
+/// and algorithm version. A high rejection rate in one group points to a scoring
+/// problem, the reasons to its kind.
+///
+/// # Returns
+///
+/// - `200 OK` with `RejectionSummary` - Rejections aggregated successfully
+/// - `400 Bad Request` - Bucket width is not a positive number
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
+pub async fn get_rejection_summary(
+    State(state): State<Arc<AdminState>>,
+    EventScope(event_id): EventScope,
+    Query(query): Query<SummaryQuery>,
+) -> AppResult<impl IntoResponse> {
+    let summary = RejectionService::summary(&state.db_pool, event_id, query.bucket_width).await?;
+    debug!(
+        matches = summary.stats.overall.matches,
+        rejections = summary.stats.overall.rejections,
+        "Aggregated match rejections"
+    );
+
+    Ok(Json(summary))
+}
+
+/// Preview exposure of a user in the matching pool
+#[derive(Debug, Serialize)]
+pub struct PreviewExposure {
+    pub user_id: Uuid,
+    pub email: String,
+    pub gender: Gender,
+    /// Number of users whose current previews show this user
+    pub exposure: i64,
+}
+
+/// Gets how often each user in the matching pool appears in other users' previews.
+///
+/// GET /api/admin/stats/exposure ?page=1&limit=20&event_id=
+///
+/// Results are ordered by exposure (highest first), so admins can spot profiles
+/// shown to almost everyone and profiles that are never shown.
+///
+/// # Returns
+///
+/// - `200 OK` with `PaginatedResponse<PreviewExposure>` - Exposure retrieved successfully
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
+pub async fn get_preview_exposure(
+    State(state): State<Arc<AdminState>>,
+    EventScope(event_id): EventScope,
+    Query(pagination): Query<PaginationQuery>,
+) -> AppResult<impl IntoResponse> {
+    let limit = pagination.limit.clamp(1, 100);
+    let page = pagination.page.max(1);
+    let offset = (page - 1) * limit;
+
+    let total = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM users u JOIN forms f ON u.id = f.user_id WHERE f.event_id = $1 AND u.status = 'form_completed'",
+        event_id
+    )
+    .fetch_one(&state.db_pool)
+    .await?
+    .unwrap_or(0) as u32;
+
+    let exposures = sqlx::query_as!(
+        PreviewExposure,
+        r#"
+        SELECT
+            u.id as user_id,
+            u.email,
+            f.gender as "gender: Gender",
+            (SELECT COUNT(*) FROM match_previews mp
+             WHERE mp.event_id = f.event_id AND u.id = ANY(mp.candidate_ids))
+                as "exposure!"
+        FROM users u
+        JOIN forms f ON u.id = f.user_id
+        WHERE f.event_id = $1 AND u.status = 'form_completed'
+        ORDER BY "exposure!" DESC, u.email
+        LIMIT $2 OFFSET $3
+        "#,
+        event_id,
+        limit as i64,
+        offset as i64
+    )
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    let total_pages = total.div_ceil(limit);
+
+    Ok(Json(PaginatedResponse {
+        data: exposures,
+        pagination: PaginationInfo {
+            page,
+            limit,
+            total,
+            total_pages,
+        },
+    }))
+}
+
+/// Audit log query parameters
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    pub actor: Option<String>,
+    pub action: Option<AdminAction>,
+    pub target_id: Option<Uuid>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub since: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub until: Option<OffsetDateTime>,
+}
+
+/// Gets a paginated, filterable view of the admin audit log.
+///
+/// GET /api/admin/audit ?page=1&limit=20&actor=&action=verify_user&target_id=&since=&until=
+///
+/// This endpoint returns audit entries for state-changing admin actions, ordered
+/// by time (newest first). Use `target_id` to answer questions like "who verified
+/// this user?".
+///
+/// # Query Parameters
+///
+/// - `page`: Page number (default: 1)
+/// - `limit`: Items per page (default: 20, max: 100)
+/// - `actor`: Optional exact actor filter (admin email or `scheduler`)
+/// - `action`: Optional action filter (e.g. "verify_user")
+/// - `target_id`: Optional id of a user, final match or scheduled match
+/// - `since`, `until`: Optional RFC 3339 time range (inclusive start, exclusive end)
+///
+/// # Returns
+///
+/// - `200 OK` with `PaginatedResponse<AuditLogEntry>` - Entries retrieved successfully
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
+pub async fn get_audit_log(
+    State(state): State<Arc<AdminState>>,
+    Query(query): Query<AuditQuery>,
+) -> AppResult<impl IntoResponse> {
+    let limit = query.limit.clamp(1, 100);
+    let page = query.page.max(1);
+    let offset = (page - 1) * limit;
+
+    let filter = AuditFilter {
+        actor: query.actor,
+        action: query.action,
+        target_id: query.target_id,
+        since: query.since,
+        until: query.until,
+    };
+
+    let total = AuditService::count(&state.db_pool, &filter).await? as u32;
+    let entries = AuditService::list(&state.db_pool, &filter, limit as i64, offset as i64).await?;
+
+    let total_pages = total.div_ceil(limit);
+
+    Ok(Json(PaginatedResponse {
+        data: entries,
+        pagination: PaginationInfo {
+            page,
+            limit,
+            total,
+            total_pages,
+        },
+    }))
+}
+
+/// Email outbox query parameters
+#[derive(Debug, Deserialize)]
+pub struct EmailOutboxQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    pub status: Option<EmailStatus>,
+}
+
+/// Gets a paginated view of the emails in the outbox, newest first.
+///
+/// GET /api/admin/emails ?page=1&limit=20&status=failed
+///
+/// Lists emails that are queued for their first attempt or a retry (`pending`),
+/// delivered emails along with the provider that accepted them (`sent`) and dead
+/// letters that are no longer retried (`failed`), until they are purged. Template
+/// variables are not returned, since they may contain login codes.
+///
+/// # Query Parameters
+///
+/// - `page`: Page number (default: 1)
+/// - `limit`: Items per page (default: 20, max: 100)
+/// - `status`: Optional status filter (`pending`, `sent` or `failed`)
+///
+/// # Returns
+///
+/// - `200 OK` with `PaginatedResponse<OutboxEmail>` - Emails retrieved successfully
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
+pub async fn get_email_outbox(
+    State(state): State<Arc<AdminState>>,
+    Query(query): Query<EmailOutboxQuery>,
+) -> AppResult<impl IntoResponse> {
+    let limit = query.limit.clamp(1, 100);
+    let page = query.page.max(1);
+    let offset = (page - 1) * limit;
+
+    let total = EmailOutboxService::count(&state.db_pool, query.status).await? as u32;
+    let emails =
+        EmailOutboxService::list(&state.db_pool, query.status, limit as i64, offset as i64).await?;
+
+    let total_pages = total.div_ceil(limit);
+
+    Ok(Json(PaginatedResponse {
+        data: emails,
+        pagination: PaginationInfo {
+            page,
+            limit,
+            total,
+            total_pages,
+        },
+    }))
+}
+
+/// Get the health of the email providers
+///
+/// Lists the providers in the order they are tried, with the state of their
+/// circuit breaker and how many emails each delivered, failed and skipped since
+/// the server started.
+///
+/// # Returns
+///
+/// - `200 OK` with `Vec<ProviderHealth>` - Health of the providers, empty if the
+///   email service does not track it
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
+pub async fn get_email_providers(
+    State(state): State<Arc<AdminState>>,
+) -> AppResult<impl IntoResponse> {
+    Ok(Json(state.email_service.health()))
+}
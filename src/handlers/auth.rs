@@ -14,8 +14,8 @@ use std::{borrow::Cow, sync::Arc, time::Instant};
 
 use axum::{
     extract::{Json, State},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{HeaderMap, StatusCode, header::ACCEPT_LANGUAGE},
+    response::{IntoResponse, Response},
 };
 use rand::Rng;
 use serde::{Deserialize, Serialize};
@@ -24,21 +24,22 @@ use validator::Validate;
 
 use crate::{
     error::{AppError, AppResult},
-    models::AppState,
-    utils::{constant::*, static_object::EMAIL_REGEX},
+    models::{AppState, NewEmail, PhaseGate},
+    services::{event::EventService, moderation::SuspensionNotice, outbox::EmailOutboxService},
+    utils::{constant::*, static_object::EMAIL_TEMPLATES},
 };
 
 /// Request payload for sending verification code to email
 #[derive(Debug, Deserialize, Validate)]
 pub struct SendCodeRequest {
-    #[validate(regex(path = "*EMAIL_REGEX"))]
+    #[validate(email)]
     pub email: String,
 }
 
 /// Request payload for verifying email with code
 #[derive(Debug, Deserialize, Validate)]
 pub struct VerifyRequest {
-    #[validate(regex(path = "*EMAIL_REGEX"))]
+    #[validate(email)]
     pub email: String,
     #[validate(length(equal = 6))]
     pub code: String,
@@ -59,6 +60,14 @@ pub struct RefreshTokenRequest {
     pub refresh_token: String,
 }
 
+/// Locale to email the client in, negotiated from its `Accept-Language` header
+fn accepted_locale(headers: &HeaderMap) -> Option<&'static str> {
+    headers
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| EMAIL_TEMPLATES.negotiate(value))
+}
+
 /// Sends a verification code to the specified email address.
 ///
 /// POST /api/auth/send-code email=
@@ -67,12 +76,29 @@ pub struct RefreshTokenRequest {
 ///
 /// Users can only request a verification code once per [`EMAIL_RATE_LIMIT`] duration.
 ///
+/// # Event Phases
+///
+/// Codes for emails without an account are only sent while registration is open,
+/// and only if their domain is an allowed domain that the current event accepts.
+/// Existing users can always request a login code. While registration is closed,
+/// emails without an account get the same response as existing users, but no code,
+/// so the endpoint does not reveal who has an account.
+///
+/// # Delivery
+///
+/// The email is written to the outbox and delivered in the background, so the
+/// response does not wait for the email provider. It is retried on failure, but
+/// not after the code expired.
+///
+/// The email is in the language of the `Accept-Language` header if there is a
+/// template for it, and otherwise in the locale stored on the account.
+///
 /// # Returns
 ///
-/// - `202 Accepted` - Verification code sent successfully
-/// - `400 Bad Request` - Invalid email format
+/// - `202 Accepted` - Verification code queued for delivery
+/// - `400 Bad Request` - Invalid email format or domain
 /// - `429 Too Many Requests` - Rate limit exceeded
-/// - `500 Internal Server Error` - Email service failure
+/// - `500 Internal Server Error` - Database error
 #[instrument(
     skip_all,
     fields(
@@ -82,6 +108,7 @@ pub struct RefreshTokenRequest {
 )]
 pub async fn send_verification_code(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Json(payload): Json<SendCodeRequest>,
 ) -> AppResult<impl IntoResponse> {
     debug!("Processing verification code request");
@@ -104,10 +131,43 @@ pub async fn send_verification_code(
         return Err(AppError::TooManyRequests);
     }
 
+    // New accounts can only be created from allowed domains while registration is open
+    let account_locale =
+        sqlx::query_scalar!("SELECT locale FROM users WHERE email = $1", payload.email)
+            .fetch_optional(&state.db_pool)
+            .await?;
+    if account_locale.is_none() {
+        let event = EventService::current_event(&state.db_pool).await?;
+        if !EventService::is_open(&state.db_pool, event.id, PhaseGate::SignUp).await? {
+            info!("Registration is closed, not sending a code to an email without an account");
+            state
+                .rate_limit_cache
+                .insert(payload.email.clone(), Instant::now());
+            return Ok((StatusCode::ACCEPTED, "Verification code sent"));
+        }
+
+        let allowed_domains = state.allowed_domains.get().await?;
+        match allowed_domains.rule_for(&payload.email) {
+            Some(rule) if event.allows_domain(&rule.domain) => {}
+            _ => {
+                warn!("Email domain is not allowed");
+                return Err(AppError::BadRequest("Invalid input"));
+            }
+        }
+    }
+
     // Generate verification code
     let code = format!("{:06}", rand::rng().random_range(0..1_000_000));
     debug!("Generated verification code");
 
+    let expires_at = time::OffsetDateTime::now_utc() + VERIFICATION_CODE_EXPIRY;
+    let locale = accepted_locale(&headers)
+        .or(account_locale.as_deref())
+        .unwrap_or(DEFAULT_EMAIL_LOCALE);
+    let email = NewEmail::verification_code(&payload.email, &code, expires_at).in_locale(locale);
+    let mut conn = state.db_pool.acquire().await?;
+    EmailOutboxService::enqueue(&mut conn, &email).await?;
+
     // Cache code and timestamp
     state
         .verification_code_cache
@@ -117,17 +177,7 @@ pub async fn send_verification_code(
         .insert(payload.email.clone(), Instant::now());
     debug!("Cached verification code and rate limit");
 
-    // Send email
-    state
-        .email_service
-        .send_email(&payload.email, "Your login code to Project Contigo", &code)
-        .await
-        .map_err(|e| {
-            error!(error = %e, "Failed to send verification code");
-            AppError::Internal
-        })?;
-
-    info!("Successfully sent verification code");
+    info!("Queued verification code");
     Ok((StatusCode::ACCEPTED, "Verification code sent"))
 }
 
@@ -144,11 +194,19 @@ pub async fn send_verification_code(
 /// - Codes expire after [`VERIFICATION_CODE_EXPIRY`] duration
 /// - Codes are removed from cache after successful verification
 /// - User accounts are created with 'unverified' status
+/// - Suspended users are not issued tokens
+///
+/// # Locale
+///
+/// The language of the `Accept-Language` header is stored on the account, so
+/// later emails are in that language. Without a header, or without templates in
+/// any of its languages, the stored locale is kept.
 ///
 /// # Returns
 ///
 /// - `200 OK` with `AuthResponse` - Code correct, returns JWT tokens
 /// - `400 Bad Request` - Invalid input or expired/invalid code
+/// - `403 Forbidden` with the suspension notice - User is suspended
 /// - `500 Internal Server Error` - Database or token generation failure
 #[instrument(
     skip_all,
@@ -159,8 +217,9 @@ pub async fn send_verification_code(
 )]
 pub async fn verify_code(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Json(payload): Json<VerifyRequest>,
-) -> AppResult<impl IntoResponse> {
+) -> AppResult<Response> {
     debug!("Processing code verification request");
 
     // Validate format
@@ -195,17 +254,24 @@ pub async fn verify_code(
     trace!("Creating/updating user in database");
     let user_id = sqlx::query_scalar!(
         r#"
-        INSERT INTO users (email, status) VALUES ($1, 'unverified')
-        ON CONFLICT (email) DO UPDATE SET email = EXCLUDED.email
+        INSERT INTO users (email, status, locale) VALUES ($1, 'unverified', COALESCE($2, $3))
+        ON CONFLICT (email) DO UPDATE SET locale = COALESCE($2, users.locale)
         RETURNING id
         "#,
-        payload.email
+        payload.email,
+        accepted_locale(&headers),
+        DEFAULT_EMAIL_LOCALE
     )
     .fetch_one(&state.db_pool)
     .await?;
 
     debug!(user_id = %user_id, "User created/updated successfully");
 
+    if let Some(notice) = SuspensionNotice::check(&state.db_pool, user_id).await? {
+        warn!(%user_id, "Suspended user denied login");
+        return Ok((StatusCode::FORBIDDEN, Json(notice)).into_response());
+    }
+
     // Generate JWT token pair
     trace!("Generating JWT token pair");
     let token_pair = state
@@ -234,7 +300,8 @@ pub async fn verify_code(
             token_type: "Bearer".into(),
             expires_in: token_pair.expires_in,
         }),
-    ))
+    )
+        .into_response())
 }
 
 /// Refreshes JWT token pair using a valid refresh token.
@@ -250,6 +317,7 @@ pub async fn verify_code(
 /// - Refresh tokens are validated against the database
 /// - Old refresh tokens are invalidated when new ones are issued
 /// - Invalid refresh tokens result in unauthorized response
+/// - Suspending a user revokes their refresh tokens
 ///
 /// # Returns
 ///
@@ -0,0 +1,30 @@
+//! # Event Phase Handler
+//!
+//! This module implements the public endpoint reporting the current phase of the
+//! event timeline, so clients can show which actions are open.
+
+use std::sync::Arc;
+
+use axum::{Json, extract::State, response::IntoResponse};
+use tracing::instrument;
+
+use crate::{error::AppResult, models::AppState, services::event::EventService};
+
+/// Gets the current and next phase of the current event.
+///
+/// GET /api/event/phase
+///
+/// `phase` is null if no phase has started yet, and `next_phase` is null if no
+/// later phase is configured.
+///
+/// # Returns
+///
+/// - `200 OK` with `EventPhaseResponse` - Current and next phase
+/// - `500 Internal Server Error` - Database error
+#[instrument(skip_all, fields(request_id = %uuid::Uuid::new_v4()))]
+pub async fn get_event_phase(State(state): State<Arc<AppState>>) -> AppResult<impl IntoResponse> {
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
+    let status = EventService::phase_status(&state.db_pool, event_id).await?;
+
+    Ok(Json(status))
+}
@@ -0,0 +1,174 @@
+//! # Event Stream Handler
+//!
+//! This module implements a Server-Sent Events stream pushing changes to the
+//! authenticated user as they happen, so clients do not have to poll their
+//! profile and the next match time.
+//!
+//! Each event carries its id. Clients reconnecting with the `Last-Event-ID`
+//! header (sent automatically by `EventSource`) or the `last_event_id` query
+//! parameter first receive the events they missed. Events are sent in the order
+//! their transactions finished rather than by id, see [`StreamPosition`]. Idle streams receive a
+//! heartbeat comment regularly so proxies keep them open.
+
+use std::{collections::VecDeque, convert::Infallible, sync::Arc};
+
+use axum::{
+    extract::{Extension, Query, State},
+    http::HeaderMap,
+    response::{
+        IntoResponse,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use futures_util::stream;
+use serde::Deserialize;
+use sqlx::PgPool;
+use tokio::sync::broadcast::{Receiver, error::RecvError};
+use tracing::{error, info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppResult},
+    middleware::AuthUser,
+    models::{AppState, StreamPosition, UserEvent},
+    services::user_event::{HubMessage, UserEventService},
+    utils::constant::EVENT_STREAM_HEARTBEAT_INTERVAL,
+};
+
+/// Header sent by reconnecting `EventSource` clients
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+#[derive(Debug, Deserialize)]
+pub struct EventStreamQuery {
+    /// Alternative to the `Last-Event-ID` header for clients that cannot set it
+    pub last_event_id: Option<i64>,
+}
+
+/// State of one event stream
+struct EventStream {
+    db_pool: PgPool,
+    user_id: Uuid,
+    receiver: Receiver<HubMessage>,
+    /// Position of the last sent event, events up to it are not sent again
+    position: StreamPosition,
+    /// Events loaded from the database, sent before live events
+    backlog: VecDeque<UserEvent>,
+}
+
+impl EventStream {
+    /// Load the events recorded after the last sent one
+    async fn catch_up(&mut self) -> Result<(), sqlx::Error> {
+        let events =
+            UserEventService::events_after(&self.db_pool, self.user_id, self.position).await?;
+        self.backlog.extend(events);
+        Ok(())
+    }
+
+    /// Wait for the next event of the user, ending the stream on failure
+    async fn next(mut self) -> Option<(Result<Event, Infallible>, Self)> {
+        loop {
+            if let Some(event) = self.backlog.pop_front() {
+                return Some(self.send(&event));
+            }
+
+            match self.receiver.recv().await {
+                Ok(HubMessage::Event(event)) => {
+                    if event.is_for(self.user_id) && event.position() > self.position {
+                        return Some(self.send(&event));
+                    }
+                }
+                Ok(HubMessage::Resync) | Err(RecvError::Lagged(_)) => {
+                    if let Err(e) = self.catch_up().await {
+                        // The client reconnects and resumes from the last event it received
+                        error!(user_id = %self.user_id, error = %e, "Failed to catch up event stream");
+                        return None;
+                    }
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    fn send(mut self, event: &UserEvent) -> (Result<Event, Infallible>, Self) {
+        self.position = self.position.max(event.position());
+        let sse_event = Event::default()
+            .id(event.id.to_string())
+            .event(event.kind.as_str())
+            .data(event.data.to_string());
+        (Ok(sse_event), self)
+    }
+}
+
+/// Streams changes relevant to the authenticated user as Server-Sent Events.
+///
+/// GET /api/events/stream ?last_event_id=
+///
+/// Events are named after their kind, with a JSON object as data:
+/// - `status_changed` - `{"status": "matched"}`
+/// - `previews_updated` - `{}`, the match candidates of the user changed
+/// - `partner_accepted` - `{}`, the partner accepted the final match
+/// - `next_match_time_changed` - `{"next": "2025-10-20T12:00:00Z"}`, same as
+///   `GET /api/final-match/time`
+///
+/// # Returns
+///
+/// - `200 OK` with a `text/event-stream` - The stream, open until the client disconnects
+/// - `400 Bad Request` - Invalid last event id
+/// - `401 Unauthorized` - Missing or invalid authentication token
+/// - `500 Internal Server Error` - Database error
+#[instrument(
+    skip_all,
+    fields(
+        user_id = %user.user_id,
+        request_id = %uuid::Uuid::new_v4()
+    )
+)]
+pub async fn stream_events(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    headers: HeaderMap,
+    Query(query): Query<EventStreamQuery>,
+) -> AppResult<impl IntoResponse> {
+    let last_event_id = match headers.get(LAST_EVENT_ID_HEADER) {
+        Some(header) => Some(
+            header
+                .to_str()
+                .ok()
+                .and_then(|id| id.trim().parse::<i64>().ok())
+                .ok_or_else(|| {
+                    warn!("Invalid Last-Event-ID header");
+                    AppError::BadRequest("Invalid Last-Event-ID header")
+                })?,
+        ),
+        None => query.last_event_id,
+    };
+
+    // Subscribe before loading missed events, so that no event falls in between
+    let mut stream = EventStream {
+        db_pool: state.db_pool.clone(),
+        user_id: user.user_id,
+        receiver: state.user_events.subscribe(),
+        position: StreamPosition::default(),
+        backlog: VecDeque::new(),
+    };
+    match last_event_id {
+        Some(last_event_id) => {
+            stream.position = UserEventService::position_of(&state.db_pool, last_event_id).await?;
+            stream.catch_up().await?;
+        }
+        None => stream.position = UserEventService::horizon(&state.db_pool).await?,
+    }
+
+    info!(
+        ?last_event_id,
+        missed = stream.backlog.len(),
+        "Event stream opened"
+    );
+    let events = stream::unfold(stream, EventStream::next);
+
+    Ok(Sse::new(events).keep_alive(
+        KeepAlive::new()
+            .interval(EVENT_STREAM_HEARTBEAT_INTERVAL)
+            .text("heartbeat"),
+    ))
+}
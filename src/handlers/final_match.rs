@@ -1,7 +1,10 @@
 //! # Final Match Result Handler
 //!
 //! This module implements endpoints for users to accept or reject their final match results.
-//! When a user rejects a match, both users are reverted to 'form_completed' status.
+//! When a user rejects a match, both users are reverted to 'form_completed' status. The
+//! reason given for a rejection is kept for admins and never shown to the partner.
+//! The partner is notified by email either way, unless they opted out.
+//! Once both users confirmed the match, each can give feedback on it.
 
 use std::sync::Arc;
 
@@ -16,8 +19,15 @@ use crate::{
     error::{AppError, AppResult},
     handlers::get_profile,
     middleware::AuthUser,
-    models::{AppState, NextMatchTimeResponse, UserStatus},
-    services::scheduler::SchedulerService,
+    models::{
+        AppState, MatchEvent, MatchFeedbackRequest, NewUserEvent, NextMatchTimeResponse,
+        Notification, PhaseGate, RejectFinalMatchRequest, RejectionReasonsResponse, UserStatus,
+    },
+    services::{
+        event::EventService, feedback::FeedbackService, notification::NotificationService,
+        rejection::RejectionService, scheduler::SchedulerService, user_event::UserEventService,
+    },
+    utils::static_object::REJECTION_REASONS,
 };
 
 /// Accepts a final match result for the authenticated user.
@@ -26,13 +36,15 @@ use crate::{
 ///
 /// Updates the user's status from 'matched' to 'confirmed', indicating
 /// acceptance of their final match partner. If both users accept their
-/// match, the pairing process is complete. Returns the updated profile.
+/// match, the pairing process is complete. The partner is notified. Returns
+/// the updated profile.
 ///
 /// # Returns
 ///
 /// - `200 OK` with `ProfileResponse`- Final match accepted successfully
 /// - `400 Bad Request` - User is not in 'matched' status
 /// - `401 Unauthorized` - Missing or invalid authentication token
+/// - `403 Forbidden` - Current event phase doesn't allow responding to final matches
 /// - `500 Internal Server Error` - Database error
 #[instrument(
     skip_all,
@@ -45,6 +57,9 @@ pub async fn accept_final_match(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<AuthUser>,
 ) -> AppResult<impl IntoResponse> {
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
+    EventService::ensure_open(&state.db_pool, event_id, PhaseGate::FinalMatchResponse).await?;
+
     // Check user status - must be 'matched'
     let user_status = UserStatus::query(&state.db_pool, &user.user_id).await?;
 
@@ -53,18 +68,47 @@ pub async fn accept_final_match(
         return Err(AppError::BadRequest("User is not in matched status"));
     }
 
+    let mut tx = state.db_pool.begin().await?;
+
+    // Lock the match, so that the second acceptance sees the first one
+    let final_match = sqlx::query!(
+        r#"
+        SELECT id, CASE WHEN user_a_id = $2 THEN user_b_id ELSE user_a_id END as "partner_id!"
+        FROM final_matches
+        WHERE event_id = $1 AND (user_a_id = $2 OR user_b_id = $2)
+        FOR UPDATE
+        "#,
+        event_id,
+        user.user_id
+    )
+    .fetch_optional(tx.as_mut())
+    .await?;
+
     // Update user status to 'confirmed'
     let result = sqlx::query!(
         "UPDATE users SET status = 'confirmed' WHERE id = $1 AND status = 'matched'",
         user.user_id
     )
-    .execute(&state.db_pool)
+    .execute(tx.as_mut())
     .await?;
 
     if result.rows_affected() == 0 {
         error!("Data race detected while accepting final match");
         return Err(AppError::Internal);
     }
+
+    if let Some(final_match) = final_match {
+        let partner_id = final_match.partner_id;
+        FeedbackService::open_window(tx.as_mut(), final_match.id).await?;
+        NotificationService::notify(
+            tx.as_mut(),
+            &[Notification::new(partner_id, MatchEvent::PartnerAccepted)],
+        )
+        .await?;
+        UserEventService::publish(tx.as_mut(), &NewUserEvent::partner_accepted(partner_id)).await?;
+    }
+
+    tx.commit().await?;
     info!("User accepted final match");
 
     get_profile(State(state), Extension(user)).await
@@ -75,15 +119,27 @@ pub async fn accept_final_match(
 /// POST /api/final-match/reject
 ///
 /// Reverts both the user and their partner to 'form_completed' status
-/// and removes the final match record from the database. This allows both
-/// users to potentially be matched again in future matching rounds. Returns
-/// the updated profile.
+/// and moves the final match record to the rejected matches. This allows both
+/// users to potentially be matched again in future matching rounds. The
+/// partner is notified, without the reason. Returns the updated profile.
+///
+/// # Request Body
+///
+/// Optional, `reason` must be one of `GET /api/final-match/rejection-reasons`:
+///
+/// ```json
+/// {
+///   "reason": "no_shared_interests",
+///   "comment": "optional"
+/// }
+/// ```
 ///
 /// # Returns
 ///
 /// - `200 OK` - Final match rejected successfully, both users reverted
-/// - `400 Bad Request` - User is not in 'matched' status or no match found
+/// - `400 Bad Request` - User is not in 'matched' status, no match found, or invalid reason or comment
 /// - `401 Unauthorized` - Missing or invalid authentication token
+/// - `403 Forbidden` - Current event phase doesn't allow responding to final matches
 /// - `500 Internal Server Error` - Database error
 #[instrument(
     skip_all,
@@ -95,7 +151,16 @@ pub async fn accept_final_match(
 pub async fn reject_final_match(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<AuthUser>,
+    request: Option<Json<RejectFinalMatchRequest>>,
 ) -> AppResult<impl IntoResponse> {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    request
+        .validate(&REJECTION_REASONS)
+        .map_err(AppError::BadRequest)?;
+
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
+    EventService::ensure_open(&state.db_pool, event_id, PhaseGate::FinalMatchResponse).await?;
+
     // Check user status - must be 'matched'
     let user_status = UserStatus::query(&state.db_pool, &user.user_id).await?;
 
@@ -109,8 +174,9 @@ pub async fn reject_final_match(
         r#"
         SELECT id, user_a_id, user_b_id
         FROM final_matches
-        WHERE user_a_id = $1 OR user_b_id = $1
+        WHERE event_id = $1 AND (user_a_id = $2 OR user_b_id = $2)
         "#,
+        event_id,
         user.user_id
     )
     .fetch_optional(&state.db_pool)
@@ -130,7 +196,7 @@ pub async fn reject_final_match(
     // Begin transaction to atomically revert both users to 'form_completed' status and delete match
     let mut tx = state.db_pool.begin().await?;
 
-    // Update both users' statuses and delete the final match record
+    // Update both users' statuses and move the final match record to the rejected ones
     let user_result = sqlx::query!(
         "UPDATE users SET status = 'form_completed' WHERE id = $1 AND status = 'matched'",
         user.user_id
@@ -145,9 +211,13 @@ pub async fn reject_final_match(
     .execute(tx.as_mut())
     .await?;
 
-    sqlx::query!("DELETE FROM final_matches WHERE id = $1", final_match.id)
-        .execute(tx.as_mut())
-        .await?;
+    RejectionService::record(tx.as_mut(), final_match.id, user.user_id, &request).await?;
+
+    NotificationService::notify(
+        tx.as_mut(),
+        &[Notification::new(partner_id, MatchEvent::MatchRejected)],
+    )
+    .await?;
 
     if user_result.rows_affected() > 0 && partner_result.rows_affected() > 0 {
         tx.commit().await?;
@@ -165,8 +235,8 @@ pub async fn reject_final_match(
 ///
 /// GET /api/next-match-time
 ///
-/// This endpoint returns the earliest scheduled final match time that is
-/// still pending and in the future. Users can use this to know when the
+/// This endpoint returns the earliest scheduled final match time of the current event
+/// that is still pending and in the future. Users can use this to know when the
 /// next automatic matching will occur. Returns null if no matches are scheduled.
 ///
 /// # Returns
@@ -178,7 +248,67 @@ pub async fn reject_final_match(
 pub async fn get_next_match_time(
     State(state): State<Arc<AppState>>,
 ) -> AppResult<impl IntoResponse> {
-    let next = SchedulerService::get_next_scheduled_time(&state.db_pool).await?;
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
+    let next = SchedulerService::get_next_scheduled_time(&state.db_pool, event_id).await?;
 
     Ok(Json(NextMatchTimeResponse { next }))
 }
+
+/// Gives feedback on the user's confirmed final match.
+///
+/// POST /api/final-match/feedback
+///
+/// Available for `FEEDBACK_WINDOW_DAYS` after both users confirmed the match. The
+/// feedback is not shown to the partner. Submitting again replaces the previous
+/// feedback.
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///   "rating": 4,
+///   "met": true,
+///   "comment": "optional"
+/// }
+/// ```
+///
+/// # Returns
+///
+/// - `200 OK` with `MatchFeedback` - Feedback recorded
+/// - `400 Bad Request` - Invalid rating or comment, or no confirmed final match
+/// - `401 Unauthorized` - Missing or invalid authentication token
+/// - `403 Forbidden` - The feedback window has closed
+/// - `500 Internal Server Error` - Database error
+#[instrument(
+    skip_all,
+    fields(
+        user_id = %user.user_id,
+        request_id = %uuid::Uuid::new_v4()
+    )
+)]
+pub async fn submit_match_feedback(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Json(request): Json<MatchFeedbackRequest>,
+) -> AppResult<impl IntoResponse> {
+    let feedback = FeedbackService::submit(&state.db_pool, user.user_id, &request).await?;
+
+    Ok(Json(feedback))
+}
+
+/// Gets the reasons users can give when rejecting their final match.
+///
+/// GET /api/final-match/rejection-reasons
+///
+/// The reasons are configured with `REJECTION_REASONS`. Clients display their own
+/// label for each.
+///
+/// # Returns
+///
+/// - `200 OK` with `RejectionReasonsResponse`
+#[instrument(skip_all)]
+pub async fn get_rejection_reasons() -> impl IntoResponse {
+    Json(RejectionReasonsResponse {
+        reasons: REJECTION_REASONS.clone(),
+    })
+}
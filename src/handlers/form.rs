@@ -1,7 +1,9 @@
 //! # Form Handler
 //!
 //! This module implements form endpoints that allow verified users to submit
-//! and retrieve their form data including tags, traits, and profile information.
+//! and retrieve their form data. The questions are defined by the form schema
+//! (see [`crate::models::FormSchema`]) and answers are stored as JSONB.
+//! Partially filled forms can be autosaved as drafts and restored later.
 
 use std::{path::Path, sync::Arc};
 
@@ -19,42 +21,66 @@ use uuid::Uuid;
 use crate::{
     error::{AppError, AppResult},
     middleware::AuthUser,
-    models::{AppState, Form, Gender, UserStatus},
-    services::matching::MatchingService,
+    models::{AppState, Form, FormAnswers, Gender, PhaseGate, UserStatus},
+    services::{event::EventService, matching::MatchingService},
     utils::{
         file,
-        static_object::{TAG_SYSTEM, UPLOAD_DIR},
+        static_object::{FORM_SCHEMA, UPLOAD_DIR},
     },
 };
 
+/// A form submission. Answers are given as top-level keys named after question ids.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FormRequest {
     pub wechat_id: String,
     pub gender: Gender,
-    pub familiar_tags: Vec<String>,
-    pub aspirational_tags: Vec<String>,
-    pub recent_topics: String,
-    pub self_traits: Vec<String>,
-    pub ideal_traits: Vec<String>,
-    pub physical_boundary: i16,
-    pub self_intro: String,
     pub profile_photo_filename: Option<String>,
+    #[serde(flatten)]
+    pub answers: FormAnswers,
+}
+
+/// A partially filled [`FormRequest`], saved while the user is still writing
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FormDraftRequest {
+    pub wechat_id: Option<String>,
+    pub gender: Option<Gender>,
+    pub profile_photo_filename: Option<String>,
+    #[serde(flatten)]
+    pub answers: FormAnswers,
+}
+
+/// A saved form draft
+#[derive(Debug, Serialize)]
+pub struct FormDraftResponse {
+    #[serde(flatten)]
+    pub draft: FormDraftRequest,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: time::OffsetDateTime,
 }
 
 /// Submits or updates the authenticated user's form data.
 ///
 /// POST /api/form FormRequest
 ///
-/// This endpoint validates the form data including tag limits and profile photo filename,
-/// then saves or updates the user's form in the database. Only users with 'verified'
-/// or 'form_completed' status can access this endpoint.
+/// This endpoint validates the answers against the form schema and the profile photo
+/// filename, then saves or updates the user's form in the database. Only users with 'verified',
+/// 'form_completed' or 'paused' status can access this endpoint, so edits are rejected
+/// while a final match is pending or confirmed.
+///
+/// Forms belong to the current event, whose catalogs the answers are validated against.
+/// The submission promotes the user's draft, if any: fields and answers missing from the
+/// request are taken from the draft before validation, and the draft is deleted.
+///
+/// Every submission is recorded in `form_revisions`. Editing an existing form drops the
+/// user's match previews and, if fields shown in previews changed, the vetoes other
+/// users cast against the previous version. Previews are then regenerated.
 ///
 /// # Returns
 ///
 /// - `200 OK` with `Form` - Form submitted/updated successfully
 /// - `400 Bad Request` - Invalid form data or validation errors
 /// - `401 Unauthorized` - Missing or invalid authentication token
-/// - `403 Forbidden` - User status doesn't allow form submission
+/// - `403 Forbidden` - User status or the current event phase doesn't allow form submission
 /// - `500 Internal Server Error` - Database error
 #[instrument(
     skip_all,
@@ -66,10 +92,13 @@ pub struct FormRequest {
 pub async fn submit_form(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<AuthUser>,
-    Json(payload): Json<FormRequest>,
+    Json(payload): Json<FormDraftRequest>,
 ) -> AppResult<impl IntoResponse> {
     debug!("Processing form submission request");
 
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
+    EventService::ensure_open(&state.db_pool, event_id, PhaseGate::SubmitForm).await?;
+
     // Check user status
     let user_status = UserStatus::query(&state.db_pool, &user.user_id).await?;
     if !user_status.can_fill_form() {
@@ -79,9 +108,29 @@ pub async fn submit_form(
         ));
     }
 
+    // Complete the submission with the saved draft
+    let draft = sqlx::query_scalar!(
+        r#"
+        SELECT data as "data: sqlx::types::Json<FormDraftRequest>"
+        FROM form_drafts
+        WHERE event_id = $1 AND user_id = $2
+        "#,
+        event_id,
+        user.user_id
+    )
+    .fetch_optional(&state.db_pool)
+    .await?
+    .map(|draft| draft.0)
+    .unwrap_or_default();
+    let payload = payload.promote(draft).map_err(|e| {
+        warn!(error = e, "Incomplete form submission");
+        AppError::BadRequest(e)
+    })?;
+
     // Validate each field of the form
+    let catalogs = EventService::catalogs(&state.db_pool, event_id).await?;
     payload
-        .validate_request(&TAG_SYSTEM)
+        .validate_request(&FORM_SCHEMA, &catalogs)
         .map_err(AppError::BadRequest)?;
 
     // Validate profile photo filename if provided
@@ -89,40 +138,77 @@ pub async fn submit_form(
         validate_profile_photo_filename(filename, &user.user_id).await?;
     }
 
+    let mut tx = state.db_pool.begin().await?;
+
+    // Re-check status under lock: final matching may have matched the user meanwhile
+    let locked_status = sqlx::query_scalar!(
+        r#"SELECT status as "status: UserStatus" FROM users WHERE id = $1 FOR UPDATE"#,
+        user.user_id
+    )
+    .fetch_one(tx.as_mut())
+    .await?;
+    if locked_status != user_status {
+        tx.rollback().await?;
+        warn!(%user_status, %locked_status, "User status changed during form submission");
+        return Err(AppError::Forbidden(
+            "User status doesn't allow form submission",
+        ));
+    }
+
+    let previous_form = sqlx::query_as!(
+        Form,
+        r#"
+        SELECT user_id, gender as "gender: Gender",
+               answers as "answers: sqlx::types::Json<FormAnswers>", profile_photo_filename
+        FROM forms
+        WHERE event_id = $1 AND user_id = $2
+        "#,
+        event_id,
+        user.user_id
+    )
+    .fetch_optional(tx.as_mut())
+    .await?;
+
     // Insert or update form data and return the result
     let form = sqlx::query_as!(
         Form,
         r#"
-        INSERT INTO forms (user_id, gender, familiar_tags, aspirational_tags, recent_topics,
-                          self_traits, ideal_traits, physical_boundary, self_intro, profile_photo_filename)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-        ON CONFLICT (user_id)
+        INSERT INTO forms (event_id, user_id, gender, answers, profile_photo_filename)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (event_id, user_id)
         DO UPDATE SET
             gender = EXCLUDED.gender,
-            familiar_tags = EXCLUDED.familiar_tags,
-            aspirational_tags = EXCLUDED.aspirational_tags,
-            recent_topics = EXCLUDED.recent_topics,
-            self_traits = EXCLUDED.self_traits,
-            ideal_traits = EXCLUDED.ideal_traits,
-            physical_boundary = EXCLUDED.physical_boundary,
-            self_intro = EXCLUDED.self_intro,
+            answers = EXCLUDED.answers,
             profile_photo_filename = EXCLUDED.profile_photo_filename
-        RETURNING user_id, gender as "gender: Gender", familiar_tags, aspirational_tags,
-                  recent_topics, self_traits, ideal_traits, physical_boundary,
-                  self_intro, profile_photo_filename
+        RETURNING user_id, gender as "gender: Gender",
+                  answers as "answers: sqlx::types::Json<FormAnswers>", profile_photo_filename
         "#,
+        event_id,
         user.user_id,
         payload.gender as Gender,
-        &payload.familiar_tags,
-        &payload.aspirational_tags,
-        payload.recent_topics,
-        &payload.self_traits,
-        &payload.ideal_traits,
-        payload.physical_boundary,
-        payload.self_intro,
+        sqlx::types::Json(&payload.answers) as _,
         payload.profile_photo_filename
     )
-    .fetch_one(&state.db_pool)
+    .fetch_one(tx.as_mut())
+    .await?;
+
+    // Record the new revision
+    let revision = sqlx::query_scalar!(
+        r#"
+        INSERT INTO form_revisions (event_id, user_id, revision, gender, answers,
+                                    profile_photo_filename)
+        SELECT $1, $2, COALESCE(MAX(revision), 0) + 1, $3, $4, $5
+        FROM form_revisions
+        WHERE event_id = $1 AND user_id = $2
+        RETURNING revision
+        "#,
+        event_id,
+        user.user_id,
+        form.gender as Gender,
+        &form.answers as _,
+        form.profile_photo_filename
+    )
+    .fetch_one(tx.as_mut())
     .await?;
 
     // Update wechat_id in users table
@@ -131,7 +217,7 @@ pub async fn submit_form(
         payload.wechat_id,
         user.user_id
     )
-    .execute(&state.db_pool)
+    .execute(tx.as_mut())
     .await?;
 
     // Update user status to form_completed if currently verified
@@ -140,14 +226,51 @@ pub async fn submit_form(
             "UPDATE users SET status = 'form_completed' WHERE id = $1",
             user.user_id
         )
-        .execute(&state.db_pool)
+        .execute(tx.as_mut())
+        .await?;
+    }
+
+    // The draft has been promoted to the form
+    sqlx::query!(
+        "DELETE FROM form_drafts WHERE event_id = $1 AND user_id = $2",
+        event_id,
+        user.user_id
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    // Invalidate previews and vetoes that were based on the previous version
+    if let Some(previous_form) = previous_form {
+        sqlx::query!(
+            "DELETE FROM match_previews WHERE event_id = $1 AND user_id = $2",
+            event_id,
+            user.user_id
+        )
+        .execute(tx.as_mut())
         .await?;
+
+        if form.preview_differs(&previous_form, &FORM_SCHEMA) {
+            let stale_vetoes = sqlx::query!(
+                "DELETE FROM vetoes WHERE event_id = $1 AND vetoed_id = $2",
+                event_id,
+                user.user_id
+            )
+            .execute(tx.as_mut())
+            .await?
+            .rows_affected();
+            debug!(
+                stale_vetoes,
+                "Removed vetoes cast against the previous form"
+            );
+        }
     }
 
+    tx.commit().await?;
+
     // Trigger a match preview
-    MatchingService::generate_match_previews(&state.db_pool, &TAG_SYSTEM).await?;
+    MatchingService::generate_match_previews(&state.db_pool, event_id).await?;
 
-    info!("Form submitted successfully");
+    info!(revision, "Form submitted successfully");
     Ok((StatusCode::OK, Json(form)))
 }
 
@@ -155,8 +278,7 @@ pub async fn submit_form(
 ///
 /// GET /api/form
 ///
-/// This endpoint returns the user's submitted form data. Only users with 'verified'
-/// or 'form_completed' status can access this endpoint.
+/// This endpoint returns the form the user submitted to the current event.
 ///
 /// # Returns
 ///
@@ -177,15 +299,16 @@ pub async fn get_form(
 ) -> AppResult<impl IntoResponse> {
     trace!("Processing get form request");
 
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
     let form = sqlx::query_as!(
         Form,
         r#"
-        SELECT user_id, gender as "gender: Gender", familiar_tags, aspirational_tags,
-               recent_topics, self_traits, ideal_traits, physical_boundary,
-               self_intro, profile_photo_filename
+        SELECT user_id, gender as "gender: Gender",
+               answers as "answers: sqlx::types::Json<FormAnswers>", profile_photo_filename
         FROM forms
-        WHERE user_id = $1
+        WHERE event_id = $1 AND user_id = $2
         "#,
+        event_id,
         user.user_id
     )
     .fetch_optional(&state.db_pool)
@@ -235,3 +358,131 @@ async fn validate_profile_photo_filename(filename: &str, user_id: &Uuid) -> Resu
 
     Ok(())
 }
+
+/// Saves a partially filled form as the authenticated user's draft.
+///
+/// PUT /api/form/draft FormDraftRequest
+///
+/// Drafts only get relaxed validation (types and size limits) and never change the user's
+/// status. Saving replaces the previous draft. The draft is deleted once the form
+/// is submitted via `POST /api/form`.
+///
+/// # Returns
+///
+/// - `200 OK` with `FormDraftResponse` - Draft saved successfully
+/// - `400 Bad Request` - Draft exceeds size limits
+/// - `401 Unauthorized` - Missing or invalid authentication token
+/// - `403 Forbidden` - User status doesn't allow form submission
+/// - `500 Internal Server Error` - Database error
+#[instrument(
+    skip_all,
+    fields(
+        user_id = %user.user_id,
+        request_id = %uuid::Uuid::new_v4()
+    )
+)]
+pub async fn save_form_draft(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<FormDraftRequest>,
+) -> AppResult<impl IntoResponse> {
+    trace!("Processing save form draft request");
+
+    let user_status = UserStatus::query(&state.db_pool, &user.user_id).await?;
+    if !user_status.can_fill_form() {
+        warn!(current_status = %user_status, "User status doesn't allow form submission");
+        return Err(AppError::Forbidden(
+            "User status doesn't allow form submission",
+        ));
+    }
+
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
+    let catalogs = EventService::catalogs(&state.db_pool, event_id).await?;
+    payload
+        .validate_draft(&FORM_SCHEMA, &catalogs)
+        .map_err(AppError::BadRequest)?;
+
+    let row = sqlx::query!(
+        r#"
+        INSERT INTO form_drafts (event_id, user_id, data)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (event_id, user_id)
+        DO UPDATE SET data = EXCLUDED.data
+        RETURNING data as "data: sqlx::types::Json<FormDraftRequest>", updated_at
+        "#,
+        event_id,
+        user.user_id,
+        sqlx::types::Json(&payload) as _
+    )
+    .fetch_one(&state.db_pool)
+    .await?;
+
+    debug!("Form draft saved");
+    Ok(Json(FormDraftResponse {
+        draft: row.data.0,
+        updated_at: row.updated_at,
+    }))
+}
+
+/// Retrieves the authenticated user's form draft.
+///
+/// GET /api/form/draft
+///
+/// # Returns
+///
+/// - `200 OK` with `FormDraftResponse` - Draft retrieved successfully
+/// - `401 Unauthorized` - Missing or invalid authentication token
+/// - `404 Not Found` - User has no saved draft
+/// - `500 Internal Server Error` - Database error
+#[instrument(
+    skip_all,
+    fields(
+        user_id = %user.user_id,
+        request_id = %uuid::Uuid::new_v4()
+    )
+)]
+pub async fn get_form_draft(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<impl IntoResponse> {
+    trace!("Processing get form draft request");
+
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
+    let row = sqlx::query!(
+        r#"
+        SELECT data as "data: sqlx::types::Json<FormDraftRequest>", updated_at
+        FROM form_drafts
+        WHERE event_id = $1 AND user_id = $2
+        "#,
+        event_id,
+        user.user_id
+    )
+    .fetch_optional(&state.db_pool)
+    .await?
+    .ok_or_else(|| {
+        debug!("User has no saved form draft");
+        AppError::NotFound("Form draft not found")
+    })?;
+
+    Ok(Json(FormDraftResponse {
+        draft: row.data.0,
+        updated_at: row.updated_at,
+    }))
+}
+
+/// Returns the form schema.
+///
+/// GET /api/form/schema
+///
+/// Lists the questions of the questionnaire with their types, limits and visibility,
+/// shared selection limits, and the scorers used for matching. Clients render the
+/// form from it.
+///
+/// # Returns
+///
+/// - `200 OK` with `FormSchema`
+#[instrument(skip_all)]
+pub async fn get_form_schema() -> impl IntoResponse {
+    trace!("Processing get form schema request");
+    Json(&*FORM_SCHEMA)
+}
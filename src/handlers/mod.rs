@@ -6,19 +6,29 @@
 //!
 //! ## Available Handlers
 //!
+//! - **Account** (`account`) - Account deletion and personal data export
 //! - **Authentication** (`auth`) - Email verification and JWT token management
+//! - **Event** (`event`) - Current phase of the event timeline
+//! - **Event Stream** (`event_stream`) - Server-Sent Events pushing live user events
 //! - **Health Check** (`health_check`) - Application health monitoring
 //! - **Profile** (`profile`) - User profile information retrieval
 //! - **Form** (`form`) - User form submission and retrieval
+//! - **Notification** (`notification`) - Match notification preferences
+//! - **Participation** (`participation`) - Pausing, withdrawing from and resuming matching
 //! - **Upload Card** (`upload_card`) - File upload functionality for student card verification
 //! - **Upload Profile Photo** (`upload_profile_photo`) - Profile photo upload for verified users
 //! - **Veto** (`veto`) - Match preview and veto functionality
 //! - **Admin** (`admin`) - Administrative endpoints for final matching
 
+mod account;
 mod admin;
 mod auth;
+mod event;
+mod event_stream;
 mod final_match;
 mod form;
+mod notification;
+mod participation;
 mod partner_image;
 mod profile;
 mod thumbnail;
@@ -26,11 +36,16 @@ mod upload_card;
 mod upload_profile_photo;
 mod veto;
 
+pub use account::*;
 pub use admin::admin_router;
 pub use auth::*;
 use axum::http::StatusCode;
+pub use event::*;
+pub use event_stream::*;
 pub use final_match::*;
 pub use form::*;
+pub use notification::*;
+pub use participation::*;
 pub use partner_image::*;
 pub use profile::*;
 pub use thumbnail::*;
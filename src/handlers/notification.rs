@@ -0,0 +1,74 @@
+//! # Notification Preference Handlers
+//!
+//! This module lets users choose which match notifications they receive by email.
+//! Every notification is enabled until the user opts out.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Extension, State},
+    response::IntoResponse,
+};
+use tracing::instrument;
+
+use crate::{
+    error::AppResult,
+    middleware::AuthUser,
+    models::{AppState, UpdateNotificationPreferencesRequest},
+    services::notification::NotificationService,
+};
+
+/// Gets the notification preferences of the authenticated user.
+///
+/// GET /api/notifications/preferences
+///
+/// # Returns
+///
+/// - `200 OK` with `NotificationPreferences` - Current preferences
+/// - `401 Unauthorized` - Missing or invalid authentication token
+/// - `500 Internal Server Error` - Database error
+#[instrument(
+    skip_all,
+    fields(
+        user_id = %user.user_id,
+        request_id = %uuid::Uuid::new_v4()
+    )
+)]
+pub async fn get_notification_preferences(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<impl IntoResponse> {
+    let preferences = NotificationService::get_preferences(&state.db_pool, user.user_id).await?;
+
+    Ok(Json(preferences))
+}
+
+/// Changes the notification preferences of the authenticated user.
+///
+/// PATCH /api/notifications/preferences
+///
+/// Omitted fields keep their current value.
+///
+/// # Returns
+///
+/// - `200 OK` with `NotificationPreferences` - Updated preferences
+/// - `401 Unauthorized` - Missing or invalid authentication token
+/// - `500 Internal Server Error` - Database error
+#[instrument(
+    skip_all,
+    fields(
+        user_id = %user.user_id,
+        request_id = %uuid::Uuid::new_v4()
+    )
+)]
+pub async fn update_notification_preferences(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Json(payload): Json<UpdateNotificationPreferencesRequest>,
+) -> AppResult<impl IntoResponse> {
+    let preferences =
+        NotificationService::update_preferences(&state.db_pool, user.user_id, &payload).await?;
+
+    Ok(Json(preferences))
+}
@@ -0,0 +1,215 @@
+//! # Participation Handlers
+//!
+//! This module lets users with a completed form control whether they take part
+//! in matching. Leaving the pool keeps the form, so users can come back later.
+//!
+//! - **Pause** - `form_completed` → `paused`; the form stays editable
+//! - **Withdraw** - `form_completed`/`paused` → `withdrawn`; the form stays locked
+//! - **Resume** - `paused`/`withdrawn` → `form_completed`
+//!
+//! Paused and withdrawn users are excluded from match previews and final matching,
+//! since both only consider users in `form_completed` status.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, State},
+    response::IntoResponse,
+};
+use sqlx::PgPool;
+use tracing::{error, info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppResult},
+    handlers::get_profile,
+    middleware::AuthUser,
+    models::{AppState, UserStatus},
+    services::{event::EventService, matching::MatchingService},
+};
+
+/// Pauses the user's participation in matching.
+///
+/// POST /api/participation/pause
+///
+/// Takes the user out of match previews and future final matching rounds while
+/// keeping the form editable. Returns the updated profile.
+///
+/// # Returns
+///
+/// - `200 OK` with `ProfileResponse` - Participation paused
+/// - `400 Bad Request` - User is not in 'form_completed' status
+/// - `401 Unauthorized` - Missing or invalid authentication token
+/// - `500 Internal Server Error` - Database error
+#[instrument(
+    skip_all,
+    fields(
+        user_id = %user.user_id,
+        request_id = %uuid::Uuid::new_v4()
+    )
+)]
+pub async fn pause_participation(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<impl IntoResponse> {
+    let user_status = UserStatus::query(&state.db_pool, &user.user_id).await?;
+
+    if user_status != UserStatus::FormCompleted {
+        warn!(
+            "User status is {:?}, expected 'form_completed'",
+            user_status
+        );
+        return Err(AppError::BadRequest("User is not in the matching pool"));
+    }
+
+    leave_pool(
+        &state.db_pool,
+        user.user_id,
+        user_status,
+        UserStatus::Paused,
+    )
+    .await?;
+    info!("User paused participation");
+
+    get_profile(State(state), Extension(user)).await
+}
+
+/// Withdraws the user from matching.
+///
+/// POST /api/participation/withdraw
+///
+/// Takes the user out of match previews and future final matching rounds. The
+/// form is kept but stays locked. Returns the updated profile.
+///
+/// # Returns
+///
+/// - `200 OK` with `ProfileResponse` - Participation withdrawn
+/// - `400 Bad Request` - User is not in 'form_completed' or 'paused' status
+/// - `401 Unauthorized` - Missing or invalid authentication token
+/// - `500 Internal Server Error` - Database error
+#[instrument(
+    skip_all,
+    fields(
+        user_id = %user.user_id,
+        request_id = %uuid::Uuid::new_v4()
+    )
+)]
+pub async fn withdraw_participation(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<impl IntoResponse> {
+    let user_status = UserStatus::query(&state.db_pool, &user.user_id).await?;
+
+    if !user_status.can_leave_pool() {
+        warn!(
+            "User status is {:?}, expected 'form_completed' or 'paused'",
+            user_status
+        );
+        return Err(AppError::BadRequest(
+            "User cannot withdraw in current status",
+        ));
+    }
+
+    leave_pool(
+        &state.db_pool,
+        user.user_id,
+        user_status,
+        UserStatus::Withdrawn,
+    )
+    .await?;
+    info!("User withdrew from matching");
+
+    get_profile(State(state), Extension(user)).await
+}
+
+/// Resumes the user's participation in matching.
+///
+/// POST /api/participation/resume
+///
+/// Returns a paused or withdrawn user to the matching pool and regenerates
+/// match previews. Returns the updated profile.
+///
+/// # Returns
+///
+/// - `200 OK` with `ProfileResponse` - Participation resumed
+/// - `400 Bad Request` - User is not in 'paused' or 'withdrawn' status
+/// - `401 Unauthorized` - Missing or invalid authentication token
+/// - `500 Internal Server Error` - Database error
+#[instrument(
+    skip_all,
+    fields(
+        user_id = %user.user_id,
+        request_id = %uuid::Uuid::new_v4()
+    )
+)]
+pub async fn resume_participation(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<impl IntoResponse> {
+    let user_status = UserStatus::query(&state.db_pool, &user.user_id).await?;
+
+    if !matches!(user_status, UserStatus::Paused | UserStatus::Withdrawn) {
+        warn!(
+            "User status is {:?}, expected 'paused' or 'withdrawn'",
+            user_status
+        );
+        return Err(AppError::BadRequest("User is not paused or withdrawn"));
+    }
+
+    let result = sqlx::query!(
+        "UPDATE users SET status = 'form_completed' WHERE id = $1 AND status = $2",
+        user.user_id,
+        user_status as UserStatus
+    )
+    .execute(&state.db_pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        error!("Data race detected while resuming participation");
+        return Err(AppError::Internal);
+    }
+    info!("User resumed participation");
+
+    // Trigger a match preview
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
+    MatchingService::generate_match_previews(&state.db_pool, event_id).await?;
+
+    get_profile(State(state), Extension(user)).await
+}
+
+/// Moves the user out of the matching pool and drops their match previews
+async fn leave_pool(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    from: UserStatus,
+    to: UserStatus,
+) -> AppResult<()> {
+    let event_id = EventService::current_event_id(db_pool).await?;
+    let mut tx = db_pool.begin().await?;
+
+    let result = sqlx::query!(
+        "UPDATE users SET status = $1 WHERE id = $2 AND status = $3",
+        to as UserStatus,
+        user_id,
+        from as UserStatus
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    if result.rows_affected() == 0 {
+        tx.rollback().await?;
+        error!("Data race detected while leaving the matching pool");
+        return Err(AppError::Internal);
+    }
+
+    sqlx::query!(
+        "DELETE FROM match_previews WHERE event_id = $1 AND user_id = $2",
+        event_id,
+        user_id
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
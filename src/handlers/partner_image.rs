@@ -18,6 +18,7 @@ use uuid::Uuid;
 use crate::{
     error::{AppError, AppResult},
     models::AppState,
+    services::event::EventService,
     utils::static_object::UPLOAD_DIR,
 };
 
@@ -40,13 +41,15 @@ pub async fn serve_partner_image(
     Extension(requested_id): Extension<Uuid>,
     req: Request<Body>,
 ) -> AppResult<impl IntoResponse> {
-    // Query the partner's profile photo filename
+    // The partner was validated against the current event, so serve the photo of its form
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
     let photo_filename = sqlx::query!(
         r#"
         SELECT profile_photo_filename
         FROM forms
-        WHERE user_id = $1
+        WHERE event_id = $1 AND user_id = $2
         "#,
+        event_id,
         requested_id
     )
     .fetch_optional(&state.db_pool)
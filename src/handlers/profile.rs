@@ -17,7 +17,9 @@ use tracing::{debug, error, instrument, trace};
 use crate::{
     error::{AppError, AppResult},
     middleware::AuthUser,
-    models::{AppState, FinalPartnerProfile, UserStatus},
+    models::{AppState, FinalPartnerProfile, FormAnswers, UserStatus, Visibility},
+    services::event::EventService,
+    utils::static_object::FORM_SCHEMA,
 };
 
 /// Response containing user profile information
@@ -94,19 +96,24 @@ pub async fn get_profile(
     ))
 }
 
-/// Fetch partner profile information for matched/confirmed users
+/// Fetch partner profile information for matched/confirmed users of the current event
+///
+/// The status is reset when an event is activated, so a matched or confirmed user
+/// always has their final match in the current event.
 async fn fetch_partner_profile(
     state: &AppState,
     self_id: &uuid::Uuid,
     self_status: UserStatus,
 ) -> AppResult<FinalPartnerProfile> {
     // Find the final match record to get partner ID
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
     let final_match = sqlx::query!(
         r#"
         SELECT user_a_id, user_b_id
         FROM final_matches
-        WHERE user_a_id = $1 OR user_b_id = $1
+        WHERE event_id = $1 AND (user_a_id = $2 OR user_b_id = $2)
         "#,
+        event_id,
         self_id
     )
     .fetch_optional(&state.db_pool)
@@ -128,16 +135,14 @@ async fn fetch_partner_profile(
             u.grade,
             u.status as "status: UserStatus",
             u.wechat_id,
-            f.familiar_tags,
-            f.aspirational_tags,
-            f.recent_topics,
-            f.self_intro,
+            f.answers as "answers?: sqlx::types::Json<FormAnswers>",
             f.profile_photo_filename
         FROM users u
-        LEFT JOIN forms f ON u.id = f.user_id
+        LEFT JOIN forms f ON u.id = f.user_id AND f.event_id = $2
         WHERE u.id = $1
         "#,
-        partner_id
+        partner_id,
+        event_id
     )
     .fetch_optional(&state.db_pool)
     .await?
@@ -163,14 +168,16 @@ async fn fetch_partner_profile(
             None
         };
 
-    Ok(FinalPartnerProfile {
+    let answers = partner_info
+        .answers
+        .map(|answers| FORM_SCHEMA.visible_answers(&answers, Visibility::Partner))
+        .unwrap_or_default();
+
+    Ok(FinalPartnerProfile::new(
         email_domain,
-        grade: partner_info.grade,
-        familiar_tags: partner_info.familiar_tags,
-        aspirational_tags: partner_info.aspirational_tags,
-        recent_topics: partner_info.recent_topics,
-        self_intro: partner_info.self_intro,
+        partner_info.grade,
+        answers,
         photo_url,
         wechat_id,
-    })
+    ))
 }
@@ -18,6 +18,7 @@ use crate::{
     error::{AppError, AppResult},
     middleware::AuthUser,
     models::{AppState, UserStatus},
+    services::event::EventService,
     utils::{file::ImageProcessor, static_object::UPLOAD_DIR},
 };
 
@@ -61,12 +62,14 @@ pub async fn serve_profile_thumbnail(
     }
 
     // Query the target user's profile photo filename
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
     let photo_filename = sqlx::query!(
         r#"
         SELECT profile_photo_filename
         FROM forms
-        WHERE user_id = $1
+        WHERE event_id = $1 AND user_id = $2
         "#,
+        event_id,
         user_id
     )
     .fetch_optional(&state.db_pool)
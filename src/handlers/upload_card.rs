@@ -23,9 +23,10 @@ use crate::{
     error::{AppError, AppResult},
     middleware::AuthUser,
     models::{AppState, UserStatus},
+    services::event::EventService,
     utils::{
         file::{FileManager, ImageUploadValidator},
-        static_object::{ALLOWED_GRADES, UPLOAD_DIR},
+        static_object::UPLOAD_DIR,
     },
 };
 
@@ -44,7 +45,8 @@ pub struct CardUploadResponse {
 ///
 /// This endpoint accepts multipart/form-data with a `card` image field and a
 /// `grade` text field, and stores it for admin verification. Only users with
-/// 'unverified' status can upload cards.
+/// 'unverified' status can upload cards, and the grade must be allowed for the
+/// user's email domain and in the current event.
 ///
 /// # Security & Validation
 ///
@@ -62,7 +64,7 @@ pub struct CardUploadResponse {
 ///
 /// - `200 OK` with `CardUploadResponse` - File uploaded successfully
 /// - `400 Bad Request` - Invalid file format or missing file
-/// - `403 Forbidden` - User status doesn't allow upload
+/// - `403 Forbidden` - User status doesn't allow upload or email domain no longer allowed
 /// - `413 Payload Too Large` - File exceeds 2MB limit (handled by Axum)
 /// - `500 Internal Server Error` - File system or database error
 #[instrument(
@@ -87,6 +89,17 @@ pub async fn upload_card(
         return Err(AppError::Forbidden("User status doesn't allow card upload"));
     }
 
+    let email = sqlx::query_scalar!("SELECT email FROM users WHERE id = $1", user.user_id)
+        .fetch_one(&state.db_pool)
+        .await?;
+    let allowed_domains = state.allowed_domains.get().await?;
+    let Some(domain) = allowed_domains.rule_for(&email) else {
+        warn!("Email domain is no longer allowed");
+        return Err(AppError::Forbidden("Email domain is no longer allowed"));
+    };
+    // The card verifies the user for the current event, so its grade rules apply
+    let event = EventService::current_event(&state.db_pool).await?;
+
     // Extract fields from multipart form
     let mut file_data = None;
     let mut grade = None;
@@ -119,7 +132,7 @@ pub async fn upload_card(
                 })?;
 
                 // Validate grade
-                if !ALLOWED_GRADES.contains(&grade_local.as_str()) {
+                if !domain.allows_grade(&grade_local) || !event.allows_grade(&grade_local) {
                     warn!(grade = %grade_local, "Invalid grade");
                     return Err(AppError::BadRequest("Invalid grade"));
                 }
@@ -182,7 +195,9 @@ pub async fn upload_card(
 
     // Update database with file path, grade, and status, returning user data
     let user_data = sqlx::query!(
-        r#"UPDATE users SET card_photo_filename = $1, grade = $2, status = $3 WHERE id = $4
+        r#"UPDATE users SET card_photo_filename = $1, grade = $2, status = $3,
+                  card_reviewed_at = NULL, card_purged_at = NULL
+           WHERE id = $4
            RETURNING email, status as "status: UserStatus", grade, card_photo_filename"#,
         filename,
         grade,
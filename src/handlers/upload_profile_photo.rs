@@ -1,12 +1,12 @@
 //! # Profile Photo Upload Handler
 //!
 //! This module implements the HTTP handler for profile photo uploads. Unlike card uploads,
-//! profile photos are only available to verified and form_completed users, and the filename
+//! profile photos are only available to users who can fill the form, and the filename
 //! is returned in the response rather than stored in the database.
 //!
 //! # Access Control
 //!
-//! Only users with 'verified' or 'form_completed' status can upload profile photos.
+//! Only users with 'verified', 'form_completed' or 'paused' status can upload profile photos.
 //!
 //! # File Storage
 //!
@@ -46,7 +46,7 @@ struct UploadProfilePhotoResponse {
 /// POST /api/upload/profile-photo MultipartForm
 ///
 /// This endpoint accepts multipart/form-data with an image file and stores it
-/// for the user's profile. Only users with 'verified' or 'form_completed' status
+/// for the user's profile. Only users with 'verified', 'form_completed' or 'paused' status
 /// can upload profile photos.
 ///
 /// # Security & Validation
@@ -82,7 +82,7 @@ pub async fn upload_profile_photo(
 ) -> AppResult<impl IntoResponse> {
     debug!("Processing profile photo upload request");
 
-    // Check user status - only verified and form_completed users can upload
+    // Check user status - only users who can fill the form can upload
     let user_status = UserStatus::query(&state.db_pool, &user.user_id).await?;
 
     if !user_status.can_fill_form() {
@@ -14,7 +14,13 @@
 //! - Vetoes are bidirectional blocks preventing matches between users
 //! - Users cannot veto themselves
 //! - Veto operations are idempotent (adding existing veto returns existing record)
+//! - Each user can hold at most `VETO_QUOTA` vetoes per round
+//! - Vetoes can be added from when the user's match previews are published until
+//!   `VETO_WINDOW_CLOSE_HOURS` before the next scheduled final match, and removed
+//!   until the window closes
 //! - All vetoes are cleared when final matching is triggered
+//!
+//! Previews and vetoes belong to the current event.
 
 use std::sync::Arc;
 
@@ -24,14 +30,19 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
 };
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use time::{Duration, OffsetDateTime};
 use tracing::{debug, error, info, instrument, trace, warn};
 use uuid::Uuid;
 
 use crate::{
     error::{AppError, AppResult},
     middleware::AuthUser,
-    models::{AppState, ProfilePreview, Veto, VetoRequest},
+    models::{
+        AppState, FormAnswers, PhaseGate, ProfilePreview, Veto, VetoQuota, VetoRequest, Visibility,
+    },
+    services::{event::EventService, matching::MatchingService, scheduler::SchedulerService},
+    utils::static_object::{FORM_SCHEMA, VETO_QUOTA, VETO_WINDOW_CLOSE_HOURS},
 };
 
 /// Gets match previews for the authenticated user.
@@ -40,7 +51,7 @@ use crate::{
 ///
 /// This endpoint returns pre-computed match suggestions for users who have completed
 /// their forms. Match previews are generated by the matching algorithm and updated
-/// periodically by administrators.
+/// periodically by administrators. Candidates vetoed by or vetoing the user are left out.
 ///
 /// # Returns
 ///
@@ -60,7 +71,8 @@ pub async fn get_previews(
 ) -> AppResult<Json<Vec<ProfilePreview>>> {
     trace!("Fetching match previews for user");
 
-    let profiles = fetch_profile_previews(&state.db_pool, user.user_id).await?;
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
+    let profiles = fetch_profile_previews(&state.db_pool, event_id, user.user_id).await?;
     debug!("Found {} match previews for user", profiles.len());
 
     Ok(Json(profiles))
@@ -72,7 +84,9 @@ pub async fn get_previews(
 ///
 /// This endpoint allows users to veto (exclude) specific users from being matched
 /// with them. The operation is idempotent - if a veto already exists, the existing
-/// veto record is returned. Users cannot veto themselves.
+/// veto record is returned. Users cannot veto themselves. A new veto regenerates the
+/// user's match previews, so the vetoed candidate is replaced right away; if that fails,
+/// the veto is still created and the previews are regenerated in the next cycle.
 ///
 /// # Returns
 ///
@@ -80,6 +94,7 @@ pub async fn get_previews(
 /// - `200 OK` with `Veto` - Veto already exists (idempotent response)
 /// - `400 Bad Request` - Invalid request or attempt to self-veto
 /// - `401 Unauthorized` - Missing or invalid authentication token
+/// - `403 Forbidden` - Veto window or event phase is not open, or veto quota is used up
 /// - `500 Internal Server Error` - Database error
 #[instrument(
     skip_all,
@@ -104,21 +119,56 @@ pub async fn add_veto(
         return Err(AppError::BadRequest("Cannot veto yourself"));
     }
 
-    match create_veto(&state.db_pool, vetoer_id, vetoed_id).await {
-        Ok(veto) => {
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
+
+    if !has_match_preview(&state.db_pool, event_id, vetoer_id).await? {
+        debug!("User has no published match previews to veto");
+        return Err(AppError::Forbidden(
+            "Veto window opens when match previews are published",
+        ));
+    }
+
+    ensure_veto_window_open(&state.db_pool, event_id).await?;
+
+    // Lock the vetoer so concurrent requests cannot exceed the quota together
+    let mut tx = state.db_pool.begin().await?;
+    sqlx::query!("SELECT id FROM users WHERE id = $1 FOR UPDATE", vetoer_id)
+        .fetch_one(tx.as_mut())
+        .await?;
+
+    // Re-adding an existing veto does not count against the quota
+    let other_vetoes = count_other_vetoes(&mut tx, event_id, vetoer_id, vetoed_id).await?;
+    if other_vetoes >= i64::from(*VETO_QUOTA) {
+        warn!(other_vetoes, "User has used up the veto quota");
+        return Err(AppError::Forbidden("Veto quota for this round is used up"));
+    }
+
+    match create_veto(&mut tx, event_id, vetoer_id, vetoed_id).await {
+        Ok(Some(veto)) => {
+            tx.commit().await?;
             info!("User successfully vetoed target user");
+
+            // Backfill the vetoed candidate's slot without waiting for the next cycle. The
+            // veto is already saved, so a failure only delays this to the next cycle.
+            if let Err(e) =
+                MatchingService::generate_user_match_preview(&state.db_pool, event_id, vetoer_id)
+                    .await
+            {
+                error!("Failed to backfill match preview after veto: {}", e);
+            }
+
             Ok((StatusCode::CREATED, Json(veto)))
         }
+        Ok(None) => {
+            debug!("User already vetoed target user");
+            // Fetch existing veto record for idempotent response
+            let existing_veto = fetch_veto(tx.as_mut(), event_id, vetoer_id, vetoed_id).await?;
+            tx.commit().await?;
+            Ok((StatusCode::OK, Json(existing_veto)))
+        }
         Err(e) => {
-            if e.to_string().contains("duplicate key") {
-                debug!("User already vetoed target user");
-                // Fetch existing veto record for idempotent response
-                let existing_veto = fetch_veto(&state.db_pool, vetoer_id, vetoed_id).await?;
-                Ok((StatusCode::OK, Json(existing_veto)))
-            } else {
-                error!("Failed to create veto: {}", e);
-                Err(AppError::Internal)
-            }
+            error!("Failed to create veto: {}", e);
+            Err(AppError::Internal)
         }
     }
 }
@@ -129,13 +179,15 @@ pub async fn add_veto(
 ///
 /// This endpoint allows users to remove an existing veto, allowing the previously
 /// vetoed user to potentially be matched again. If no veto exists between the users,
-/// returns 404 Not Found.
+/// returns 404 Not Found. Like adding, removing is only possible while the veto window
+/// is open, so the vetoes cannot change right before final matching.
 ///
 /// # Returns
 ///
 /// - `200 OK` with `Veto` - Veto removed successfully
 /// - `404 Not Found` - No veto exists between users
 /// - `401 Unauthorized` - Missing or invalid authentication token
+/// - `403 Forbidden` - Veto window or event phase is not open
 /// - `500 Internal Server Error` - Database error
 #[instrument(
     skip_all,
@@ -154,10 +206,15 @@ pub async fn remove_veto(
     let vetoer_id = user.user_id;
     let vetoed_id = request.vetoed_id;
 
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
+    ensure_veto_window_open(&state.db_pool, event_id).await?;
+
     // Fetch the veto record first (before deleting) to return it
-    let veto_to_delete = fetch_veto(&state.db_pool, vetoer_id, vetoed_id).await.ok();
+    let veto_to_delete = fetch_veto(&state.db_pool, event_id, vetoer_id, vetoed_id)
+        .await
+        .ok();
 
-    let rows_affected = delete_veto(&state.db_pool, vetoer_id, vetoed_id).await?;
+    let rows_affected = delete_veto(&state.db_pool, event_id, vetoer_id, vetoed_id).await?;
     if rows_affected > 0
         && let Some(veto) = veto_to_delete
     {
@@ -171,7 +228,7 @@ pub async fn remove_veto(
 
 /// Gets all vetoes for the authenticated user.
 ///
-/// GET /api/veto
+/// GET /api/vetoes
 ///
 /// This endpoint returns a list of user IDs that the authenticated user has vetoed.
 /// The response contains only the UUIDs of vetoed users, not their full profiles.
@@ -194,38 +251,140 @@ pub async fn get_vetoes(
 ) -> AppResult<Json<Vec<Uuid>>> {
     trace!("Fetching vetoes for user");
 
-    let vetoes = fetch_user_vetoes(&state.db_pool, user.user_id).await?;
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
+    let vetoes = fetch_user_vetoes(&state.db_pool, event_id, user.user_id).await?;
     let vetoed_ids: Vec<Uuid> = vetoes.into_iter().map(|v| v.vetoed_id).collect();
     debug!("Found {} vetoes for user", vetoed_ids.len());
 
     Ok(Json(vetoed_ids))
 }
 
+/// Gets the veto quota and window of the authenticated user.
+///
+/// GET /api/veto/quota
+///
+/// # Returns
+///
+/// - `200 OK` with `VetoQuota` - Remaining quota and when the veto window closes
+/// - `401 Unauthorized` - Missing or invalid authentication token
+/// - `500 Internal Server Error` - Database error
+#[instrument(
+    skip_all,
+    fields(
+        user_id = %user.user_id,
+        request_id = %uuid::Uuid::new_v4()
+    )
+)]
+pub async fn get_veto_quota(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<Json<VetoQuota>> {
+    trace!("Fetching veto quota for user");
+
+    let event_id = EventService::current_event_id(&state.db_pool).await?;
+    let vetoes = fetch_user_vetoes(&state.db_pool, event_id, user.user_id).await?;
+    let remaining_quota = VETO_QUOTA.saturating_sub(vetoes.len() as u32);
+    let window_closes_at = veto_window_closes_at(&state.db_pool, event_id).await?;
+
+    Ok(Json(VetoQuota {
+        remaining_quota,
+        window_closes_at,
+    }))
+}
+
 // --- Database helper functions ---
 
-async fn create_veto(
+/// Rejects changes to vetoes outside the veto phase or after the veto window closed
+async fn ensure_veto_window_open(db_pool: &PgPool, event_id: Uuid) -> AppResult<()> {
+    EventService::ensure_open(db_pool, event_id, PhaseGate::Veto).await?;
+
+    if let Some(closes_at) = veto_window_closes_at(db_pool, event_id).await?
+        && closes_at <= OffsetDateTime::now_utc()
+    {
+        debug!(%closes_at, "Veto window is closed");
+        return Err(AppError::Forbidden(
+            "Veto window is closed until the next final match",
+        ));
+    }
+
+    Ok(())
+}
+
+/// The veto window closes `VETO_WINDOW_CLOSE_HOURS` before the next scheduled final match
+async fn veto_window_closes_at(
     db_pool: &PgPool,
+    event_id: Uuid,
+) -> AppResult<Option<OffsetDateTime>> {
+    let next = SchedulerService::get_next_scheduled_time(db_pool, event_id).await?;
+    Ok(next.map(|time| time - Duration::hours(*VETO_WINDOW_CLOSE_HOURS)))
+}
+
+async fn has_match_preview(
+    db_pool: &PgPool,
+    event_id: Uuid,
+    user_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS(SELECT 1 FROM match_previews WHERE event_id = $1 AND user_id = $2)
+            as "exists!"
+        "#,
+        event_id,
+        user_id
+    )
+    .fetch_one(db_pool)
+    .await
+}
+
+async fn count_other_vetoes(
+    conn: &mut PgConnection,
+    event_id: Uuid,
     vetoer_id: Uuid,
     vetoed_id: Uuid,
-) -> Result<Veto, sqlx::Error> {
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!"
+        FROM vetoes
+        WHERE event_id = $1 AND vetoer_id = $2 AND vetoed_id != $3
+        "#,
+        event_id,
+        vetoer_id,
+        vetoed_id
+    )
+    .fetch_one(conn)
+    .await
+}
+
+/// Returns `None` if the veto already exists
+async fn create_veto(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    vetoer_id: Uuid,
+    vetoed_id: Uuid,
+) -> Result<Option<Veto>, sqlx::Error> {
     sqlx::query_as!(
         Veto,
-        "INSERT INTO vetoes (vetoer_id, vetoed_id) VALUES ($1, $2)
+        "INSERT INTO vetoes (event_id, vetoer_id, vetoed_id) VALUES ($1, $2, $3)
+         ON CONFLICT DO NOTHING
          RETURNING id, vetoer_id, vetoed_id",
+        event_id,
         vetoer_id,
         vetoed_id
     )
-    .fetch_one(db_pool)
+    .fetch_optional(conn)
     .await
 }
 
 async fn delete_veto(
     db_pool: &PgPool,
+    event_id: Uuid,
     vetoer_id: Uuid,
     vetoed_id: Uuid,
 ) -> Result<u64, sqlx::Error> {
     let result = sqlx::query!(
-        "DELETE FROM vetoes WHERE vetoer_id = $1 AND vetoed_id = $2",
+        "DELETE FROM vetoes WHERE event_id = $1 AND vetoer_id = $2 AND vetoed_id = $3",
+        event_id,
         vetoer_id,
         vetoed_id
     )
@@ -236,24 +395,32 @@ async fn delete_veto(
 }
 
 async fn fetch_veto(
-    db_pool: &PgPool,
+    executor: impl PgExecutor<'_>,
+    event_id: Uuid,
     vetoer_id: Uuid,
     vetoed_id: Uuid,
 ) -> Result<Veto, sqlx::Error> {
     sqlx::query_as!(
         Veto,
-        "SELECT id, vetoer_id, vetoed_id FROM vetoes WHERE vetoer_id = $1 AND vetoed_id = $2",
+        "SELECT id, vetoer_id, vetoed_id FROM vetoes
+         WHERE event_id = $1 AND vetoer_id = $2 AND vetoed_id = $3",
+        event_id,
         vetoer_id,
         vetoed_id
     )
-    .fetch_one(db_pool)
+    .fetch_one(executor)
     .await
 }
 
-async fn fetch_user_vetoes(db_pool: &PgPool, user_id: Uuid) -> Result<Vec<Veto>, sqlx::Error> {
+async fn fetch_user_vetoes(
+    db_pool: &PgPool,
+    event_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<Veto>, sqlx::Error> {
     sqlx::query_as!(
         Veto,
-        "SELECT id, vetoer_id, vetoed_id FROM vetoes WHERE vetoer_id = $1",
+        "SELECT id, vetoer_id, vetoed_id FROM vetoes WHERE event_id = $1 AND vetoer_id = $2",
+        event_id,
         user_id
     )
     .fetch_all(db_pool)
@@ -262,22 +429,28 @@ async fn fetch_user_vetoes(db_pool: &PgPool, user_id: Uuid) -> Result<Vec<Veto>,
 
 async fn fetch_profile_previews(
     db_pool: &PgPool,
+    event_id: Uuid,
     user_id: Uuid,
 ) -> Result<Vec<ProfilePreview>, sqlx::Error> {
     let result = sqlx::query!(
         r#"
         SELECT
             f.user_id,
-            f.familiar_tags,
-            f.aspirational_tags,
-            f.recent_topics,
+            f.answers as "answers: sqlx::types::Json<FormAnswers>",
             u.email,
             u.grade
         FROM match_previews mp
-        JOIN forms f ON f.user_id = ANY(mp.candidate_ids)
+        JOIN forms f ON f.event_id = mp.event_id AND f.user_id = ANY(mp.candidate_ids)
         JOIN users u ON u.id = f.user_id
-        WHERE mp.user_id = $1
+        WHERE mp.event_id = $1 AND mp.user_id = $2 AND u.status = 'form_completed'
+          AND NOT EXISTS (
+              SELECT 1 FROM vetoes v
+              WHERE v.event_id = $1
+                AND ((v.vetoer_id = $2 AND v.vetoed_id = f.user_id)
+                  OR (v.vetoer_id = f.user_id AND v.vetoed_id = $2))
+          )
         "#,
+        event_id,
         user_id
     )
     .fetch_all(db_pool)
@@ -287,14 +460,12 @@ async fn fetch_profile_previews(
         .into_iter()
         .map(|row| {
             let email_domain = row.email.split('@').nth(1).unwrap_or("").to_string();
-            ProfilePreview {
-                candidate_id: row.user_id,
-                familiar_tags: row.familiar_tags,
-                aspirational_tags: row.aspirational_tags,
-                recent_topics: row.recent_topics,
+            ProfilePreview::new(
+                row.user_id,
+                FORM_SCHEMA.visible_answers(&row.answers, Visibility::Preview),
                 email_domain,
-                grade: row.grade,
-            }
+                row.grade,
+            )
         })
         .collect();
 
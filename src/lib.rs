@@ -14,12 +14,12 @@ pub mod models;
 pub mod services;
 pub mod utils;
 
-use std::{env, sync::Arc};
+use std::{env, sync::Arc, time::Duration};
 
 use axum::{
     Router,
     middleware::from_fn_with_state,
-    routing::{delete, get, post},
+    routing::{delete, get, patch, post, put},
 };
 use jsonwebtoken::{DecodingKey, EncodingKey};
 use sqlx::PgPool;
@@ -27,34 +27,98 @@ use tracing::info;
 
 use crate::{
     handlers::{
-        accept_final_match, add_veto, get_form, get_next_match_time, get_previews, get_profile,
-        get_vetoes, health_check, refresh_token, reject_final_match, remove_veto,
-        send_verification_code, serve_partner_image, serve_profile_thumbnail, submit_form,
-        upload_card, upload_profile_photo, verify_code,
+        accept_final_match, add_veto, delete_account, export_account, get_event_phase, get_form,
+        get_form_draft, get_form_schema, get_next_match_time, get_notification_preferences,
+        get_previews, get_profile, get_rejection_reasons, get_veto_quota, get_vetoes, health_check,
+        pause_participation, refresh_token, reject_final_match, remove_veto, resume_participation,
+        save_form_draft, send_verification_code, serve_partner_image, serve_profile_thumbnail,
+        stream_events, submit_form, submit_match_feedback, update_notification_preferences,
+        upload_card, upload_profile_photo, verify_code, withdraw_participation,
     },
     models::AppState,
     services::{
-        email::{EmailService, ExternalEmailer, LogEmailer},
+        email::{
+            EmailService, ExternalEmailer, FailoverEmailer, LogEmailer, SmtpConfig, SmtpEmailer,
+            SmtpSecurity,
+        },
         jwt::JwtService,
         matching::MatchingService,
+        moderation::ModerationService,
+        outbox::EmailOutboxService,
+        retention::RetentionService,
         scheduler::SchedulerService,
+        user_event::UserEventService,
     },
-    utils::{constant::*, secret, static_object::TAG_SYSTEM},
+    utils::{constant::*, secret, static_object::CARD_PHOTO_RETENTION_DAYS},
 };
 
 /// Creates an Axum router with default email service configuration.
 ///
+/// See [`email_service_from_env`] for the email-related environment variables.
+pub fn app(db_pool: PgPool) -> Router {
+    app_with_email_service(db_pool, email_service_from_env())
+}
+
+/// Builds the email service selected by the environment.
+///
 /// # Environment Variables
 ///
-/// - `EMAIL_PROVIDER` - "external" uses ExternalEmailer, "log" uses LogEmailer (default)
+/// - `EMAIL_PROVIDER` - Comma-separated providers tried in order, see [`FailoverEmailer`].
+///   "external" uses ExternalEmailer, "smtp" uses SmtpEmailer, "log" uses LogEmailer (default).
+///   Unknown providers fail startup.
+/// - `EMAIL_CIRCUIT_FAILURE_THRESHOLD` - Optional consecutive failures after which a provider is skipped
+/// - `EMAIL_CIRCUIT_OPEN_SECONDS` - Optional seconds a failing provider is skipped before it is probed
 /// - `MAIL_API_URL`   - Required in production for external email service
 /// - `MAIL_API_KEY` or `MAIL_API_KEY_FILE` (preferred)  - Required for external email service
-/// - `SENDER_EMAIL`   - Required in production for external email service
-pub fn app(db_pool: PgPool) -> Router {
-    let email_service: Arc<dyn EmailService> = match env::var("EMAIL_PROVIDER")
+/// - `SENDER_EMAIL`   - Required in production for external and SMTP email service
+/// - `SMTP_HOST`      - Required for SMTP email service
+/// - `SMTP_PORT`      - Optional, defaults to the port of `SMTP_SECURITY`
+/// - `SMTP_SECURITY`  - "starttls" (default), "tls" or "none"
+/// - `SMTP_USERNAME` and `SMTP_PASSWORD` or `SMTP_PASSWORD_FILE` (preferred) - Optional SMTP authentication
+/// - `SMTP_POOL_SIZE` - Optional positive maximum number of pooled SMTP connections
+pub fn email_service_from_env() -> Arc<dyn EmailService> {
+    let providers = env::var("EMAIL_PROVIDER")
         .expect("Env variable `EMAIL_PROVIDER` should be set")
-        .as_str()
-    {
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| (name.to_string(), email_provider_from_env(name)))
+        .collect::<Vec<_>>();
+
+    if providers.is_empty() {
+        return email_provider_from_env("log");
+    }
+
+    let failure_threshold = env::var("EMAIL_CIRCUIT_FAILURE_THRESHOLD").map_or(
+        DEFAULT_EMAIL_CIRCUIT_FAILURE_THRESHOLD,
+        |threshold| {
+            threshold
+                .parse()
+                .ok()
+                .filter(|threshold| *threshold > 0)
+                .expect(
+                    "Env variable `EMAIL_CIRCUIT_FAILURE_THRESHOLD` should be a positive integer",
+                )
+        },
+    );
+    let open_duration = env::var("EMAIL_CIRCUIT_OPEN_SECONDS").map_or(
+        DEFAULT_EMAIL_CIRCUIT_OPEN_DURATION,
+        |seconds| {
+            seconds
+                .parse()
+                .ok()
+                .filter(|seconds| *seconds > 0)
+                .map(Duration::from_secs)
+                .expect("Env variable `EMAIL_CIRCUIT_OPEN_SECONDS` should be a positive integer")
+        },
+    );
+
+    Arc::new(FailoverEmailer::new(providers).circuit_breaker(failure_threshold, open_duration))
+}
+
+/// Builds a single email provider by name.
+fn email_provider_from_env(name: &str) -> Arc<dyn EmailService> {
+    match name {
         "external" => {
             info!("Email provider set to [ExternalEmailer]");
             let api_url =
@@ -65,13 +129,49 @@ pub fn app(db_pool: PgPool) -> Router {
                 env::var("SENDER_EMAIL").expect("Env variable `SENDER_EMAIL` should be set");
             Arc::new(ExternalEmailer::new(api_url, api_key, sender))
         }
-        _ => {
+        "smtp" => {
+            info!("Email provider set to [SmtpEmailer]");
+            let host = env::var("SMTP_HOST").expect("Env variable `SMTP_HOST` should be set");
+            let port = env::var("SMTP_PORT").ok().map(|port| {
+                port.parse()
+                    .expect("Env variable `SMTP_PORT` should be a valid port")
+            });
+            let security = env::var("SMTP_SECURITY")
+                .map_or(Ok(SmtpSecurity::StartTls), |security| security.parse())
+                .expect("Env variable `SMTP_SECURITY` should be `starttls`, `tls` or `none`");
+            let credentials = env::var("SMTP_USERNAME").ok().map(|username| {
+                let password = secret::get_secret("SMTP_PASSWORD_FILE", "SMTP_PASSWORD").expect(
+                    "Either `SMTP_PASSWORD_FILE` or `SMTP_PASSWORD` env variable should be set",
+                );
+                (username, password)
+            });
+            let pool_size = env::var("SMTP_POOL_SIZE").map_or(DEFAULT_SMTP_POOL_SIZE, |size| {
+                size.parse()
+                    .ok()
+                    .filter(|size| *size > 0)
+                    .expect("Env variable `SMTP_POOL_SIZE` should be a positive integer")
+            });
+            let sender_email =
+                env::var("SENDER_EMAIL").expect("Env variable `SENDER_EMAIL` should be set");
+
+            let config = SmtpConfig {
+                host,
+                port,
+                security,
+                credentials,
+                pool_size,
+                sender_email,
+            };
+            Arc::new(SmtpEmailer::new(config).expect("SMTP email service should be configured"))
+        }
+        "log" => {
             info!("Email provider set to [LogEmailer]");
             Arc::new(LogEmailer)
         }
-    };
-
-    app_with_email_service(db_pool, email_service)
+        other => panic!(
+            "Env variable `EMAIL_PROVIDER` contains unknown provider `{other}`, expected `external`, `smtp` or `log`"
+        ),
+    }
 }
 
 /// Creates an Axum router with application routes and state.
@@ -111,28 +211,67 @@ pub fn app_with_email_service(db_pool: PgPool, email_service: Arc<dyn EmailServi
         }
     });
 
+    // Reload allowed domains whenever admins change them
+    state.allowed_domains.spawn_reload_task();
+
     // Spawn the match preview generation background task
-    MatchingService::spawn_preview_generation_task(state.db_pool.clone(), &TAG_SYSTEM);
+    MatchingService::spawn_preview_generation_task(state.db_pool.clone());
 
     // Spawn the scheduler background task
-    SchedulerService::spawn_scheduler_task(state.db_pool.clone(), &TAG_SYSTEM);
+    SchedulerService::spawn_scheduler_task(state.db_pool.clone());
 
     // Spawn the auto-accept background task
     SchedulerService::spawn_auto_accept_task(state.db_pool.clone());
 
+    // Spawn the email outbox delivery background task
+    EmailOutboxService::spawn_delivery_task(
+        state.db_pool.clone(),
+        Arc::clone(&state.email_service),
+    );
+
+    // Spawn the card photo retention background task
+    RetentionService::spawn_card_retention_task(state.db_pool.clone(), *CARD_PHOTO_RETENTION_DAYS);
+
+    // Spawn the suspension expiry background task
+    ModerationService::spawn_suspension_expiry_task(state.db_pool.clone());
+
+    // Forward user events notified by the database to the connected event streams
+    state.user_events.spawn_listen_task();
+
+    // Spawn the user event retention background task
+    UserEventService::spawn_retention_task(state.db_pool.clone());
+
     let protected_routes = Router::new()
         .route("/api/profile", get(get_profile))
+        .route("/api/account", delete(delete_account))
+        .route("/api/account/export", get(export_account))
         .route("/api/form", post(submit_form))
         .route("/api/form", get(get_form))
+        .route("/api/form/draft", put(save_form_draft))
+        .route("/api/form/draft", get(get_form_draft))
+        .route("/api/participation/pause", post(pause_participation))
+        .route("/api/participation/resume", post(resume_participation))
+        .route("/api/participation/withdraw", post(withdraw_participation))
         .route("/api/upload/card", post(upload_card))
         .route("/api/upload/profile-photo", post(upload_profile_photo))
         .route("/api/veto/previews", get(get_previews))
         .route("/api/veto", post(add_veto))
         .route("/api/veto", delete(remove_veto))
+        .route("/api/veto/quota", get(get_veto_quota))
         .route("/api/vetoes", get(get_vetoes))
         .route("/api/final-match/accept", post(accept_final_match))
         .route("/api/final-match/reject", post(reject_final_match))
         .route("/api/final-match/time", get(get_next_match_time))
+        .route("/api/final-match/feedback", post(submit_match_feedback))
+        .route("/api/events/stream", get(stream_events))
+        .route(
+            "/api/notifications/preferences",
+            get(get_notification_preferences),
+        )
+        .route(
+            "/api/notifications/preferences",
+            patch(update_notification_preferences),
+        )
         .route(
             "/api/images/thumbnail/{user_id}",
             get(serve_profile_thumbnail),
@@ -151,6 +290,12 @@ pub fn app_with_email_service(db_pool: PgPool, email_service: Arc<dyn EmailServi
 
     let public_routes = Router::new()
         .route("/health-check", get(health_check))
+        .route("/api/form/schema", get(get_form_schema))
+        .route("/api/event/phase", get(get_event_phase))
+        .route(
+            "/api/final-match/rejection-reasons",
+            get(get_rejection_reasons),
+        )
         .route("/api/auth/send-code", post(send_verification_code))
         .route("/api/auth/verify-code", post(verify_code))
         .route("/api/auth/refresh", post(refresh_token));
@@ -7,14 +7,19 @@
 //! - `RUST_LOG` - Logging level (optional, defaults to `info`)
 //! - `LOG_FORMAT` - Log format, either `json` or `plain` (optional, defaults to `plain`)
 //! - `NO_COLOR` - If set, disables colored log output (optional)
+//! - `ALLOWED_DOMAINS`, `ALLOWED_GRADES` - Domains allowed while none are (optional)
 
-use std::{env, sync::LazyLock};
+use std::{
+    env,
+    sync::{Arc, LazyLock},
+};
 
 use hilo::{
-    app,
+    app_with_email_service, email_service_from_env,
     handlers::admin_router,
+    services::domain::DomainService,
     utils::{
-        static_object::{EMAIL_REGEX, TAG_SYSTEM},
+        static_object::{DEFAULT_CATALOGS, EMAIL_TEMPLATES},
         thumbnail_fixup,
     },
 };
@@ -45,6 +50,10 @@ async fn main() {
 
     info!("Connected to PostgreSQL database");
 
+    DomainService::seed_from_env(&db_pool)
+        .await
+        .expect("Failed to seed allowed domains");
+
     // Generate missing thumbnails for existing profile photos (background task)
     tokio::spawn(async {
         thumbnail_fixup::generate_missing_thumbnails().await;
@@ -79,11 +88,13 @@ async fn main() {
     };
 
     // Start main server
-    LazyLock::force(&EMAIL_REGEX); // ensure panic happens at startup
-    LazyLock::force(&TAG_SYSTEM);
+    LazyLock::force(&DEFAULT_CATALOGS); // ensure panic happens at startup
+    LazyLock::force(&EMAIL_TEMPLATES);
+    let email_service = email_service_from_env();
+    let admin_email_service = Arc::clone(&email_service);
     let main_db = db_pool.clone();
     let mut main_server = tokio::spawn(async move {
-        let router = app(main_db);
+        let router = app_with_email_service(main_db, email_service);
         let addr = env::var("ADDRESS").expect("Env variable `ADDRESS` should be set");
         let listener = TcpListener::bind(&addr).await.unwrap();
         info!("Main server starting at http://{}", addr);
@@ -97,7 +108,7 @@ async fn main() {
     // Start admin server
     // Admin server is protected by Cloudflare Access, so no additional auth is needed
     let mut admin_server = tokio::spawn(async move {
-        let router = admin_router(db_pool);
+        let router = admin_router(db_pool, admin_email_service);
         let addr = env::var("ADMIN_ADDRESS").expect("Env variable `ADMIN_ADDRESS` should be set");
         let listener = TcpListener::bind(&addr).await.unwrap();
         info!("Admin server starting at http://{}", addr);
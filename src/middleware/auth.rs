@@ -6,15 +6,19 @@
 use std::sync::Arc;
 
 use axum::{
+    Json,
     extract::{Request, State},
     http::{StatusCode, header},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use tracing::{debug, error, instrument, trace, warn};
 use uuid::Uuid;
 
-use crate::{models::AppState, services::jwt::Claims};
+use crate::{
+    models::AppState,
+    services::{jwt::Claims, moderation::SuspensionNotice},
+};
 
 /// Authentication middleware for protecting routes
 ///
@@ -27,12 +31,15 @@ use crate::{models::AppState, services::jwt::Claims};
 /// 1. Extracts `Authorization` header with `Bearer <token>` format
 /// 2. Validates the JWT token signature and expiration
 /// 3. Parses user ID from token claims
-/// 4. Adds [`AuthUser`] to request extensions for handler access
+/// 4. Rejects suspended users, using the cached suspension state (expired
+///    suspensions are lifted on the spot)
+/// 5. Adds [`AuthUser`] to request extensions for handler access
 ///
 /// # Returns
 ///
 /// - **Success**: Continues to next handler with user context
 /// - **Failure**: Returns `401 Unauthorized` for invalid/missing tokens
+/// - **Suspended**: Returns `403 Forbidden` with the suspension reason and expiry
 #[instrument(
     skip_all,
     fields(
@@ -73,6 +80,18 @@ pub async fn auth_middleware(
                 StatusCode::UNAUTHORIZED
             })?;
 
+            match SuspensionNotice::check_cached(&state.db_pool, user_id).await {
+                Ok(None) => {}
+                Ok(Some(notice)) => {
+                    warn!(user_id = %user_id, "Suspended user denied access");
+                    return Ok((StatusCode::FORBIDDEN, Json(notice)).into_response());
+                }
+                Err(e) => {
+                    error!(error = %e, "Failed to check suspension status");
+                    return Err(StatusCode::INTERNAL_SERVER_ERROR);
+                }
+            }
+
             debug!(user_id = %user_id, "Authentication successful");
             req.extensions_mut().insert(AuthUser { user_id, claims });
 
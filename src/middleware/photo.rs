@@ -2,6 +2,8 @@
 //!
 //! This middleware validates that a user requesting a partner's image
 //! is actually matched with that partner in the final_matches table.
+//! Only the final match of the current event grants access; partners of
+//! earlier events can no longer see each other's images.
 
 use std::sync::Arc;
 
@@ -14,7 +16,7 @@ use axum::{
 use tracing::{debug, instrument, trace, warn};
 use uuid::Uuid;
 
-use crate::{middleware::AuthUser, models::AppState};
+use crate::{middleware::AuthUser, models::AppState, services::event::EventService};
 
 /// Partner validation middleware for image access
 ///
@@ -48,14 +50,19 @@ pub async fn photo_middleware(
         return Err(StatusCode::BAD_REQUEST);
     };
 
+    let event_id = EventService::current_event_id(&state.db_pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     let exists = sqlx::query_scalar!(
         r#"
         SELECT EXISTS(
             SELECT 1 FROM final_matches
-            WHERE (user_a_id = $1 AND user_b_id = $2)
-               OR (user_a_id = $2 AND user_b_id = $1)
+            WHERE event_id = $1
+              AND ((user_a_id = $2 AND user_b_id = $3)
+                OR (user_a_id = $3 AND user_b_id = $2))
         )
         "#,
+        event_id,
         user.user_id,
         requested_id
     )
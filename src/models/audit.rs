@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::models::UserStatus;
+
+/// State-changing admin actions that are recorded in the audit log.
+///
+/// This enum corresponds to the PostgreSQL `admin_action` enum type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "admin_action", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AdminAction {
+    VerifyUser,
+    DeleteFinalMatch,
+    TriggerFinalMatching,
+    CreateScheduledMatches,
+    CancelScheduledMatch,
+    SuspendUser,
+    LiftSuspension,
+    CreateEventPhases,
+    CancelEventPhase,
+    CreateEvent,
+    ActivateEvent,
+    CreateAllowedDomain,
+    UpdateAllowedDomain,
+    DeleteAllowedDomain,
+    UpdateMatchPreviews,
+}
+
+/// A row of the `admin_audit_log` table
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct AuditLogEntry {
+    pub id: Uuid,
+    pub actor: String,
+    pub action: AdminAction,
+    pub target_ids: Vec<Uuid>,
+    pub payload: Option<serde_json::Value>,
+    pub status_before: Option<UserStatus>,
+    pub status_after: Option<UserStatus>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+/// An audit entry to be written alongside the change it describes
+#[derive(Debug)]
+pub struct NewAuditEntry<'a> {
+    pub actor: &'a str,
+    pub action: AdminAction,
+    pub target_ids: Vec<Uuid>,
+    pub payload: Option<serde_json::Value>,
+    pub status_before: Option<UserStatus>,
+    pub status_after: Option<UserStatus>,
+}
+
+impl<'a> NewAuditEntry<'a> {
+    /// Creates an entry with no payload and no status transition.
+    pub fn new(actor: &'a str, action: AdminAction, target_ids: Vec<Uuid>) -> Self {
+        Self {
+            actor,
+            action,
+            target_ids,
+            payload: None,
+            status_before: None,
+            status_after: None,
+        }
+    }
+
+    pub fn payload(mut self, payload: serde_json::Value) -> Self {
+        self.payload = Some(payload);
+        self
+    }
+
+    pub fn status_change(mut self, before: Option<UserStatus>, after: UserStatus) -> Self {
+        self.status_before = before;
+        self.status_after = Some(after);
+        self
+    }
+}
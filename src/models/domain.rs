@@ -0,0 +1,69 @@
+//! # Allowed Email Domains
+//!
+//! Users sign up with the email address of a participating institution. Each
+//! allowed domain has a display name, optionally covers its subdomains, and lists
+//! the grades its students may register with.
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// A row of the `allowed_domains` table
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AllowedDomain {
+    pub id: Uuid,
+    pub domain: String,
+    pub display_name: String,
+    pub include_subdomains: bool,
+    pub allowed_grades: Vec<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+impl AllowedDomain {
+    /// Whether `host`, already lowercased, is this domain or one of its allowed subdomains
+    pub fn matches(&self, host: &str) -> bool {
+        host == self.domain
+            || (self.include_subdomains
+                && host
+                    .strip_suffix(self.domain.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.')))
+    }
+
+    pub fn allows_grade(&self, grade: &str) -> bool {
+        self.allowed_grades.iter().any(|allowed| allowed == grade)
+    }
+}
+
+/// The set of allowed domains, as loaded by the domain registry
+#[derive(Debug, Default)]
+pub struct AllowedDomains(pub Vec<AllowedDomain>);
+
+impl AllowedDomains {
+    /// Find the domain rule an email address belongs to
+    ///
+    /// The email must already be validated. An exact match wins over subdomain
+    /// wildcards, and among wildcards the most specific domain wins.
+    pub fn rule_for(&self, email: &str) -> Option<&AllowedDomain> {
+        let (_, host) = email.rsplit_once('@')?;
+        let host = host.to_lowercase();
+
+        self.0
+            .iter()
+            .filter(|rule| rule.matches(&host))
+            .max_by_key(|rule| (rule.domain == host, rule.domain.len()))
+    }
+}
+
+/// Request payload for creating or updating an allowed domain
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AllowedDomainRequest {
+    pub domain: String,
+    pub display_name: String,
+    #[serde(default)]
+    pub include_subdomains: bool,
+    pub allowed_grades: Vec<String>,
+}
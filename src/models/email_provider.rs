@@ -0,0 +1,60 @@
+//! # Email Provider Health
+//!
+//! With several email providers configured, emails fail over from one provider
+//! to the next. Each provider has a circuit breaker that takes it out of rotation
+//! after repeated failures, and counts the emails it delivered.
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// State of the circuit breaker of an email provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Healthy, emails are sent through the provider
+    Closed,
+    /// Failing, the provider is skipped until `open_until`
+    Open,
+    /// One email is being sent to probe whether the provider recovered
+    HalfOpen,
+}
+
+/// Health and delivery metrics of an email provider since the server started
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderHealth {
+    pub name: String,
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    /// Emails delivered by this provider
+    pub delivered: u64,
+    /// Failed attempts to deliver through this provider
+    pub failed: u64,
+    /// Emails that skipped this provider because its circuit was open
+    pub skipped: u64,
+    pub last_error: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_delivered_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_failed_at: Option<OffsetDateTime>,
+    /// When an open circuit lets the next probe through
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub open_until: Option<OffsetDateTime>,
+}
+
+impl ProviderHealth {
+    /// A healthy provider that has not sent anything yet
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            delivered: 0,
+            failed: 0,
+            skipped: 0,
+            last_error: None,
+            last_delivered_at: None,
+            last_failed_at: None,
+            open_until: None,
+        }
+    }
+}
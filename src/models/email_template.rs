@@ -0,0 +1,301 @@
+//! # Email Templates
+//!
+//! Every email is rendered from a template in `email_templates.json`, keyed by
+//! [`TemplateId`] and locale. A template has a subject, a plain-text part, an
+//! HTML part and optionally a preheader, the preview shown next to the subject
+//! in inboxes. All of them may reference the variables of their template as
+//! `{{name}}`.
+//!
+//! The templates are loaded and checked at startup: every template must exist in
+//! [`DEFAULT_EMAIL_LOCALE`], and may only reference the variables its
+//! [`TemplateId`] provides. Locales other than the default may be incomplete;
+//! missing templates fall back to the default locale.
+//!
+//! Users are emailed in the locale stored on their account, which is negotiated
+//! from the `Accept-Language` header they log in with.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::utils::{
+    constant::DEFAULT_EMAIL_LOCALE,
+    html::{escape_html, generate_email_html},
+};
+
+#[derive(Debug, Error)]
+pub enum TemplateError {
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error("invalid email templates: {0}")]
+    Invalid(String),
+
+    #[error("variables of template '{0}' do not match, expected {1:?}")]
+    Variables(TemplateId, &'static [&'static str]),
+}
+
+/// Identifier of an email template.
+///
+/// This enum corresponds to the PostgreSQL `email_template` enum type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "email_template", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateId {
+    /// Login code, see [`crate::handlers::send_verification_code`]
+    VerificationCode,
+    /// Free-form message with its own subject
+    Notification,
+    FinalMatchCreated,
+    PartnerAccepted,
+    MatchRejected,
+    /// The partner was suspended, dissolving the match
+    MatchDissolved,
+    MatchAutoConfirmed,
+    /// The user has not responded to their match yet
+    MatchReminder,
+}
+
+impl TemplateId {
+    pub const ALL: [TemplateId; 8] = [
+        TemplateId::VerificationCode,
+        TemplateId::Notification,
+        TemplateId::FinalMatchCreated,
+        TemplateId::PartnerAccepted,
+        TemplateId::MatchRejected,
+        TemplateId::MatchDissolved,
+        TemplateId::MatchAutoConfirmed,
+        TemplateId::MatchReminder,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TemplateId::VerificationCode => "verification_code",
+            TemplateId::Notification => "notification",
+            TemplateId::FinalMatchCreated => "final_match_created",
+            TemplateId::PartnerAccepted => "partner_accepted",
+            TemplateId::MatchRejected => "match_rejected",
+            TemplateId::MatchDissolved => "match_dissolved",
+            TemplateId::MatchAutoConfirmed => "match_auto_confirmed",
+            TemplateId::MatchReminder => "match_reminder",
+        }
+    }
+
+    /// Variables every email of this template provides
+    pub fn variables(&self) -> &'static [&'static str] {
+        match self {
+            TemplateId::VerificationCode => &["code", "expiry_minutes"],
+            TemplateId::Notification => &["subject", "message"],
+            TemplateId::FinalMatchCreated => &["auto_accept_hours"],
+            TemplateId::MatchReminder => &["hours_left"],
+            TemplateId::PartnerAccepted
+            | TemplateId::MatchRejected
+            | TemplateId::MatchDissolved
+            | TemplateId::MatchAutoConfirmed => &[],
+        }
+    }
+}
+
+impl std::fmt::Display for TemplateId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One template in one locale
+#[derive(Debug, Deserialize)]
+struct TemplateParts {
+    subject: String,
+    text: String,
+    html: String,
+    /// Hidden text shown as preview by email clients
+    #[serde(default)]
+    preheader: String,
+}
+
+/// A rendered email, ready to be sent by an [`crate::services::email::EmailService`]
+#[derive(Debug, Clone)]
+pub struct EmailMessage {
+    pub recipient: String,
+    pub template: TemplateId,
+    /// Locale the email was rendered in, which may differ from the requested one
+    pub locale: String,
+    pub variables: BTreeMap<String, String>,
+    pub subject: String,
+    pub text: String,
+    /// Complete HTML document, including the layout
+    pub html: String,
+}
+
+/// The email templates, loaded from `email_templates.json`
+#[derive(Debug)]
+pub struct EmailTemplates {
+    templates: HashMap<TemplateId, HashMap<String, TemplateParts>>,
+}
+
+impl EmailTemplates {
+    /// Loads the templates from JSON and checks that they are complete.
+    pub fn from_json(content: &str) -> Result<Self, TemplateError> {
+        let templates = Self {
+            templates: serde_json::from_str(content)?,
+        };
+        templates.check()?;
+        Ok(templates)
+    }
+
+    /// Ensures every template exists in the default locale and only references
+    /// its own variables.
+    fn check(&self) -> Result<(), TemplateError> {
+        for id in TemplateId::ALL {
+            let locales = self
+                .templates
+                .get(&id)
+                .filter(|locales| locales.contains_key(DEFAULT_EMAIL_LOCALE))
+                .ok_or_else(|| {
+                    TemplateError::Invalid(format!(
+                        "template '{id}' is missing in locale '{DEFAULT_EMAIL_LOCALE}'"
+                    ))
+                })?;
+
+            for (locale, parts) in locales {
+                if parts.subject.contains('\n') || parts.preheader.contains('\n') {
+                    return Err(TemplateError::Invalid(format!(
+                        "subject or preheader of template '{id}' in locale '{locale}' spans several lines"
+                    )));
+                }
+                for part in [&parts.subject, &parts.text, &parts.html, &parts.preheader] {
+                    for name in placeholders(part).map_err(|e| {
+                        TemplateError::Invalid(format!("template '{id}' in locale '{locale}': {e}"))
+                    })? {
+                        if !id.variables().contains(&name) {
+                            return Err(TemplateError::Invalid(format!(
+                                "template '{id}' in locale '{locale}' references unknown variable '{name}'"
+                            )));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Renders an email, falling back to the default locale if the template is
+    /// not available in the requested one.
+    ///
+    /// Variables are inserted as they are into the subject and plain-text part,
+    /// and escaped in the HTML part.
+    pub fn render(
+        &self,
+        recipient: &str,
+        template: TemplateId,
+        locale: &str,
+        variables: BTreeMap<String, String>,
+    ) -> Result<EmailMessage, TemplateError> {
+        let expected = template.variables();
+        if variables.len() != expected.len()
+            || !expected.iter().all(|name| variables.contains_key(*name))
+        {
+            return Err(TemplateError::Variables(template, expected));
+        }
+
+        let locales = &self.templates[&template];
+        let (locale, parts) = locales
+            .get_key_value(locale)
+            .or_else(|| locales.get_key_value(DEFAULT_EMAIL_LOCALE))
+            .expect("templates are checked to exist in the default locale");
+
+        let subject = substitute(&parts.subject, &variables, |value| value.to_string());
+        let text = substitute(&parts.text, &variables, |value| value.to_string());
+        let html = generate_email_html(
+            locale,
+            &substitute(&parts.preheader, &variables, escape_html),
+            &substitute(&parts.html, &variables, escape_html),
+        );
+
+        Ok(EmailMessage {
+            recipient: recipient.to_string(),
+            template,
+            locale: locale.clone(),
+            variables,
+            subject,
+            text,
+            html,
+        })
+    }
+
+    /// Picks the locale to email someone in from an `Accept-Language` header.
+    ///
+    /// Returns the highest ranked language that any template is available in,
+    /// matching either the full tag or its primary language, e.g. `zh` for
+    /// `zh-CN`, or `None` if there is no such language.
+    pub fn negotiate(&self, accept_language: &str) -> Option<&str> {
+        let mut ranges: Vec<(f32, String)> = accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut params = range.split(';');
+                let tag = params.next()?.trim().to_ascii_lowercase();
+                let quality = match params.find_map(|param| param.trim().strip_prefix("q=")) {
+                    Some(quality) => quality.trim().parse().ok()?,
+                    None => 1.0,
+                };
+                (!tag.is_empty() && quality > 0.0).then_some((quality, tag))
+            })
+            .collect();
+        // Stable, so equally ranked languages keep their order
+        ranges.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        ranges.iter().find_map(|(_, tag)| {
+            let primary = tag.split('-').next().unwrap_or(tag);
+            self.templates
+                .values()
+                .flat_map(HashMap::keys)
+                .find(|locale| *locale == tag || *locale == primary)
+                .map(String::as_str)
+        })
+    }
+}
+
+/// Names of the `{{name}}` placeholders in a template part
+fn placeholders(part: &str) -> Result<Vec<&str>, String> {
+    let mut names = Vec::new();
+    let mut rest = part;
+
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| "unclosed placeholder".to_string())?;
+        let name = after[..end].trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_lowercase() || c == '_') {
+            return Err(format!("invalid placeholder '{{{{{name}}}}}'"));
+        }
+        names.push(name);
+        rest = &after[end + 2..];
+    }
+
+    Ok(names)
+}
+
+/// Replaces the placeholders of a checked template part with the formatted variables
+fn substitute(
+    part: &str,
+    variables: &BTreeMap<String, String>,
+    format: impl Fn(&str) -> String,
+) -> String {
+    let mut rendered = String::with_capacity(part.len());
+    let mut rest = part;
+
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find("}}").unwrap_or(after.len());
+        let name = after[..end].trim();
+        rendered.push_str(&format(variables.get(name).map_or("", String::as_str)));
+        rest = after.get(end + 2..).unwrap_or("");
+    }
+    rendered.push_str(rest);
+
+    rendered
+}
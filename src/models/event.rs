@@ -0,0 +1,154 @@
+//! # Event Types
+//!
+//! This module defines events and the phases of their timeline configured by admins.
+//! Users are shared by all events, while forms, previews, vetoes, matches and
+//! schedules belong to one event. In the current event, the current phase decides
+//! which user actions are open.
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use super::{TagNode, TraitEntry};
+
+/// A row of the `events` table, without its catalogs
+///
+/// Allowed domains and grades narrow down the deployment's allowed domains and
+/// their grades; left empty, they do not restrict anything.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Event {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub allowed_domains: Option<Vec<String>>,
+    pub allowed_grades: Option<Vec<String>>,
+    pub is_current: bool,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl Event {
+    /// Whether users of an allowed domain can sign up for the event
+    pub fn allows_domain(&self, domain: &str) -> bool {
+        self.allowed_domains
+            .as_ref()
+            .is_none_or(|domains| domains.iter().any(|allowed| allowed == domain))
+    }
+
+    /// Whether users of this grade can take part in the event
+    pub fn allows_grade(&self, grade: &str) -> bool {
+        self.allowed_grades
+            .as_ref()
+            .is_none_or(|grades| grades.iter().any(|allowed| allowed == grade))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateEventRequest {
+    pub slug: String,
+    pub name: String,
+    pub allowed_domains: Option<Vec<String>>,
+    pub allowed_grades: Option<Vec<String>>,
+    /// Tag tree in the format of `tags.json`
+    pub tags: Option<Vec<TagNode>>,
+    /// Trait list in the format of `traits.json`
+    pub traits: Option<Vec<TraitEntry>>,
+}
+
+/// Phases of the event, in their usual order.
+///
+/// This enum corresponds to the PostgreSQL `event_phase` enum type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "event_phase", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum EventPhase {
+    /// New users can sign up and fill in their form
+    Registration,
+    /// Only existing users can log in and fill in their form
+    FormFilling,
+    /// Match previews are published and users can veto candidates
+    PreviewVeto,
+    /// Final matching is running, no user action is open
+    FinalMatch,
+    /// Matched users accept or reject their final match
+    Confirmation,
+}
+
+/// User actions that are only open in some event phases
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhaseGate {
+    /// Sending a login code to an email without an account
+    SignUp,
+    /// Submitting or editing the form
+    SubmitForm,
+    /// Adding a veto
+    Veto,
+    /// Accepting or rejecting a final match
+    FinalMatchResponse,
+}
+
+impl EventPhase {
+    /// Whether `gate` is open during this phase
+    pub fn opens(self, gate: PhaseGate) -> bool {
+        match gate {
+            PhaseGate::SignUp => self == Self::Registration,
+            PhaseGate::SubmitForm => matches!(self, Self::Registration | Self::FormFilling),
+            PhaseGate::Veto => self == Self::PreviewVeto,
+            PhaseGate::FinalMatchResponse => self == Self::Confirmation,
+        }
+    }
+}
+
+impl PhaseGate {
+    /// Error message returned while the gate is closed
+    pub fn closed_message(self) -> &'static str {
+        match self {
+            Self::SignUp => "Registration is closed in the current event phase",
+            Self::SubmitForm => "Form submission is closed in the current event phase",
+            Self::Veto => "Vetoing is closed in the current event phase",
+            Self::FinalMatchResponse => {
+                "Responding to final matches is closed in the current event phase"
+            }
+        }
+    }
+}
+
+/// A row of the `event_phases` table
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct EventPhaseEntry {
+    pub id: Uuid,
+    pub phase: EventPhase,
+    #[serde(with = "time::serde::rfc3339")]
+    pub starts_at: OffsetDateTime,
+    /// Set by the scheduler once the phase has started
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub entered_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateEventPhaseRequest {
+    pub phase: EventPhase,
+    #[serde(with = "time::serde::rfc3339")]
+    pub starts_at: OffsetDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateEventPhasesRequest {
+    pub phases: Vec<CreateEventPhaseRequest>,
+}
+
+/// Current and next phase of the event timeline
+///
+/// `phase` is `None` if no phase has started yet, in which case no action is gated.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventPhaseResponse {
+    pub phase: Option<EventPhase>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub started_at: Option<OffsetDateTime>,
+    pub next_phase: Option<EventPhase>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub next_starts_at: Option<OffsetDateTime>,
+}
@@ -0,0 +1,72 @@
+//! # Match Feedback
+//!
+//! After both users confirmed their final match, each of them can rate it for
+//! `FEEDBACK_WINDOW_DAYS`. Aggregated by what the match was based on, the feedback
+//! shows admins whether the scoring produces good pairs.
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    models::GroupedStats,
+    utils::{
+        comment::{trim_comment, validate_comment},
+        constant::FEEDBACK_RATING_RANGE,
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MatchFeedbackRequest {
+    /// From 1 (poor) to 5 (great)
+    pub rating: i16,
+    /// Whether the users met in person
+    pub met: bool,
+    pub comment: Option<String>,
+}
+
+impl MatchFeedbackRequest {
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if !FEEDBACK_RATING_RANGE.contains(&self.rating) {
+            return Err("Rating must be between 1 and 5");
+        }
+        validate_comment(self.comment.as_deref())
+    }
+
+    /// The comment, `None` if blank
+    pub fn comment(&self) -> Option<&str> {
+        trim_comment(self.comment.as_deref())
+    }
+}
+
+/// Feedback of a user on their final match
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MatchFeedback {
+    pub final_match_id: Uuid,
+    pub user_id: Uuid,
+    pub rating: i16,
+    pub met: bool,
+    pub comment: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+/// Feedback statistics of a group of confirmed final matches
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FeedbackStats {
+    /// Confirmed final matches in the group
+    pub matches: i64,
+    /// Feedback given on them, up to two per match
+    pub responses: i64,
+    /// `None` without responses
+    pub average_rating: Option<f64>,
+    /// Share of responses stating the users met, `None` without responses
+    pub met_rate: Option<f64>,
+    /// Number of responses per rating, from 1 to 5
+    pub rating_counts: [i64; 5],
+}
+
+/// Feedback on the confirmed final matches of an event
+pub type FeedbackSummary = GroupedStats<FeedbackStats>;
@@ -1,16 +1,12 @@
-use std::collections::HashSet;
-
 use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
 use tracing::warn;
 use uuid::Uuid;
 
 use crate::{
-    handlers::FormRequest,
-    models::TagSystem,
-    utils::{
-        constant::*,
-        static_object::{TAGS_LIMIT_SUM, TRAITS, TRAITS_LIMIT_EACH},
-    },
+    handlers::{FormDraftRequest, FormRequest},
+    models::{Catalogs, FormAnswers, FormSchema, ValidationMode, Visibility},
+    utils::constant::*,
 };
 
 #[derive(Debug, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy)]
@@ -25,19 +21,66 @@ pub enum Gender {
 pub struct Form {
     pub user_id: Uuid,
     pub gender: Gender,
-    pub familiar_tags: Vec<String>,
-    pub aspirational_tags: Vec<String>,
-    pub recent_topics: String,
-    pub self_traits: Vec<String>,
-    pub ideal_traits: Vec<String>,
-    /// Between 1 and 4
-    pub physical_boundary: i16,
-    pub self_intro: String,
+    /// Answers to the questions of the [`FormSchema`]
+    #[serde(flatten)]
+    pub answers: Json<FormAnswers>,
     pub profile_photo_filename: Option<String>,
 }
 
+impl Form {
+    /// Returns true if any answer shown in match previews differs between the two forms.
+    ///
+    /// Vetoes are cast based on preview content, so such a change makes vetoes
+    /// against this user stale.
+    pub fn preview_differs(&self, other: &Form, schema: &FormSchema) -> bool {
+        schema.visible_answers(&self.answers, Visibility::Preview)
+            != schema.visible_answers(&other.answers, Visibility::Preview)
+    }
+}
+
+/// A snapshot of a user's form, recorded on every submission or edit
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FormRevision {
+    pub event_id: Uuid,
+    pub revision: i32,
+    pub gender: Gender,
+    #[serde(flatten)]
+    pub answers: Json<FormAnswers>,
+    pub profile_photo_filename: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: time::OffsetDateTime,
+}
+
+impl FormRevision {
+    /// Queries the form revisions of a user, oldest first, in one event or in all of them.
+    pub async fn list(
+        db_pool: &sqlx::PgPool,
+        user_id: &Uuid,
+        event_id: Option<Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            FormRevision,
+            r#"
+            SELECT event_id, revision, gender as "gender: Gender",
+                   answers as "answers: Json<FormAnswers>", profile_photo_filename, created_at
+            FROM form_revisions
+            WHERE user_id = $1 AND ($2::uuid IS NULL OR event_id = $2)
+            ORDER BY created_at, revision
+            "#,
+            user_id,
+            event_id
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+}
+
 impl FormRequest {
-    pub fn validate_request(&self, tag_system: &TagSystem) -> Result<(), &'static str> {
+    pub fn validate_request(
+        &self,
+        schema: &FormSchema,
+        catalogs: &Catalogs,
+    ) -> Result<(), &'static str> {
         // Validate wechat_id
         if self.wechat_id.is_empty() {
             warn!("wechat_id cannot be empty");
@@ -52,114 +95,58 @@ impl FormRequest {
             return Err("wechat_id too long");
         }
 
-        // Validate total tags limit
-        let tags_limit_sum = *TAGS_LIMIT_SUM;
-
-        let user_total_tags = self.familiar_tags.len() + self.aspirational_tags.len();
-        if user_total_tags > tags_limit_sum {
-            warn!(
-                "User submitted {} tags, exceeding limit of {}",
-                user_total_tags, tags_limit_sum
-            );
-            return Err("Total tags exceed limit");
-        }
-
-        // Validate all tags exist in the tag system
-        // Check for duplicate tags within familiar_tags and aspirational_tags
-        let mut all_tags = HashSet::new();
-        for tag in &self.familiar_tags {
-            if !tag_system.is_matchable(tag) {
-                warn!("Invalid familiar tag: {}", tag);
-                return Err("Invalid familiar tag");
-            }
-            if !all_tags.insert(tag) {
-                warn!("Duplicate tag found in familiar_tags: {}", tag);
-                return Err("Duplicate tag not allowed");
-            }
-        }
-        for tag in &self.aspirational_tags {
-            if !tag_system.is_matchable(tag) {
-                warn!("Invalid aspirational tag: {}", tag);
-                return Err("Invalid aspirational tag");
-            }
-            if !all_tags.insert(tag) {
-                warn!("Duplicate tag found in aspirational_tags: {}", tag);
-                return Err("Duplicate tag not allowed");
-            }
-        }
-
-        // Make sure written text is not too long
-        if self.recent_topics.len() > MAX_TEXT_FIELD_LENGTH {
-            warn!(
-                "recent_topics length {} exceeds max {}",
-                self.recent_topics.len(),
-                MAX_TEXT_FIELD_LENGTH
-            );
-            return Err("recent_topics too long");
-        }
-        if self.self_intro.len() > MAX_TEXT_FIELD_LENGTH {
-            warn!(
-                "self_intro length {} exceeds max {}",
-                self.self_intro.len(),
-                MAX_TEXT_FIELD_LENGTH
-            );
-            return Err("self_intro too long");
-        }
-
-        // Validate self_traits and ideal_traits tags exist and limits
-        let traits_limit = *TRAITS_LIMIT_EACH;
+        schema.validate_answers(&self.answers, catalogs, ValidationMode::Submission)
+    }
+}
 
-        if self.self_traits.len() > traits_limit {
-            warn!(
-                "self_traits count {} exceeds limit of {}",
-                self.self_traits.len(),
-                traits_limit
-            );
-            return Err("Too many self traits");
-        }
+impl FormDraftRequest {
+    /// Completes this submission with the saved `draft`: fields and answers given here
+    /// take precedence, the missing ones are taken from the draft.
+    ///
+    /// Returns an error if the result still lacks `wechat_id` or `gender`.
+    pub fn promote(self, draft: FormDraftRequest) -> Result<FormRequest, &'static str> {
+        let mut answers = draft.answers;
+        answers.extend(self.answers);
+
+        Ok(FormRequest {
+            wechat_id: self
+                .wechat_id
+                .or(draft.wechat_id)
+                .ok_or("wechat_id cannot be empty")?,
+            gender: self.gender.or(draft.gender).ok_or("gender is required")?,
+            profile_photo_filename: self.profile_photo_filename.or(draft.profile_photo_filename),
+            answers,
+        })
+    }
 
-        if self.ideal_traits.len() > traits_limit {
+    /// Relaxed validation for autosaved drafts.
+    ///
+    /// All answers are optional and choices are not checked against their catalogs;
+    /// only types and size limits are enforced so drafts cannot grow unbounded.
+    /// The full [`FormRequest::validate_request`] runs on final submission.
+    pub fn validate_draft(
+        &self,
+        schema: &FormSchema,
+        catalogs: &Catalogs,
+    ) -> Result<(), &'static str> {
+        if let Some(wechat_id) = &self.wechat_id
+            && wechat_id.len() > MAX_WECHAT_ID_LENGTH
+        {
             warn!(
-                "ideal_traits count {} exceeds limit of {}",
-                self.ideal_traits.len(),
-                traits_limit
+                "Draft wechat_id length {} exceeds max {}",
+                wechat_id.len(),
+                MAX_WECHAT_ID_LENGTH
             );
-            return Err("Too many ideal traits");
-        }
-
-        let mut self_traits_set = HashSet::new();
-        for trait_id in &self.self_traits {
-            if !TRAITS.contains(trait_id) {
-                warn!("Invalid self trait: {}", trait_id);
-                return Err("Invalid self trait");
-            }
-            if !self_traits_set.insert(trait_id) {
-                warn!("Duplicate trait found in self_traits: {}", trait_id);
-                return Err("Duplicate self trait not allowed");
-            }
-        }
-
-        let mut ideal_traits_set = HashSet::new();
-        for trait_id in &self.ideal_traits {
-            if !TRAITS.contains(trait_id) {
-                warn!("Invalid ideal trait: {}", trait_id);
-                return Err("Invalid ideal trait");
-            }
-            if !ideal_traits_set.insert(trait_id) {
-                warn!("Duplicate trait found in ideal_traits: {}", trait_id);
-                return Err("Duplicate ideal trait not allowed");
-            }
+            return Err("wechat_id too long");
         }
 
-        // Validate physical_boundary is between 1 and 4
-        if !(1..=4).contains(&self.physical_boundary) {
-            warn!(
-                "Invalid physical_boundary value: {}",
-                self.physical_boundary
-            );
-            return Err("physical_boundary must be between 1 and 4");
+        if let Some(filename) = &self.profile_photo_filename
+            && filename.len() > MAX_DRAFT_ID_LENGTH
+        {
+            warn!("Draft profile photo filename is too long");
+            return Err("Profile photo filename too long");
         }
 
-        Ok(())
+        schema.validate_answers(&self.answers, catalogs, ValidationMode::Draft)
     }
 }
@@ -0,0 +1,546 @@
+//! # Form Schema
+//!
+//! The questionnaire is defined in `form_schema.json` rather than hardcoded, so
+//! every event can ask different questions. A schema declares:
+//!
+//! - **Questions** - single/multi choice from a catalog or inline options, scales,
+//!   and free text with length limits
+//! - **Selection groups** - limits shared by several multi choice questions
+//! - **Scorers** - matching components and the questions they consume
+//!
+//! Answers are stored as JSONB keyed by question id and checked by the generic
+//! validator in [`FormSchema::validate_answers`].
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::warn;
+
+use crate::{models::Catalogs, utils::constant::MAX_DRAFT_ID_LENGTH};
+
+/// Keys of the form request that are not questions
+const RESERVED_KEYS: [&str; 3] = ["wechat_id", "gender", "profile_photo_filename"];
+
+#[derive(Debug, Error)]
+pub enum FormSchemaError {
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error("invalid form schema: {0}")]
+    Invalid(String),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FormSchema {
+    pub questions: Vec<Question>,
+    #[serde(default)]
+    pub selection_groups: Vec<SelectionGroup>,
+    #[serde(default)]
+    pub scorers: Vec<Scorer>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Question {
+    pub id: String,
+    #[serde(flatten)]
+    pub kind: QuestionKind,
+    #[serde(default = "default_required")]
+    pub required: bool,
+    #[serde(default)]
+    pub visibility: Visibility,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QuestionKind {
+    SingleChoice {
+        source: ChoiceSource,
+    },
+    MultiChoice {
+        source: ChoiceSource,
+        #[serde(default)]
+        min_selections: usize,
+        max_selections: usize,
+    },
+    /// Integer between `min` and `max`, inclusive
+    Scale {
+        min: i64,
+        max: i64,
+    },
+    /// Free text, limits are in **bytes**
+    Text {
+        #[serde(default)]
+        min_length: usize,
+        max_length: usize,
+    },
+}
+
+/// Where the choices of a choice question come from
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChoiceSource {
+    Catalog(Catalog),
+    Options(Vec<String>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Catalog {
+    /// Matchable tags from `tags.json`
+    Tags,
+    /// Matchable traits from `traits.json`
+    Traits,
+}
+
+/// Who may see the answer to a question besides the user and admins.
+///
+/// Levels are ordered: answers shown in previews are also shown to the final partner.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    #[default]
+    Private,
+    /// Shown to the final match partner
+    Partner,
+    /// Shown in match previews and to the final match partner
+    Preview,
+}
+
+/// A limit on the combined selections of several multi choice questions.
+///
+/// Choices must also be distinct across the questions of a group.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SelectionGroup {
+    pub questions: Vec<String>,
+    pub max_total: usize,
+}
+
+/// A matching score component and the questions it consumes
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Scorer {
+    /// Hierarchical tag matching with IDF weighting; familiar x aspirational
+    /// matches are weighted by `COMPLEMENTARY_TAG_WEIGHT`.
+    /// Both questions must be multi choice from the `tags` catalog.
+    TagOverlap {
+        familiar: String,
+        aspirational: String,
+    },
+    /// `TRAIT_MATCH_POINTS` for every ideal trait of one user the other has, and a
+    /// share decayed by `TRAIT_SCORE_DECAY_FACTOR` for a related trait of the catalog.
+    ///
+    /// Optionally, the unmet part of every ideal trait costs `missing_weight` points
+    /// and every own trait the other did not ask for costs `extra_weight` points, both
+    /// in units of `TRAIT_MATCH_POINTS`. Usually `missing_weight` is the larger one.
+    TraitMatch {
+        #[serde(rename = "self")]
+        own: String,
+        ideal: String,
+        #[serde(default)]
+        missing_weight: f64,
+        #[serde(default)]
+        extra_weight: f64,
+    },
+    /// Users whose answers differ by more than `max_difference` are incompatible;
+    /// equal answers get `BOUNDARY_MATCH_POINTS`
+    ScaleProximity {
+        question: String,
+        max_difference: i64,
+    },
+    /// `weight` times the TF-IDF cosine similarity of the users' combined answers
+    /// to the text questions (see [`crate::services::text_similarity`])
+    TextSimilarity { questions: Vec<String>, weight: f64 },
+}
+
+impl Scorer {
+    /// Ids of the questions whose answers this scorer reads
+    pub fn questions(&self) -> Vec<&str> {
+        match self {
+            Scorer::TagOverlap {
+                familiar,
+                aspirational,
+            } => vec![familiar, aspirational],
+            Scorer::TraitMatch { own, ideal, .. } => vec![own, ideal],
+            Scorer::ScaleProximity { question, .. } => vec![question],
+            Scorer::TextSimilarity { questions, .. } => {
+                questions.iter().map(String::as_str).collect()
+            }
+        }
+    }
+
+    /// Returns true if the scorer can consume answers to the question
+    fn accepts(&self, kind: &QuestionKind) -> bool {
+        match self {
+            Scorer::TagOverlap { .. } => matches!(
+                kind,
+                QuestionKind::MultiChoice {
+                    source: ChoiceSource::Catalog(Catalog::Tags),
+                    ..
+                }
+            ),
+            Scorer::TraitMatch { .. } => matches!(kind, QuestionKind::MultiChoice { .. }),
+            Scorer::ScaleProximity { .. } => matches!(kind, QuestionKind::Scale { .. }),
+            Scorer::TextSimilarity { .. } => matches!(kind, QuestionKind::Text { .. }),
+        }
+    }
+}
+
+/// The answer to a single question
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Scale(i64),
+    /// Free text or the id of a single choice
+    Text(String),
+    Choices(Vec<String>),
+}
+
+/// Answers of a form, keyed by question id
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FormAnswers(BTreeMap<String, Answer>);
+
+impl FormAnswers {
+    pub fn get(&self, question_id: &str) -> Option<&Answer> {
+        self.0.get(question_id)
+    }
+
+    /// Selected choices of a multi choice question, empty if unanswered
+    pub fn choices(&self, question_id: &str) -> &[String] {
+        match self.0.get(question_id) {
+            Some(Answer::Choices(choices)) => choices,
+            _ => &[],
+        }
+    }
+
+    pub fn scale(&self, question_id: &str) -> Option<i64> {
+        match self.0.get(question_id) {
+            Some(Answer::Scale(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn text(&self, question_id: &str) -> Option<&str> {
+        match self.0.get(question_id) {
+            Some(Answer::Text(text)) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Answer)> {
+        self.0.iter()
+    }
+}
+
+impl Extend<(String, Answer)> for FormAnswers {
+    fn extend<I: IntoIterator<Item = (String, Answer)>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
+impl IntoIterator for FormAnswers {
+    type Item = (String, Answer);
+    type IntoIter = std::collections::btree_map::IntoIter<String, Answer>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl FromIterator<(String, Answer)> for FormAnswers {
+    fn from_iter<I: IntoIterator<Item = (String, Answer)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+/// How strictly [`FormSchema::validate_answers`] checks answers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationMode {
+    /// Full validation of a submitted form
+    Submission,
+    /// Only types and size limits, so drafts cannot grow unbounded
+    Draft,
+}
+
+impl FormSchema {
+    /// Loads the schema from JSON and checks that it is consistent.
+    pub fn from_json(content: &str) -> Result<Self, FormSchemaError> {
+        let schema: FormSchema = serde_json::from_str(content)?;
+        schema.check()?;
+        Ok(schema)
+    }
+
+    pub fn question(&self, id: &str) -> Option<&Question> {
+        self.questions.iter().find(|q| q.id == id)
+    }
+
+    /// Ensures question ids are unique and that selection groups and scorers
+    /// reference existing questions of a suitable type.
+    fn check(&self) -> Result<(), FormSchemaError> {
+        let invalid = |msg: String| Err(FormSchemaError::Invalid(msg));
+
+        let mut ids = HashSet::new();
+        for question in &self.questions {
+            if RESERVED_KEYS.contains(&question.id.as_str()) {
+                return invalid(format!("question id '{}' is reserved", question.id));
+            }
+            if !ids.insert(question.id.as_str()) {
+                return invalid(format!("duplicate question id '{}'", question.id));
+            }
+            match &question.kind {
+                QuestionKind::Scale { min, max } if min > max => {
+                    return invalid(format!("scale '{}' has min > max", question.id));
+                }
+                QuestionKind::Text {
+                    min_length,
+                    max_length,
+                } if min_length > max_length => {
+                    return invalid(format!(
+                        "text '{}' has min_length > max_length",
+                        question.id
+                    ));
+                }
+                QuestionKind::MultiChoice {
+                    min_selections,
+                    max_selections,
+                    ..
+                } if min_selections > max_selections => {
+                    return invalid(format!(
+                        "multi choice '{}' has min_selections > max_selections",
+                        question.id
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        for group in &self.selection_groups {
+            for id in &group.questions {
+                match self.question(id).map(|q| &q.kind) {
+                    Some(QuestionKind::MultiChoice { .. }) => {}
+                    _ => {
+                        return invalid(format!(
+                            "selection group references '{id}', which is not a multi choice question"
+                        ));
+                    }
+                }
+            }
+        }
+
+        // The text corpus is built from the questions of a single scorer
+        let text_scorers = self
+            .scorers
+            .iter()
+            .filter(|s| matches!(s, Scorer::TextSimilarity { .. }))
+            .count();
+        if text_scorers > 1 {
+            return invalid("at most one text_similarity scorer is allowed".to_string());
+        }
+
+        for scorer in &self.scorers {
+            if let Scorer::TraitMatch {
+                missing_weight,
+                extra_weight,
+                ..
+            } = scorer
+                && (*missing_weight < 0.0 || *extra_weight < 0.0)
+            {
+                return invalid("trait_match weights must not be negative".to_string());
+            }
+
+            for id in scorer.questions() {
+                match self.question(id) {
+                    Some(question) if scorer.accepts(&question.kind) => {}
+                    Some(_) => {
+                        return invalid(format!("scorer cannot consume question '{id}'"));
+                    }
+                    None => return invalid(format!("scorer references unknown question '{id}'")),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates answers against the schema.
+    ///
+    /// In [`ValidationMode::Draft`], required answers, catalog membership, duplicates
+    /// and scale ranges are not checked.
+    pub fn validate_answers(
+        &self,
+        answers: &FormAnswers,
+        catalogs: &Catalogs,
+        mode: ValidationMode,
+    ) -> Result<(), &'static str> {
+        let strict = mode == ValidationMode::Submission;
+
+        for (id, answer) in answers.iter() {
+            let Some(question) = self.question(id) else {
+                warn!("Unknown question: {}", id);
+                return Err("Unknown question");
+            };
+
+            match (&question.kind, answer) {
+                (QuestionKind::SingleChoice { source }, Answer::Text(choice)) => {
+                    if choice.len() > MAX_DRAFT_ID_LENGTH {
+                        warn!("Choice for {} is too long", id);
+                        return Err("Choice too long");
+                    }
+                    if strict && !source.contains(choice, catalogs) {
+                        warn!("Invalid choice for {}: {}", id, choice);
+                        return Err("Invalid choice");
+                    }
+                }
+                (
+                    QuestionKind::MultiChoice {
+                        source,
+                        min_selections,
+                        max_selections,
+                    },
+                    Answer::Choices(choices),
+                ) => {
+                    if choices.len() > *max_selections {
+                        warn!(
+                            "{} has {} selections, exceeding limit of {}",
+                            id,
+                            choices.len(),
+                            max_selections
+                        );
+                        return Err("Too many selections");
+                    }
+                    if choices.iter().any(|c| c.len() > MAX_DRAFT_ID_LENGTH) {
+                        warn!("Choice for {} is too long", id);
+                        return Err("Choice too long");
+                    }
+                    if !strict {
+                        continue;
+                    }
+                    if choices.len() < *min_selections {
+                        warn!(
+                            "{} has {} selections, below minimum of {}",
+                            id,
+                            choices.len(),
+                            min_selections
+                        );
+                        return Err("Too few selections");
+                    }
+                    let mut seen = HashSet::new();
+                    for choice in choices {
+                        if !source.contains(choice, catalogs) {
+                            warn!("Invalid choice for {}: {}", id, choice);
+                            return Err("Invalid choice");
+                        }
+                        if !seen.insert(choice) {
+                            warn!("Duplicate choice found in {}: {}", id, choice);
+                            return Err("Duplicate choice not allowed");
+                        }
+                    }
+                }
+                (QuestionKind::Scale { min, max }, Answer::Scale(value)) => {
+                    if strict && !(min..=max).contains(&value) {
+                        warn!("Invalid {} value: {}", id, value);
+                        return Err("Scale answer out of range");
+                    }
+                }
+                (
+                    QuestionKind::Text {
+                        min_length,
+                        max_length,
+                    },
+                    Answer::Text(text),
+                ) => {
+                    if text.len() > *max_length {
+                        warn!("{} length {} exceeds max {}", id, text.len(), max_length);
+                        return Err("Text answer too long");
+                    }
+                    if strict && text.len() < *min_length {
+                        warn!("{} length {} is below min {}", id, text.len(), min_length);
+                        return Err("Text answer too short");
+                    }
+                }
+                _ => {
+                    warn!("Answer to {} has the wrong type", id);
+                    return Err("Answer has wrong type");
+                }
+            }
+        }
+
+        if strict
+            && let Some(question) = self
+                .questions
+                .iter()
+                .find(|q| q.required && answers.get(&q.id).is_none())
+        {
+            warn!("Missing answer to required question {}", question.id);
+            return Err("Missing required answer");
+        }
+
+        for group in &self.selection_groups {
+            let total: usize = group
+                .questions
+                .iter()
+                .map(|id| answers.choices(id).len())
+                .sum();
+            if total > group.max_total {
+                warn!(
+                    "{:?} have {} selections, exceeding limit of {}",
+                    group.questions, total, group.max_total
+                );
+                return Err("Total selections exceed limit");
+            }
+
+            if strict {
+                let mut seen = HashSet::new();
+                for choice in group.questions.iter().flat_map(|id| answers.choices(id)) {
+                    if !seen.insert(choice) {
+                        warn!(
+                            "Duplicate choice found in {:?}: {}",
+                            group.questions, choice
+                        );
+                        return Err("Duplicate choice not allowed");
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Keeps only the answers to questions visible at `level` or wider.
+    pub fn visible_answers(&self, answers: &FormAnswers, level: Visibility) -> FormAnswers {
+        let visibility: HashMap<&str, Visibility> = self
+            .questions
+            .iter()
+            .map(|q| (q.id.as_str(), q.visibility))
+            .collect();
+
+        answers
+            .iter()
+            .filter(|(id, _)| visibility.get(id.as_str()).is_some_and(|v| *v >= level))
+            .map(|(id, answer)| (id.clone(), answer.clone()))
+            .collect()
+    }
+
+    /// Ids of all questions consumed by [`Scorer::TagOverlap`] scorers
+    pub fn tag_questions(&self) -> impl Iterator<Item = &str> {
+        self.scorers
+            .iter()
+            .filter(|s| matches!(s, Scorer::TagOverlap { .. }))
+            .flat_map(Scorer::questions)
+    }
+}
+
+impl ChoiceSource {
+    fn contains(&self, choice: &str, catalogs: &Catalogs) -> bool {
+        match self {
+            ChoiceSource::Catalog(Catalog::Tags) => catalogs.tags.is_matchable(choice),
+            ChoiceSource::Catalog(Catalog::Traits) => catalogs.traits.is_matchable(choice),
+            ChoiceSource::Options(options) => options.iter().any(|o| o == choice),
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! # Match Statistics
+//!
+//! Feedback and rejections are both aggregated overall and broken down by what the
+//! final matches were based on: their score, the number of tags their users shared
+//! and the version of the matching algorithm.
+
+use serde::{Deserialize, Serialize};
+
+/// Statistics `S` of the matches whose score is at least `min_score` and below `max_score`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScoreBucketStats<S> {
+    pub min_score: f64,
+    pub max_score: f64,
+    #[serde(flatten)]
+    pub stats: S,
+}
+
+/// Statistics `S` of the matches whose users shared `tag_overlap` tags
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagOverlapStats<S> {
+    /// `None` for matches created before the overlap was recorded
+    pub tag_overlap: Option<i32>,
+    #[serde(flatten)]
+    pub stats: S,
+}
+
+/// Statistics `S` of the matches created by an algorithm version
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AlgorithmVersionStats<S> {
+    /// `None` for matches created before the version was recorded
+    pub algorithm_version: Option<String>,
+    #[serde(flatten)]
+    pub stats: S,
+}
+
+/// Statistics `S` of the final matches of an event, overall and by what they were based on
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupedStats<S> {
+    pub overall: S,
+    /// Ordered by score
+    pub by_score_bucket: Vec<ScoreBucketStats<S>>,
+    /// Ordered by tag overlap, unknown first
+    pub by_tag_overlap: Vec<TagOverlapStats<S>>,
+    /// Ordered by version, unknown first
+    pub by_algorithm_version: Vec<AlgorithmVersionStats<S>>,
+}
@@ -3,6 +3,11 @@ use sqlx::FromRow;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
+use crate::{
+    models::FormAnswers,
+    utils::constant::{DEFAULT_AUTO_ACCEPT_HOURS, DEFAULT_REMINDER_HOURS, MAX_AUTO_ACCEPT_HOURS},
+};
+
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct MatchPreview {
     pub id: Uuid,
@@ -30,20 +35,57 @@ pub struct VetoRequest {
     pub vetoed_id: Uuid,
 }
 
+/// Veto quota and window of a user in the current round
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VetoQuota {
+    /// Vetoes the user can still cast this round
+    pub remaining_quota: u32,
+    /// When the veto window closes, `None` if no final match is scheduled
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub window_closes_at: Option<OffsetDateTime>,
+}
+
+/// Match preview of a candidate
+///
+/// `familiar_tags`, `aspirational_tags` and `recent_topics` are the fields from before
+/// the form schema, kept for existing clients. They are empty unless the schema has a
+/// visible question with that id.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProfilePreview {
     pub candidate_id: Uuid,
     pub familiar_tags: Vec<String>,
     pub aspirational_tags: Vec<String>,
     pub recent_topics: String,
+    /// Answers to questions with `preview` visibility, keyed by question id
+    pub answers: FormAnswers,
     pub email_domain: String,
     pub grade: Option<String>,
 }
 
+impl ProfilePreview {
+    pub fn new(
+        candidate_id: Uuid,
+        answers: FormAnswers,
+        email_domain: String,
+        grade: Option<String>,
+    ) -> Self {
+        Self {
+            candidate_id,
+            familiar_tags: answers.choices("familiar_tags").to_vec(),
+            aspirational_tags: answers.choices("aspirational_tags").to_vec(),
+            recent_topics: answers.text("recent_topics").unwrap_or_default().to_owned(),
+            answers,
+            email_domain,
+            grade,
+        }
+    }
+}
+
 /// Profile information of the final match partner
 ///
-/// Containing: email domain, grade, familiar tags, aspirational tags,
-/// self introduction, and photo URL (if any).
+/// Containing: email domain, grade, answers to questions with `preview` or
+/// `partner` visibility, and photo URL (if any). As in [`ProfilePreview`], the
+/// answers of the questions from before the form schema are also given as fields.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FinalPartnerProfile {
     pub email_domain: String,
@@ -52,12 +94,36 @@ pub struct FinalPartnerProfile {
     pub aspirational_tags: Vec<String>,
     pub recent_topics: String,
     pub self_intro: String,
+    /// Answers to questions with `preview` or `partner` visibility, keyed by question id
+    pub answers: FormAnswers,
     /// Format: /api/images/partner/someuuid.ext
     pub photo_url: Option<String>,
     /// WeChat ID is included only if both users have accepted the match
     pub wechat_id: Option<String>,
 }
 
+impl FinalPartnerProfile {
+    pub fn new(
+        email_domain: String,
+        grade: Option<String>,
+        answers: FormAnswers,
+        photo_url: Option<String>,
+        wechat_id: Option<String>,
+    ) -> Self {
+        Self {
+            email_domain,
+            grade,
+            familiar_tags: answers.choices("familiar_tags").to_vec(),
+            aspirational_tags: answers.choices("aspirational_tags").to_vec(),
+            recent_topics: answers.text("recent_topics").unwrap_or_default().to_owned(),
+            self_intro: answers.text("self_intro").unwrap_or_default().to_owned(),
+            answers,
+            photo_url,
+            wechat_id,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "schedule_status", rename_all = "lowercase")]
 pub enum ScheduleStatus {
@@ -78,12 +144,66 @@ pub struct ScheduledFinalMatch {
     pub executed_at: Option<OffsetDateTime>,
     pub matches_created: Option<i32>,
     pub error_message: Option<String>,
+    pub auto_accept_hours: Option<i32>,
+    pub reminder_hours: Option<i32>,
+}
+
+impl ScheduledFinalMatch {
+    pub fn deadlines(&self) -> RoundDeadlines {
+        RoundDeadlines {
+            auto_accept_hours: self.auto_accept_hours,
+            reminder_hours: self.reminder_hours,
+        }
+    }
+}
+
+/// How long users of a final matching round have to respond to their match
+///
+/// Omitted fields use [`DEFAULT_AUTO_ACCEPT_HOURS`] and [`DEFAULT_REMINDER_HOURS`].
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct RoundDeadlines {
+    /// Hours after which matches are confirmed automatically
+    pub auto_accept_hours: Option<i32>,
+    /// Hours before the auto-confirmation at which users who have not responded are reminded
+    pub reminder_hours: Option<i32>,
+}
+
+impl RoundDeadlines {
+    pub fn auto_accept_hours(&self) -> i32 {
+        self.auto_accept_hours.unwrap_or(DEFAULT_AUTO_ACCEPT_HOURS)
+    }
+
+    pub fn reminder_hours(&self) -> i32 {
+        self.reminder_hours.unwrap_or(DEFAULT_REMINDER_HOURS)
+    }
+
+    /// When a match created at `created_at` is auto-confirmed and its users reminded
+    pub fn schedule(&self, created_at: OffsetDateTime) -> (OffsetDateTime, OffsetDateTime) {
+        let auto_accept_at = created_at + time::Duration::hours(self.auto_accept_hours().into());
+        let reminder_at = auto_accept_at - time::Duration::hours(self.reminder_hours().into());
+        (auto_accept_at, reminder_at)
+    }
+
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.auto_accept_hours() <= 0 || self.reminder_hours() <= 0 {
+            return Err("Deadlines must be positive numbers of hours");
+        }
+        if self.auto_accept_hours() > MAX_AUTO_ACCEPT_HOURS {
+            return Err("Matches must be auto-accepted within 30 days");
+        }
+        if self.reminder_hours() >= self.auto_accept_hours() {
+            return Err("Reminder must be sent before matches are auto-accepted");
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateScheduledMatchRequest {
     #[serde(with = "time::serde::rfc3339")]
     pub scheduled_time: OffsetDateTime,
+    #[serde(flatten)]
+    pub deadlines: RoundDeadlines,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -1,15 +1,50 @@
+mod audit;
+mod domain;
+mod email_provider;
+mod email_template;
+mod event;
+mod feedback;
 mod form;
+mod form_schema;
+mod match_stats;
 mod matching;
+mod notification;
+mod outbox;
+mod rejection;
 mod state;
 mod tag;
+mod user_event;
 mod user_status;
 
-pub use form::{Form, Gender};
+pub use audit::{AdminAction, AuditLogEntry, NewAuditEntry};
+pub use domain::{AllowedDomain, AllowedDomainRequest, AllowedDomains};
+pub use email_provider::{CircuitState, ProviderHealth};
+pub use email_template::{EmailMessage, EmailTemplates, TemplateError, TemplateId};
+pub use event::{
+    CreateEventPhaseRequest, CreateEventPhasesRequest, CreateEventRequest, Event, EventPhase,
+    EventPhaseEntry, EventPhaseResponse, PhaseGate,
+};
+pub use feedback::{FeedbackStats, FeedbackSummary, MatchFeedback, MatchFeedbackRequest};
+pub use form::{Form, FormRevision, Gender};
+pub use form_schema::{
+    Answer, Catalog, ChoiceSource, FormAnswers, FormSchema, FormSchemaError, Question,
+    QuestionKind, Scorer, SelectionGroup, ValidationMode, Visibility,
+};
+pub use match_stats::{AlgorithmVersionStats, GroupedStats, ScoreBucketStats, TagOverlapStats};
 pub use matching::{
     CreateScheduledMatchRequest, CreateScheduledMatchesRequest, FinalMatch, FinalPartnerProfile,
-    MatchPreview, NextMatchTimeResponse, ProfilePreview, ScheduleStatus, ScheduledFinalMatch, Veto,
-    VetoRequest,
+    MatchPreview, NextMatchTimeResponse, ProfilePreview, RoundDeadlines, ScheduleStatus,
+    ScheduledFinalMatch, Veto, VetoQuota, VetoRequest,
+};
+pub use notification::{
+    MatchEvent, Notification, NotificationPreferences, UpdateNotificationPreferencesRequest,
+};
+pub use outbox::{EmailStatus, NewEmail, OutboxEmail};
+pub use rejection::{
+    MatchRejection, ReasonRejections, RejectFinalMatchRequest, RejectionReasonsResponse,
+    RejectionStats, RejectionSummary,
 };
 pub use state::AppState;
-pub use tag::{TagNode, TagSystem};
+pub use tag::{Catalogs, TagNode, TagSystem, TraitEntry};
+pub use user_event::{NewUserEvent, StreamPosition, UserEvent, UserEventKind};
 pub use user_status::UserStatus;
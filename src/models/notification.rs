@@ -0,0 +1,88 @@
+//! # Match Notifications
+//!
+//! Users are notified by email about changes to their final match, so they do
+//! not have to poll their profile. Each kind of notification can be turned off.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Match lifecycle events users are notified about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchEvent {
+    /// Final matching paired the user with a partner
+    FinalMatchCreated { auto_accept_hours: i32 },
+    /// The partner accepted the final match
+    PartnerAccepted,
+    /// The partner rejected the final match, reverting the user to `form_completed`
+    MatchRejected,
+    /// The partner was suspended, dissolving the final match and reverting the user
+    /// to `form_completed`
+    MatchDissolved,
+    /// The final match was confirmed automatically after the response window
+    MatchAutoConfirmed,
+    /// The user has not responded and the match is confirmed automatically soon
+    MatchReminder { hours_left: i64 },
+}
+
+/// A match event addressed to one user
+#[derive(Debug, Clone, Copy)]
+pub struct Notification {
+    pub user_id: Uuid,
+    pub event: MatchEvent,
+}
+
+impl Notification {
+    pub fn new(user_id: Uuid, event: MatchEvent) -> Self {
+        Self { user_id, event }
+    }
+}
+
+/// Which notifications a user receives
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationPreferences {
+    pub final_match_created: bool,
+    pub partner_accepted: bool,
+    pub match_rejected: bool,
+    pub match_dissolved: bool,
+    pub match_auto_confirmed: bool,
+    pub match_reminder: bool,
+}
+
+impl Default for NotificationPreferences {
+    /// Every notification is enabled unless the user opts out
+    fn default() -> Self {
+        Self {
+            final_match_created: true,
+            partner_accepted: true,
+            match_rejected: true,
+            match_dissolved: true,
+            match_auto_confirmed: true,
+            match_reminder: true,
+        }
+    }
+}
+
+impl NotificationPreferences {
+    pub fn allows(&self, event: MatchEvent) -> bool {
+        match event {
+            MatchEvent::FinalMatchCreated { .. } => self.final_match_created,
+            MatchEvent::PartnerAccepted => self.partner_accepted,
+            MatchEvent::MatchRejected => self.match_rejected,
+            MatchEvent::MatchDissolved => self.match_dissolved,
+            MatchEvent::MatchAutoConfirmed => self.match_auto_confirmed,
+            MatchEvent::MatchReminder { .. } => self.match_reminder,
+        }
+    }
+}
+
+/// Request payload for changing notification preferences; omitted fields are kept
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UpdateNotificationPreferencesRequest {
+    pub final_match_created: Option<bool>,
+    pub partner_accepted: Option<bool>,
+    pub match_rejected: Option<bool>,
+    pub match_dissolved: Option<bool>,
+    pub match_auto_confirmed: Option<bool>,
+    pub match_reminder: Option<bool>,
+}
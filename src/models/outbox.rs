@@ -0,0 +1,111 @@
+//! # Email Outbox
+//!
+//! Emails are written to the `email_outbox` table and delivered by a background
+//! worker, so a slow or failing email provider never fails the request that sent
+//! them. Failed deliveries are retried with exponential backoff until they succeed,
+//! expire or run out of attempts. Sent emails record the provider that accepted them.
+//!
+//! The outbox stores the template, locale and variables of an email; it is
+//! rendered from the [`super::EmailTemplates`] on every delivery attempt.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use super::TemplateId;
+use crate::utils::constant::{DEFAULT_EMAIL_LOCALE, VERIFICATION_CODE_EXPIRY};
+
+/// Delivery status of an email in the outbox.
+///
+/// This enum corresponds to the PostgreSQL `email_status` enum type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "email_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum EmailStatus {
+    /// Waiting for its first or next delivery attempt
+    Pending,
+    /// Dead letter: out of attempts or expired, no longer retried
+    Failed,
+    /// Delivered, kept for inspection until it is purged
+    Sent,
+}
+
+/// An email to be written to the outbox
+#[derive(Debug)]
+pub struct NewEmail {
+    pub template: TemplateId,
+    pub recipient: String,
+    pub locale: String,
+    pub variables: BTreeMap<String, String>,
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+impl NewEmail {
+    /// An email in the default locale
+    pub fn new<'a>(
+        recipient: &str,
+        template: TemplateId,
+        variables: impl IntoIterator<Item = (&'a str, String)>,
+    ) -> Self {
+        Self {
+            template,
+            recipient: recipient.to_string(),
+            locale: DEFAULT_EMAIL_LOCALE.to_string(),
+            variables: variables
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+            expires_at: None,
+        }
+    }
+
+    /// The same email in another locale
+    pub fn in_locale(self, locale: &str) -> Self {
+        Self {
+            locale: locale.to_string(),
+            ..self
+        }
+    }
+
+    /// An email carrying a login code, which is not sent after the code expired
+    pub fn verification_code(recipient: &str, code: &str, expires_at: OffsetDateTime) -> Self {
+        let expiry_minutes = VERIFICATION_CODE_EXPIRY.as_secs() / 60;
+        Self {
+            expires_at: Some(expires_at),
+            ..Self::new(
+                recipient,
+                TemplateId::VerificationCode,
+                [
+                    ("code", code.to_string()),
+                    ("expiry_minutes", expiry_minutes.to_string()),
+                ],
+            )
+        }
+    }
+}
+
+/// An email in the outbox as shown to admins, without its variables
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct OutboxEmail {
+    pub id: Uuid,
+    pub template: TemplateId,
+    pub locale: String,
+    pub recipient: String,
+    pub subject: String,
+    pub status: EmailStatus,
+    pub attempts: i32,
+    #[serde(with = "time::serde::rfc3339")]
+    pub next_attempt_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+    pub last_error: Option<String>,
+    /// Name of the provider that accepted the email, once it was sent
+    pub provider: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub sent_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
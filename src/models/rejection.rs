@@ -0,0 +1,107 @@
+//! # Match Rejections
+//!
+//! Users rejecting their final match can give one of the configured
+//! `REJECTION_REASONS` and a comment. Rejected matches are kept with the reason,
+//! only visible to admins, so they can spot pairs the scoring gets wrong.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    models::GroupedStats,
+    utils::comment::{trim_comment, validate_comment},
+};
+
+/// Optional body of a final match rejection
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RejectFinalMatchRequest {
+    /// One of the configured rejection reasons
+    pub reason: Option<String>,
+    pub comment: Option<String>,
+}
+
+impl RejectFinalMatchRequest {
+    pub fn validate(&self, reasons: &[String]) -> Result<(), &'static str> {
+        if let Some(reason) = &self.reason
+            && !reasons.contains(reason)
+        {
+            return Err("Unknown rejection reason");
+        }
+        validate_comment(self.comment.as_deref())
+    }
+
+    /// The comment, `None` if blank
+    pub fn comment(&self) -> Option<&str> {
+        trim_comment(self.comment.as_deref())
+    }
+}
+
+/// Reasons users can give when rejecting a final match
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RejectionReasonsResponse {
+    pub reasons: Vec<String>,
+}
+
+/// A final match rejected by one of its users or deleted by an admin
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MatchRejection {
+    pub id: Uuid,
+    pub final_match_id: Uuid,
+    /// `None` if an admin deleted the match
+    pub rejected_by: Option<Uuid>,
+    pub rejected_by_email: Option<String>,
+    /// `None` if the partner deleted their account or an admin deleted the match
+    pub partner_id: Option<Uuid>,
+    pub partner_email: Option<String>,
+    /// Admin who deleted the match, `None` if a user rejected it
+    pub deleted_by: Option<String>,
+    pub score: f64,
+    pub tag_overlap: Option<i32>,
+    pub algorithm_version: Option<String>,
+    pub reason: Option<String>,
+    pub comment: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub matched_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub rejected_at: OffsetDateTime,
+}
+
+/// Rejections of a group of final matches
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RejectionStats {
+    /// Final matches created in the group, rejected or not
+    pub matches: i64,
+    pub rejections: i64,
+    /// Share of the matches that were rejected, `None` without matches
+    pub rejection_rate: Option<f64>,
+    /// Number of rejections per reason given
+    pub reasons: BTreeMap<String, i64>,
+    /// Number of rejections without a reason
+    pub without_reason: i64,
+    /// Matches deleted by an admin, counted as matches but not as rejections
+    pub deleted_by_admin: i64,
+}
+
+/// Rejections giving a reason
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReasonRejections {
+    /// `None` for rejections without a reason
+    pub reason: Option<String>,
+    pub rejections: i64,
+    /// Average score of the rejected matches
+    pub average_score: f64,
+    /// Average number of tags the users shared, `None` if never recorded
+    pub average_tag_overlap: Option<f64>,
+}
+
+/// Rejections of the final matches of an event
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RejectionSummary {
+    #[serde(flatten)]
+    pub stats: GroupedStats<RejectionStats>,
+    /// Ordered by number of rejections, most first
+    pub by_reason: Vec<ReasonRejections>,
+}
@@ -5,7 +5,10 @@ use sqlx::PgPool;
 use tracing::{debug, info, instrument};
 
 use crate::{
-    services::{email::EmailService, jwt::JwtService},
+    services::{
+        domain::DomainRegistry, email::EmailService, jwt::JwtService, moderation::SuspensionNotice,
+        user_event::UserEventHub,
+    },
     utils::constant::*,
 };
 
@@ -21,6 +24,10 @@ pub struct AppState {
     pub db_pool: PgPool,
     /// JWT service for token generation and validation.
     pub jwt_service: JwtService,
+    /// Email domains allowed to sign up, reloaded when admins change them.
+    pub allowed_domains: Arc<DomainRegistry>,
+    /// User events fanned out to the event streams connected to this server.
+    pub user_events: Arc<UserEventHub>,
 }
 
 impl AppState {
@@ -46,15 +53,19 @@ impl AppState {
             rate_limit_cache: DashMap::new(),
             verification_code_cache: DashMap::new(),
             email_service,
+            allowed_domains: Arc::new(DomainRegistry::new(db_pool.clone())),
+            user_events: Arc::new(UserEventHub::new(db_pool.clone())),
             db_pool,
             jwt_service,
         }
     }
 
-    /// Cleans up expired entries from both verification code and rate limit caches.
+    /// Cleans up expired entries from the verification code, rate limit and suspension
+    /// caches.
     ///
     /// This method is called periodically to prevent memory leaks from expired entries.
-    /// Only performs cleanup when cache size exceeds the configured capacity.
+    /// Only performs cleanup when cache size exceeds the configured capacity, apart from
+    /// the suspension cache, which is cleaned up every time.
     #[instrument(skip_all)]
     pub fn cleanup_expired_entries(&self) {
         let verification_cache_size = self.verification_code_cache.len();
@@ -94,5 +105,7 @@ impl AppState {
                 "Cleaned up expired rate limit entries"
             );
         }
+
+        SuspensionNotice::cleanup_cache();
     }
 }
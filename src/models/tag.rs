@@ -30,15 +30,20 @@ impl TagSystem {
     pub fn from_json(content: &str) -> Result<Self, serde_json::Error> {
         let nodes: Vec<TagNode> = serde_json::from_str(content)?;
 
+        Ok(Self::from_nodes(&nodes))
+    }
+
+    /// Builds the tag system from already parsed tag nodes.
+    pub fn from_nodes(nodes: &[TagNode]) -> Self {
         let mut parent_map = HashMap::new();
         let mut matchable_map = HashMap::new();
 
-        Self::build_maps(&nodes, None, &mut parent_map, &mut matchable_map);
+        Self::build_maps(nodes, None, &mut parent_map, &mut matchable_map);
 
-        Ok(TagSystem {
+        TagSystem {
             parent_map,
             matchable_map,
-        })
+        }
     }
 
     /// Recursively builds the parent and matchable maps from the tag nodes.
@@ -84,3 +89,77 @@ impl TagSystem {
         ancestors
     }
 }
+
+/// An entry of `traits.json`.
+///
+/// Traits are listed flat, as clients have always read them. Traits naming the same
+/// `group` are related: the group becomes their common, non-matchable parent.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TraitEntry {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+}
+
+impl TraitEntry {
+    /// Builds the trait tree, with one parent node per group in order of appearance
+    pub fn tree(entries: &[TraitEntry]) -> Vec<TagNode> {
+        let mut tree: Vec<TagNode> = Vec::new();
+        for entry in entries {
+            let node = TagNode {
+                id: entry.id.clone(),
+                name: entry.name.clone(),
+                desc: None,
+                children: None,
+                is_matchable: true,
+            };
+            let Some(group) = &entry.group else {
+                tree.push(node);
+                continue;
+            };
+
+            match tree.iter_mut().find(|parent| &parent.id == group) {
+                Some(parent) => parent.children.get_or_insert_with(Vec::new).push(node),
+                None => tree.push(TagNode {
+                    id: group.clone(),
+                    name: group.clone(),
+                    desc: None,
+                    children: Some(vec![node]),
+                    is_matchable: false,
+                }),
+            }
+        }
+        tree
+    }
+}
+
+/// The tag and trait catalogs of an event.
+///
+/// Events without their own catalogs use the ones loaded from `tags.json` and
+/// `traits.json`.
+pub struct Catalogs {
+    pub tags: TagSystem,
+    pub traits: TagSystem,
+    /// The tag nodes `tags` was built from, kept for the admin tag statistics
+    pub tag_tree: Vec<TagNode>,
+}
+
+impl Catalogs {
+    /// Builds the catalogs from the tag tree and the trait list.
+    pub fn new(tag_tree: Vec<TagNode>, traits: &[TraitEntry]) -> Self {
+        Catalogs {
+            tags: TagSystem::from_nodes(&tag_tree),
+            traits: TagSystem::from_nodes(&TraitEntry::tree(traits)),
+            tag_tree,
+        }
+    }
+
+    /// Loads the catalogs from a JSON tag tree and a JSON trait list.
+    pub fn from_json(tags: &str, traits: &str) -> Result<Self, serde_json::Error> {
+        let tag_tree: Vec<TagNode> = serde_json::from_str(tags)?;
+        let traits: Vec<TraitEntry> = serde_json::from_str(traits)?;
+
+        Ok(Self::new(tag_tree, &traits))
+    }
+}
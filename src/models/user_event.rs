@@ -0,0 +1,108 @@
+//! # User Events
+//!
+//! Changes users would otherwise poll for are recorded in `user_events` and
+//! pushed to them over the event stream. Status changes and new previews are
+//! recorded by database triggers, other events by the code making the change.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::models::NextMatchTimeResponse;
+
+/// Kind of a user event, sent as the SSE event name.
+///
+/// This enum corresponds to the PostgreSQL `user_event_kind` enum type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "user_event_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum UserEventKind {
+    /// The status of the user changed, data: `{"status": ...}`
+    StatusChanged,
+    /// New match previews were generated for the user
+    PreviewsUpdated,
+    /// The partner accepted the final match
+    PartnerAccepted,
+    /// The next final match time of the current event changed, data: [`NextMatchTimeResponse`]
+    NextMatchTimeChanged,
+}
+
+impl UserEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserEventKind::StatusChanged => "status_changed",
+            UserEventKind::PreviewsUpdated => "previews_updated",
+            UserEventKind::PartnerAccepted => "partner_accepted",
+            UserEventKind::NextMatchTimeChanged => "next_match_time_changed",
+        }
+    }
+}
+
+/// Position of an event in the event stream
+///
+/// Ids are taken when events are recorded, so an event can commit after one with a
+/// higher id. Events are streamed in the order of the transactions recording them
+/// instead, once every older transaction has finished, so a stream resumed after an
+/// event never skips one committed later.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StreamPosition {
+    /// Id of the transaction that recorded the event
+    pub xact_id: i64,
+    pub id: i64,
+}
+
+/// A recorded user event
+#[derive(Debug, Clone)]
+pub struct UserEvent {
+    /// Unique id, sent as the SSE event id to resume the stream
+    pub id: i64,
+    /// Id of the transaction that recorded the event
+    pub xact_id: i64,
+    /// `None` for events addressed to every user
+    pub user_id: Option<Uuid>,
+    pub kind: UserEventKind,
+    pub data: Value,
+    pub created_at: OffsetDateTime,
+}
+
+impl UserEvent {
+    /// Whether the event is addressed to the user
+    pub fn is_for(&self, user_id: Uuid) -> bool {
+        self.user_id.is_none_or(|id| id == user_id)
+    }
+
+    pub fn position(&self) -> StreamPosition {
+        StreamPosition {
+            xact_id: self.xact_id,
+            id: self.id,
+        }
+    }
+}
+
+/// A user event to record
+#[derive(Debug, Clone)]
+pub struct NewUserEvent {
+    pub user_id: Option<Uuid>,
+    pub kind: UserEventKind,
+    pub data: Value,
+}
+
+impl NewUserEvent {
+    pub fn partner_accepted(user_id: Uuid) -> Self {
+        Self {
+            user_id: Some(user_id),
+            kind: UserEventKind::PartnerAccepted,
+            data: json!({}),
+        }
+    }
+
+    /// Addressed to every user
+    pub fn next_match_time_changed(next: Option<OffsetDateTime>) -> Self {
+        Self {
+            user_id: None,
+            kind: UserEventKind::NextMatchTimeChanged,
+            data: serde_json::to_value(NextMatchTimeResponse { next }).unwrap_or_default(),
+        }
+    }
+}
@@ -23,6 +23,13 @@ use crate::error::{AppError, AppResult};
 /// - `FormCompleted` - Form completed, waiting to be matched
 /// - `Matched` - Matched pair generated, awaiting confirmation from both parties
 /// - `Confirmed` - Match confirmed
+///
+/// A `FormCompleted` user can leave the matching pool without losing their form:
+/// `Paused` keeps the form editable, `Withdrawn` locks it. Both return to
+/// `FormCompleted` on resume.
+///
+/// `Suspended` can be entered from any status by an admin and is left by lifting
+/// the suspension, which restores the previous status.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
 #[sqlx(type_name = "user_status", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
@@ -39,6 +46,12 @@ pub enum UserStatus {
     Matched,
     /// Match confirmed
     Confirmed,
+    /// Taken out of the event by an admin
+    Suspended,
+    /// Form completed, temporarily out of the matching pool
+    Paused,
+    /// Form completed, withdrawn from the matching pool
+    Withdrawn,
 }
 
 impl std::fmt::Display for UserStatus {
@@ -50,6 +63,9 @@ impl std::fmt::Display for UserStatus {
             UserStatus::FormCompleted => "form_completed",
             UserStatus::Matched => "matched",
             UserStatus::Confirmed => "confirmed",
+            UserStatus::Suspended => "suspended",
+            UserStatus::Paused => "paused",
+            UserStatus::Withdrawn => "withdrawn",
         };
         write!(f, "{status_str}")
     }
@@ -64,10 +80,14 @@ impl UserStatus {
     }
 
     /// Returns true if the user is allowed to fill/update a form or upload a profile photo.
-    /// Only verified users have access. Updates are not allowed after form completion.
+    /// Updates are not allowed once a final match is pending or confirmed, or after
+    /// withdrawing from matching.
     #[inline]
     pub fn can_fill_form(&self) -> bool {
-        matches!(self, UserStatus::Verified)
+        matches!(
+            self,
+            UserStatus::Verified | UserStatus::FormCompleted | UserStatus::Paused
+        )
     }
 
     /// Returns true if the user has completed card verification.
@@ -79,9 +99,23 @@ impl UserStatus {
                 | UserStatus::FormCompleted
                 | UserStatus::Matched
                 | UserStatus::Confirmed
+                | UserStatus::Paused
+                | UserStatus::Withdrawn
         )
     }
 
+    /// Returns true if the user can leave the matching pool with pause or withdraw.
+    #[inline]
+    pub fn can_leave_pool(&self) -> bool {
+        matches!(self, UserStatus::FormCompleted | UserStatus::Paused)
+    }
+
+    /// Returns true if the user has been suspended by an admin.
+    #[inline]
+    pub fn is_suspended(&self) -> bool {
+        matches!(self, UserStatus::Suspended)
+    }
+
     /// Queries the database for the user's status by their user ID.
     pub async fn query(db_pool: &sqlx::PgPool, user_id: &uuid::Uuid) -> AppResult<Self> {
         let user_status_result = sqlx::query!(
@@ -0,0 +1,109 @@
+//! # Admin Audit Service
+//!
+//! This module persists and queries the admin audit log. Entries are written
+//! through a borrowed connection so that callers can insert them inside the
+//! same transaction as the change being audited.
+
+use sqlx::{PgConnection, PgPool};
+use time::OffsetDateTime;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::models::{AdminAction, AuditLogEntry, NewAuditEntry, UserStatus};
+
+/// Filters for listing audit log entries. `None` fields match everything.
+#[derive(Debug, Default)]
+pub struct AuditFilter {
+    pub actor: Option<String>,
+    pub action: Option<AdminAction>,
+    pub target_id: Option<Uuid>,
+    pub since: Option<OffsetDateTime>,
+    pub until: Option<OffsetDateTime>,
+}
+
+pub struct AuditService;
+
+impl AuditService {
+    /// Writes an audit entry using the given connection (usually a transaction).
+    pub async fn record(
+        conn: &mut PgConnection,
+        entry: &NewAuditEntry<'_>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO admin_audit_log
+                (actor, action, target_ids, payload, status_before, status_after)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            entry.actor,
+            entry.action as AdminAction,
+            &entry.target_ids,
+            entry.payload,
+            entry.status_before as Option<UserStatus>,
+            entry.status_after as Option<UserStatus>,
+        )
+        .execute(conn)
+        .await?;
+
+        debug!(actor = %entry.actor, action = ?entry.action, "Audit entry recorded");
+        Ok(())
+    }
+
+    /// Counts the audit entries matching the filter.
+    pub async fn count(db_pool: &PgPool, filter: &AuditFilter) -> Result<i64, sqlx::Error> {
+        let total = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*)
+            FROM admin_audit_log
+            WHERE ($1::text IS NULL OR actor = $1)
+              AND ($2::admin_action IS NULL OR action = $2)
+              AND ($3::uuid IS NULL OR $3 = ANY(target_ids))
+              AND ($4::timestamptz IS NULL OR created_at >= $4)
+              AND ($5::timestamptz IS NULL OR created_at < $5)
+            "#,
+            filter.actor,
+            filter.action as Option<AdminAction>,
+            filter.target_id,
+            filter.since,
+            filter.until,
+        )
+        .fetch_one(db_pool)
+        .await?;
+
+        Ok(total.unwrap_or(0))
+    }
+
+    /// Lists audit entries matching the filter, newest first.
+    pub async fn list(
+        db_pool: &PgPool,
+        filter: &AuditFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AuditLogEntry>, sqlx::Error> {
+        sqlx::query_as!(
+            AuditLogEntry,
+            r#"
+            SELECT id, actor, action as "action: AdminAction", target_ids, payload,
+                   status_before as "status_before: UserStatus",
+                   status_after as "status_after: UserStatus", created_at
+            FROM admin_audit_log
+            WHERE ($1::text IS NULL OR actor = $1)
+              AND ($2::admin_action IS NULL OR action = $2)
+              AND ($3::uuid IS NULL OR $3 = ANY(target_ids))
+              AND ($4::timestamptz IS NULL OR created_at >= $4)
+              AND ($5::timestamptz IS NULL OR created_at < $5)
+            ORDER BY created_at DESC
+            LIMIT $6 OFFSET $7
+            "#,
+            filter.actor,
+            filter.action as Option<AdminAction>,
+            filter.target_id,
+            filter.since,
+            filter.until,
+            limit,
+            offset,
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+}
@@ -0,0 +1,351 @@
+//! # Allowed Domain Service
+//!
+//! Allowed email domains are managed by admins at runtime and stored in the
+//! `allowed_domains` table. Every change fires a `NOTIFY` on
+//! [`ALLOWED_DOMAINS_CHANNEL`], upon which running servers drop their cached set
+//! and load it again on the next sign-up, so no redeploy is needed.
+//!
+//! # Environment Variables
+//!
+//! - `ALLOWED_DOMAINS` - Colon-separated domains allowed at startup if the table is empty
+//!   (e.g., "example.com:university.edu")
+//! - `ALLOWED_GRADES` - Colon-separated grades of those domains
+//!   (defaults to "undergraduate:graduate")
+
+use std::{
+    env,
+    sync::{
+        Arc, PoisonError, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use sqlx::{PgConnection, PgPool, postgres::PgListener};
+use tracing::{debug, error, info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{AllowedDomain, AllowedDomainRequest, AllowedDomains},
+    utils::constant::DOMAIN_LISTENER_RETRY_INTERVAL,
+};
+
+/// Channel notified by the database whenever `allowed_domains` changes
+pub const ALLOWED_DOMAINS_CHANNEL: &str = "allowed_domains_changed";
+
+/// In-memory set of allowed domains, reloaded after every change
+pub struct DomainRegistry {
+    db_pool: PgPool,
+    domains: RwLock<Option<Arc<AllowedDomains>>>,
+    /// Bumped on every invalidation, so a load racing with a change is not cached
+    generation: AtomicU64,
+}
+
+impl DomainRegistry {
+    pub fn new(db_pool: PgPool) -> Self {
+        Self {
+            db_pool,
+            domains: RwLock::new(None),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Get the current set of allowed domains, loading it if it is not cached
+    pub async fn get(&self) -> AppResult<Arc<AllowedDomains>> {
+        if let Some(domains) = self
+            .domains
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+        {
+            return Ok(Arc::clone(domains));
+        }
+
+        let generation = self.generation.load(Ordering::Acquire);
+        let domains = Arc::new(AllowedDomains(
+            DomainService::get_all_domains(&self.db_pool).await?,
+        ));
+        debug!(count = domains.0.len(), "Loaded allowed domains");
+
+        let mut cached = self.domains.write().unwrap_or_else(PoisonError::into_inner);
+        if self.generation.load(Ordering::Acquire) == generation {
+            *cached = Some(Arc::clone(&domains));
+        }
+        Ok(domains)
+    }
+
+    /// Drop the cached set so that the next lookup reloads it
+    pub fn invalidate(&self) {
+        let mut cached = self.domains.write().unwrap_or_else(PoisonError::into_inner);
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        *cached = None;
+    }
+
+    /// Spawn the task that invalidates the cached set whenever the table changes
+    pub fn spawn_reload_task(self: &Arc<Self>) {
+        let registry = Arc::clone(self);
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = registry.listen().await {
+                    error!(error = %e, "Allowed domains listener failed");
+                }
+                // Changes may have been missed while not listening
+                registry.invalidate();
+                tokio::time::sleep(DOMAIN_LISTENER_RETRY_INTERVAL).await;
+            }
+        });
+    }
+
+    async fn listen(&self) -> Result<(), sqlx::Error> {
+        let mut listener = PgListener::connect_with(&self.db_pool).await?;
+        listener.listen(ALLOWED_DOMAINS_CHANNEL).await?;
+        // Changes made before listening started are not cached either
+        self.invalidate();
+
+        loop {
+            match listener.try_recv().await? {
+                Some(_) => debug!("Allowed domains changed"),
+                None => warn!("Allowed domains listener reconnected"),
+            }
+            self.invalidate();
+        }
+    }
+}
+
+/// Normalized fields of an [`AllowedDomainRequest`]
+struct DomainFields {
+    domain: String,
+    display_name: String,
+    include_subdomains: bool,
+    allowed_grades: Vec<String>,
+}
+
+pub struct DomainService;
+
+impl DomainService {
+    /// Get all allowed domains, ordered by domain
+    pub async fn get_all_domains(db_pool: &PgPool) -> AppResult<Vec<AllowedDomain>> {
+        let domains = sqlx::query_as!(
+            AllowedDomain,
+            r#"
+            SELECT id, domain, display_name, include_subdomains, allowed_grades,
+                   created_at, updated_at
+            FROM allowed_domains
+            ORDER BY domain
+            "#
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(domains)
+    }
+
+    /// Allow the domains of `ALLOWED_DOMAINS` with the grades of `ALLOWED_GRADES`
+    /// if no domain is allowed yet
+    ///
+    /// Deployments configured through the env vars keep accepting their domains after
+    /// upgrading. Once any domain is allowed, the env vars are ignored and domains are
+    /// managed through the admin API. Returns the number of domains added.
+    #[instrument(skip_all, err)]
+    pub async fn seed_from_env(db_pool: &PgPool) -> AppResult<u64> {
+        let Ok(allowed_domains) = env::var("ALLOWED_DOMAINS") else {
+            debug!("No ALLOWED_DOMAINS env var, not seeding allowed domains");
+            return Ok(0);
+        };
+        let allowed_grades = env::var("ALLOWED_GRADES").unwrap_or_else(|_| {
+            warn!("Missing ALLOWED_GRADES env var, using fallback 'undergraduate:graduate'");
+            "undergraduate:graduate".to_string()
+        });
+        let allowed_grades: Vec<String> = allowed_grades.split(':').map(String::from).collect();
+
+        let mut domains = Vec::new();
+        for domain in allowed_domains.split(':') {
+            let request = AllowedDomainRequest {
+                domain: domain.to_string(),
+                display_name: domain.to_string(),
+                include_subdomains: false,
+                allowed_grades: allowed_grades.clone(),
+            };
+            match Self::validate(&request) {
+                Ok(fields) => domains.push(fields.domain),
+                Err(_) => error!(domain, "Ignoring invalid domain in ALLOWED_DOMAINS"),
+            }
+        }
+
+        let seeded = sqlx::query!(
+            r#"
+            INSERT INTO allowed_domains (domain, display_name, allowed_grades)
+            SELECT domain, domain, $2
+            FROM UNNEST($1::text[]) AS domain
+            WHERE NOT EXISTS (SELECT 1 FROM allowed_domains)
+            ON CONFLICT (domain) DO NOTHING
+            "#,
+            &domains,
+            &allowed_grades
+        )
+        .execute(db_pool)
+        .await?
+        .rows_affected();
+
+        if seeded > 0 {
+            info!(seeded, "Allowed the domains of ALLOWED_DOMAINS");
+        }
+        Ok(seeded)
+    }
+
+    /// Allow a new email domain
+    #[instrument(skip_all, err)]
+    pub async fn create_domain(
+        conn: &mut PgConnection,
+        request: &AllowedDomainRequest,
+    ) -> AppResult<AllowedDomain> {
+        let fields = Self::validate(request)?;
+
+        let domain = sqlx::query_as!(
+            AllowedDomain,
+            r#"
+            INSERT INTO allowed_domains (domain, display_name, include_subdomains, allowed_grades)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (domain) DO NOTHING
+            RETURNING id, domain, display_name, include_subdomains, allowed_grades,
+                      created_at, updated_at
+            "#,
+            fields.domain,
+            fields.display_name,
+            fields.include_subdomains,
+            &fields.allowed_grades
+        )
+        .fetch_optional(conn)
+        .await?;
+
+        let domain = domain.ok_or(AppError::BadRequest("Domain is already allowed"))?;
+        info!(domain = %domain.domain, "Allowed email domain");
+        Ok(domain)
+    }
+
+    /// Update an allowed domain
+    ///
+    /// Returns the domain before and after the update, or `None` if it does not exist.
+    #[instrument(skip(conn, request), err)]
+    pub async fn update_domain(
+        conn: &mut PgConnection,
+        domain_id: Uuid,
+        request: &AllowedDomainRequest,
+    ) -> AppResult<Option<(AllowedDomain, AllowedDomain)>> {
+        let fields = Self::validate(request)?;
+
+        let Some(before) = sqlx::query_as!(
+            AllowedDomain,
+            r#"
+            SELECT id, domain, display_name, include_subdomains, allowed_grades,
+                   created_at, updated_at
+            FROM allowed_domains
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+            domain_id
+        )
+        .fetch_optional(&mut *conn)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let taken = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM allowed_domains WHERE domain = $1 AND id != $2) as "exists!""#,
+            fields.domain,
+            domain_id
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+        if taken {
+            return Err(AppError::BadRequest("Domain is already allowed"));
+        }
+
+        let after = sqlx::query_as!(
+            AllowedDomain,
+            r#"
+            UPDATE allowed_domains
+            SET domain = $2, display_name = $3, include_subdomains = $4, allowed_grades = $5
+            WHERE id = $1
+            RETURNING id, domain, display_name, include_subdomains, allowed_grades,
+                      created_at, updated_at
+            "#,
+            domain_id,
+            fields.domain,
+            fields.display_name,
+            fields.include_subdomains,
+            &fields.allowed_grades
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+
+        info!(domain = %after.domain, "Updated allowed email domain");
+        Ok(Some((before, after)))
+    }
+
+    /// Stop allowing an email domain for new accounts. Existing users can still log in.
+    ///
+    /// Returns the deleted domain, or `None` if it does not exist.
+    #[instrument(skip(conn), err)]
+    pub async fn delete_domain(
+        conn: &mut PgConnection,
+        domain_id: Uuid,
+    ) -> AppResult<Option<AllowedDomain>> {
+        let domain = sqlx::query_as!(
+            AllowedDomain,
+            r#"
+            DELETE FROM allowed_domains
+            WHERE id = $1
+            RETURNING id, domain, display_name, include_subdomains, allowed_grades,
+                      created_at, updated_at
+            "#,
+            domain_id
+        )
+        .fetch_optional(conn)
+        .await?;
+
+        if let Some(domain) = &domain {
+            info!(domain = %domain.domain, "Removed allowed email domain");
+        }
+        Ok(domain)
+    }
+
+    /// Normalize and validate an admin request
+    fn validate(request: &AllowedDomainRequest) -> AppResult<DomainFields> {
+        let domain = request.domain.trim().trim_end_matches('.').to_lowercase();
+        let valid_label = |label: &str| {
+            (1..=63).contains(&label.len())
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+        };
+        if domain.len() > 253 || !domain.contains('.') || !domain.split('.').all(valid_label) {
+            warn!(domain = %request.domain, "Invalid domain");
+            return Err(AppError::BadRequest("Invalid domain"));
+        }
+
+        let display_name = request.display_name.trim();
+        if display_name.is_empty() {
+            return Err(AppError::BadRequest("Display name must not be empty"));
+        }
+
+        let allowed_grades: Vec<String> = request
+            .allowed_grades
+            .iter()
+            .map(|grade| grade.trim().to_string())
+            .collect();
+        if allowed_grades.is_empty() || allowed_grades.iter().any(String::is_empty) {
+            return Err(AppError::BadRequest("Allowed grades must not be empty"));
+        }
+
+        Ok(DomainFields {
+            domain,
+            display_name: display_name.to_string(),
+            include_subdomains: request.include_subdomains,
+            allowed_grades,
+        })
+    }
+}
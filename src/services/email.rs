@@ -8,18 +8,42 @@
 //!
 //! - [`LogEmailer`] - Development/testing implementation that logs emails to console
 //! - [`ExternalEmailer`] - Production implementation using external email API
+//! - [`SmtpEmailer`] - Production implementation sending through an SMTP relay
+//! - [`FailoverEmailer`] - Composite trying several of the above in order
+
+use std::{
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use async_trait::async_trait;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, MultiPart},
+    transport::smtp::{PoolConfig, authentication::Credentials},
+};
 use thiserror::Error;
-use tracing::{debug, error, info, instrument};
+use time::OffsetDateTime;
+use tracing::{debug, error, info, instrument, warn};
 
-use crate::utils::html::generate_verification_email_html;
+use crate::{
+    models::{CircuitState, EmailMessage, ProviderHealth},
+    utils::constant::{
+        DEFAULT_EMAIL_CIRCUIT_FAILURE_THRESHOLD, DEFAULT_EMAIL_CIRCUIT_OPEN_DURATION,
+    },
+};
 
 /// Errors that can occur during email operations
 #[derive(Debug, Error)]
 pub enum EmailError {
     #[error("Failed to send email: {0}")]
     SendFailed(String),
+    /// The email was refused for good, e.g. by an SMTP 5xx reply, so retrying cannot help
+    #[error("Email rejected: {0}")]
+    Rejected(String),
+    #[error("Invalid email configuration: {0}")]
+    InvalidConfig(String),
 }
 
 /// Trait for email sending services
@@ -29,24 +53,24 @@ pub enum EmailError {
 /// implementations for testing.
 #[async_trait]
 pub trait EmailService: Send + Sync {
-    /// Sends an email to the specified recipient.
-    ///
-    /// # Arguments
+    /// Sends a rendered email to its recipient.
     ///
-    /// * `recipient` - Email address of the recipient
-    /// * `subject` - Email subject line
-    /// * `core` - The most essential field; structs implementing this trait should format it
+    /// Implementations send both the plain-text and the HTML part if they can.
+    /// Returns the name of the provider that accepted the email.
     ///
     /// # Errors
     ///
     /// Returns [`EmailError::SendFailed`] if the email cannot be sent due to
-    /// network issues, API errors, or other delivery problems.
-    async fn send_email(
-        &self,
-        recipient: &str,
-        subject: &str,
-        core: &str,
-    ) -> Result<(), EmailError>;
+    /// network issues, API errors, or other delivery problems, and
+    /// [`EmailError::Rejected`] if the provider refused it permanently.
+    async fn send(&self, message: &EmailMessage) -> Result<String, EmailError>;
+
+    /// Health of the providers behind this service.
+    ///
+    /// Empty for services that do not track the health of their provider.
+    fn health(&self) -> Vec<ProviderHealth> {
+        Vec::new()
+    }
 }
 
 /// Mock email service for development and testing
@@ -58,24 +82,19 @@ pub struct LogEmailer;
 
 #[async_trait]
 impl EmailService for LogEmailer {
-    #[instrument(skip(self, code))]
-    async fn send_email(
-        &self,
-        recipient: &str,
-        subject: &str,
-        code: &str,
-    ) -> Result<(), EmailError> {
+    #[instrument(skip_all, fields(recipient = %message.recipient, template = %message.template))]
+    async fn send(&self, message: &EmailMessage) -> Result<String, EmailError> {
         info!("Sending mock email");
 
         println!("====== MOCK EMAIL SENT ======");
-        println!("To: {recipient}");
-        println!("Subject: {subject}");
+        println!("To: {}", message.recipient);
+        println!("Subject: {}", message.subject);
         println!("-----------------------------");
-        println!("Your verification code is: {code}");
+        println!("{}", message.text);
         println!("=============================");
 
         debug!("Mock email logged to console");
-        Ok(())
+        Ok("log".to_string())
     }
 }
 
@@ -124,17 +143,14 @@ impl ExternalEmailer {
 #[async_trait]
 impl EmailService for ExternalEmailer {
     #[instrument(
-        skip(self, code),
+        skip_all,
         fields(
-            sender = %self.sender_email
+            sender = %self.sender_email,
+            recipient = %message.recipient,
+            template = %message.template
         )
     )]
-    async fn send_email(
-        &self,
-        recipient: &str,
-        subject: &str,
-        code: &str,
-    ) -> Result<(), EmailError> {
+    async fn send(&self, message: &EmailMessage) -> Result<String, EmailError> {
         debug!("Sending HTTP request to email API");
         let response = self
             .http_client
@@ -142,9 +158,10 @@ impl EmailService for ExternalEmailer {
             .basic_auth("api", Some(&self.api_key))
             .form(&[
                 ("from", self.sender_email.as_str()),
-                ("to", recipient),
-                ("subject", subject),
-                ("html", generate_verification_email_html(code).as_str()),
+                ("to", message.recipient.as_str()),
+                ("subject", message.subject.as_str()),
+                ("text", message.text.as_str()),
+                ("html", message.html.as_str()),
             ])
             .send()
             .await;
@@ -152,7 +169,7 @@ impl EmailService for ExternalEmailer {
         match response {
             Ok(res) if res.status().is_success() => {
                 info!("Email sent successfully via external API");
-                Ok(())
+                Ok("external".to_string())
             }
             Ok(res) => {
                 let status = res.status();
@@ -180,3 +197,312 @@ impl EmailService for ExternalEmailer {
         }
     }
 }
+
+/// How the connection to an SMTP relay is secured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// Upgrade to TLS with `STARTTLS`, usually on port 587
+    StartTls,
+    /// TLS from the start of the connection, usually on port 465
+    Tls,
+    /// No encryption; only meant for relays on the local network and testing
+    None,
+}
+
+impl FromStr for SmtpSecurity {
+    type Err = EmailError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "starttls" => Ok(Self::StartTls),
+            "tls" => Ok(Self::Tls),
+            "none" => Ok(Self::None),
+            other => Err(EmailError::InvalidConfig(format!(
+                "Unknown SMTP security `{other}`, expected `starttls`, `tls` or `none`"
+            ))),
+        }
+    }
+}
+
+/// Settings of an [`SmtpEmailer`]
+pub struct SmtpConfig {
+    pub host: String,
+    /// Defaults to the port of the security mode: 587, 465 or 25
+    pub port: Option<u16>,
+    pub security: SmtpSecurity,
+    /// Username and password, if the relay requires authentication
+    pub credentials: Option<(String, String)>,
+    /// Maximum number of pooled connections to the relay
+    pub pool_size: u32,
+    pub sender_email: String,
+}
+
+/// SMTP email service for production use
+///
+/// This implementation sends emails through an SMTP relay, e.g. the one of a
+/// university that does not accept mail from third party providers. Connections
+/// are kept in a pool and reused across emails.
+///
+/// # Configuration
+///
+/// Requires the following environment variables in production:
+/// - `SMTP_HOST` - Host name of the SMTP relay
+/// - `SENDER_EMAIL` - Email address to use as sender
+///
+/// See [`crate::email_service_from_env`] for the optional settings.
+pub struct SmtpEmailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    sender: Mailbox,
+}
+
+impl SmtpEmailer {
+    /// Creates a new SMTP email service instance.
+    ///
+    /// No connection is opened until the first email is sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EmailError::InvalidConfig`] if the sender address is invalid, the
+    /// pool size is zero or TLS cannot be set up for the host.
+    pub fn new(config: SmtpConfig) -> Result<Self, EmailError> {
+        if config.pool_size == 0 {
+            return Err(EmailError::InvalidConfig(
+                "SMTP pool size should be positive".to_string(),
+            ));
+        }
+        let sender: Mailbox = config
+            .sender_email
+            .parse()
+            .map_err(|e| EmailError::InvalidConfig(format!("Invalid sender email: {e}")))?;
+
+        let mut builder = match config.security {
+            SmtpSecurity::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+            }
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host),
+            SmtpSecurity::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+                &config.host,
+            )),
+        }
+        .map_err(|e| EmailError::InvalidConfig(format!("Failed to set up TLS: {e}")))?;
+
+        if let Some(port) = config.port {
+            builder = builder.port(port);
+        }
+        if let Some((username, password)) = config.credentials {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+        let transport = builder
+            .pool_config(PoolConfig::new().max_size(config.pool_size))
+            .build();
+
+        info!(
+            host = %config.host,
+            port = ?config.port,
+            security = ?config.security,
+            sender_email = %sender,
+            "Initializing SMTP email service"
+        );
+
+        Ok(Self { transport, sender })
+    }
+}
+
+#[async_trait]
+impl EmailService for SmtpEmailer {
+    #[instrument(
+        skip_all,
+        fields(
+            sender = %self.sender,
+            recipient = %message.recipient,
+            template = %message.template
+        )
+    )]
+    async fn send(&self, message: &EmailMessage) -> Result<String, EmailError> {
+        let to: Mailbox = message
+            .recipient
+            .parse()
+            .map_err(|e| EmailError::Rejected(format!("Invalid recipient: {e}")))?;
+        let email = Message::builder()
+            .from(self.sender.clone())
+            .to(to)
+            .subject(&message.subject)
+            .multipart(MultiPart::alternative_plain_html(
+                message.text.clone(),
+                message.html.clone(),
+            ))
+            .map_err(|e| EmailError::Rejected(format!("Failed to build email: {e}")))?;
+
+        debug!("Sending email to SMTP relay");
+        match self.transport.send(email).await {
+            Ok(response) => {
+                info!(code = %response.code(), "Email sent successfully via SMTP");
+                Ok("smtp".to_string())
+            }
+            Err(e) if e.is_permanent() => {
+                error!(error = %e, "SMTP relay rejected email permanently");
+                Err(EmailError::Rejected(format!("SMTP error: {e}")))
+            }
+            Err(e) => {
+                error!(error = %e, "SMTP relay failed to accept email");
+                Err(EmailError::SendFailed(format!("SMTP error: {e}")))
+            }
+        }
+    }
+}
+
+/// A provider of a [`FailoverEmailer`] with its circuit breaker
+struct FailoverProvider {
+    service: Arc<dyn EmailService>,
+    health: Mutex<ProviderHealth>,
+}
+
+/// Composite email service failing over across an ordered list of providers
+///
+/// Every email is sent through the first provider that accepts it, and the name
+/// of that provider is returned. Each provider
+/// has a circuit breaker: after `failure_threshold` consecutive failures the
+/// provider is skipped for `open_duration`, after which a single email probes
+/// whether it recovered. Success closes the circuit, failure opens it again.
+///
+/// # Configuration
+///
+/// `EMAIL_PROVIDER` takes a comma-separated list of providers, in the order they
+/// are tried, e.g. `external,smtp`. `EMAIL_CIRCUIT_FAILURE_THRESHOLD` and
+/// `EMAIL_CIRCUIT_OPEN_SECONDS` configure the circuit breakers.
+pub struct FailoverEmailer {
+    providers: Vec<FailoverProvider>,
+    failure_threshold: u32,
+    open_duration: Duration,
+}
+
+impl FailoverEmailer {
+    /// Creates a failover service trying the named providers in the given order.
+    pub fn new(providers: Vec<(String, Arc<dyn EmailService>)>) -> Self {
+        info!(
+            providers = ?providers.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            "Initializing failover email service"
+        );
+
+        Self {
+            providers: providers
+                .into_iter()
+                .map(|(name, service)| FailoverProvider {
+                    service,
+                    health: Mutex::new(ProviderHealth::new(&name)),
+                })
+                .collect(),
+            failure_threshold: DEFAULT_EMAIL_CIRCUIT_FAILURE_THRESHOLD,
+            open_duration: DEFAULT_EMAIL_CIRCUIT_OPEN_DURATION,
+        }
+    }
+
+    /// Overrides when circuits open and how long they stay open.
+    pub fn circuit_breaker(mut self, failure_threshold: u32, open_duration: Duration) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self.open_duration = open_duration;
+        self
+    }
+
+    /// Whether an email may be sent through the provider now
+    fn try_acquire(&self, provider: &FailoverProvider) -> bool {
+        let mut health = provider.health.lock().unwrap();
+        let now = OffsetDateTime::now_utc();
+
+        let probe_due = health.open_until.is_none_or(|until| until <= now);
+        match health.state {
+            CircuitState::Closed => true,
+            // A probe that never finished, e.g. because it was cancelled, is replaced
+            CircuitState::Open | CircuitState::HalfOpen if probe_due => {
+                health.state = CircuitState::HalfOpen;
+                health.open_until = Some(now + self.open_duration);
+                true
+            }
+            CircuitState::Open | CircuitState::HalfOpen => {
+                health.skipped += 1;
+                false
+            }
+        }
+    }
+
+    fn record_success(&self, provider: &FailoverProvider) {
+        let mut health = provider.health.lock().unwrap();
+        if health.state != CircuitState::Closed {
+            info!(provider = %health.name, "Email provider recovered, closing circuit");
+        }
+        health.state = CircuitState::Closed;
+        health.consecutive_failures = 0;
+        health.open_until = None;
+        health.delivered += 1;
+        health.last_delivered_at = Some(OffsetDateTime::now_utc());
+    }
+
+    fn record_failure(&self, provider: &FailoverProvider, error: &EmailError) {
+        let mut health = provider.health.lock().unwrap();
+        let now = OffsetDateTime::now_utc();
+        health.consecutive_failures += 1;
+        health.failed += 1;
+        health.last_error = Some(error.to_string());
+        health.last_failed_at = Some(now);
+
+        if health.state == CircuitState::HalfOpen
+            || health.consecutive_failures >= self.failure_threshold
+        {
+            warn!(
+                provider = %health.name,
+                consecutive_failures = health.consecutive_failures,
+                "Email provider failing, opening circuit"
+            );
+            health.state = CircuitState::Open;
+            health.open_until = Some(now + self.open_duration);
+        }
+    }
+}
+
+#[async_trait]
+impl EmailService for FailoverEmailer {
+    #[instrument(skip_all, fields(recipient = %message.recipient, template = %message.template))]
+    async fn send(&self, message: &EmailMessage) -> Result<String, EmailError> {
+        let mut errors = Vec::new();
+        // Another provider may still accept an email that one rejected
+        let mut all_rejected = true;
+
+        for provider in &self.providers {
+            let name = provider.health.lock().unwrap().name.clone();
+            if !self.try_acquire(provider) {
+                debug!(provider = %name, "Skipping email provider with open circuit");
+                errors.push(format!("{name}: circuit open"));
+                all_rejected = false;
+                continue;
+            }
+
+            match provider.service.send(message).await {
+                Ok(_) => {
+                    self.record_success(provider);
+                    info!(provider = %name, "Email delivered");
+                    return Ok(name);
+                }
+                Err(e) => {
+                    self.record_failure(provider, &e);
+                    warn!(provider = %name, error = %e, "Email provider failed, failing over");
+                    all_rejected &= matches!(e, EmailError::Rejected(_));
+                    errors.push(format!("{name}: {e}"));
+                }
+            }
+        }
+
+        let message = format!("All email providers failed ({})", errors.join("; "));
+        if all_rejected && !errors.is_empty() {
+            Err(EmailError::Rejected(message))
+        } else {
+            Err(EmailError::SendFailed(message))
+        }
+    }
+
+    fn health(&self) -> Vec<ProviderHealth> {
+        self.providers
+            .iter()
+            .map(|provider| provider.health.lock().unwrap().clone())
+            .collect()
+    }
+}
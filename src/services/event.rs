@@ -0,0 +1,444 @@
+//! # Event Service
+//!
+//! A deployment hosts a series of events, e.g. one per semester. Users are shared by
+//! all events, while forms, previews, vetoes, matches and schedules belong to one
+//! event. Exactly one event is current: users sign up, fill in their form and get
+//! matched in it. Activating another event starts over with every participant back
+//! at `verified`.
+//!
+//! Each event has a timeline of phases with start times configured by admins.
+//! In the current event, the phase with the latest start time in the past is current
+//! and decides which user actions are open. Without a started phase nothing is gated.
+//!
+//! Phase transitions are processed by the scheduler loop, which marks started
+//! phases as entered and publishes match previews when the veto phase begins.
+
+use std::sync::{Arc, LazyLock};
+
+use dashmap::DashMap;
+use sqlx::{PgConnection, PgPool, types::Json};
+use time::OffsetDateTime;
+use tracing::{error, info, instrument, warn};
+use uuid::Uuid;
+
+use super::{matching::MatchingService, user_event::UserEventService};
+use crate::{
+    error::{AppError, AppResult},
+    models::{
+        Catalogs, CreateEventRequest, Event, EventPhase, EventPhaseEntry, EventPhaseResponse,
+        PhaseGate, TagNode, TagSystem, TraitEntry,
+    },
+    utils::static_object::DEFAULT_CATALOGS,
+};
+
+/// Catalogs of events with their own tags or traits, which cannot change once created
+static EVENT_CATALOGS: LazyLock<DashMap<Uuid, Arc<Catalogs>>> = LazyLock::new(DashMap::new);
+
+pub struct EventService;
+
+impl EventService {
+    /// Get the current event
+    pub async fn current_event(db_pool: &PgPool) -> AppResult<Event> {
+        let event = sqlx::query_as!(
+            Event,
+            r#"
+            SELECT id, slug, name, allowed_domains, allowed_grades, is_current, created_at
+            FROM events
+            WHERE is_current
+            "#
+        )
+        .fetch_optional(db_pool)
+        .await?;
+
+        event.ok_or_else(|| {
+            error!("No current event configured");
+            AppError::Internal
+        })
+    }
+
+    /// Get the id of the current event
+    pub async fn current_event_id(db_pool: &PgPool) -> AppResult<Uuid> {
+        let event_id = sqlx::query_scalar!("SELECT id FROM events WHERE is_current")
+            .fetch_optional(db_pool)
+            .await?;
+
+        event_id.ok_or_else(|| {
+            error!("No current event configured");
+            AppError::Internal
+        })
+    }
+
+    /// Lock the events and return the current one
+    ///
+    /// Concurrent activations wait for each other here. Every row is locked rather
+    /// than only the current one, so a waiting activation sees the event made
+    /// current by the one it waited for.
+    pub async fn lock_current_event(conn: &mut PgConnection) -> AppResult<Uuid> {
+        let events = sqlx::query!("SELECT id, is_current FROM events ORDER BY id FOR UPDATE")
+            .fetch_all(conn)
+            .await?;
+
+        events
+            .into_iter()
+            .find(|event| event.is_current)
+            .map(|event| event.id)
+            .ok_or_else(|| {
+                error!("No current event configured");
+                AppError::Internal
+            })
+    }
+
+    /// Get an event by id
+    pub async fn get_event(db_pool: &PgPool, event_id: Uuid) -> AppResult<Event> {
+        let event = sqlx::query_as!(
+            Event,
+            r#"
+            SELECT id, slug, name, allowed_domains, allowed_grades, is_current, created_at
+            FROM events
+            WHERE id = $1
+            "#,
+            event_id
+        )
+        .fetch_optional(db_pool)
+        .await?;
+
+        event.ok_or(AppError::NotFound("Event not found"))
+    }
+
+    /// Get all events, newest first (admin view)
+    pub async fn get_all_events(db_pool: &PgPool) -> AppResult<Vec<Event>> {
+        let events = sqlx::query_as!(
+            Event,
+            r#"
+            SELECT id, slug, name, allowed_domains, allowed_grades, is_current, created_at
+            FROM events
+            ORDER BY created_at DESC
+            "#
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Create an event, which does not become current until it is activated
+    pub async fn create_event(
+        conn: &mut PgConnection,
+        request: &CreateEventRequest,
+    ) -> AppResult<Event> {
+        if request.slug.trim().is_empty() || request.name.trim().is_empty() {
+            return Err(AppError::BadRequest(
+                "Event slug and name must not be empty",
+            ));
+        }
+        if request.allowed_domains.as_ref().is_some_and(Vec::is_empty)
+            || request.allowed_grades.as_ref().is_some_and(Vec::is_empty)
+        {
+            return Err(AppError::BadRequest(
+                "Allowed domains and grades must not be empty lists",
+            ));
+        }
+
+        let event = sqlx::query_as!(
+            Event,
+            r#"
+            INSERT INTO events (slug, name, allowed_domains, allowed_grades, tags, traits)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (slug) DO NOTHING
+            RETURNING id, slug, name, allowed_domains, allowed_grades, is_current, created_at
+            "#,
+            request.slug.trim(),
+            request.name.trim(),
+            request.allowed_domains.as_deref(),
+            request.allowed_grades.as_deref(),
+            request.tags.as_ref().map(Json) as Option<Json<&Vec<TagNode>>>,
+            request.traits.as_ref().map(Json) as Option<Json<&Vec<TraitEntry>>>
+        )
+        .fetch_optional(conn)
+        .await?;
+
+        event.ok_or(AppError::BadRequest("Event slug is already taken"))
+    }
+
+    /// Make `event_id` the current event
+    ///
+    /// Participants of the previous event go back to `verified`, so they fill in a new
+    /// form for the new event. Suspended users get the same status restored once their
+    /// suspension is lifted. Match previews and vetoes only apply to the round they were
+    /// made in, so those of other events are removed. Returns the number of users reset,
+    /// or `None` if the event does not exist. Call after [`Self::lock_current_event`]
+    /// in the same transaction.
+    pub async fn activate_event(conn: &mut PgConnection, event_id: Uuid) -> AppResult<Option<u64>> {
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM events WHERE id = $1) as "exists!""#,
+            event_id
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+        if !exists {
+            return Ok(None);
+        }
+
+        sqlx::query!(
+            "UPDATE events SET is_current = FALSE WHERE is_current AND id != $1",
+            event_id
+        )
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query!(
+            "UPDATE events SET is_current = TRUE WHERE id = $1",
+            event_id
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db_error)
+                if db_error.constraint() == Some("idx_events_current") =>
+            {
+                warn!(%event_id, "Another event was activated concurrently");
+                AppError::BadRequest("Another event was activated concurrently")
+            }
+            _ => AppError::from(e),
+        })?;
+
+        let reset = sqlx::query!(
+            "UPDATE users SET status = 'verified'
+             WHERE status IN ('form_completed', 'matched', 'confirmed', 'paused', 'withdrawn')"
+        )
+        .execute(&mut *conn)
+        .await?;
+        let reset_suspended = sqlx::query!(
+            "UPDATE users SET status_before_suspension = 'verified'
+             WHERE status = 'suspended'
+               AND status_before_suspension IN
+                   ('form_completed', 'matched', 'confirmed', 'paused', 'withdrawn')"
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query!("DELETE FROM match_previews WHERE event_id != $1", event_id)
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query!("DELETE FROM vetoes WHERE event_id != $1", event_id)
+            .execute(&mut *conn)
+            .await?;
+
+        UserEventService::publish_next_match_time(conn).await?;
+
+        Ok(Some(
+            reset.rows_affected() + reset_suspended.rows_affected(),
+        ))
+    }
+
+    /// Get the tag and trait catalogs of an event
+    pub async fn catalogs(db_pool: &PgPool, event_id: Uuid) -> AppResult<Arc<Catalogs>> {
+        if let Some(catalogs) = EVENT_CATALOGS.get(&event_id) {
+            return Ok(catalogs.clone());
+        }
+
+        let row = sqlx::query!(
+            r#"
+            SELECT tags as "tags: Json<Vec<TagNode>>", traits as "traits: Json<Vec<TraitEntry>>"
+            FROM events
+            WHERE id = $1
+            "#,
+            event_id
+        )
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or(AppError::NotFound("Event not found"))?;
+
+        let catalogs = match (row.tags, row.traits) {
+            (None, None) => DEFAULT_CATALOGS.clone(),
+            (tags, traits) => {
+                let tag_tree =
+                    tags.map_or_else(|| DEFAULT_CATALOGS.tag_tree.clone(), |tags| tags.0);
+                let traits = traits.map_or_else(
+                    || DEFAULT_CATALOGS.traits.clone(),
+                    |traits| TagSystem::from_nodes(&TraitEntry::tree(&traits)),
+                );
+                Arc::new(Catalogs {
+                    tags: TagSystem::from_nodes(&tag_tree),
+                    traits,
+                    tag_tree,
+                })
+            }
+        };
+
+        EVENT_CATALOGS.insert(event_id, catalogs.clone());
+        Ok(catalogs)
+    }
+
+    /// Get the phase of an event that started most recently, if any
+    pub async fn current_phase(
+        db_pool: &PgPool,
+        event_id: Uuid,
+    ) -> AppResult<Option<EventPhaseEntry>> {
+        let phase = sqlx::query_as!(
+            EventPhaseEntry,
+            r#"
+            SELECT id, phase as "phase: EventPhase", starts_at, entered_at, created_at
+            FROM event_phases
+            WHERE event_id = $1 AND starts_at <= CURRENT_TIMESTAMP
+            ORDER BY starts_at DESC
+            LIMIT 1
+            "#,
+            event_id
+        )
+        .fetch_optional(db_pool)
+        .await?;
+
+        Ok(phase)
+    }
+
+    /// Get the current and next phase of an event's timeline
+    pub async fn phase_status(db_pool: &PgPool, event_id: Uuid) -> AppResult<EventPhaseResponse> {
+        let current = Self::current_phase(db_pool, event_id).await?;
+        let next = sqlx::query!(
+            r#"
+            SELECT phase as "phase: EventPhase", starts_at
+            FROM event_phases
+            WHERE event_id = $1 AND starts_at > CURRENT_TIMESTAMP
+            ORDER BY starts_at ASC
+            LIMIT 1
+            "#,
+            event_id
+        )
+        .fetch_optional(db_pool)
+        .await?;
+
+        Ok(EventPhaseResponse {
+            phase: current.as_ref().map(|entry| entry.phase),
+            started_at: current.map(|entry| entry.starts_at),
+            next_phase: next.as_ref().map(|row| row.phase),
+            next_starts_at: next.map(|row| row.starts_at),
+        })
+    }
+
+    /// Reject the request unless `gate` is open in the current phase of an event
+    pub async fn ensure_open(db_pool: &PgPool, event_id: Uuid, gate: PhaseGate) -> AppResult<()> {
+        if !Self::is_open(db_pool, event_id, gate).await? {
+            return Err(AppError::Forbidden(gate.closed_message()));
+        }
+
+        Ok(())
+    }
+
+    /// Whether the current phase of an event opens the gated action
+    pub async fn is_open(db_pool: &PgPool, event_id: Uuid, gate: PhaseGate) -> AppResult<bool> {
+        if let Some(current) = Self::current_phase(db_pool, event_id).await?
+            && !current.phase.opens(gate)
+        {
+            warn!(phase = ?current.phase, ?gate, "Action is closed in the current event phase");
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    /// Create or reschedule phases of an event's timeline
+    ///
+    /// A phase starting at the same time as an existing one replaces it.
+    pub async fn create_phases(
+        conn: &mut PgConnection,
+        event_id: Uuid,
+        phases: &[(EventPhase, OffsetDateTime)],
+    ) -> AppResult<Vec<EventPhaseEntry>> {
+        let mut entries = Vec::with_capacity(phases.len());
+
+        for &(phase, starts_at) in phases {
+            if starts_at <= OffsetDateTime::now_utc() {
+                return Err(AppError::BadRequest(
+                    "Phase start time must be in the future",
+                ));
+            }
+
+            let entry = sqlx::query_as!(
+                EventPhaseEntry,
+                r#"
+                INSERT INTO event_phases (event_id, phase, starts_at)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (event_id, starts_at)
+                DO UPDATE SET phase = EXCLUDED.phase
+                RETURNING id, phase as "phase: EventPhase", starts_at, entered_at, created_at
+                "#,
+                event_id,
+                phase as EventPhase,
+                starts_at
+            )
+            .fetch_one(&mut *conn)
+            .await?;
+
+            entries.push(entry);
+        }
+
+        Ok(entries)
+    }
+
+    /// Get the whole timeline of an event (admin view)
+    pub async fn get_all_phases(
+        db_pool: &PgPool,
+        event_id: Uuid,
+    ) -> AppResult<Vec<EventPhaseEntry>> {
+        let phases = sqlx::query_as!(
+            EventPhaseEntry,
+            r#"
+            SELECT id, phase as "phase: EventPhase", starts_at, entered_at, created_at
+            FROM event_phases
+            WHERE event_id = $1
+            ORDER BY starts_at ASC
+            "#,
+            event_id
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(phases)
+    }
+
+    /// Cancel a phase that has not started yet
+    pub async fn cancel_phase(conn: &mut PgConnection, phase_id: Uuid) -> AppResult<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM event_phases WHERE id = $1 AND starts_at > CURRENT_TIMESTAMP",
+            phase_id
+        )
+        .execute(conn)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Mark phases of the current event that have started as entered and run their
+    /// side effects
+    ///
+    /// Phases skipped while the scheduler was down are marked as well, but only the
+    /// current phase runs its side effects. Timelines of other events wait until their
+    /// event is activated.
+    #[instrument(skip_all, err)]
+    pub async fn advance_phases(db_pool: &PgPool) -> AppResult<()> {
+        let event_id = Self::current_event_id(db_pool).await?;
+        let entered = sqlx::query_as!(
+            EventPhaseEntry,
+            r#"
+            UPDATE event_phases
+            SET entered_at = CURRENT_TIMESTAMP
+            WHERE event_id = $1 AND entered_at IS NULL AND starts_at <= CURRENT_TIMESTAMP
+            RETURNING id, phase as "phase: EventPhase", starts_at, entered_at, created_at
+            "#,
+            event_id
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let Some(current) = entered.into_iter().max_by_key(|entry| entry.starts_at) else {
+            return Ok(());
+        };
+        info!(phase = ?current.phase, starts_at = %current.starts_at, "Entered event phase");
+
+        if current.phase == EventPhase::PreviewVeto {
+            MatchingService::generate_match_previews(db_pool, event_id).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+//! # Feedback Service
+//!
+//! The feedback window of a final match opens when its second user confirms it,
+//! either by accepting or by auto-acceptance, and stays open for
+//! `FEEDBACK_WINDOW_DAYS`. Within it, each user can give and revise one piece of
+//! feedback on the match.
+//!
+//! Every final match records its score, the number of tags its users shared and the
+//! version of the matching algorithm, so that admins can see which kind of pairs
+//! turn out well.
+
+use std::collections::HashSet;
+
+use sqlx::{PgConnection, PgPool};
+use time::OffsetDateTime;
+use tracing::{debug, info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{FeedbackStats, FeedbackSummary, MatchFeedback, MatchFeedbackRequest},
+    services::match_stats::{MatchGroups, StatsBuilder},
+    utils::static_object::FEEDBACK_WINDOW_DAYS,
+};
+
+pub struct FeedbackService;
+
+impl FeedbackService {
+    /// Open the feedback window of a final match if both its users confirmed it
+    ///
+    /// Call in the transaction confirming a user of the match, after locking the match.
+    pub async fn open_window(conn: &mut PgConnection, final_match_id: Uuid) -> AppResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE final_matches fm SET confirmed_at = NOW()
+            WHERE fm.id = $1 AND fm.confirmed_at IS NULL
+            AND NOT EXISTS (
+                SELECT 1 FROM users u
+                WHERE u.id IN (fm.user_a_id, fm.user_b_id) AND u.status != 'confirmed'
+            )
+            "#,
+            final_match_id
+        )
+        .execute(conn)
+        .await?;
+
+        let opened = result.rows_affected() > 0;
+        if opened {
+            debug!(%final_match_id, "Feedback window opened");
+        }
+        Ok(opened)
+    }
+
+    /// Record the feedback of a user on their latest confirmed final match, replacing
+    /// what they gave before
+    #[instrument(skip_all, fields(%user_id))]
+    pub async fn submit(
+        db_pool: &PgPool,
+        user_id: Uuid,
+        request: &MatchFeedbackRequest,
+    ) -> AppResult<MatchFeedback> {
+        request.validate().map_err(AppError::BadRequest)?;
+
+        let final_match = sqlx::query!(
+            r#"
+            SELECT id, confirmed_at as "confirmed_at!"
+            FROM final_matches
+            WHERE (user_a_id = $1 OR user_b_id = $1) AND confirmed_at IS NOT NULL
+            ORDER BY confirmed_at DESC
+            LIMIT 1
+            "#,
+            user_id
+        )
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or_else(|| {
+            warn!("User has no confirmed final match");
+            AppError::BadRequest("No confirmed final match")
+        })?;
+
+        let window_closes_at =
+            final_match.confirmed_at + time::Duration::days((*FEEDBACK_WINDOW_DAYS).into());
+        if OffsetDateTime::now_utc() > window_closes_at {
+            warn!(final_match_id = %final_match.id, %window_closes_at, "Feedback window closed");
+            return Err(AppError::Forbidden("Feedback window has closed"));
+        }
+
+        let feedback = sqlx::query_as!(
+            MatchFeedback,
+            r#"
+            INSERT INTO match_feedback (final_match_id, user_id, rating, met, comment)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (final_match_id, user_id) DO UPDATE
+            SET rating = EXCLUDED.rating, met = EXCLUDED.met, comment = EXCLUDED.comment,
+                updated_at = NOW()
+            RETURNING final_match_id, user_id, rating, met, comment, created_at, updated_at
+            "#,
+            final_match.id,
+            user_id,
+            request.rating,
+            request.met,
+            request.comment()
+        )
+        .fetch_one(db_pool)
+        .await?;
+
+        info!(final_match_id = %final_match.id, rating = request.rating, met = request.met, "Feedback recorded");
+        Ok(feedback)
+    }
+
+    /// Feedback given on the final matches, ordered by match and time
+    pub async fn list_for_matches(
+        db_pool: &PgPool,
+        final_match_ids: &[Uuid],
+    ) -> Result<Vec<MatchFeedback>, sqlx::Error> {
+        sqlx::query_as!(
+            MatchFeedback,
+            r#"
+            SELECT final_match_id, user_id, rating, met, comment, created_at, updated_at
+            FROM match_feedback
+            WHERE final_match_id = ANY($1)
+            ORDER BY final_match_id, created_at
+            "#,
+            final_match_ids
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+
+    /// Aggregate the feedback on the confirmed final matches of an event by score
+    /// buckets `bucket_width` wide, tag overlap and algorithm version
+    #[instrument(skip(db_pool), err)]
+    pub async fn summary(
+        db_pool: &PgPool,
+        event_id: Uuid,
+        bucket_width: f64,
+    ) -> AppResult<FeedbackSummary> {
+        let mut groups = MatchGroups::<FeedbackStatsBuilder>::new(bucket_width)?;
+
+        // One row per feedback, or per match without feedback
+        let rows = sqlx::query!(
+            r#"
+            SELECT fm.id, fm.score, fm.tag_overlap, fm.algorithm_version,
+                   f.rating as "rating?", f.met as "met?"
+            FROM final_matches fm
+            LEFT JOIN match_feedback f ON f.final_match_id = fm.id
+            WHERE fm.event_id = $1 AND fm.confirmed_at IS NOT NULL
+            "#,
+            event_id
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        for row in rows {
+            let feedback = row.rating.zip(row.met);
+            groups.add(
+                row.score,
+                row.tag_overlap,
+                row.algorithm_version,
+                &(row.id, feedback),
+            );
+        }
+
+        Ok(groups.build())
+    }
+}
+
+/// Accumulates the feedback of a group of matches
+#[derive(Default)]
+struct FeedbackStatsBuilder {
+    match_ids: HashSet<Uuid>,
+    rating_sum: i64,
+    met_count: i64,
+    rating_counts: [i64; 5],
+}
+
+impl StatsBuilder for FeedbackStatsBuilder {
+    /// A final match with one of its feedbacks, if any
+    type Row = (Uuid, Option<(i16, bool)>);
+    type Stats = FeedbackStats;
+
+    fn add(&mut self, &(final_match_id, feedback): &Self::Row) {
+        self.match_ids.insert(final_match_id);
+        if let Some((rating, met)) = feedback {
+            self.rating_sum += i64::from(rating);
+            self.met_count += i64::from(met);
+            self.rating_counts[(rating - 1) as usize] += 1;
+        }
+    }
+
+    fn build(self) -> FeedbackStats {
+        let responses: i64 = self.rating_counts.iter().sum();
+        let share = |count: i64| (responses > 0).then(|| count as f64 / responses as f64);
+        FeedbackStats {
+            matches: self.match_ids.len() as i64,
+            responses,
+            average_rating: share(self.rating_sum),
+            met_rate: share(self.met_count),
+            rating_counts: self.rating_counts,
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! # Match Statistics
+//!
+//! Feedback and rejections are aggregated the same way: [`MatchGroups`] sorts the
+//! final matches into every group the statistics break them down by, and a
+//! [`StatsBuilder`] accumulates what is counted in each group.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{AlgorithmVersionStats, GroupedStats, ScoreBucketStats, TagOverlapStats},
+};
+
+/// Accumulates the statistics of a group of final matches
+pub trait StatsBuilder: Default {
+    /// What a final match, or one of its rows, adds to its groups
+    type Row;
+    type Stats;
+
+    fn add(&mut self, row: &Self::Row);
+
+    fn build(self) -> Self::Stats;
+}
+
+/// Final matches grouped overall, by score bucket, tag overlap and algorithm version
+pub struct MatchGroups<B> {
+    bucket_width: f64,
+    overall: B,
+    by_score_bucket: BTreeMap<i64, B>,
+    by_tag_overlap: BTreeMap<Option<i32>, B>,
+    by_algorithm_version: BTreeMap<Option<String>, B>,
+}
+
+impl<B: StatsBuilder> MatchGroups<B> {
+    /// Groups with score buckets `bucket_width` wide
+    pub fn new(bucket_width: f64) -> AppResult<Self> {
+        if !bucket_width.is_finite() || bucket_width <= 0.0 {
+            return Err(AppError::BadRequest(
+                "Bucket width must be a positive number",
+            ));
+        }
+
+        Ok(Self {
+            bucket_width,
+            overall: B::default(),
+            by_score_bucket: BTreeMap::new(),
+            by_tag_overlap: BTreeMap::new(),
+            by_algorithm_version: BTreeMap::new(),
+        })
+    }
+
+    /// Add a row of a final match to each of its groups
+    pub fn add(
+        &mut self,
+        score: f64,
+        tag_overlap: Option<i32>,
+        algorithm_version: Option<String>,
+        row: &B::Row,
+    ) {
+        let bucket = (score / self.bucket_width).floor() as i64;
+        for stats in [
+            &mut self.overall,
+            self.by_score_bucket.entry(bucket).or_default(),
+            self.by_tag_overlap.entry(tag_overlap).or_default(),
+            self.by_algorithm_version
+                .entry(algorithm_version)
+                .or_default(),
+        ] {
+            stats.add(row);
+        }
+    }
+
+    pub fn build(self) -> GroupedStats<B::Stats> {
+        let bucket_width = self.bucket_width;
+        GroupedStats {
+            overall: self.overall.build(),
+            by_score_bucket: self
+                .by_score_bucket
+                .into_iter()
+                .map(|(bucket, stats)| ScoreBucketStats {
+                    min_score: bucket as f64 * bucket_width,
+                    max_score: (bucket + 1) as f64 * bucket_width,
+                    stats: stats.build(),
+                })
+                .collect(),
+            by_tag_overlap: self
+                .by_tag_overlap
+                .into_iter()
+                .map(|(tag_overlap, stats)| TagOverlapStats {
+                    tag_overlap,
+                    stats: stats.build(),
+                })
+                .collect(),
+            by_algorithm_version: self
+                .by_algorithm_version
+                .into_iter()
+                .map(|(algorithm_version, stats)| AlgorithmVersionStats {
+                    algorithm_version,
+                    stats: stats.build(),
+                })
+                .collect(),
+        }
+    }
+}
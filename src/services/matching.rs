@@ -1,17 +1,24 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, LazyLock},
+    time::Instant,
+};
 
-use sqlx::PgPool;
+use dashmap::DashMap;
+use sqlx::{PgConnection, PgExecutor, PgPool, types::Json};
 use tracing::{debug, instrument, trace};
 use uuid::Uuid;
 
 use crate::{
     error::AppResult,
-    models::{Form, Gender, TagSystem},
+    models::{Catalogs, Form, FormAnswers, FormSchema, Gender, Scorer, TagSystem},
+    services::{event::EventService, text_similarity::TextCorpus},
     utils::{
         constant::{IDF_MIN, MATCH_PREVIEW_INTERVAL},
         static_object::{
-            BOUNDARY_MATCH_POINTS, COMPLEMENTARY_TAG_WEIGHT, MAX_PREVIEW_CANDIDATES,
-            TAG_SCORE_DECAY_FACTOR, TRAIT_MATCH_POINTS,
+            BOUNDARY_MATCH_POINTS, COMPLEMENTARY_TAG_WEIGHT, FORM_SCHEMA, MAX_PREVIEW_CANDIDATES,
+            MAX_PREVIEW_EXPOSURE, PREVIEW_DIVERSITY_WEIGHT, TAG_SCORE_DECAY_FACTOR,
+            TRAIT_MATCH_POINTS, TRAIT_SCORE_DECAY_FACTOR,
         },
     },
 };
@@ -20,73 +27,154 @@ pub struct MatchingService;
 
 static INCOMPATIBLE_MATCH_SCORE: f64 = -1.0;
 
+/// Matching pool of each event as of its last preview generation, reused to regenerate
+/// a single preview without refetching every form
+static PREVIEW_POOLS: LazyLock<DashMap<Uuid, Arc<PreviewPool>>> = LazyLock::new(DashMap::new);
+
+/// The forms of a matching pool with the scoring inputs derived from them
+struct PreviewPool {
+    forms: Vec<Form>,
+    catalogs: Arc<Catalogs>,
+    tag_frequencies: HashMap<String, u32>,
+    text_corpus: TextCorpus,
+    built_at: Instant,
+}
+
 impl MatchingService {
     /// Calculates the compatibility score between two users
     /// Returns INCOMPATIBLE_MATCH_SCORE for impossible matches, positive scores for viable matches
+    ///
+    /// Apart from the gender filter, the score is made up of the scorers declared in the
+    /// form schema, each reading the answers to the questions it consumes.
+    ///
+    /// Text similarity needs the answers of all users, so it scores 0 here; see
+    /// [`Self::calculate_match_score_with_corpus`].
     pub fn calculate_match_score(
         form_a: &Form,
         form_b: &Form,
-        tag_system: &TagSystem,
+        schema: &FormSchema,
+        catalogs: &Catalogs,
         tag_frequencies: &HashMap<String, u32>,
         total_user_count: u32,
     ) -> f64 {
-        // Hard Filters (Dealbreakers)
+        Self::calculate_match_score_with_corpus(
+            form_a,
+            form_b,
+            schema,
+            catalogs,
+            tag_frequencies,
+            total_user_count,
+            &TextCorpus::default(),
+        )
+    }
 
-        // Gender Filter: Must be one male and one female
+    /// Like [`Self::calculate_match_score`], with text similarity measured against the
+    /// corpus built from the forms of all candidates
+    pub fn calculate_match_score_with_corpus(
+        form_a: &Form,
+        form_b: &Form,
+        schema: &FormSchema,
+        catalogs: &Catalogs,
+        tag_frequencies: &HashMap<String, u32>,
+        total_user_count: u32,
+        text_corpus: &TextCorpus,
+    ) -> f64 {
+        // Gender Filter (Dealbreaker): Must be one male and one female
         if !Self::is_gender_compatible(form_a.gender, form_b.gender) {
             return INCOMPATIBLE_MATCH_SCORE;
         }
 
-        // Physical Boundary Filter: Difference must be <= 1
-        let boundary_diff =
-            Self::physical_boundary_difference(form_a.physical_boundary, form_b.physical_boundary);
-        if boundary_diff > 1 {
-            trace!(
-                "Physical boundary incompatible: {} and {}",
-                form_a.physical_boundary, form_b.physical_boundary
-            );
-            return INCOMPATIBLE_MATCH_SCORE;
-        }
-
-        // Scored Components (Points-based)
         let mut score = 0.0;
 
-        // A. Hierarchical Tag Scoring (Most Important Component)
-        let complementary_weight = *COMPLEMENTARY_TAG_WEIGHT;
-
-        // Familiar x Familiar (high weight)
-        score += Self::calculate_tag_set_score(
-            &form_a.familiar_tags,
-            &form_b.familiar_tags,
-            tag_system,
-            tag_frequencies,
-            total_user_count,
-        ) * 1.0;
-
-        // Familiar x Aspirational (cross-matching)
-        score += Self::calculate_tag_set_score(
-            &form_a.familiar_tags,
-            &form_b.aspirational_tags,
-            tag_system,
-            tag_frequencies,
-            total_user_count,
-        ) * complementary_weight;
-
-        score += Self::calculate_tag_set_score(
-            &form_b.familiar_tags,
-            &form_a.aspirational_tags,
-            tag_system,
-            tag_frequencies,
-            total_user_count,
-        ) * complementary_weight;
+        for scorer in &schema.scorers {
+            match scorer {
+                // Hierarchical Tag Scoring (Most Important Component)
+                Scorer::TagOverlap {
+                    familiar,
+                    aspirational,
+                } => {
+                    let complementary_weight = *COMPLEMENTARY_TAG_WEIGHT;
+
+                    // Familiar x Familiar (high weight)
+                    score += Self::calculate_tag_set_score(
+                        form_a.answers.choices(familiar),
+                        form_b.answers.choices(familiar),
+                        &catalogs.tags,
+                        tag_frequencies,
+                        total_user_count,
+                    ) * 1.0;
 
-        // B. Personal Traits Scoring (B4)
-        score += Self::calculate_trait_compatibility(form_a, form_b);
+                    // Familiar x Aspirational (cross-matching)
+                    score += Self::calculate_tag_set_score(
+                        form_a.answers.choices(familiar),
+                        form_b.answers.choices(aspirational),
+                        &catalogs.tags,
+                        tag_frequencies,
+                        total_user_count,
+                    ) * complementary_weight;
 
-        // C. Physical Boundary Scoring (B5)
-        // Equal boundary get a small bonus
-        if boundary_diff == 0 {
-            score += *BOUNDARY_MATCH_POINTS;
+                    score += Self::calculate_tag_set_score(
+                        form_b.answers.choices(familiar),
+                        form_a.answers.choices(aspirational),
+                        &catalogs.tags,
+                        tag_frequencies,
+                        total_user_count,
+                    ) * complementary_weight;
+                }
+                Scorer::TraitMatch {
+                    own,
+                    ideal,
+                    missing_weight,
+                    extra_weight,
+                } => {
+                    // Both directions: A's ideal vs B's self, and B's ideal vs A's self
+                    score += Self::calculate_trait_compatibility(
+                        form_a.answers.choices(ideal),
+                        form_b.answers.choices(own),
+                        &catalogs.traits,
+                        *missing_weight,
+                        *extra_weight,
+                    );
+                    score += Self::calculate_trait_compatibility(
+                        form_b.answers.choices(ideal),
+                        form_a.answers.choices(own),
+                        &catalogs.traits,
+                        *missing_weight,
+                        *extra_weight,
+                    );
+                }
+                Scorer::ScaleProximity {
+                    question,
+                    max_difference,
+                } => {
+                    let (Some(value_a), Some(value_b)) = (
+                        form_a.answers.scale(question),
+                        form_b.answers.scale(question),
+                    ) else {
+                        // Optional question left unanswered: no filter and no bonus
+                        debug!(
+                            user_a = %form_a.user_id, user_b = %form_b.user_id,
+                            "Missing answer to {}, scored 0", question
+                        );
+                        continue;
+                    };
+
+                    // Hard filter: answers too far apart
+                    let difference = (value_a - value_b).abs();
+                    if difference > *max_difference {
+                        trace!("{} incompatible: {} and {}", question, value_a, value_b);
+                        return INCOMPATIBLE_MATCH_SCORE;
+                    }
+
+                    // Equal answers get a small bonus
+                    if difference == 0 {
+                        score += *BOUNDARY_MATCH_POINTS;
+                    }
+                }
+                Scorer::TextSimilarity { weight, .. } => {
+                    score += text_corpus.similarity(form_a.user_id, form_b.user_id) * weight;
+                }
+            }
         }
 
         trace!(
@@ -105,11 +193,6 @@ impl MatchingService {
         )
     }
 
-    /// Calculate the absolute difference between two physical boundary values
-    fn physical_boundary_difference(boundary_a: i16, boundary_b: i16) -> i16 {
-        (boundary_a - boundary_b).abs()
-    }
-
     /// Calculate compatibility score for a pair of tag sets using hierarchical matching
     pub fn calculate_tag_set_score(
         tags_a: &[String],
@@ -214,125 +297,357 @@ impl MatchingService {
         idf.max(IDF_MIN)
     }
 
-    /// Calculate trait compatibility score between two users
-    fn calculate_trait_compatibility(form_a: &Form, form_b: &Form) -> f64 {
-        let set_a_desired: HashSet<&String> = form_a.ideal_traits.iter().collect();
-        let set_b_self: HashSet<&String> = form_b.self_traits.iter().collect();
-        let set_b_desired: HashSet<&String> = form_b.ideal_traits.iter().collect();
-        let set_a_self: HashSet<&String> = form_a.self_traits.iter().collect();
+    /// Calculate how well one user's own traits satisfy another user's ideal traits
+    ///
+    /// Every ideal trait earns the credit of the closest own trait (see
+    /// [`Self::calculate_trait_credit`]). With asymmetric weighting, the unmet part of
+    /// every ideal trait and of every own trait nobody asked for is subtracted.
+    pub fn calculate_trait_compatibility(
+        ideal_traits: &[String],
+        own_traits: &[String],
+        trait_system: &TagSystem,
+        missing_weight: f64,
+        extra_weight: f64,
+    ) -> f64 {
+        if ideal_traits.is_empty() {
+            return 0.0;
+        }
+
+        let best_credit = |trait_id: &String, candidates: &[String]| {
+            candidates
+                .iter()
+                .map(|other| Self::calculate_trait_credit(trait_id, other, trait_system))
+                .fold(0.0, f64::max)
+        };
+
+        let mut satisfied = 0.0;
+        let mut missing = 0.0;
+        for ideal in ideal_traits {
+            let credit = best_credit(ideal, own_traits);
+            satisfied += credit;
+            missing += 1.0 - credit;
+        }
 
-        // Count how many of A's desired traits are in B's self traits
-        let a_satisfied = set_a_desired.intersection(&set_b_self).count();
+        let extra: f64 = own_traits
+            .iter()
+            .map(|own| 1.0 - best_credit(own, ideal_traits))
+            .sum();
 
-        // Count how many of B's desired traits are in A's self traits
-        let b_satisfied = set_b_desired.intersection(&set_a_self).count();
+        trace!(
+            "Trait match: satisfied {}, missing {}, extra {}",
+            satisfied, missing, extra
+        );
 
-        let trait_match_points = *TRAIT_MATCH_POINTS;
-        let total_matches = (a_satisfied + b_satisfied) as f64;
+        (satisfied - missing * missing_weight - extra * extra_weight) * *TRAIT_MATCH_POINTS
+    }
 
-        total_matches * trait_match_points
+    /// Credit between 0 and 1 for a trait in place of another: 1 for the same trait,
+    /// decayed by `TRAIT_SCORE_DECAY_FACTOR` for every level up to their closest common
+    /// parent, and 0 for unrelated traits.
+    pub fn calculate_trait_credit(trait_a: &str, trait_b: &str, trait_system: &TagSystem) -> f64 {
+        if trait_a == trait_b {
+            return 1.0;
+        }
+
+        let ancestors_a = trait_system.get_all_ancestors(trait_a);
+        let ancestors_b = trait_system.get_all_ancestors(trait_b);
+
+        // Ancestors are ordered from the immediate parent to the root
+        ancestors_a
+            .iter()
+            .enumerate()
+            .find_map(|(level_a, ancestor)| {
+                let level_b = ancestors_b.iter().position(|a| a == ancestor)?;
+                let levels = level_a.max(level_b) + 1;
+                Some(TRAIT_SCORE_DECAY_FACTOR.powi(levels as i32))
+            })
+            .unwrap_or(0.0)
     }
 
-    /// Generate match previews for all users and store them in the database
-    #[instrument(skip_all, err)]
-    pub async fn generate_match_previews(
+    /// Generate match previews for all users of an event and store them in the database
+    #[instrument(skip(db_pool), err)]
+    pub async fn generate_match_previews(db_pool: &PgPool, event_id: Uuid) -> AppResult<()> {
+        let mut tx = db_pool.begin().await?;
+        Self::generate_match_previews_in(db_pool, &mut tx, event_id).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Generate match previews like [`Self::generate_match_previews`], storing them with
+    /// the given connection (usually a transaction) alongside other changes
+    #[instrument(skip(db_pool, conn), err)]
+    pub async fn generate_match_previews_in(
         db_pool: &PgPool,
-        tag_system: &TagSystem,
+        conn: &mut PgConnection,
+        event_id: Uuid,
     ) -> AppResult<()> {
-        let forms = Self::fetch_unmatched_forms(db_pool).await?;
-        if forms.is_empty() {
+        let pool = Self::build_preview_pool(db_pool, event_id).await?;
+        if pool.forms.is_empty() {
             debug!("No forms found, skipping match preview generation");
             return Ok(());
         }
 
         // Fetch all existing vetoes
-        let veto_map = Self::build_map_vetoed_as_key(db_pool).await?;
+        let veto_map = Self::build_map_vetoed_as_key(db_pool, event_id).await?;
+        let total_user_count = pool.forms.len() as u32;
+
+        // Score every user against all other users
+        let mut scored_candidates: Vec<_> = pool
+            .forms
+            .iter()
+            .map(|user_form| {
+                let candidate_scores = Self::score_candidates(user_form, &pool, &veto_map);
+                (user_form.user_id, candidate_scores)
+            })
+            .collect();
+
+        // Users with the fewest viable candidates pick first, so the exposure cap
+        // does not leave them without previews
+        scored_candidates.sort_by_key(|(_, candidates)| candidates.len());
+
+        let tag_sets = Self::build_tag_sets(&pool.forms, &FORM_SCHEMA);
+        let mut exposure: HashMap<Uuid, usize> = HashMap::new();
+
+        for (user_id, candidate_scores) in scored_candidates {
+            let (top_candidates, top_scores): (Vec<_>, Vec<_>) = Self::select_diverse_candidates(
+                candidate_scores,
+                &tag_sets,
+                &exposure,
+                *MAX_PREVIEW_CANDIDATES,
+                *PREVIEW_DIVERSITY_WEIGHT,
+                *MAX_PREVIEW_EXPOSURE,
+            )
+            .into_iter()
+            .unzip();
+
+            for candidate_id in &top_candidates {
+                *exposure.entry(*candidate_id).or_insert(0) += 1;
+            }
 
-        // Calculate tag frequencies for IDF scoring
-        let tag_frequencies = Self::calculate_tag_frequencies(&forms, tag_system);
-        let total_user_count = forms.len() as u32;
+            // Store in database using UPSERT
+            Self::store_match_preview(&mut *conn, event_id, user_id, &top_candidates, &top_scores)
+                .await?;
 
-        // Generate previews for each user
-        for (i, user_form) in forms.iter().enumerate() {
-            let mut candidate_scores = Vec::new();
+            trace!(
+                user_id = %user_id,
+                "Generated {} match previews for user, scores: {:?}",
+                top_candidates.len(), top_scores
+            );
+        }
 
-            // Score against all other users
-            for (j, candidate_form) in forms.iter().enumerate() {
-                if i == j {
-                    continue; // Skip self
-                }
+        debug!(user_count = %total_user_count, "Preview generation completed");
+        Ok(())
+    }
 
-                // Skip if candidate has vetoed this user
-                if let Some(vetoers) = veto_map.get(&user_form.user_id)
-                    && vetoers.contains(&candidate_form.user_id)
-                {
-                    continue;
-                }
+    /// Regenerate the match preview of a single user, e.g. to backfill after a veto
+    ///
+    /// Candidates are scored like in `generate_match_previews`, with the exposure cap
+    /// counted over the previews of all other users. The pool of the last generation is
+    /// reused for up to [`MATCH_PREVIEW_INTERVAL`]: candidates who left the pool since are
+    /// skipped, and those who joined since are only considered by the next generation.
+    #[instrument(skip(db_pool), err)]
+    pub async fn generate_user_match_preview(
+        db_pool: &PgPool,
+        event_id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<()> {
+        let cached = PREVIEW_POOLS
+            .get(&event_id)
+            .map(|pool| Arc::clone(pool.value()))
+            .filter(|pool| {
+                pool.built_at.elapsed() < MATCH_PREVIEW_INTERVAL
+                    && pool.forms.iter().any(|form| form.user_id == user_id)
+            });
+        let pool = match cached {
+            Some(pool) => pool,
+            None => Self::build_preview_pool(db_pool, event_id).await?,
+        };
+
+        let in_pool = Self::fetch_pool_user_ids(db_pool, event_id).await?;
+        let Some(user_form) = pool
+            .forms
+            .iter()
+            .find(|form| form.user_id == user_id && in_pool.contains(&user_id))
+        else {
+            debug!("User is not in the matching pool, skipping match preview generation");
+            return Ok(());
+        };
+
+        let veto_map = Self::build_map_vetoed_as_key(db_pool, event_id).await?;
+        let mut candidate_scores = Self::score_candidates(user_form, &pool, &veto_map);
+        candidate_scores.retain(|(candidate_id, _)| in_pool.contains(candidate_id));
+
+        let tag_sets = Self::build_tag_sets(&pool.forms, &FORM_SCHEMA);
+        let exposure = Self::fetch_preview_exposure(db_pool, event_id, user_id).await?;
+
+        let (top_candidates, top_scores): (Vec<_>, Vec<_>) = Self::select_diverse_candidates(
+            candidate_scores,
+            &tag_sets,
+            &exposure,
+            *MAX_PREVIEW_CANDIDATES,
+            *PREVIEW_DIVERSITY_WEIGHT,
+            *MAX_PREVIEW_EXPOSURE,
+        )
+        .into_iter()
+        .unzip();
+
+        Self::store_match_preview(db_pool, event_id, user_id, &top_candidates, &top_scores).await?;
+
+        trace!(
+            "Regenerated {} match previews for user, scores: {:?}",
+            top_candidates.len(),
+            top_scores
+        );
+        Ok(())
+    }
 
-                let score = Self::calculate_match_score(
+    /// Fetch the forms of the matching pool and derive the scoring inputs, caching the
+    /// result for [`Self::generate_user_match_preview`]
+    async fn build_preview_pool(db_pool: &PgPool, event_id: Uuid) -> AppResult<Arc<PreviewPool>> {
+        let forms = Self::fetch_unmatched_forms(db_pool, event_id).await?;
+
+        // Calculate tag frequencies for IDF scoring
+        let catalogs = EventService::catalogs(db_pool, event_id).await?;
+        let tag_frequencies = Self::calculate_tag_frequencies(&forms, &FORM_SCHEMA, &catalogs.tags);
+        let text_corpus = TextCorpus::build(&forms, &FORM_SCHEMA);
+
+        let pool = Arc::new(PreviewPool {
+            forms,
+            catalogs,
+            tag_frequencies,
+            text_corpus,
+            built_at: Instant::now(),
+        });
+        PREVIEW_POOLS.insert(event_id, Arc::clone(&pool));
+        Ok(pool)
+    }
+
+    /// Score `user_form` against every other form of the pool with a positive score
+    ///
+    /// Pairs where either user has vetoed the other are skipped.
+    fn score_candidates(
+        user_form: &Form,
+        pool: &PreviewPool,
+        veto_map: &HashMap<Uuid, HashSet<Uuid>>,
+    ) -> Vec<(Uuid, f64)> {
+        pool.forms
+            .iter()
+            .filter(|candidate_form| candidate_form.user_id != user_form.user_id)
+            .filter(|candidate_form| {
+                !Self::is_vetoed(user_form.user_id, candidate_form.user_id, veto_map)
+                    && !Self::is_vetoed(candidate_form.user_id, user_form.user_id, veto_map)
+            })
+            .filter_map(|candidate_form| {
+                let score = Self::calculate_match_score_with_corpus(
                     user_form,
                     candidate_form,
-                    tag_system,
-                    &tag_frequencies,
-                    total_user_count,
+                    &FORM_SCHEMA,
+                    &pool.catalogs,
+                    &pool.tag_frequencies,
+                    pool.forms.len() as u32,
+                    &pool.text_corpus,
                 );
+                (score > 0.0).then_some((candidate_form.user_id, score))
+            })
+            .collect()
+    }
 
-                if score > 0.0 {
-                    candidate_scores.push((candidate_form.user_id, score));
+    /// Pick up to `limit` preview candidates by Maximal Marginal Relevance
+    ///
+    /// Candidates are picked one at a time, trading their score (relative to the best
+    /// candidate) against their tag overlap with the candidates already picked:
+    /// `(1 - diversity_weight) * relevance - diversity_weight * max_similarity`.
+    /// Candidates already shown in `max_exposure` previews are skipped.
+    pub fn select_diverse_candidates(
+        mut candidates: Vec<(Uuid, f64)>,
+        tag_sets: &HashMap<Uuid, HashSet<&str>>,
+        exposure: &HashMap<Uuid, usize>,
+        limit: usize,
+        diversity_weight: f64,
+        max_exposure: usize,
+    ) -> Vec<(Uuid, f64)> {
+        candidates.retain(|(id, _)| exposure.get(id).copied().unwrap_or(0) < max_exposure);
+        // Ties go to the higher score
+        candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let max_score = candidates.first().map_or(1.0, |(_, score)| *score);
+        let empty = HashSet::new();
+        let tags_of = |id: &Uuid| tag_sets.get(id).unwrap_or(&empty);
+
+        let mut selected: Vec<(Uuid, f64)> = Vec::with_capacity(limit);
+        while selected.len() < limit && !candidates.is_empty() {
+            let mut best_index = 0;
+            let mut best_value = f64::NEG_INFINITY;
+
+            for (index, (id, score)) in candidates.iter().enumerate() {
+                let max_similarity = selected
+                    .iter()
+                    .map(|(other, _)| Self::jaccard_similarity(tags_of(id), tags_of(other)))
+                    .fold(0.0, f64::max);
+                let value = (1.0 - diversity_weight) * (score / max_score)
+                    - diversity_weight * max_similarity;
+
+                if value > best_value {
+                    best_index = index;
+                    best_value = value;
                 }
             }
 
-            // Sort by score (descending) and take top N candidates
-            candidate_scores
-                .sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-            let (top_candidates, top_scores): (Vec<_>, Vec<_>) = candidate_scores
-                .into_iter()
-                .take(*MAX_PREVIEW_CANDIDATES)
-                .unzip();
+            selected.push(candidates.remove(best_index));
+        }
 
-            // Store in database using UPSERT
-            Self::store_match_preview(db_pool, user_form.user_id, &top_candidates, &top_scores)
-                .await?;
+        selected
+    }
 
-            trace!(
-                user_id = %user_form.user_id,
-                "Generated {} match previews for user, scores: {:?}",
-                top_candidates.len(), top_scores
-            );
+    /// Jaccard similarity of two tag sets, 0 if both are empty
+    fn jaccard_similarity(tags_a: &HashSet<&str>, tags_b: &HashSet<&str>) -> f64 {
+        let union = tags_a.union(tags_b).count();
+        if union == 0 {
+            return 0.0;
         }
 
-        debug!(user_count = %total_user_count, "Preview generation completed");
-        Ok(())
+        tags_a.intersection(tags_b).count() as f64 / union as f64
+    }
+
+    /// Collect the tags each user chose in questions consumed by tag scorers
+    fn build_tag_sets<'a>(
+        forms: &'a [Form],
+        schema: &FormSchema,
+    ) -> HashMap<Uuid, HashSet<&'a str>> {
+        forms
+            .iter()
+            .map(|form| {
+                let tags = schema
+                    .tag_questions()
+                    .flat_map(|question| form.answers.choices(question))
+                    .map(String::as_str)
+                    .collect();
+                (form.user_id, tags)
+            })
+            .collect()
     }
 
     /// Calculate tag frequencies across all forms for IDF scoring
     /// Counts both leaf tags and all their ancestors to ensure realistic IDF scores
+    ///
+    /// Only answers to questions consumed by tag scorers are counted.
     pub(crate) fn calculate_tag_frequencies(
         forms: &[Form],
+        schema: &FormSchema,
         tag_system: &TagSystem,
     ) -> HashMap<String, u32> {
         let mut frequencies = HashMap::new();
 
         for form in forms {
-            // Count familiar tags and their ancestors
-            for tag in &form.familiar_tags {
-                // Count the tag itself
-                *frequencies.entry(tag.clone()).or_insert(0) += 1;
-
-                // Count all ancestors
-                for ancestor in tag_system.get_all_ancestors(tag) {
-                    *frequencies.entry(ancestor).or_insert(0) += 1;
-                }
-            }
-
-            // Count aspirational tags and their ancestors
-            for tag in &form.aspirational_tags {
-                // Count the tag itself
-                *frequencies.entry(tag.clone()).or_insert(0) += 1;
-
-                // Count all ancestors
-                for ancestor in tag_system.get_all_ancestors(tag) {
-                    *frequencies.entry(ancestor).or_insert(0) += 1;
+            for question in schema.tag_questions() {
+                for tag in form.answers.choices(question) {
+                    // Count the tag itself
+                    *frequencies.entry(tag.clone()).or_insert(0) += 1;
+
+                    // Count all ancestors
+                    for ancestor in tag_system.get_all_ancestors(tag) {
+                        *frequencies.entry(ancestor).or_insert(0) += 1;
+                    }
                 }
             }
         }
@@ -340,70 +655,148 @@ impl MatchingService {
         frequencies
     }
 
-    /// Fetch all forms that have completed status and are eligible for matching
-    pub(crate) async fn fetch_unmatched_forms(db_pool: &PgPool) -> Result<Vec<Form>, sqlx::Error> {
+    /// Number of distinct tags both users chose in answers to tag questions
+    pub fn count_shared_tags(form_a: &Form, form_b: &Form, schema: &FormSchema) -> usize {
+        let tags = |form: &Form| -> HashSet<String> {
+            schema
+                .tag_questions()
+                .flat_map(|question| form.answers.choices(question))
+                .cloned()
+                .collect()
+        };
+
+        tags(form_a).intersection(&tags(form_b)).count()
+    }
+
+    /// Fetch all forms of an event that have completed status and are eligible for matching
+    /// (suspended, paused and withdrawn users are excluded since their status is no longer
+    /// `form_completed`)
+    pub(crate) async fn fetch_unmatched_forms(
+        db_pool: &PgPool,
+        event_id: Uuid,
+    ) -> Result<Vec<Form>, sqlx::Error> {
         sqlx::query_as!(
             Form,
             r#"
-            SELECT user_id, gender as "gender: Gender", familiar_tags, aspirational_tags, recent_topics,
-                   self_traits, ideal_traits, physical_boundary, self_intro, profile_photo_filename
+            SELECT user_id, gender as "gender: Gender",
+                   answers as "answers: Json<FormAnswers>", profile_photo_filename
             FROM forms f
             JOIN users u ON u.id = f.user_id
-            WHERE u.status = 'form_completed'
+            WHERE f.event_id = $1 AND u.status = 'form_completed'
             "#,
+            event_id
         )
         .fetch_all(db_pool)
         .await
     }
 
-    /// Fetch all forms that have been submitted, regardless of user status
+    /// Ids of the users in the matching pool of an event, see [`Self::fetch_unmatched_forms`]
+    async fn fetch_pool_user_ids(
+        db_pool: &PgPool,
+        event_id: Uuid,
+    ) -> Result<HashSet<Uuid>, sqlx::Error> {
+        let user_ids = sqlx::query_scalar!(
+            r#"
+            SELECT f.user_id
+            FROM forms f
+            JOIN users u ON u.id = f.user_id
+            WHERE f.event_id = $1 AND u.status = 'form_completed'
+            "#,
+            event_id
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(user_ids.into_iter().collect())
+    }
+
+    /// Fetch all forms that have been submitted to an event, regardless of user status
     /// Used for calculating tag frequencies to ensure stable IDF scores
     pub(crate) async fn fetch_all_submitted_forms(
         db_pool: &PgPool,
+        event_id: Uuid,
     ) -> Result<Vec<Form>, sqlx::Error> {
         sqlx::query_as!(
             Form,
             r#"
-            SELECT user_id, gender as "gender: Gender", familiar_tags, aspirational_tags, recent_topics,
-                   self_traits, ideal_traits, physical_boundary, self_intro, profile_photo_filename
+            SELECT user_id, gender as "gender: Gender",
+                   answers as "answers: Json<FormAnswers>", profile_photo_filename
             FROM forms
+            WHERE event_id = $1
             "#,
+            event_id
         )
         .fetch_all(db_pool)
         .await
     }
 
     /// Store match preview in database using UPSERT operation
+    ///
+    /// Skipped if the user left the matching pool (e.g. paused) while previews were
+    /// being generated.
     async fn store_match_preview(
-        db_pool: &PgPool,
+        executor: impl PgExecutor<'_>,
+        event_id: Uuid,
         user_id: Uuid,
         candidate_ids: &[Uuid],
         scores: &[f64],
     ) -> Result<(), sqlx::Error> {
         sqlx::query!(
-            "INSERT INTO match_previews (user_id, candidate_ids, scores)
-             VALUES ($1, $2, $3)
-             ON CONFLICT (user_id)
+            "INSERT INTO match_previews (event_id, user_id, candidate_ids, scores)
+             SELECT $1, $2, $3, $4
+             WHERE EXISTS (SELECT 1 FROM users WHERE id = $2 AND status = 'form_completed')
+             ON CONFLICT (event_id, user_id)
              DO UPDATE SET
                 candidate_ids = EXCLUDED.candidate_ids,
                 scores = EXCLUDED.scores",
+            event_id,
             user_id,
             candidate_ids,
             scores
         )
-        .execute(db_pool)
+        .execute(executor)
         .await?;
 
         Ok(())
     }
 
-    /// Build a map of vetoed_id -> set of vetoer_ids for efficient lookup
+    /// Count how many previews of users other than `user_id` show each candidate
+    async fn fetch_preview_exposure(
+        db_pool: &PgPool,
+        event_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<HashMap<Uuid, usize>, sqlx::Error> {
+        let candidate_ids = sqlx::query_scalar!(
+            r#"
+            SELECT UNNEST(candidate_ids) as "candidate_id!"
+            FROM match_previews
+            WHERE event_id = $1 AND user_id != $2
+            "#,
+            event_id,
+            user_id
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let mut exposure = HashMap::new();
+        for candidate_id in candidate_ids {
+            *exposure.entry(candidate_id).or_insert(0) += 1;
+        }
+
+        Ok(exposure)
+    }
+
+    /// Build a map of vetoed_id -> set of vetoer_ids of an event for efficient lookup
     pub(crate) async fn build_map_vetoed_as_key(
         db_pool: &PgPool,
+        event_id: Uuid,
     ) -> Result<HashMap<Uuid, HashSet<Uuid>>, sqlx::Error> {
-        let vetoes = sqlx::query!("SELECT vetoer_id, vetoed_id FROM vetoes")
-            .fetch_all(db_pool)
-            .await?;
+        let vetoes = sqlx::query!(
+            "SELECT vetoer_id, vetoed_id FROM vetoes WHERE event_id = $1",
+            event_id
+        )
+        .fetch_all(db_pool)
+        .await?;
 
         let mut veto_map: HashMap<Uuid, HashSet<Uuid>> = HashMap::new();
         for veto in vetoes {
@@ -427,15 +820,17 @@ impl MatchingService {
             .is_some_and(|vetoed_set| vetoed_set.contains(&user_b))
     }
 
-    /// Spawn the periodic preview generation task
-    pub fn spawn_preview_generation_task(db_pool: PgPool, tag_system: &'static TagSystem) {
+    /// Spawn the periodic preview generation task for the current event
+    pub fn spawn_preview_generation_task(db_pool: PgPool) {
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(MATCH_PREVIEW_INTERVAL);
             interval.tick().await; // First tick completes immediately, so we skip it
 
             loop {
                 interval.tick().await;
-                let _ = Self::generate_match_previews(&db_pool, tag_system).await;
+                if let Ok(event_id) = EventService::current_event_id(&db_pool).await {
+                    let _ = Self::generate_match_previews(&db_pool, event_id).await;
+                }
             }
         });
     }
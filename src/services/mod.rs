@@ -6,12 +6,36 @@
 //!
 //! ## Available Services
 //!
+//! - **Audit** (`audit`) - Admin audit log persistence and queries
+//! - **Domain** (`domain`) - Runtime-managed allowed email domains
 //! - **Email** (`email`) - Email delivery service with multiple implementations
+//! - **Event** (`event`) - Events and their phase timeline gating user actions
+//! - **Feedback** (`feedback`) - Feedback on confirmed final matches and its aggregation
 //! - **JWT** (`jwt`) - JSON Web Token creation, validation, and management
+//! - **Match Stats** (`match_stats`) - Grouping of final matches for feedback and rejection statistics
 //! - **Matching** (`matching`) - User compatibility scoring and matching algorithms
+//! - **Moderation** (`moderation`) - User suspension and lifting
+//! - **Notification** (`notification`) - Email notifications about match lifecycle events
+//! - **Outbox** (`outbox`) - Durable email queue with retries and a background delivery worker
+//! - **Rejection** (`rejection`) - Rejected final matches with their reasons and their aggregation
+//! - **Retention** (`retention`) - Purging of student card photos after review
 //! - **Scheduler** (`scheduler`) - Scheduled final match execution service
+//! - **Text Similarity** (`text_similarity`) - TF-IDF similarity of free text answers
+//! - **User Event** (`user_event`) - Live user events fanned out through LISTEN/NOTIFY
 
+pub mod audit;
+pub mod domain;
 pub mod email;
+pub mod event;
+pub mod feedback;
 pub mod jwt;
+pub mod match_stats;
 pub mod matching;
+pub mod moderation;
+pub mod notification;
+pub mod outbox;
+pub mod rejection;
+pub mod retention;
 pub mod scheduler;
+pub mod text_similarity;
+pub mod user_event;
@@ -0,0 +1,353 @@
+//! # Moderation Service
+//!
+//! Admins can suspend misbehaving users, taking them out of the event until the
+//! suspension is lifted (manually or when `suspended_until` passes). Suspending a
+//! user dissolves their final match, if any, and notifies the partner by email
+//! unless they opted out. The dissolved match is kept among the rejections like
+//! one deleted by an admin.
+//!
+//! The status the user had before the suspension is kept in
+//! `status_before_suspension` and restored on lift. A dissolved match cannot be
+//! restored, so `matched` and `confirmed` users come back as `form_completed`.
+//!
+//! Suspended users cannot log in, and suspending revokes their refresh tokens.
+//! Access tokens issued before are rejected by the auth middleware, which caches
+//! the suspension state of each user for [`SUSPENSION_CACHE_TTL`].
+
+use std::{sync::LazyLock, time::Instant};
+
+use dashmap::DashMap;
+use sqlx::PgPool;
+use time::OffsetDateTime;
+use tracing::{debug, info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{AdminAction, MatchEvent, NewAuditEntry, Notification, UserStatus},
+    services::{
+        audit::AuditService, event::EventService, notification::NotificationService,
+        rejection::RejectionService, scheduler::SCHEDULER_ACTOR,
+    },
+    utils::constant::{CHECK_SUSPENSION_EXPIRY_INTERVAL, SUSPENSION_CACHE_TTL},
+};
+
+/// Suspension state of recently authenticated users, shared by the main and admin
+/// servers of this process. Suspending or lifting a suspension evicts the user, so
+/// only changes made outside this process wait for the entry to expire.
+static SUSPENSION_CACHE: LazyLock<DashMap<Uuid, (Option<SuspensionNotice>, Instant)>> =
+    LazyLock::new(DashMap::new);
+
+pub struct ModerationService;
+
+impl ModerationService {
+    /// Suspends a user and dissolves their final match.
+    ///
+    /// Returns the status the user had before the suspension.
+    #[instrument(skip(db_pool, reason), err)]
+    pub async fn suspend_user(
+        db_pool: &PgPool,
+        actor: &str,
+        user_id: Uuid,
+        reason: &str,
+        suspended_until: Option<OffsetDateTime>,
+    ) -> AppResult<UserStatus> {
+        let event_id = EventService::current_event_id(db_pool).await?;
+        let mut tx = db_pool.begin().await?;
+
+        let current_status = sqlx::query_scalar!(
+            r#"SELECT status as "status: UserStatus" FROM users WHERE id = $1 FOR UPDATE"#,
+            user_id
+        )
+        .fetch_optional(tx.as_mut())
+        .await?
+        .ok_or_else(|| {
+            warn!(%user_id, "User not found");
+            AppError::NotFound("User not found")
+        })?;
+
+        if current_status.is_suspended() {
+            warn!(%user_id, "User is already suspended");
+            return Err(AppError::BadRequest("User is already suspended"));
+        }
+
+        // Release the partner of an active final match
+        let final_match = sqlx::query!(
+            r#"
+            SELECT id, user_a_id, user_b_id
+            FROM final_matches
+            WHERE event_id = $1 AND (user_a_id = $2 OR user_b_id = $2)
+            "#,
+            event_id,
+            user_id
+        )
+        .fetch_optional(tx.as_mut())
+        .await?;
+
+        let mut notifications = Vec::new();
+        if let Some(final_match) = &final_match {
+            let partner_id = if final_match.user_a_id == user_id {
+                final_match.user_b_id
+            } else {
+                final_match.user_a_id
+            };
+
+            // Keep the dissolved match for the rejection statistics
+            RejectionService::record_deletion(tx.as_mut(), final_match.id, actor).await?;
+
+            let partner_result = sqlx::query!(
+                "UPDATE users SET status = 'form_completed' WHERE id = $1 AND (status = 'matched' OR status = 'confirmed')",
+                partner_id
+            )
+            .execute(tx.as_mut())
+            .await?;
+
+            // A partner who is no longer matched or confirmed has nothing to revert
+            if partner_result.rows_affected() == 0 {
+                debug!(final_match_id = %final_match.id, %partner_id, "Partner not in an active match, left unchanged");
+            } else {
+                notifications.push(Notification::new(partner_id, MatchEvent::MatchDissolved));
+            }
+
+            info!(final_match_id = %final_match.id, %partner_id, "Final match dissolved due to suspension");
+        }
+
+        sqlx::query!(
+            "DELETE FROM match_previews WHERE event_id = $1 AND user_id = $2",
+            event_id,
+            user_id
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        let restore_status = match current_status {
+            UserStatus::Matched | UserStatus::Confirmed => UserStatus::FormCompleted,
+            status => status,
+        };
+
+        sqlx::query!(
+            r#"
+            UPDATE users
+            SET status = 'suspended',
+                suspension_reason = $1,
+                suspended_until = $2,
+                status_before_suspension = $3
+            WHERE id = $4
+            "#,
+            reason,
+            suspended_until,
+            restore_status as UserStatus,
+            user_id
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        // Keep the user from refreshing their session
+        sqlx::query!("DELETE FROM refresh_tokens WHERE user_id = $1", user_id)
+            .execute(tx.as_mut())
+            .await?;
+
+        let mut target_ids = vec![user_id];
+        if let Some(final_match) = &final_match {
+            target_ids.push(final_match.id);
+        }
+        let audit = NewAuditEntry::new(actor, AdminAction::SuspendUser, target_ids)
+            .payload(serde_json::json!({
+                "reason": reason,
+                "suspended_until": suspended_until,
+                "dissolved_final_match": final_match.as_ref().map(|fm| fm.id),
+            }))
+            .status_change(Some(current_status), UserStatus::Suspended);
+        AuditService::record(tx.as_mut(), &audit).await?;
+
+        NotificationService::notify(tx.as_mut(), &notifications).await?;
+
+        tx.commit().await?;
+        SuspensionNotice::evict(user_id);
+        info!(%user_id, previous_status = %current_status, "User suspended");
+
+        Ok(current_status)
+    }
+
+    /// Lifts a user's suspension and restores their previous status.
+    ///
+    /// Returns the restored status.
+    #[instrument(skip(db_pool), err)]
+    pub async fn lift_suspension(
+        db_pool: &PgPool,
+        actor: &str,
+        user_id: Uuid,
+    ) -> AppResult<UserStatus> {
+        let mut tx = db_pool.begin().await?;
+
+        let current_status = sqlx::query_scalar!(
+            r#"SELECT status as "status: UserStatus" FROM users WHERE id = $1 FOR UPDATE"#,
+            user_id
+        )
+        .fetch_optional(tx.as_mut())
+        .await?
+        .ok_or_else(|| {
+            warn!(%user_id, "User not found");
+            AppError::NotFound("User not found")
+        })?;
+
+        if !current_status.is_suspended() {
+            warn!(%user_id, status = %current_status, "User is not suspended");
+            return Err(AppError::BadRequest("User is not suspended"));
+        }
+
+        let restored_status = sqlx::query_scalar!(
+            r#"
+            UPDATE users
+            SET status = COALESCE(status_before_suspension, 'unverified'),
+                suspension_reason = NULL,
+                suspended_until = NULL,
+                status_before_suspension = NULL
+            WHERE id = $1
+            RETURNING status as "status: UserStatus"
+            "#,
+            user_id
+        )
+        .fetch_one(tx.as_mut())
+        .await?;
+
+        let audit = NewAuditEntry::new(actor, AdminAction::LiftSuspension, vec![user_id])
+            .status_change(Some(current_status), restored_status);
+        AuditService::record(tx.as_mut(), &audit).await?;
+
+        tx.commit().await?;
+        SuspensionNotice::evict(user_id);
+        info!(%user_id, %restored_status, "Suspension lifted");
+
+        Ok(restored_status)
+    }
+
+    /// Lifts all suspensions whose `suspended_until` has passed.
+    ///
+    /// Returns the number of suspensions lifted.
+    #[instrument(skip_all, err)]
+    pub async fn lift_expired_suspensions(db_pool: &PgPool) -> AppResult<usize> {
+        let expired = sqlx::query_scalar!(
+            r#"
+            SELECT id
+            FROM users
+            WHERE status = 'suspended' AND suspended_until <= NOW()
+            "#
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let mut lifted = 0;
+        for user_id in expired {
+            match Self::lift_suspension(db_pool, SCHEDULER_ACTOR, user_id).await {
+                Ok(_) => lifted += 1,
+                // Lifted concurrently by an admin or the auth middleware
+                Err(AppError::BadRequest(_)) => {
+                    debug!(%user_id, "Suspension already lifted");
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        if lifted > 0 {
+            info!(lifted, "Lifted expired suspensions");
+        }
+        Ok(lifted)
+    }
+
+    /// Spawn the periodic task that lifts expired suspensions
+    pub fn spawn_suspension_expiry_task(db_pool: PgPool) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(CHECK_SUSPENSION_EXPIRY_INTERVAL);
+            interval.tick().await; // First tick completes immediately, so we skip it
+
+            loop {
+                interval.tick().await;
+                let _ = Self::lift_expired_suspensions(&db_pool).await;
+            }
+        });
+    }
+}
+
+/// Details returned to a suspended user when they try to use the API
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SuspensionNotice {
+    pub message: &'static str,
+    pub reason: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub suspended_until: Option<OffsetDateTime>,
+}
+
+impl SuspensionNotice {
+    /// Returns the notice if the user is currently suspended.
+    ///
+    /// An expired suspension is lifted on the spot instead of waiting for the
+    /// background task, so the user is never locked out past `suspended_until`.
+    pub async fn check(db_pool: &PgPool, user_id: Uuid) -> AppResult<Option<Self>> {
+        let Some(row) = sqlx::query!(
+            r#"
+            SELECT status as "status: UserStatus", suspension_reason, suspended_until
+            FROM users
+            WHERE id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(db_pool)
+        .await?
+        else {
+            // Let the handler decide how to respond to a missing user
+            return Ok(None);
+        };
+
+        if !row.status.is_suspended() {
+            return Ok(None);
+        }
+
+        if row
+            .suspended_until
+            .is_some_and(|until| until <= OffsetDateTime::now_utc())
+        {
+            match ModerationService::lift_suspension(db_pool, SCHEDULER_ACTOR, user_id).await {
+                Ok(_) | Err(AppError::BadRequest(_)) => return Ok(None),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(Some(Self {
+            message: "Your account has been suspended",
+            reason: row.suspension_reason,
+            suspended_until: row.suspended_until,
+        }))
+    }
+
+    /// Like [`Self::check`], but answered from the cache while its entry is fresh
+    /// and the cached suspension has not expired.
+    pub async fn check_cached(db_pool: &PgPool, user_id: Uuid) -> AppResult<Option<Self>> {
+        let cached = SUSPENSION_CACHE.get(&user_id).and_then(|entry| {
+            let (notice, checked_at) = entry.value();
+            let suspension_expired = notice
+                .as_ref()
+                .and_then(|notice| notice.suspended_until)
+                .is_some_and(|until| until <= OffsetDateTime::now_utc());
+            (checked_at.elapsed() < SUSPENSION_CACHE_TTL && !suspension_expired)
+                .then(|| notice.clone())
+        });
+        if let Some(notice) = cached {
+            return Ok(notice);
+        }
+
+        let notice = Self::check(db_pool, user_id).await?;
+        SUSPENSION_CACHE.insert(user_id, (notice.clone(), Instant::now()));
+        Ok(notice)
+    }
+
+    /// Removes the cached state of a user whose suspension changed
+    fn evict(user_id: Uuid) {
+        SUSPENSION_CACHE.remove(&user_id);
+    }
+
+    /// Removes the cache entries that are no longer fresh
+    pub fn cleanup_cache() {
+        SUSPENSION_CACHE.retain(|_, (_, checked_at)| checked_at.elapsed() < SUSPENSION_CACHE_TTL);
+    }
+}
@@ -0,0 +1,191 @@
+//! # Notification Service
+//!
+//! Final matching, responses to final matches, suspensions and the auto-accept
+//! task emit [`MatchEvent`]s for the affected users. Unless the user opted out of
+//! that kind of notification, an email is written to the outbox in the same
+//! transaction as the change, so it is sent if and only if the change is committed.
+
+use std::collections::HashMap;
+
+use sqlx::{PgConnection, PgPool};
+use tracing::{debug, info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    error::AppResult,
+    models::{
+        MatchEvent, NewEmail, Notification, NotificationPreferences, TemplateId,
+        UpdateNotificationPreferencesRequest,
+    },
+    services::outbox::EmailOutboxService,
+};
+
+pub struct NotificationService;
+
+/// Where and whether a notified user is emailed
+struct Recipient {
+    email: String,
+    locale: String,
+    preferences: NotificationPreferences,
+}
+
+impl NotificationService {
+    /// The email sent to a user for an event
+    pub fn email(recipient: &str, event: MatchEvent) -> NewEmail {
+        match event {
+            MatchEvent::FinalMatchCreated { auto_accept_hours } => NewEmail::new(
+                recipient,
+                TemplateId::FinalMatchCreated,
+                [("auto_accept_hours", auto_accept_hours.to_string())],
+            ),
+            MatchEvent::PartnerAccepted => {
+                NewEmail::new(recipient, TemplateId::PartnerAccepted, [])
+            }
+            MatchEvent::MatchRejected => NewEmail::new(recipient, TemplateId::MatchRejected, []),
+            MatchEvent::MatchDissolved => NewEmail::new(recipient, TemplateId::MatchDissolved, []),
+            MatchEvent::MatchAutoConfirmed => {
+                NewEmail::new(recipient, TemplateId::MatchAutoConfirmed, [])
+            }
+            MatchEvent::MatchReminder { hours_left } => NewEmail::new(
+                recipient,
+                TemplateId::MatchReminder,
+                [("hours_left", hours_left.to_string())],
+            ),
+        }
+    }
+
+    /// Get the notification preferences of a user
+    pub async fn get_preferences(
+        db_pool: &PgPool,
+        user_id: Uuid,
+    ) -> AppResult<NotificationPreferences> {
+        let preferences = sqlx::query_as!(
+            NotificationPreferences,
+            r#"
+            SELECT final_match_created, partner_accepted, match_rejected, match_dissolved,
+                   match_auto_confirmed, match_reminder
+            FROM notification_preferences
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(db_pool)
+        .await?;
+
+        Ok(preferences.unwrap_or_default())
+    }
+
+    /// Change the notification preferences of a user, keeping omitted fields
+    #[instrument(skip(db_pool), err)]
+    pub async fn update_preferences(
+        db_pool: &PgPool,
+        user_id: Uuid,
+        request: &UpdateNotificationPreferencesRequest,
+    ) -> AppResult<NotificationPreferences> {
+        let preferences = sqlx::query_as!(
+            NotificationPreferences,
+            r#"
+            INSERT INTO notification_preferences
+                (user_id, final_match_created, partner_accepted, match_rejected, match_dissolved,
+                 match_auto_confirmed, match_reminder)
+            VALUES ($1, COALESCE($2, TRUE), COALESCE($3, TRUE), COALESCE($4, TRUE), COALESCE($5, TRUE),
+                    COALESCE($6, TRUE), COALESCE($7, TRUE))
+            ON CONFLICT (user_id) DO UPDATE SET
+                final_match_created = COALESCE($2, notification_preferences.final_match_created),
+                partner_accepted = COALESCE($3, notification_preferences.partner_accepted),
+                match_rejected = COALESCE($4, notification_preferences.match_rejected),
+                match_dissolved = COALESCE($5, notification_preferences.match_dissolved),
+                match_auto_confirmed = COALESCE($6, notification_preferences.match_auto_confirmed),
+                match_reminder = COALESCE($7, notification_preferences.match_reminder)
+            RETURNING final_match_created, partner_accepted, match_rejected, match_dissolved,
+                      match_auto_confirmed, match_reminder
+            "#,
+            user_id,
+            request.final_match_created,
+            request.partner_accepted,
+            request.match_rejected,
+            request.match_dissolved,
+            request.match_auto_confirmed,
+            request.match_reminder
+        )
+        .fetch_one(db_pool)
+        .await?;
+
+        info!(?preferences, "Updated notification preferences");
+        Ok(preferences)
+    }
+
+    /// Enqueue emails for the notifications of users who did not opt out of them
+    ///
+    /// Call within the transaction making the change the notifications are about.
+    #[instrument(skip_all, fields(count = notifications.len()), err)]
+    pub async fn notify(conn: &mut PgConnection, notifications: &[Notification]) -> AppResult<()> {
+        if notifications.is_empty() {
+            return Ok(());
+        }
+
+        let recipients = Self::fetch_recipients(&mut *conn, notifications).await?;
+
+        for notification in notifications {
+            let Some(recipient) = recipients.get(&notification.user_id) else {
+                continue;
+            };
+            if !recipient.preferences.allows(notification.event) {
+                debug!(user_id = %notification.user_id, event = ?notification.event, "User opted out of notification");
+                continue;
+            }
+
+            let email =
+                Self::email(&recipient.email, notification.event).in_locale(&recipient.locale);
+            EmailOutboxService::enqueue(&mut *conn, &email).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Email address, locale and preferences of each notified user
+    async fn fetch_recipients(
+        conn: &mut PgConnection,
+        notifications: &[Notification],
+    ) -> AppResult<HashMap<Uuid, Recipient>> {
+        let user_ids: Vec<Uuid> = notifications.iter().map(|n| n.user_id).collect();
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT u.id, u.email, u.locale,
+                   np.final_match_created as "final_match_created?",
+                   np.partner_accepted as "partner_accepted?",
+                   np.match_rejected as "match_rejected?",
+                   np.match_dissolved as "match_dissolved?",
+                   np.match_auto_confirmed as "match_auto_confirmed?",
+                   np.match_reminder as "match_reminder?"
+            FROM users u
+            LEFT JOIN notification_preferences np ON np.user_id = u.id
+            WHERE u.id = ANY($1)
+            "#,
+            &user_ids
+        )
+        .fetch_all(conn)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let preferences = NotificationPreferences {
+                    final_match_created: row.final_match_created.unwrap_or(true),
+                    partner_accepted: row.partner_accepted.unwrap_or(true),
+                    match_rejected: row.match_rejected.unwrap_or(true),
+                    match_dissolved: row.match_dissolved.unwrap_or(true),
+                    match_auto_confirmed: row.match_auto_confirmed.unwrap_or(true),
+                    match_reminder: row.match_reminder.unwrap_or(true),
+                };
+                let recipient = Recipient {
+                    email: row.email,
+                    locale: row.locale,
+                    preferences,
+                };
+                (row.id, recipient)
+            })
+            .collect())
+    }
+}
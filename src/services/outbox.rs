@@ -0,0 +1,342 @@
+//! # Email Outbox Service
+//!
+//! Emails are enqueued in `email_outbox`, usually in the same transaction as the
+//! change they are about, and delivered by a background worker. Inserting into
+//! the outbox fires a `NOTIFY` on [`EMAIL_OUTBOX_CHANNEL`] that wakes up the
+//! workers of all running servers; retries are picked up by polling.
+//!
+//! Every delivery attempt first leases the email by pushing `next_attempt_at`
+//! forward, so concurrent workers never send it twice and an attempt that never
+//! finished is retried once the lease runs out. Delivered emails are marked sent
+//! along with the provider that accepted them. Failed attempts are retried with
+//! exponential backoff until the email expires or runs out of attempts, after
+//! which it stays in the outbox as a dead letter. Emails rejected permanently,
+//! e.g. by an SMTP 5xx reply, are not retried. Sent emails and dead letters no
+//! longer carry verification codes and are purged after [`EMAIL_OUTBOX_RETENTION`].
+//!
+//! Emails are rendered from their template when enqueued, so invalid variables
+//! fail the change they are about, and again on every delivery attempt.
+//!
+//! Emails to an existing account are linked to it and deleted along with it, so
+//! a later account of the same address never sees them.
+
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use sqlx::{PgConnection, PgPool, postgres::PgListener, types::Json};
+use time::OffsetDateTime;
+use tracing::{debug, error, info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppResult},
+    models::{EmailStatus, NewEmail, OutboxEmail, TemplateId},
+    services::email::{EmailError, EmailService},
+    utils::{
+        constant::{
+            EMAIL_DELIVERY_LEASE, EMAIL_MAX_ATTEMPTS, EMAIL_OUTBOX_BATCH_SIZE,
+            EMAIL_OUTBOX_POLL_INTERVAL, EMAIL_OUTBOX_RETENTION, EMAIL_RETRY_BASE_DELAY,
+            EMAIL_RETRY_MAX_DELAY,
+        },
+        static_object::EMAIL_TEMPLATES,
+    },
+};
+
+/// Channel notified by the database whenever emails are enqueued
+pub const EMAIL_OUTBOX_CHANNEL: &str = "email_outbox";
+
+/// An email claimed for a delivery attempt
+struct LeasedEmail {
+    id: Uuid,
+    template: TemplateId,
+    recipient: String,
+    locale: String,
+    variables: Json<BTreeMap<String, String>>,
+    attempts: i32,
+}
+
+pub struct EmailOutboxService;
+
+impl EmailOutboxService {
+    /// Write an email to the outbox. It is delivered once the transaction commits.
+    ///
+    /// The email is linked to the account of the recipient, if there is one.
+    pub async fn enqueue(conn: &mut PgConnection, email: &NewEmail) -> AppResult<Uuid> {
+        let message = EMAIL_TEMPLATES
+            .render(
+                &email.recipient,
+                email.template,
+                &email.locale,
+                email.variables.clone(),
+            )
+            .map_err(|e| {
+                error!(error = %e, template = %email.template, "Failed to render email");
+                AppError::Internal
+            })?;
+
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO email_outbox
+                (template, recipient, locale, variables, subject, expires_at, user_id)
+            VALUES ($1, $2::text, $3, $4, $5, $6, (SELECT id FROM users WHERE email = $2::text))
+            RETURNING id
+            "#,
+            email.template as TemplateId,
+            email.recipient,
+            email.locale,
+            Json(&email.variables) as _,
+            message.subject,
+            email.expires_at
+        )
+        .fetch_one(conn)
+        .await?;
+
+        debug!(email_id = %id, template = %email.template, "Enqueued email");
+        Ok(id)
+    }
+
+    /// Delay before the next attempt after `attempts` failed ones
+    pub fn retry_delay(attempts: i32) -> Duration {
+        let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+        EMAIL_RETRY_BASE_DELAY
+            .saturating_mul(2_u32.pow(exponent))
+            .min(EMAIL_RETRY_MAX_DELAY)
+    }
+
+    /// Attempt to deliver one batch of due emails
+    ///
+    /// Returns the number of emails attempted, successful or not.
+    #[instrument(skip_all, err)]
+    pub async fn deliver_due(
+        db_pool: &PgPool,
+        email_service: &dyn EmailService,
+    ) -> AppResult<usize> {
+        let now = OffsetDateTime::now_utc();
+
+        let expired = sqlx::query!(
+            r#"
+            UPDATE email_outbox
+            SET status = 'failed', last_error = 'Expired before delivery',
+                variables = variables - 'code'
+            WHERE status = 'pending' AND expires_at <= $1
+            "#,
+            now
+        )
+        .execute(db_pool)
+        .await?;
+        if expired.rows_affected() > 0 {
+            warn!(
+                count = expired.rows_affected(),
+                "Emails expired before delivery"
+            );
+        }
+
+        let purged = sqlx::query!(
+            "DELETE FROM email_outbox WHERE status <> 'pending' AND created_at <= $1",
+            now - EMAIL_OUTBOX_RETENTION
+        )
+        .execute(db_pool)
+        .await?;
+        if purged.rows_affected() > 0 {
+            info!(
+                count = purged.rows_affected(),
+                "Purged old sent emails and dead letters"
+            );
+        }
+
+        let leased = sqlx::query_as!(
+            LeasedEmail,
+            r#"
+            UPDATE email_outbox
+            SET attempts = attempts + 1, next_attempt_at = $2
+            WHERE id IN (
+                SELECT id FROM email_outbox
+                WHERE status = 'pending' AND next_attempt_at <= $1
+                ORDER BY next_attempt_at
+                LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, template as "template: TemplateId", recipient, locale,
+                      variables as "variables: Json<BTreeMap<String, String>>", attempts
+            "#,
+            now,
+            now + EMAIL_DELIVERY_LEASE,
+            EMAIL_OUTBOX_BATCH_SIZE
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        for email in &leased {
+            let message = match EMAIL_TEMPLATES.render(
+                &email.recipient,
+                email.template,
+                &email.locale,
+                email.variables.0.clone(),
+            ) {
+                Ok(message) => message,
+                Err(e) => {
+                    // Retrying cannot help, the templates only change on restart
+                    Self::mark_dead(db_pool, email.id, &e.to_string()).await?;
+                    error!(error = %e, email_id = %email.id, "Failed to render email");
+                    continue;
+                }
+            };
+
+            match email_service.send(&message).await {
+                Ok(provider) => {
+                    sqlx::query!(
+                        r#"
+                        UPDATE email_outbox
+                        SET status = 'sent', provider = $2, sent_at = NOW(),
+                            variables = variables - 'code'
+                        WHERE id = $1
+                        "#,
+                        email.id,
+                        provider
+                    )
+                    .execute(db_pool)
+                    .await?;
+                    info!(email_id = %email.id, attempts = email.attempts, %provider, "Delivered email");
+                }
+                Err(e @ EmailError::Rejected(_)) => {
+                    Self::mark_dead(db_pool, email.id, &e.to_string()).await?;
+                    error!(error = %e, email_id = %email.id, "Email rejected, not retrying");
+                }
+                Err(e) if email.attempts >= EMAIL_MAX_ATTEMPTS => {
+                    Self::mark_dead(db_pool, email.id, &e.to_string()).await?;
+                    error!(error = %e, email_id = %email.id, "Giving up on email delivery");
+                }
+                Err(e) => {
+                    let retry_at = OffsetDateTime::now_utc() + Self::retry_delay(email.attempts);
+                    sqlx::query!(
+                        r#"
+                        UPDATE email_outbox
+                        SET next_attempt_at = $2, last_error = $3
+                        WHERE id = $1
+                        "#,
+                        email.id,
+                        retry_at,
+                        e.to_string()
+                    )
+                    .execute(db_pool)
+                    .await?;
+                    warn!(
+                        error = %e,
+                        email_id = %email.id,
+                        attempts = email.attempts,
+                        %retry_at,
+                        "Email delivery failed, will retry"
+                    );
+                }
+            }
+        }
+
+        Ok(leased.len())
+    }
+
+    /// Keep an email as a dead letter, without the verification code it may contain
+    async fn mark_dead(db_pool: &PgPool, email_id: Uuid, error: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE email_outbox
+            SET status = 'failed', last_error = $2, variables = variables - 'code'
+            WHERE id = $1
+            "#,
+            email_id,
+            error
+        )
+        .execute(db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Spawn the worker delivering emails from the outbox
+    pub fn spawn_delivery_task(db_pool: PgPool, email_service: Arc<dyn EmailService>) {
+        tokio::spawn(async move {
+            let mut listener = Self::listen(&db_pool).await;
+
+            loop {
+                // Keep going while full batches are due
+                loop {
+                    match Self::deliver_due(&db_pool, email_service.as_ref()).await {
+                        Ok(count) if count as i64 == EMAIL_OUTBOX_BATCH_SIZE => continue,
+                        _ => break,
+                    }
+                }
+
+                if listener.is_none() {
+                    listener = Self::listen(&db_pool).await;
+                }
+                match listener.as_mut() {
+                    Some(active) => {
+                        match tokio::time::timeout(EMAIL_OUTBOX_POLL_INTERVAL, active.recv()).await
+                        {
+                            // Woken up by a new email, or time to look for retries
+                            Ok(Ok(_)) | Err(_) => {}
+                            Ok(Err(e)) => {
+                                warn!(error = %e, "Email outbox listener failed, reconnecting");
+                                listener = None;
+                            }
+                        }
+                    }
+                    None => tokio::time::sleep(EMAIL_OUTBOX_POLL_INTERVAL).await,
+                }
+            }
+        });
+    }
+
+    async fn listen(db_pool: &PgPool) -> Option<PgListener> {
+        let result = async {
+            let mut listener = PgListener::connect_with(db_pool).await?;
+            listener.listen(EMAIL_OUTBOX_CHANNEL).await?;
+            Ok::<_, sqlx::Error>(listener)
+        }
+        .await;
+
+        result
+            .inspect_err(|e| error!(error = %e, "Failed to listen for enqueued emails"))
+            .ok()
+    }
+
+    /// Count the emails in the outbox, optionally only those with the given status
+    pub async fn count(db_pool: &PgPool, status: Option<EmailStatus>) -> Result<i64, sqlx::Error> {
+        let total = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*)
+            FROM email_outbox
+            WHERE ($1::email_status IS NULL OR status = $1)
+            "#,
+            status as Option<EmailStatus>
+        )
+        .fetch_one(db_pool)
+        .await?;
+
+        Ok(total.unwrap_or(0))
+    }
+
+    /// List the emails in the outbox, newest first
+    pub async fn list(
+        db_pool: &PgPool,
+        status: Option<EmailStatus>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OutboxEmail>, sqlx::Error> {
+        sqlx::query_as!(
+            OutboxEmail,
+            r#"
+            SELECT id, template as "template: TemplateId", locale, recipient, subject,
+                   status as "status: EmailStatus", attempts, next_attempt_at, expires_at,
+                   last_error, provider, sent_at, created_at
+            FROM email_outbox
+            WHERE ($1::email_status IS NULL OR status = $1)
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            status as Option<EmailStatus>,
+            limit,
+            offset
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+}
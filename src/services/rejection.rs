@@ -0,0 +1,276 @@
+//! # Rejection Service
+//!
+//! A rejected final match is moved from `final_matches` to `match_rejections`,
+//! together with the reason and comment its rejecter gave. Neither is ever shown
+//! to the partner.
+//!
+//! Aggregated against the final matches that were not rejected, the rejections
+//! show admins which scores, tag overlaps and algorithm versions produce pairs
+//! users turn down, and why.
+
+use std::{cmp::Reverse, collections::BTreeMap};
+
+use sqlx::{PgConnection, PgPool};
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    error::AppResult,
+    models::{
+        MatchRejection, ReasonRejections, RejectFinalMatchRequest, RejectionStats, RejectionSummary,
+    },
+    services::match_stats::{MatchGroups, StatsBuilder},
+};
+
+pub struct RejectionService;
+
+impl RejectionService {
+    /// Delete a rejected final match, keeping it with the reason of its rejecter
+    pub async fn record(
+        conn: &mut PgConnection,
+        final_match_id: Uuid,
+        rejected_by: Uuid,
+        request: &RejectFinalMatchRequest,
+    ) -> AppResult<()> {
+        sqlx::query!(
+            r#"
+            WITH rejected AS (
+                DELETE FROM final_matches WHERE id = $1
+                RETURNING id, event_id, user_a_id, user_b_id, score, tag_overlap,
+                          algorithm_version, created_at
+            )
+            INSERT INTO match_rejections
+                (event_id, final_match_id, rejected_by, partner_id, score, tag_overlap,
+                 algorithm_version, reason, comment, matched_at)
+            SELECT event_id, id, $2,
+                   CASE WHEN user_a_id = $2 THEN user_b_id ELSE user_a_id END,
+                   score, tag_overlap, algorithm_version, $3, $4, created_at
+            FROM rejected
+            "#,
+            final_match_id,
+            rejected_by,
+            request.reason,
+            request.comment()
+        )
+        .execute(conn)
+        .await?;
+
+        info!(%final_match_id, reason = ?request.reason, "Rejection recorded");
+        Ok(())
+    }
+
+    /// Delete a final match on behalf of an admin, keeping it like a rejection so that
+    /// rejection rates still count it, and return its users if it existed
+    pub async fn record_deletion(
+        conn: &mut PgConnection,
+        final_match_id: Uuid,
+        admin: &str,
+    ) -> AppResult<Option<(Uuid, Uuid)>> {
+        let deleted = sqlx::query!(
+            r#"
+            WITH deleted AS (
+                DELETE FROM final_matches WHERE id = $1
+                RETURNING id, event_id, user_a_id, user_b_id, score, tag_overlap,
+                          algorithm_version, created_at
+            ), recorded AS (
+                INSERT INTO match_rejections
+                    (event_id, final_match_id, deleted_by, score, tag_overlap,
+                     algorithm_version, matched_at)
+                SELECT event_id, id, $2, score, tag_overlap, algorithm_version, created_at
+                FROM deleted
+            )
+            SELECT user_a_id, user_b_id FROM deleted
+            "#,
+            final_match_id,
+            admin
+        )
+        .fetch_optional(conn)
+        .await?;
+
+        info!(%final_match_id, deleted = deleted.is_some(), "Deletion recorded");
+        Ok(deleted.map(|row| (row.user_a_id, row.user_b_id)))
+    }
+
+    /// Rejections of an event, latest first, and their total number
+    pub async fn list(
+        db_pool: &PgPool,
+        event_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(i64, Vec<MatchRejection>), sqlx::Error> {
+        let total = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!" FROM match_rejections WHERE event_id = $1"#,
+            event_id
+        )
+        .fetch_one(db_pool)
+        .await?;
+
+        let rejections = sqlx::query_as!(
+            MatchRejection,
+            r#"
+            SELECT r.id, r.final_match_id, r.rejected_by, ur.email as "rejected_by_email?",
+                   r.partner_id, up.email as "partner_email?", r.deleted_by, r.score,
+                   r.tag_overlap, r.algorithm_version, r.reason, r.comment, r.matched_at,
+                   r.rejected_at
+            FROM match_rejections r
+            LEFT JOIN users ur ON ur.id = r.rejected_by
+            LEFT JOIN users up ON up.id = r.partner_id
+            WHERE r.event_id = $1
+            ORDER BY r.rejected_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            event_id,
+            limit,
+            offset
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok((total, rejections))
+    }
+
+    /// Aggregate the rejections of an event by reason, score buckets `bucket_width`
+    /// wide, tag overlap and algorithm version
+    #[instrument(skip(db_pool), err)]
+    pub async fn summary(
+        db_pool: &PgPool,
+        event_id: Uuid,
+        bucket_width: f64,
+    ) -> AppResult<RejectionSummary> {
+        let mut groups = MatchGroups::<RejectionStatsBuilder>::new(bucket_width)?;
+
+        let matches = sqlx::query!(
+            r#"
+            SELECT score, tag_overlap, algorithm_version
+            FROM final_matches
+            WHERE event_id = $1
+            "#,
+            event_id
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let rejections = sqlx::query!(
+            r#"
+            SELECT score, tag_overlap, algorithm_version, reason,
+                   deleted_by IS NOT NULL as "deleted_by_admin!"
+            FROM match_rejections
+            WHERE event_id = $1
+            "#,
+            event_id
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        for row in matches {
+            groups.add(
+                row.score,
+                row.tag_overlap,
+                row.algorithm_version,
+                &Outcome::Kept,
+            );
+        }
+
+        let mut by_reason = BTreeMap::<Option<String>, ReasonBuilder>::new();
+        for row in rejections {
+            if row.deleted_by_admin {
+                groups.add(
+                    row.score,
+                    row.tag_overlap,
+                    row.algorithm_version,
+                    &Outcome::DeletedByAdmin,
+                );
+                continue;
+            }
+
+            let reason = by_reason.entry(row.reason.clone()).or_default();
+            reason.rejections += 1;
+            reason.score_sum += row.score;
+            if let Some(tag_overlap) = row.tag_overlap {
+                reason.tag_overlaps.push(tag_overlap);
+            }
+
+            groups.add(
+                row.score,
+                row.tag_overlap,
+                row.algorithm_version,
+                &Outcome::Rejected(row.reason),
+            );
+        }
+
+        let mut by_reason: Vec<ReasonRejections> = by_reason
+            .into_iter()
+            .map(|(reason, builder)| builder.build(reason))
+            .collect();
+        by_reason.sort_by_key(|reason| Reverse(reason.rejections));
+
+        Ok(RejectionSummary {
+            stats: groups.build(),
+            by_reason,
+        })
+    }
+}
+
+/// What became of a final match
+enum Outcome {
+    Kept,
+    /// Rejected by one of its users, with the reason they gave
+    Rejected(Option<String>),
+    DeletedByAdmin,
+}
+
+/// Accumulates the rejections of a group of matches
+#[derive(Default)]
+struct RejectionStatsBuilder(RejectionStats);
+
+impl StatsBuilder for RejectionStatsBuilder {
+    type Row = Outcome;
+    type Stats = RejectionStats;
+
+    fn add(&mut self, outcome: &Outcome) {
+        let stats = &mut self.0;
+        stats.matches += 1;
+        match outcome {
+            Outcome::Kept => return,
+            Outcome::DeletedByAdmin => {
+                stats.deleted_by_admin += 1;
+                return;
+            }
+            Outcome::Rejected(Some(reason)) => {
+                *stats.reasons.entry(reason.clone()).or_default() += 1
+            }
+            Outcome::Rejected(None) => stats.without_reason += 1,
+        }
+        stats.rejections += 1;
+    }
+
+    fn build(self) -> RejectionStats {
+        let mut stats = self.0;
+        stats.rejection_rate =
+            (stats.matches > 0).then(|| stats.rejections as f64 / stats.matches as f64);
+        stats
+    }
+}
+
+/// Accumulates the rejections giving a reason
+#[derive(Default)]
+struct ReasonBuilder {
+    rejections: i64,
+    score_sum: f64,
+    tag_overlaps: Vec<i32>,
+}
+
+impl ReasonBuilder {
+    fn build(self, reason: Option<String>) -> ReasonRejections {
+        let average_tag_overlap = (!self.tag_overlaps.is_empty()).then(|| {
+            self.tag_overlaps.iter().map(|&n| f64::from(n)).sum::<f64>()
+                / self.tag_overlaps.len() as f64
+        });
+        ReasonRejections {
+            reason,
+            rejections: self.rejections,
+            average_score: self.score_sum / self.rejections as f64,
+            average_tag_overlap,
+        }
+    }
+}
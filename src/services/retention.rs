@@ -0,0 +1,118 @@
+//! # Card Photo Retention Service
+//!
+//! Student card photos are only needed until an admin has reviewed them. This
+//! service deletes card photos once `CARD_PHOTO_RETENTION_DAYS` have passed since
+//! the last review (verification or rejection) and clears `card_photo_filename`.
+//! The review itself stays recorded in `card_verified_at` / `card_reviewed_at`.
+//! A photo is only recorded as purged once its file is gone, so a failed
+//! deletion is retried on the next run.
+
+use std::path::Path;
+
+use sqlx::PgPool;
+use tokio::fs;
+use tracing::{debug, error, info, instrument, warn};
+
+use crate::{
+    error::AppResult,
+    utils::{constant::CHECK_CARD_RETENTION_INTERVAL, static_object::UPLOAD_DIR},
+};
+
+pub struct RetentionService;
+
+impl RetentionService {
+    /// Deletes card photos whose retention period has expired.
+    ///
+    /// Users still awaiting review are never touched. Photos whose file cannot be
+    /// deleted are kept and retried on the next run. Returns the number of
+    /// photos purged.
+    #[instrument(skip_all, err)]
+    pub async fn purge_expired_card_photos(
+        db_pool: &PgPool,
+        retention_days: i32,
+    ) -> AppResult<usize> {
+        let candidates = sqlx::query!(
+            r#"
+            SELECT id
+            FROM users
+            WHERE card_photo_filename IS NOT NULL
+              AND card_reviewed_at <= NOW() - make_interval(days => $1)
+              AND status != 'verification_pending'
+            "#,
+            retention_days
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let mut purged = 0;
+        for candidate in candidates {
+            let mut tx = db_pool.begin().await?;
+
+            // Re-check under lock: the user may have re-uploaded in the meantime
+            let Some(filename) = sqlx::query_scalar!(
+                r#"
+                SELECT card_photo_filename as "card_photo_filename!"
+                FROM users
+                WHERE id = $1
+                  AND card_photo_filename IS NOT NULL
+                  AND card_reviewed_at <= NOW() - make_interval(days => $2)
+                  AND status != 'verification_pending'
+                FOR UPDATE
+                "#,
+                candidate.id,
+                retention_days
+            )
+            .fetch_optional(tx.as_mut())
+            .await?
+            else {
+                debug!(user_id = %candidate.id, "Card photo no longer eligible for purge");
+                continue;
+            };
+
+            sqlx::query!(
+                "UPDATE users SET card_photo_filename = NULL, card_purged_at = NOW() WHERE id = $1",
+                candidate.id
+            )
+            .execute(tx.as_mut())
+            .await?;
+
+            // Delete the file while holding the row lock and only commit once it is
+            // gone. Should the commit fail, the next run finds the file missing and
+            // records the purge then.
+            let file_path = Path::new(UPLOAD_DIR.as_str())
+                .join("card_photos")
+                .join(&filename);
+            match fs::remove_file(&file_path).await {
+                Ok(()) => debug!(user_id = %candidate.id, "Card photo purged"),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    warn!(user_id = %candidate.id, %filename, "Card photo already missing on disk");
+                }
+                Err(e) => {
+                    error!(user_id = %candidate.id, error = %e, file_path = %file_path.display(), "Failed to delete card photo, retrying on the next run");
+                    continue;
+                }
+            }
+
+            tx.commit().await?;
+            purged += 1;
+        }
+
+        if purged > 0 {
+            info!(purged, "Purged expired card photos");
+        }
+        Ok(purged)
+    }
+
+    /// Spawn the periodic task that purges expired card photos
+    pub fn spawn_card_retention_task(db_pool: PgPool, retention_days: i32) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(CHECK_CARD_RETENTION_INTERVAL);
+            interval.tick().await; // First tick completes immediately, so we skip it
+
+            loop {
+                interval.tick().await;
+                let _ = Self::purge_expired_card_photos(&db_pool, retention_days).await;
+            }
+        });
+    }
+}
@@ -2,21 +2,24 @@ use std::collections::HashMap;
 
 use pathfinding::{kuhn_munkres::kuhn_munkres, matrix::Matrix};
 use serde::Serialize;
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 use time::OffsetDateTime;
-use tracing::{debug, error, info, instrument};
+use tracing::{debug, error, info, instrument, warn};
 use uuid::Uuid;
 
-use super::matching::MatchingService;
+use super::{
+    audit::AuditService, event::EventService, feedback::FeedbackService, matching::MatchingService,
+    notification::NotificationService, text_similarity::TextCorpus, user_event::UserEventService,
+};
 use crate::{
     error::{AppError, AppResult},
-    models::{FinalMatch, Gender, ScheduleStatus, ScheduledFinalMatch, TagSystem},
+    models::{
+        AdminAction, CreateScheduledMatchRequest, FinalMatch, Gender, MatchEvent, NewAuditEntry,
+        Notification, RoundDeadlines, ScheduleStatus, ScheduledFinalMatch, UserStatus,
+    },
     utils::{
-        constant::{
-            CHECK_AUTO_ACCEPT_INTERVAL, CHECK_SCHEDULED_MATCH_INTERVAL,
-            FINAL_MATCH_AUTO_ACCEPT_TIMEOUT,
-        },
-        static_object::UPLOAD_DIR,
+        constant::{CHECK_AUTO_ACCEPT_INTERVAL, CHECK_SCHEDULED_MATCH_INTERVAL},
+        static_object::{FORM_SCHEMA, MATCHING_ALGORITHM_VERSION, UPLOAD_DIR},
     },
 };
 
@@ -27,6 +30,7 @@ struct DryRunMatch {
     user_b_id: Uuid,
     user_b_email: String,
     score: f64,
+    tag_overlap: i32,
 }
 
 #[derive(Debug, Serialize)]
@@ -36,19 +40,26 @@ struct DryRunOutput {
     matches: Vec<DryRunMatch>,
 }
 
+/// Actor name recorded in the audit log for changes made by background tasks
+pub const SCHEDULER_ACTOR: &str = "scheduler";
+
 pub struct SchedulerService;
 
 impl SchedulerService {
-    /// Get the next scheduled final match time (earliest pending match)
-    pub async fn get_next_scheduled_time(db_pool: &PgPool) -> AppResult<Option<OffsetDateTime>> {
+    /// Get the next scheduled final match time of an event (earliest pending match)
+    pub async fn get_next_scheduled_time(
+        db_pool: &PgPool,
+        event_id: Uuid,
+    ) -> AppResult<Option<OffsetDateTime>> {
         let result = sqlx::query!(
             r#"
             SELECT scheduled_time
             FROM scheduled_final_matches
-            WHERE status = 'pending'
+            WHERE event_id = $1 AND status = 'pending'
             ORDER BY scheduled_time ASC
             LIMIT 1
-            "#
+            "#,
+            event_id
         )
         .fetch_optional(db_pool)
         .await?;
@@ -56,52 +67,69 @@ impl SchedulerService {
         Ok(result.map(|row| row.scheduled_time))
     }
 
-    /// Create multiple scheduled final match triggers
+    /// Create multiple scheduled final match triggers for an event
+    ///
+    /// Rescheduling an existing time replaces its deadlines.
     pub async fn create_scheduled_matches(
-        db_pool: &PgPool,
-        scheduled_times: &[OffsetDateTime],
+        conn: &mut PgConnection,
+        event_id: Uuid,
+        requests: &[CreateScheduledMatchRequest],
     ) -> AppResult<Vec<ScheduledFinalMatch>> {
         let mut scheduled_matches = Vec::new();
 
-        for &scheduled_time in scheduled_times {
+        for request in requests {
+            let scheduled_time = request.scheduled_time;
             // Validate that the time is in the future
             if scheduled_time <= OffsetDateTime::now_utc() {
                 return Err(AppError::BadRequest("Scheduled time must be in the future"));
             }
+            request.deadlines.validate().map_err(AppError::BadRequest)?;
 
             let scheduled_match = sqlx::query_as!(
                 ScheduledFinalMatch,
                 r#"
-                INSERT INTO scheduled_final_matches (scheduled_time)
-                VALUES ($1)
-                ON CONFLICT (scheduled_time)
-                DO UPDATE SET scheduled_time = EXCLUDED.scheduled_time
+                INSERT INTO scheduled_final_matches
+                    (event_id, scheduled_time, auto_accept_hours, reminder_hours)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (event_id, scheduled_time)
+                DO UPDATE SET auto_accept_hours = EXCLUDED.auto_accept_hours,
+                              reminder_hours = EXCLUDED.reminder_hours
                 RETURNING id, scheduled_time, status as "status: ScheduleStatus",
-                         created_at, executed_at, matches_created, error_message
+                         created_at, executed_at, matches_created, error_message,
+                         auto_accept_hours, reminder_hours
                 "#,
-                scheduled_time
+                event_id,
+                scheduled_time,
+                request.deadlines.auto_accept_hours,
+                request.deadlines.reminder_hours
             )
-            .fetch_one(db_pool)
+            .fetch_one(&mut *conn)
             .await?;
 
             scheduled_matches.push(scheduled_match);
         }
 
+        UserEventService::publish_next_match_time(conn).await?;
+
         Ok(scheduled_matches)
     }
 
-    /// Get all scheduled matches (admin view)
+    /// Get all scheduled matches of an event (admin view)
     pub async fn get_all_scheduled_matches(
         db_pool: &PgPool,
+        event_id: Uuid,
     ) -> AppResult<Vec<ScheduledFinalMatch>> {
         let scheduled_matches = sqlx::query_as!(
             ScheduledFinalMatch,
             r#"
             SELECT id, scheduled_time, status as "status: ScheduleStatus",
-                   created_at, executed_at, matches_created, error_message
+                   created_at, executed_at, matches_created, error_message,
+                   auto_accept_hours, reminder_hours
             FROM scheduled_final_matches
+            WHERE event_id = $1
             ORDER BY scheduled_time ASC
-            "#
+            "#,
+            event_id
         )
         .fetch_all(db_pool)
         .await?;
@@ -110,7 +138,10 @@ impl SchedulerService {
     }
 
     /// Cancel a scheduled match (delete it)
-    pub async fn cancel_scheduled_match(db_pool: &PgPool, match_id: Uuid) -> AppResult<bool> {
+    pub async fn cancel_scheduled_match(
+        conn: &mut PgConnection,
+        match_id: Uuid,
+    ) -> AppResult<bool> {
         let result = sqlx::query!(
             r#"
             DELETE FROM scheduled_final_matches
@@ -118,35 +149,45 @@ impl SchedulerService {
             "#,
             match_id
         )
-        .execute(db_pool)
+        .execute(&mut *conn)
         .await?;
 
+        UserEventService::publish_next_match_time(conn).await?;
+
         Ok(result.rows_affected() > 0)
     }
 
-    /// Check for and execute any due scheduled matches
+    /// Check for and execute any due scheduled matches of the current event
+    ///
+    /// Schedules of other events wait until their event is activated.
     #[instrument(skip_all, err)]
-    async fn check_and_execute_scheduled_matches(
-        db_pool: &PgPool,
-        tag_system: &TagSystem,
-    ) -> AppResult<()> {
+    async fn check_and_execute_scheduled_matches(db_pool: &PgPool) -> AppResult<()> {
+        let event_id = EventService::current_event_id(db_pool).await?;
+
         // Find all pending matches that are due
         let due_matches = sqlx::query_as!(
             ScheduledFinalMatch,
             r#"
             SELECT id, scheduled_time, status as "status: ScheduleStatus",
-                   created_at, executed_at, matches_created, error_message
+                   created_at, executed_at, matches_created, error_message,
+                   auto_accept_hours, reminder_hours
             FROM scheduled_final_matches
-            WHERE status = 'pending' AND scheduled_time <= CURRENT_TIMESTAMP
+            WHERE event_id = $1 AND status = 'pending' AND scheduled_time <= CURRENT_TIMESTAMP
             ORDER BY scheduled_time ASC
-            "#
+            "#,
+            event_id
         )
         .fetch_all(db_pool)
         .await?;
 
         for due_match in due_matches {
-            let matches_created =
-                Self::execute_scheduled_final_match(db_pool, tag_system, due_match.id).await?;
+            let matches_created = Self::execute_scheduled_final_match(
+                db_pool,
+                event_id,
+                due_match.id,
+                due_match.deadlines(),
+            )
+            .await?;
             info!(
                 scheduled_match_id = %due_match.id,
                 %matches_created,
@@ -160,8 +201,9 @@ impl SchedulerService {
     /// Execute a specific scheduled final match, returning number of matches created
     async fn execute_scheduled_final_match(
         db_pool: &PgPool,
-        tag_system: &TagSystem,
+        event_id: Uuid,
         scheduled_match_id: Uuid,
+        deadlines: RoundDeadlines,
     ) -> AppResult<usize> {
         let now = OffsetDateTime::now_utc();
 
@@ -179,7 +221,15 @@ impl SchedulerService {
         .await?;
 
         // Execute the final matching algorithm
-        match Self::execute_final_matching(db_pool, tag_system, false).await {
+        let result = match Self::execute_final_matching(
+            db_pool,
+            event_id,
+            false,
+            deadlines,
+            SCHEDULER_ACTOR,
+        )
+        .await
+        {
             Ok(matches_created) => {
                 // Update status to completed
                 sqlx::query!(
@@ -213,29 +263,48 @@ impl SchedulerService {
 
                 Err(e)
             }
+        };
+
+        // The executed match is no longer pending. Failing to tell the users is only
+        // logged, so that the result of the matching is still returned
+        match db_pool.acquire().await {
+            Ok(mut conn) => {
+                // Logged by the instrumentation
+                let _ = UserEventService::publish_next_match_time(&mut conn).await;
+            }
+            Err(e) => error!(error = %e, "Failed to publish the next match time"),
         }
+
+        result
     }
 
-    /// Execute the final matching algorithm using bipartite matching.
+    /// Execute the final matching algorithm of an event using bipartite matching.
     ///
     /// Matches males to females using the Kuhn-Munkres algorithm.
     /// Users from the larger gender group may remain unmatched if sizes are unequal.
     ///
     /// If `dry_run` is true, simulates matching without database changes and saves
-    /// results to a JSON file in UPLOAD_DIR.
+    /// results to a JSON file in UPLOAD_DIR. Otherwise all changes are committed in
+    /// one transaction together with an audit entry attributed to `actor`, and the
+    /// matched users are notified. The matches are auto-accepted and their users
+    /// reminded according to `deadlines`. Pairs with a user who left the matching
+    /// pool (e.g. paused, withdrew or was suspended) while the pairs were scored
+    /// are skipped.
     ///
     /// Ok value is the number of matches created
     pub async fn execute_final_matching(
         db_pool: &PgPool,
-        tag_system: &TagSystem,
+        event_id: Uuid,
         dry_run: bool,
+        deadlines: RoundDeadlines,
+        actor: &str,
     ) -> AppResult<usize> {
         // Fetch unmatched users for matching
-        let unmatched_forms = MatchingService::fetch_unmatched_forms(db_pool).await?;
+        let unmatched_forms = MatchingService::fetch_unmatched_forms(db_pool, event_id).await?;
 
         // Fetch ALL submitted forms for stable tag frequency calculation
         // This ensures IDF scores remain consistent across multiple matching runs
-        let all_forms = MatchingService::fetch_all_submitted_forms(db_pool).await?;
+        let all_forms = MatchingService::fetch_all_submitted_forms(db_pool, event_id).await?;
 
         // Partition unmatched users by gender
         let mut males = Vec::new();
@@ -276,11 +345,14 @@ impl SchedulerService {
         );
 
         // Fetch all veto records
-        let veto_map = MatchingService::build_map_vetoed_as_key(db_pool).await?;
+        let veto_map = MatchingService::build_map_vetoed_as_key(db_pool, event_id).await?;
 
         // Calculate tag frequencies for IDF scoring using ALL forms (not just unmatched)
-        let tag_frequencies = MatchingService::calculate_tag_frequencies(&all_forms, tag_system);
+        let catalogs = EventService::catalogs(db_pool, event_id).await?;
+        let tag_frequencies =
+            MatchingService::calculate_tag_frequencies(&all_forms, &FORM_SCHEMA, &catalogs.tags);
         let total_user_count = all_forms.len() as u32;
+        let text_corpus = TextCorpus::build(&all_forms, &FORM_SCHEMA);
 
         // Build bipartite weight matrix
         // Requires Ord so we scale f64 scores by 1000 and convert to i64 to preserve precision
@@ -291,12 +363,14 @@ impl SchedulerService {
 
         for (i, form_row) in rows.iter().enumerate() {
             for (j, form_col) in cols.iter().enumerate() {
-                let score = MatchingService::calculate_match_score(
+                let score = MatchingService::calculate_match_score_with_corpus(
                     form_row,
                     form_col,
-                    tag_system,
+                    &FORM_SCHEMA,
+                    &catalogs,
                     &tag_frequencies,
                     total_user_count,
+                    &text_corpus,
                 );
 
                 // Validate score is not NaN or infinite
@@ -353,10 +427,13 @@ impl SchedulerService {
                 AppError::Internal
             })?;
 
-            matched_pairs.push((user_row, user_col, score));
+            let tag_overlap =
+                MatchingService::count_shared_tags(rows[i], cols[j], &FORM_SCHEMA) as i32;
+
+            matched_pairs.push((user_row, user_col, score, tag_overlap));
         }
 
-        let matches_count = matched_pairs.len();
+        let mut matches_count = matched_pairs.len();
 
         if dry_run {
             // Dry run mode: save results to file without modifying database
@@ -367,7 +444,7 @@ impl SchedulerService {
 
             // Fetch user emails for the matched pairs
             let mut dry_run_matches = Vec::new();
-            for (user_a_id, user_b_id, score) in matched_pairs {
+            for (user_a_id, user_b_id, score, tag_overlap) in matched_pairs {
                 let user_a = sqlx::query!(r#"SELECT email FROM users WHERE id = $1"#, user_a_id)
                     .fetch_one(db_pool)
                     .await?;
@@ -382,6 +459,7 @@ impl SchedulerService {
                     user_b_id,
                     user_b_email: user_b.email,
                     score,
+                    tag_overlap,
                 });
             }
 
@@ -427,48 +505,108 @@ impl SchedulerService {
             );
         } else {
             // Normal mode: persist matches to database
+            let mut tx = db_pool.begin().await?;
+
+            let (auto_accept_at, reminder_at) = deadlines.schedule(OffsetDateTime::now_utc());
             let mut final_matches = Vec::new();
-            for (user_row, user_col, score) in matched_pairs {
+            for (user_row, user_col, score, tag_overlap) in matched_pairs {
+                // The pool was read before the transaction, so lock the pair and
+                // check that both users are still waiting for a match
+                let available = sqlx::query_scalar!(
+                    r#"
+                    SELECT id FROM users
+                    WHERE id = ANY($1) AND status = 'form_completed'
+                    ORDER BY id
+                    FOR UPDATE
+                    "#,
+                    &[user_row, user_col]
+                )
+                .fetch_all(tx.as_mut())
+                .await?;
+                if available.len() < 2 {
+                    warn!(%user_row, %user_col, "User left the matching pool during matching, skipping pair");
+                    continue;
+                }
+
                 // Create the final match
-                let final_match =
-                    Self::create_final_match(db_pool, user_row, user_col, score).await?;
+                let final_match = Self::create_final_match(
+                    tx.as_mut(),
+                    event_id,
+                    (user_row, user_col),
+                    (score, tag_overlap),
+                    (auto_accept_at, reminder_at),
+                )
+                .await?;
                 debug!(%final_match.id, %score, "Created a final pair");
                 final_matches.push(final_match);
             }
+            matches_count = final_matches.len();
 
             // Update status of matched users to 'matched'
+            let mut matched_user_ids = Vec::with_capacity(final_matches.len() * 2);
             for final_match in &final_matches {
                 sqlx::query!(
-                    r#"UPDATE users SET status = 'matched' WHERE id = $1"#,
+                    r#"UPDATE users SET status = 'matched' WHERE id = $1 AND status = 'form_completed'"#,
                     final_match.user_a_id
                 )
-                .execute(db_pool)
+                .execute(tx.as_mut())
                 .await?;
 
                 sqlx::query!(
-                    r#"UPDATE users SET status = 'matched' WHERE id = $1"#,
+                    r#"UPDATE users SET status = 'matched' WHERE id = $1 AND status = 'form_completed'"#,
                     final_match.user_b_id
                 )
-                .execute(db_pool)
+                .execute(tx.as_mut())
                 .await?;
+
+                matched_user_ids.push(final_match.user_a_id);
+                matched_user_ids.push(final_match.user_b_id);
             }
 
-            // Clear all vetoes and previews after final matching
+            // Clear all vetoes and previews of the event after final matching
             info!("Clearing all vetoes and match previews");
-            sqlx::query!("DELETE FROM vetoes").execute(db_pool).await?;
-            sqlx::query!("DELETE FROM match_previews")
-                .execute(db_pool)
+            sqlx::query!("DELETE FROM vetoes WHERE event_id = $1", event_id)
+                .execute(tx.as_mut())
                 .await?;
+            sqlx::query!("DELETE FROM match_previews WHERE event_id = $1", event_id)
+                .execute(tx.as_mut())
+                .await?;
+
+            let final_match_ids: Vec<Uuid> = final_matches.iter().map(|fm| fm.id).collect();
+            let event = MatchEvent::FinalMatchCreated {
+                auto_accept_hours: deadlines.auto_accept_hours(),
+            };
+            let notifications: Vec<Notification> = matched_user_ids
+                .iter()
+                .map(|&user_id| Notification::new(user_id, event))
+                .collect();
+            NotificationService::notify(tx.as_mut(), &notifications).await?;
+
+            let audit =
+                NewAuditEntry::new(actor, AdminAction::TriggerFinalMatching, matched_user_ids)
+                    .payload(serde_json::json!({
+                        "matches_created": matches_count,
+                        "final_match_ids": final_match_ids,
+                        "auto_accept_hours": deadlines.auto_accept_hours(),
+                        "reminder_hours": deadlines.reminder_hours(),
+                        "algorithm_version": MATCHING_ALGORITHM_VERSION.as_str(),
+                    }))
+                    .status_change(Some(UserStatus::FormCompleted), UserStatus::Matched);
+            AuditService::record(tx.as_mut(), &audit).await?;
+
+            tx.commit().await?;
         }
 
         Ok(matches_count)
     }
 
+    /// Create a final match, recording the algorithm version it was found by
     async fn create_final_match(
-        db_pool: &PgPool,
-        user_a_id: Uuid,
-        user_b_id: Uuid,
-        score: f64,
+        conn: &mut PgConnection,
+        event_id: Uuid,
+        (user_a_id, user_b_id): (Uuid, Uuid),
+        (score, tag_overlap): (f64, i32),
+        (auto_accept_at, reminder_at): (OffsetDateTime, OffsetDateTime),
     ) -> Result<FinalMatch, sqlx::Error> {
         // Ensure consistent ordering: smaller UUID first
         let (first_user, second_user) = if user_a_id < user_b_id {
@@ -480,34 +618,116 @@ impl SchedulerService {
         sqlx::query_as!(
             FinalMatch,
             r#"
-            INSERT INTO final_matches (user_a_id, user_b_id, score)
-            VALUES ($1, $2, $3)
+            INSERT INTO final_matches
+                (event_id, user_a_id, user_b_id, score, tag_overlap, algorithm_version,
+                 auto_accept_at, reminder_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
             RETURNING id, user_a_id, user_b_id, score
             "#,
+            event_id,
             first_user,
             second_user,
-            score
+            score,
+            tag_overlap,
+            MATCHING_ALGORITHM_VERSION.as_str(),
+            auto_accept_at,
+            reminder_at
         )
-        .fetch_one(db_pool)
+        .fetch_one(conn)
         .await
     }
 
-    /// Auto-accept final matches that have been pending for more than 24 hours
+    /// Remind users of the current event who have not responded to their final match
+    /// once its reminder time is reached
+    ///
+    /// Each match is reminded at most once, and not after it was auto-accepted.
+    #[instrument(skip_all, err)]
+    pub async fn send_match_reminders(db_pool: &PgPool) -> AppResult<()> {
+        let event_id = EventService::current_event_id(db_pool).await?;
+
+        let due_matches = sqlx::query!(
+            r#"
+            SELECT fm.id
+            FROM final_matches fm
+            JOIN users ua ON fm.user_a_id = ua.id
+            JOIN users ub ON fm.user_b_id = ub.id
+            WHERE fm.event_id = $1 AND fm.reminded_at IS NULL
+            AND fm.reminder_at <= NOW() AND fm.auto_accept_at > NOW()
+            AND (ua.status = 'matched' OR ub.status = 'matched')
+            "#,
+            event_id
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        for due_match in due_matches {
+            let mut tx = db_pool.begin().await?;
+
+            // Marking the match first ensures concurrent runs remind it only once
+            let reminded = sqlx::query!(
+                r#"
+                UPDATE final_matches fm
+                SET reminded_at = NOW()
+                WHERE fm.id = $1 AND fm.reminded_at IS NULL
+                RETURNING fm.auto_accept_at,
+                    (SELECT array_agg(u.id) FROM users u
+                     WHERE u.id IN (fm.user_a_id, fm.user_b_id) AND u.status = 'matched') as "pending_user_ids: Vec<Uuid>"
+                "#,
+                due_match.id
+            )
+            .fetch_optional(tx.as_mut())
+            .await?;
+
+            let Some(reminded) = reminded else {
+                tx.rollback().await?;
+                continue;
+            };
+
+            let seconds_left = (reminded.auto_accept_at - OffsetDateTime::now_utc())
+                .whole_seconds()
+                .max(0);
+            let event = MatchEvent::MatchReminder {
+                hours_left: (seconds_left + 3599) / 3600,
+            };
+            let notifications: Vec<Notification> = reminded
+                .pending_user_ids
+                .unwrap_or_default()
+                .into_iter()
+                .map(|user_id| Notification::new(user_id, event))
+                .collect();
+            NotificationService::notify(tx.as_mut(), &notifications).await?;
+
+            tx.commit().await?;
+            info!(
+                final_match_id = %due_match.id,
+                reminded_users = notifications.len(),
+                "Reminded users to respond to their final match"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Auto-accept final matches of the current event whose response window has
+    /// passed, notifying the users who had not confirmed yet
+    ///
+    /// Matches of earlier events are skipped, since activating an event resets the
+    /// statuses of their users.
     #[instrument(skip_all, err)]
     pub async fn auto_accept_expired_matches(db_pool: &PgPool) -> AppResult<()> {
-        let cutoff_time = OffsetDateTime::now_utc() - FINAL_MATCH_AUTO_ACCEPT_TIMEOUT;
+        let event_id = EventService::current_event_id(db_pool).await?;
 
-        // Find final matches older than 24 hours where at least one user has not confirmed
+        // Find expired final matches where at least one user has not confirmed
         let expired_matches = sqlx::query!(
             r#"
             SELECT fm.id, fm.user_a_id, fm.user_b_id
             FROM final_matches fm
             JOIN users ua ON fm.user_a_id = ua.id
             JOIN users ub ON fm.user_b_id = ub.id
-            WHERE fm.created_at <= $1
+            WHERE fm.event_id = $1 AND fm.auto_accept_at <= NOW()
             AND (ua.status = 'matched' OR ub.status = 'matched')
             "#,
-            cutoff_time
+            event_id
         )
         .fetch_all(db_pool)
         .await?;
@@ -532,6 +752,19 @@ impl SchedulerService {
 
             // Only commit if at lease one user is still in 'matched' status
             if user_a_result.rows_affected() > 0 || user_b_result.rows_affected() > 0 {
+                FeedbackService::open_window(tx.as_mut(), expired_match.id).await?;
+
+                // A user who confirmed in time was not confirmed automatically
+                let notifications: Vec<Notification> = [
+                    (expired_match.user_a_id, user_a_result),
+                    (expired_match.user_b_id, user_b_result),
+                ]
+                .into_iter()
+                .filter(|(_, result)| result.rows_affected() > 0)
+                .map(|(user_id, _)| Notification::new(user_id, MatchEvent::MatchAutoConfirmed))
+                .collect();
+                NotificationService::notify(tx.as_mut(), &notifications).await?;
+
                 tx.commit().await?;
                 info!(
                     final_match_id = %expired_match.id,
@@ -548,30 +781,33 @@ impl SchedulerService {
         Ok(())
     }
 
-    /// Spawn the periodic task to auto-accept expired final matches
+    /// Spawn the periodic task to remind users and auto-accept expired final matches
     pub fn spawn_auto_accept_task(db_pool: PgPool) {
         tokio::spawn(async move {
-            // Check every 10 minutes for expired final matches
+            // Check every 10 minutes for final matches due for a reminder or expired
             let mut interval = tokio::time::interval(CHECK_AUTO_ACCEPT_INTERVAL);
             interval.tick().await; // First tick completes immediately, so we skip it
 
             loop {
                 interval.tick().await;
+                let _ = Self::send_match_reminders(&db_pool).await;
                 let _ = Self::auto_accept_expired_matches(&db_pool).await;
             }
         });
     }
 
-    /// Spawn the periodic scheduler task to check for due scheduled matches
-    pub fn spawn_scheduler_task(db_pool: PgPool, tag_system: &'static TagSystem) {
+    /// Spawn the periodic scheduler task to advance event phases and check for due
+    /// scheduled matches
+    pub fn spawn_scheduler_task(db_pool: PgPool) {
         tokio::spawn(async move {
-            // Check every minute for due scheduled matches
+            // Check every minute for started phases and due scheduled matches
             let mut interval = tokio::time::interval(CHECK_SCHEDULED_MATCH_INTERVAL);
             interval.tick().await; // First tick completes immediately, so we skip it
 
             loop {
                 interval.tick().await;
-                let _ = Self::check_and_execute_scheduled_matches(&db_pool, tag_system).await;
+                let _ = EventService::advance_phases(&db_pool).await;
+                let _ = Self::check_and_execute_scheduled_matches(&db_pool).await;
             }
         });
     }
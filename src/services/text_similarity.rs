@@ -0,0 +1,155 @@
+//! # Text Similarity
+//!
+//! Offline TF-IDF cosine similarity between the free text answers of participants,
+//! used by the `text_similarity` scorer of the form schema.
+//!
+//! Tokenization is CJK-aware: Chinese, Japanese and Korean text has no word
+//! boundaries, so runs of CJK characters are split into overlapping character
+//! bigrams, while other scripts are split into lowercase words.
+
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::{
+    models::{Form, FormSchema, Scorer},
+    utils::constant::IDF_MIN,
+};
+
+/// Sparse TF-IDF vector, L2-normalized
+type TermVector = HashMap<String, f64>;
+
+/// TF-IDF vectors of all participants' text answers, built once per matching run
+#[derive(Debug, Default)]
+pub struct TextCorpus {
+    vectors: HashMap<Uuid, TermVector>,
+}
+
+impl TextCorpus {
+    /// Builds the corpus from the answers to the questions consumed by the
+    /// schema's `text_similarity` scorer. Empty if the schema has none.
+    pub fn build(forms: &[Form], schema: &FormSchema) -> Self {
+        let Some(questions) = schema.scorers.iter().find_map(|scorer| match scorer {
+            Scorer::TextSimilarity { questions, .. } => Some(questions),
+            _ => None,
+        }) else {
+            return Self::default();
+        };
+
+        let documents: Vec<(Uuid, HashMap<String, u32>)> = forms
+            .iter()
+            .map(|form| {
+                let mut term_counts = HashMap::new();
+                for question in questions {
+                    if let Some(text) = form.answers.text(question) {
+                        for token in tokenize(text) {
+                            *term_counts.entry(token).or_insert(0) += 1;
+                        }
+                    }
+                }
+                (form.user_id, term_counts)
+            })
+            .collect();
+
+        let mut document_frequencies: HashMap<&str, u32> = HashMap::new();
+        for (_, term_counts) in &documents {
+            for term in term_counts.keys() {
+                *document_frequencies.entry(term).or_insert(0) += 1;
+            }
+        }
+
+        // Same IDF as tag scoring, so terms used by everyone carry little weight
+        let total_documents = documents.len() as f64;
+        let idf = |term: &str| {
+            let frequency = document_frequencies.get(term).copied().unwrap_or(1);
+            (total_documents / frequency as f64).log2().max(IDF_MIN)
+        };
+
+        let vectors = documents
+            .iter()
+            .map(|(user_id, term_counts)| {
+                let mut vector: TermVector = term_counts
+                    .iter()
+                    .map(|(term, count)| (term.clone(), *count as f64 * idf(term)))
+                    .collect();
+
+                let norm = vector.values().map(|w| w * w).sum::<f64>().sqrt();
+                if norm > 0.0 {
+                    vector.values_mut().for_each(|w| *w /= norm);
+                }
+                (*user_id, vector)
+            })
+            .collect();
+
+        TextCorpus { vectors }
+    }
+
+    /// Cosine similarity between two participants' text answers, between 0 and 1.
+    /// Returns 0 if either is not part of the corpus.
+    pub fn similarity(&self, user_a: Uuid, user_b: Uuid) -> f64 {
+        let (Some(a), Some(b)) = (self.vectors.get(&user_a), self.vectors.get(&user_b)) else {
+            return 0.0;
+        };
+
+        // Iterate over the smaller vector
+        let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+        small
+            .iter()
+            .filter_map(|(term, weight)| large.get(term).map(|other| weight * other))
+            .sum()
+    }
+}
+
+/// Splits text into terms: character bigrams for CJK runs (a lone CJK character
+/// is kept as is) and lowercase words of at least two characters otherwise.
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut cjk_run: Vec<char> = Vec::new();
+    let mut word = String::new();
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            flush_word(&mut word, &mut tokens);
+            cjk_run.push(c);
+        } else if c.is_alphanumeric() {
+            flush_cjk_run(&mut cjk_run, &mut tokens);
+            word.extend(c.to_lowercase());
+        } else {
+            flush_word(&mut word, &mut tokens);
+            flush_cjk_run(&mut cjk_run, &mut tokens);
+        }
+    }
+    flush_word(&mut word, &mut tokens);
+    flush_cjk_run(&mut cjk_run, &mut tokens);
+
+    tokens
+}
+
+fn flush_word(word: &mut String, tokens: &mut Vec<String>) {
+    if word.chars().count() >= 2 {
+        tokens.push(std::mem::take(word));
+    } else {
+        word.clear();
+    }
+}
+
+fn flush_cjk_run(run: &mut Vec<char>, tokens: &mut Vec<String>) {
+    match run.len() {
+        0 => {}
+        1 => tokens.push(run[0].to_string()),
+        _ => tokens.extend(run.windows(2).map(|pair| pair.iter().collect())),
+    }
+    run.clear();
+}
+
+/// Han ideographs, kana and hangul
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'     // Hiragana, Katakana
+        | '\u{3400}'..='\u{4DBF}'   // CJK Extension A
+        | '\u{4E00}'..='\u{9FFF}'   // CJK Unified Ideographs
+        | '\u{AC00}'..='\u{D7AF}'   // Hangul Syllables
+        | '\u{F900}'..='\u{FAFF}'   // CJK Compatibility Ideographs
+        | '\u{20000}'..='\u{2EBEF}' // CJK Extensions B-F
+    )
+}
@@ -0,0 +1,320 @@
+//! # User Event Service
+//!
+//! Events recorded in `user_events` fire a `NOTIFY` on [`USER_EVENTS_CHANNEL`].
+//! Every running server listens on it, loads the new events and fans them out to
+//! its connected event streams through a [`UserEventHub`], so users receive them
+//! no matter which server made the change.
+//!
+//! Events are sent in the order of their [`StreamPosition`], and held back while
+//! an older transaction that may still record events is running.
+//!
+//! Events are kept for [`USER_EVENT_RETENTION`], so streams that missed events
+//! because they fell behind catch up from the database, as do clients reconnecting
+//! with the id of the last event they saw.
+
+use std::sync::Arc;
+
+use sqlx::{PgConnection, PgPool, postgres::PgListener, types::Json};
+use tokio::sync::broadcast;
+use tracing::{debug, error, info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    error::AppResult,
+    models::{NewUserEvent, StreamPosition, UserEvent, UserEventKind},
+    utils::constant::{
+        CHECK_USER_EVENT_RETENTION_INTERVAL, EVENT_STREAM_BUFFER_SIZE,
+        USER_EVENT_HOLD_BACK_RETRY_INTERVAL, USER_EVENT_LISTENER_RETRY_INTERVAL,
+        USER_EVENT_RETENTION,
+    },
+};
+
+/// Channel notified by the database with every recorded user event
+pub const USER_EVENTS_CHANNEL: &str = "user_events";
+
+/// Message fanned out to the event streams of a server
+#[derive(Debug, Clone)]
+pub enum HubMessage {
+    Event(Arc<UserEvent>),
+    /// Events may have been missed, streams should catch up from the database
+    Resync,
+}
+
+/// Fans user events out to the event streams connected to this server
+pub struct UserEventHub {
+    db_pool: PgPool,
+    sender: broadcast::Sender<HubMessage>,
+}
+
+impl UserEventHub {
+    pub fn new(db_pool: PgPool) -> Self {
+        Self {
+            db_pool,
+            sender: broadcast::Sender::new(EVENT_STREAM_BUFFER_SIZE),
+        }
+    }
+
+    /// Receive the events recorded from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<HubMessage> {
+        self.sender.subscribe()
+    }
+
+    fn broadcast(&self, message: HubMessage) {
+        // Fails only if no stream is connected
+        let _ = self.sender.send(message);
+    }
+
+    /// Spawn the task that forwards the events notified by the database
+    pub fn spawn_listen_task(self: &Arc<Self>) {
+        let hub = Arc::clone(self);
+        tokio::spawn(async move {
+            // Kept across failures, the events recorded meanwhile are forwarded once
+            // the listener is back
+            let mut position = None;
+            loop {
+                if let Err(e) = hub.listen(&mut position).await {
+                    error!(error = %e, "User events listener failed");
+                }
+                tokio::time::sleep(USER_EVENT_LISTENER_RETRY_INTERVAL).await;
+            }
+        });
+    }
+
+    async fn listen(&self, position: &mut Option<StreamPosition>) -> Result<(), sqlx::Error> {
+        let mut listener = PgListener::connect_with(&self.db_pool).await?;
+        listener.listen(USER_EVENTS_CHANNEL).await?;
+        let position = match position {
+            Some(position) => position,
+            None => {
+                let horizon = position.insert(UserEventService::horizon(&self.db_pool).await?);
+                // Streams opened before missed the events recorded in between
+                self.broadcast(HubMessage::Resync);
+                horizon
+            }
+        };
+
+        let mut held_back = self.forward(position).await?;
+        loop {
+            if held_back {
+                // Older transactions may finish without recording an event
+                let received =
+                    tokio::time::timeout(USER_EVENT_HOLD_BACK_RETRY_INTERVAL, listener.try_recv())
+                        .await;
+                if let Ok(received) = received {
+                    received?;
+                }
+            } else if listener.try_recv().await?.is_none() {
+                warn!("User events listener reconnected");
+            }
+            held_back = self.forward(position).await?;
+        }
+    }
+
+    /// Broadcast the events recorded after `position` that may be sent, returning
+    /// whether later events are held back
+    async fn forward(&self, position: &mut StreamPosition) -> Result<bool, sqlx::Error> {
+        let (events, held_back) =
+            UserEventService::recorded_after(&self.db_pool, *position).await?;
+        for event in events {
+            debug!(event_id = event.id, kind = ?event.kind, "User event received");
+            *position = event.position();
+            self.broadcast(HubMessage::Event(Arc::new(event)));
+        }
+        Ok(held_back)
+    }
+}
+
+pub struct UserEventService;
+
+impl UserEventService {
+    /// Record an event, pushed to its users once the transaction commits
+    pub async fn publish(conn: &mut PgConnection, event: &NewUserEvent) -> AppResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO user_events (user_id, kind, data)
+            VALUES ($1, $2, $3)
+            "#,
+            event.user_id,
+            event.kind as UserEventKind,
+            event.data
+        )
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Tell every user about the next final match time of the current event, unless
+    /// it did not change since they were last told
+    ///
+    /// Call after changing scheduled final matches or the current event.
+    #[instrument(skip_all, err)]
+    pub async fn publish_next_match_time(conn: &mut PgConnection) -> AppResult<()> {
+        let next = sqlx::query_scalar!(
+            r#"
+            SELECT MIN(s.scheduled_time)
+            FROM scheduled_final_matches s
+            JOIN events e ON e.id = s.event_id
+            WHERE e.is_current AND s.status = 'pending'
+            "#
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+
+        let event = NewUserEvent::next_match_time_changed(next);
+        let last_published = sqlx::query_scalar!(
+            r#"
+            SELECT data
+            FROM user_events
+            WHERE kind = 'next_match_time_changed'
+            ORDER BY id DESC
+            LIMIT 1
+            "#
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+        if last_published.as_ref() == Some(&event.data) {
+            return Ok(());
+        }
+
+        info!(?next, "Next final match time changed");
+        Self::publish(conn, &event).await
+    }
+
+    /// Position before the events that may not be sent yet, as their transaction or
+    /// an older one is still running
+    pub async fn horizon(db_pool: &PgPool) -> Result<StreamPosition, sqlx::Error> {
+        let xact_id = sqlx::query_scalar!(
+            r#"SELECT pg_snapshot_xmin(pg_current_snapshot())::text::bigint AS "xact_id!""#
+        )
+        .fetch_one(db_pool)
+        .await?;
+
+        Ok(StreamPosition { xact_id, id: 0 })
+    }
+
+    /// Position of the event with id `id`, or of the latest event before it if it
+    /// was purged
+    pub async fn position_of(db_pool: &PgPool, id: i64) -> Result<StreamPosition, sqlx::Error> {
+        let position = sqlx::query_as!(
+            StreamPosition,
+            r#"
+            SELECT xact_id, id
+            FROM user_events
+            WHERE id <= $1
+            ORDER BY id DESC
+            LIMIT 1
+            "#,
+            id
+        )
+        .fetch_optional(db_pool)
+        .await?;
+
+        Ok(position.unwrap_or_default())
+    }
+
+    /// Events of a user after `position` that may be sent, in stream order
+    pub async fn events_after(
+        db_pool: &PgPool,
+        user_id: Uuid,
+        position: StreamPosition,
+    ) -> Result<Vec<UserEvent>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT id, xact_id, user_id, kind as "kind: UserEventKind",
+                   data as "data: Json<serde_json::Value>", created_at
+            FROM user_events
+            WHERE (xact_id, id) > ($2, $3)
+              AND xact_id < pg_snapshot_xmin(pg_current_snapshot())::text::bigint
+              AND (user_id = $1 OR user_id IS NULL)
+            ORDER BY xact_id, id
+            "#,
+            user_id,
+            position.xact_id,
+            position.id
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| UserEvent {
+                id: row.id,
+                xact_id: row.xact_id,
+                user_id: row.user_id,
+                kind: row.kind,
+                data: row.data.0,
+                created_at: row.created_at,
+            })
+            .collect())
+    }
+
+    /// Events of every user after `position` that may be sent, in stream order, and
+    /// whether later events are held back
+    async fn recorded_after(
+        db_pool: &PgPool,
+        position: StreamPosition,
+    ) -> Result<(Vec<UserEvent>, bool), sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT id, xact_id, user_id, kind as "kind: UserEventKind",
+                   data as "data: Json<serde_json::Value>", created_at,
+                   xact_id < pg_snapshot_xmin(pg_current_snapshot())::text::bigint
+                       AS "sendable!"
+            FROM user_events
+            WHERE (xact_id, id) > ($1, $2)
+            ORDER BY xact_id, id
+            "#,
+            position.xact_id,
+            position.id
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let held_back = rows.iter().any(|row| !row.sendable);
+        let events = rows
+            .into_iter()
+            .take_while(|row| row.sendable)
+            .map(|row| UserEvent {
+                id: row.id,
+                xact_id: row.xact_id,
+                user_id: row.user_id,
+                kind: row.kind,
+                data: row.data.0,
+                created_at: row.created_at,
+            })
+            .collect();
+        Ok((events, held_back))
+    }
+
+    /// Delete the events that exceeded their retention period
+    #[instrument(skip_all, err)]
+    pub async fn purge_expired(db_pool: &PgPool) -> AppResult<u64> {
+        let retention_seconds = USER_EVENT_RETENTION.as_secs_f64();
+        let purged = sqlx::query!(
+            "DELETE FROM user_events WHERE created_at < NOW() - make_interval(secs => $1)",
+            retention_seconds
+        )
+        .execute(db_pool)
+        .await?
+        .rows_affected();
+
+        if purged > 0 {
+            info!(purged, "Purged expired user events");
+        }
+        Ok(purged)
+    }
+
+    /// Spawn the periodic task that purges expired events
+    pub fn spawn_retention_task(db_pool: PgPool) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(CHECK_USER_EVENT_RETENTION_INTERVAL);
+            interval.tick().await; // First tick completes immediately, so we skip it
+
+            loop {
+                interval.tick().await;
+                let _ = Self::purge_expired(&db_pool).await;
+            }
+        });
+    }
+}
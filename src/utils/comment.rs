@@ -0,0 +1,18 @@
+//! # Comments
+//!
+//! Free text users can attach to their feedback and rejections, only shown to admins.
+
+use crate::utils::constant::MAX_COMMENT_LENGTH;
+
+/// Check that a comment is at most [`MAX_COMMENT_LENGTH`] characters long
+pub fn validate_comment(comment: Option<&str>) -> Result<(), &'static str> {
+    if comment.is_some_and(|comment| comment.chars().count() > MAX_COMMENT_LENGTH) {
+        return Err("Comment is too long");
+    }
+    Ok(())
+}
+
+/// The comment to store, trimmed and `None` if blank
+pub fn trim_comment(comment: Option<&str>) -> Option<&str> {
+    comment.map(str::trim).filter(|comment| !comment.is_empty())
+}
@@ -19,12 +19,12 @@ pub const ACCESS_TOKEN_EXPIRY: Duration = Duration::from_secs(15 * 60);
 /// Expiration time for JWT refresh tokens
 pub const REFRESH_TOKEN_EXPIRY: Duration = Duration::from_secs(7 * 24 * 60 * 60); // 7 days
 
-/// Maximum length in **bytes** for text fields like self_intro and recent_topics
-pub const MAX_TEXT_FIELD_LENGTH: usize = 4 * 200; // assume 4 bytes per char
-
 /// Maximum length for WeChat ID
 pub const MAX_WECHAT_ID_LENGTH: usize = 100;
 
+/// Maximum length for choice IDs and filenames stored in form drafts (not checked against their catalog)
+pub const MAX_DRAFT_ID_LENGTH: usize = 100;
+
 /// Minimum IDF value to avoid division by zero or overly aggressive down-weighting
 pub const IDF_MIN: f64 = 0.1;
 
@@ -34,11 +34,105 @@ pub const MATCH_PREVIEW_INTERVAL: Duration = Duration::from_secs(20 * 60); // 20
 /// Interval to check for scheduled matches
 pub const CHECK_SCHEDULED_MATCH_INTERVAL: Duration = Duration::from_secs(60); // 1 minute
 
-/// Timeout after which final matches are automatically accepted
-pub const FINAL_MATCH_AUTO_ACCEPT_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60); // 24 hours
+/// Hours after which final matches are automatically accepted, unless the round sets its own
+pub const DEFAULT_AUTO_ACCEPT_HOURS: i32 = 24;
+
+/// Longest a round may give users to respond to their final match
+pub const MAX_AUTO_ACCEPT_HOURS: i32 = 24 * 30; // 30 days
+
+/// Hours before the auto-acceptance at which users who have not responded are reminded,
+/// unless the round sets its own
+pub const DEFAULT_REMINDER_HOURS: i32 = 6;
 
-/// Interval to check for final matches that need auto-acceptance
+/// Interval to check for final matches that need a reminder or auto-acceptance
 pub const CHECK_AUTO_ACCEPT_INTERVAL: Duration = Duration::from_secs(10 * 60); // 10 minutes
 
 /// Size (the larger dimension) of profile photo thumbnails in pixels
 pub const THUMBNAIL_SIZE: u32 = 80;
+
+/// Interval to purge card photos that exceeded their retention period
+pub const CHECK_CARD_RETENTION_INTERVAL: Duration = Duration::from_secs(60 * 60); // 1 hour
+
+/// Interval to lift suspensions whose `suspended_until` has passed
+pub const CHECK_SUSPENSION_EXPIRY_INTERVAL: Duration = Duration::from_secs(60); // 1 minute
+
+/// Time for which the auth middleware trusts the cached suspension state of a user
+pub const SUSPENSION_CACHE_TTL: Duration = Duration::from_secs(60); // 1 minute
+
+/// Delay before reconnecting the allowed domains listener after an error
+pub const DOMAIN_LISTENER_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Interval to check the email outbox for retries, in case no new email wakes up the worker
+pub const EMAIL_OUTBOX_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Maximum number of emails claimed by one delivery round
+pub const EMAIL_OUTBOX_BATCH_SIZE: i64 = 50;
+
+/// Time after which a claimed email is attempted again if its delivery never finished
+pub const EMAIL_DELIVERY_LEASE: Duration = Duration::from_secs(2 * 60);
+
+/// Delay before the first retry of a failed delivery, doubled for every further attempt
+pub const EMAIL_RETRY_BASE_DELAY: Duration = Duration::from_secs(30);
+
+/// Upper bound of the delay between delivery attempts
+pub const EMAIL_RETRY_MAX_DELAY: Duration = Duration::from_secs(60 * 60); // 1 hour
+
+/// Number of delivery attempts after which an email becomes a dead letter
+pub const EMAIL_MAX_ATTEMPTS: i32 = 8;
+
+/// Time after enqueueing for which sent and failed emails are kept in the outbox for inspection
+pub const EMAIL_OUTBOX_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60); // 30 days
+
+/// Default number of consecutive failures after which an email provider is skipped
+pub const DEFAULT_EMAIL_CIRCUIT_FAILURE_THRESHOLD: u32 = 3;
+
+/// Default time a failing email provider is skipped before it is probed again
+pub const DEFAULT_EMAIL_CIRCUIT_OPEN_DURATION: Duration = Duration::from_secs(60);
+
+/// Default maximum number of pooled connections to the SMTP relay
+pub const DEFAULT_SMTP_POOL_SIZE: u32 = 4;
+
+/// Locale every email template must provide, used when a locale is missing
+pub const DEFAULT_EMAIL_LOCALE: &str = "en";
+
+/// Interval of the heartbeat comments keeping idle event streams open
+pub const EVENT_STREAM_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Number of user events buffered for slow event streams before they catch up from the database
+pub const EVENT_STREAM_BUFFER_SIZE: usize = 1024;
+
+/// Delay before reconnecting the user events listener after an error
+pub const USER_EVENT_LISTENER_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Delay before forwarding user events again while they are held back by older
+/// transactions that are still running
+pub const USER_EVENT_HOLD_BACK_RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Time for which user events are kept, so reconnecting clients can resume their stream
+pub const USER_EVENT_RETENTION: Duration = Duration::from_secs(24 * 60 * 60); // 24 hours
+
+/// Interval to purge user events that exceeded their retention period
+pub const CHECK_USER_EVENT_RETENTION_INTERVAL: Duration = Duration::from_secs(60 * 60); // 1 hour
+
+/// Revision of the matching code, part of the version recorded with every final match.
+/// Increase it whenever a change to the scoring or matching code affects the pairs found.
+pub const MATCHING_ALGORITHM_REVISION: u32 = 1;
+
+/// Lowest and highest rating users can give their confirmed final match
+pub const FEEDBACK_RATING_RANGE: std::ops::RangeInclusive<i16> = 1..=5;
+
+/// Maximum number of characters of the comment given with feedback or a rejection
+pub const MAX_COMMENT_LENGTH: usize = 2000;
+
+/// Default width of the score buckets feedback and rejections are aggregated by
+pub const DEFAULT_SCORE_BUCKET_WIDTH: f64 = 5.0;
+
+/// Rejection reasons used when `REJECTION_REASONS` is not set
+pub const DEFAULT_REJECTION_REASONS: [&str; 6] = [
+    "no_shared_interests",
+    "personality_mismatch",
+    "not_attracted",
+    "already_know_each_other",
+    "not_ready",
+    "other",
+];
@@ -4,7 +4,7 @@
 //! and file management. These utilities are shared between different upload handlers to
 //! ensure consistent validation and file handling.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use image::{GenericImageView, ImageFormat, imageops::FilterType};
 use tokio::{fs, io::AsyncWriteExt};
@@ -141,6 +141,58 @@ impl FileManager {
         debug!(file_path = %file_path.display(), "File saved successfully");
         Ok(())
     }
+
+    /// Lists all files in `dir` that belong to the given user.
+    ///
+    /// Matches both `{uuid}.{extension}` and thumbnails named `{uuid}_thumb.{extension}`.
+    /// A missing directory yields an empty list.
+    pub async fn find_user_files(
+        dir: &Path,
+        user_id: uuid::Uuid,
+    ) -> Result<Vec<PathBuf>, std::io::Error> {
+        let mut entries = match fs::read_dir(dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let original_stem = user_id.to_string();
+        let thumbnail_stem = format!("{original_stem}_thumb");
+
+        let mut files = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let is_user_file = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .is_some_and(|stem| stem == original_stem || stem == thumbnail_stem);
+
+            if is_user_file && entry.file_type().await?.is_file() {
+                files.push(path);
+            }
+        }
+
+        trace!(dir = %dir.display(), count = files.len(), "Found user files");
+        Ok(files)
+    }
+
+    /// Removes all files in `dir` that belong to the given user, including thumbnails.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - Number of files removed
+    /// * `Err(std::io::Error)` - Failed to read the directory or remove a file
+    pub async fn remove_user_files(
+        dir: &Path,
+        user_id: uuid::Uuid,
+    ) -> Result<usize, std::io::Error> {
+        let files = Self::find_user_files(dir, user_id).await?;
+        for file in &files {
+            debug!(file_path = %file.display(), "Removing user file");
+            fs::remove_file(file).await?;
+        }
+        Ok(files.len())
+    }
 }
 
 /// Provides image processing utilities for thumbnails and resizing.
@@ -1,37 +1,53 @@
-/// Generates a styled HTML email for sending a verification code.
+/// Escapes text for embedding in HTML, preserving its line breaks.
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "<br>")
+}
+
+/// Generates a styled HTML email around the rendered HTML part of a template.
 ///
 /// This function creates a responsive HTML email body that includes:
-/// 1. A hidden preheader for email client previews.
+/// 1. A hidden preheader for email client previews, if there is one.
 /// 2. The project's branding.
-/// 3. The verification code, displayed prominently.
+/// 3. The content, styled as body text unless it brings its own styles.
 /// 4. A professional and clean design using inline CSS for maximum compatibility.
 ///
 /// # Arguments
 ///
-/// * `code` - A string slice that holds the verification code to be embedded in the email.
+/// * `locale` - Language of the content, e.g. `en`
+/// * `preheader` - HTML shown as preview by email clients, may be empty; it is not escaped.
+/// * `content` - HTML to embed in the email; it is not escaped.
 ///
 /// # Returns
 ///
 /// A `String` containing the full HTML content of the email.
-pub fn generate_verification_email_html(code: &str) -> String {
-    use super::constant::VERIFICATION_CODE_EXPIRY;
+pub fn generate_email_html(locale: &str, preheader: &str, content: &str) -> String {
     let current_year = time::OffsetDateTime::now_utc().year();
+    let preheader = if preheader.is_empty() {
+        String::new()
+    } else {
+        format!(
+            r#"
+    <!-- This is a hidden preheader text. -->
+    <div style="display:none;font-size:1px;color:#ffffff;line-height:1px;max-height:0px;max-width:0px;opacity:0;overflow:hidden;">
+        {preheader}
+    </div>
+"#
+        )
+    };
 
     format!(
         r#"<!DOCTYPE html>
-<html lang="en">
+<html lang="{locale}">
 <head>
     <meta charset="UTF-8">
     <meta name="viewport" content="width=device-width, initial-scale=1.0">
     <title>Contigo✨</title>
 </head>
-<body style="margin: 0; padding: 0; font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif, 'Apple Color Emoji', 'Segoe UI Emoji', 'Segoe UI Symbol'; background-color: #f0f2f5;">
-
-    <!-- This is a hidden preheader text. -->
-    <div style="display:none;font-size:1px;color:#ffffff;line-height:1px;max-height:0px;max-width:0px;opacity:0;overflow:hidden;">
-        Your login code: {code}
-    </div>
-
+<body style="margin: 0; padding: 0; font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif, 'Apple Color Emoji', 'Segoe UI Emoji', 'Segoe UI Symbol'; background-color: #f0f2f5;">{preheader}
     <table width="100%" border="0" cellspacing="0" cellpadding="0" style="background-color: #f0f2f5;">
         <tr>
             <td align="center" style="padding: 20px;">
@@ -41,29 +57,14 @@ pub fn generate_verification_email_html(code: &str) -> String {
                     <!-- Header Section -->
                     <tr>
                         <td align="center" style="padding: 40px 20px 20px 20px;">
-                            <h1 style="margin: 0; color: #1c1e21; font-size: 32px; font-weight: 600;">🎉 Welcome 🥳</h1>
-                            <p style="margin: 4px 0 0 0; color: #606770; font-size: 14px;">to Project Contigo</p>
+                            <h1 style="margin: 0; color: #1c1e21; font-size: 28px; font-weight: 600;">Project Contigo</h1>
                         </td>
                     </tr>
 
                     <!-- Body Section -->
                     <tr>
-                        <td style="padding: 20px 40px;">
-                            <h2 style="margin: 0 0 24px 0; font-size: 22px; font-weight: 600; color: #1c1e21; text-align: center;">Confirm Your Email Address</h2>
-                            <p style="margin: 0 0 24px 0; font-size: 16px; line-height: 1.6; color: #606770; text-align: center;">
-                                Thanks for signing up for Project Contigo! Please use the following code to complete your registration.
-                            </p>
-
-                            <!-- Verification Code Box -->
-                            <div style="background-color: #e7f3ff; border-radius: 8px; padding: 16px; text-align: center;">
-                                <p style="margin: 0; font-size: 36px; font-weight: 700; color: #1877f2; letter-spacing: 5px; line-height: 1.2;">
-                                    {code}
-                                </p>
-                            </div>
-
-                            <p style="margin: 24px 0 0 0; font-size: 14px; color: #606770; text-align: center;">
-                                This code will expire in {} minutes. If you did not request this, please disregard this email.
-                            </p>
+                        <td style="padding: 20px 40px; font-size: 16px; line-height: 1.6; color: #606770;">
+                            {content}
                         </td>
                     </tr>
 
@@ -72,7 +73,7 @@ pub fn generate_verification_email_html(code: &str) -> String {
                         <td align="center" style="padding: 30px 40px; border-top: 1px solid #e1e4e8;">
                             <p style="margin: 0; font-size: 12px; color: #90949c; line-height: 1.5;">
                                 &copy; {current_year} Project Contigo. All rights reserved.<br>
-                                This email was sent to you as part of the account verification process on <a href="https://contigo.maplewrt.com" style="color: #1877f2; text-decoration: none;">contigo.maplewrt.com</a>.
+                                This email was sent to you by <a href="https://contigo.maplewrt.com" style="color: #1877f2; text-decoration: none;">contigo.maplewrt.com</a>.
                             </p>
                         </td>
                     </tr>
@@ -81,7 +82,6 @@ pub fn generate_verification_email_html(code: &str) -> String {
         </tr>
     </table>
 </body>
-</html>"#,
-        VERIFICATION_CODE_EXPIRY.as_secs() / 60,
+</html>"#
     )
 }
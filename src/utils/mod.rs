@@ -1,3 +1,4 @@
+pub mod comment;
 pub mod constant;
 pub mod file;
 pub mod html;
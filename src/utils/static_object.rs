@@ -1,92 +1,74 @@
-use std::{collections::HashSet, env, sync::LazyLock};
-
-use regex::Regex;
-use tracing::error;
-
-use crate::models::{TagNode, TagSystem};
-
-/// Email validation regex pattern
-///
-/// This regex validates email addresses against a list of allowed domains
-/// specified in the `ALLOWED_DOMAINS` environment variable. The domains
-/// are separated by colons and are properly escaped for regex use.
-///
-/// # Environment Variables
-///
-/// - `ALLOWED_DOMAINS` - Colon-separated list of allowed email domains
-///   (e.g., "example.com:university.edu")
-///
-/// # Examples
-///
-/// For `ALLOWED_DOMAINS="mails.tsinghua.edu.cn"`:
-/// - `user@mails.tsinghua.edu.cn` ✓ Valid
-/// - `user@gmail.com` ✗ Invalid
-/// - `invalid-email` ✗ Invalid format
-pub static EMAIL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    let allowed_domains_str = env::var("ALLOWED_DOMAINS").unwrap_or_else(|_| {
-        error!("Missing ALLOWED_DOMAINS env var, using fallback 'mails.tsinghua.edu.cn'");
-        "mails.tsinghua.edu.cn".to_string()
-    });
+use std::{
+    env,
+    sync::{Arc, LazyLock},
+};
+
+use sha2::{Digest, Sha256};
+use tracing::{error, warn};
+
+use crate::{
+    models::{Catalogs, EmailTemplates, FormSchema},
+    utils::constant::{DEFAULT_REJECTION_REASONS, MATCHING_ALGORITHM_REVISION},
+};
+
+/// Tag catalog from `tags.json` and trait catalog from `traits.json`, used by events
+/// without their own catalogs. Traits have the same parent/child structure as the
+/// tags: only matchable traits can be chosen, their parents group related traits.
+pub static DEFAULT_CATALOGS: LazyLock<Arc<Catalogs>> = LazyLock::new(|| {
+    let read = |path: &str| {
+        std::fs::read_to_string(path).unwrap_or_else(|_| {
+            error!("Failed to read {} file", path);
+            std::process::exit(1);
+        })
+    };
 
-    let escaped_domains: Vec<String> = allowed_domains_str
-        .split(':')
-        .map(regex::escape) // encode special chars like period
-        .collect();
-    let domains_pattern = escaped_domains.join("|");
-    let pattern = format!(r"^[a-zA-Z0-9._%+-]+@({domains_pattern})$");
+    let catalogs =
+        Catalogs::from_json(&read("tags.json"), &read("traits.json")).unwrap_or_else(|e| {
+            error!("Failed to parse tags.json or traits.json: {}", e);
+            std::process::exit(1)
+        });
 
-    Regex::new(&pattern).unwrap_or_else(|e| {
-        error!("Failed to compile email regex: {}", e);
-        std::process::exit(1);
-    })
+    Arc::new(catalogs)
 });
 
-pub static TAG_SYSTEM: LazyLock<TagSystem> = LazyLock::new(|| {
-    let raw = std::fs::read_to_string("tags.json").unwrap_or_else(|_| {
-        error!("Failed to read tags.json file");
+/// The questionnaire, loaded from `form_schema.json`
+///
+/// Selection limits are part of the schema, replacing the former `TAGS_LIMIT_SUM` and
+/// `TRAITS_LIMIT_EACH` env vars, which are ignored with a warning if still set.
+pub static FORM_SCHEMA: LazyLock<FormSchema> = LazyLock::new(|| {
+    for removed in ["TAGS_LIMIT_SUM", "TRAITS_LIMIT_EACH"] {
+        if env::var(removed).is_ok() {
+            warn!(
+                "{} env var is no longer used, set the limits in form_schema.json",
+                removed
+            );
+        }
+    }
+
+    let raw = std::fs::read_to_string("form_schema.json").unwrap_or_else(|_| {
+        error!("Failed to read form_schema.json file");
         std::process::exit(1);
     });
 
-    TagSystem::from_json(&raw).unwrap_or_else(|e| {
-        error!("Failed to parse tags.json: {}", e);
+    FormSchema::from_json(&raw).unwrap_or_else(|e| {
+        error!("Failed to parse form_schema.json: {}", e);
         std::process::exit(1)
     })
 });
 
-pub static TAG_TREE: LazyLock<Vec<TagNode>> = LazyLock::new(|| {
-    let raw = std::fs::read_to_string("tags.json").unwrap_or_else(|_| {
-        error!("Failed to read tags.json file");
+/// The email templates, loaded from `email_templates.json`
+pub static EMAIL_TEMPLATES: LazyLock<EmailTemplates> = LazyLock::new(|| {
+    let raw = std::fs::read_to_string("email_templates.json").unwrap_or_else(|_| {
+        error!("Failed to read email_templates.json file");
         std::process::exit(1);
     });
 
-    serde_json::from_str(&raw).unwrap_or_else(|e| {
-        error!("Failed to parse tags.json: {}", e);
+    EmailTemplates::from_json(&raw).unwrap_or_else(|e| {
+        error!("Failed to parse email_templates.json: {}", e);
         std::process::exit(1)
     })
 });
 
-pub static TRAITS: LazyLock<HashSet<String>> = LazyLock::new(|| {
-    let raw = std::fs::read_to_string("traits.json").unwrap_or_else(|_| {
-        error!("Failed to read traits.json file");
-        std::process::exit(1);
-    });
-
-    let traits: Vec<serde_json::Value> = serde_json::from_str(&raw).unwrap_or_else(|e| {
-        error!("Failed to parse traits.json: {}", e);
-        std::process::exit(1)
-    });
-
-    traits
-        .into_iter()
-        .filter_map(|trait_obj| {
-            trait_obj
-                .get("id")
-                .and_then(|id| id.as_str())
-                .map(|s| s.to_string())
-        })
-        .collect()
-});
-
 pub static TAG_SCORE_DECAY_FACTOR: LazyLock<f64> = LazyLock::new(|| {
     env::var("TAG_SCORE_DECAY_FACTOR")
         .ok()
@@ -117,6 +99,20 @@ pub static TRAIT_MATCH_POINTS: LazyLock<f64> = LazyLock::new(|| {
         })
 });
 
+/// Credit for a related instead of the desired trait, applied once per level up to
+/// their closest common parent. Between 0 and 1, so a related trait never earns more
+/// than the desired one.
+pub static TRAIT_SCORE_DECAY_FACTOR: LazyLock<f64> = LazyLock::new(|| {
+    env::var("TRAIT_SCORE_DECAY_FACTOR")
+        .ok()
+        .and_then(|val| val.parse().ok())
+        .filter(|factor| (0.0..=1.0).contains(factor))
+        .unwrap_or_else(|| {
+            error!("Invalid or missing TRAIT_SCORE_DECAY_FACTOR env var, using fallback 0.5");
+            0.5
+        })
+});
+
 pub static BOUNDARY_MATCH_POINTS: LazyLock<f64> = LazyLock::new(|| {
     env::var("BOUNDARY_MATCH_POINTS")
         .ok()
@@ -127,33 +123,58 @@ pub static BOUNDARY_MATCH_POINTS: LazyLock<f64> = LazyLock::new(|| {
         })
 });
 
-pub static TAGS_LIMIT_SUM: LazyLock<usize> = LazyLock::new(|| {
-    env::var("TAGS_LIMIT_SUM")
+pub static MAX_PREVIEW_CANDIDATES: LazyLock<usize> = LazyLock::new(|| {
+    env::var("MAX_PREVIEW_CANDIDATES")
+        .ok()
+        .and_then(|val| val.parse().ok())
+        .unwrap_or_else(|| {
+            error!("Invalid or missing MAX_PREVIEW_CANDIDATES env var, using fallback 6");
+            6
+        })
+});
+
+/// Weight of diversity against relevance when re-ranking preview candidates (between 0 and 1)
+pub static PREVIEW_DIVERSITY_WEIGHT: LazyLock<f64> = LazyLock::new(|| {
+    env::var("PREVIEW_DIVERSITY_WEIGHT")
         .ok()
-        .and_then(|val| val.parse::<usize>().ok())
+        .and_then(|val| val.parse().ok())
+        .filter(|weight| (0.0..=1.0).contains(weight))
         .unwrap_or_else(|| {
-            error!("Invalid or missing TAGS_LIMIT_SUM env var, using fallback value 10");
-            10
+            error!("Invalid or missing PREVIEW_DIVERSITY_WEIGHT env var, using fallback 0.3");
+            0.3
         })
 });
 
-pub static TRAITS_LIMIT_EACH: LazyLock<usize> = LazyLock::new(|| {
-    env::var("TRAITS_LIMIT_EACH")
+/// Maximum number of previews a single candidate appears in per generation run
+pub static MAX_PREVIEW_EXPOSURE: LazyLock<usize> = LazyLock::new(|| {
+    env::var("MAX_PREVIEW_EXPOSURE")
         .ok()
-        .and_then(|val| val.parse::<usize>().ok())
+        .and_then(|val| val.parse().ok())
         .unwrap_or_else(|| {
-            error!("Invalid or missing TRAITS_LIMIT_EACH env var, using fallback value 3");
-            3
+            error!("Invalid or missing MAX_PREVIEW_EXPOSURE env var, using fallback 12");
+            12
         })
 });
 
-pub static MAX_PREVIEW_CANDIDATES: LazyLock<usize> = LazyLock::new(|| {
-    env::var("MAX_PREVIEW_CANDIDATES")
+/// Maximum number of vetoes a user can hold per final matching round
+pub static VETO_QUOTA: LazyLock<u32> = LazyLock::new(|| {
+    env::var("VETO_QUOTA")
         .ok()
         .and_then(|val| val.parse().ok())
         .unwrap_or_else(|| {
-            error!("Invalid or missing MAX_PREVIEW_CANDIDATES env var, using fallback 6");
-            6
+            error!("Invalid or missing VETO_QUOTA env var, using fallback 5");
+            5
+        })
+});
+
+/// Hours before the next scheduled final match at which the veto window closes
+pub static VETO_WINDOW_CLOSE_HOURS: LazyLock<i64> = LazyLock::new(|| {
+    env::var("VETO_WINDOW_CLOSE_HOURS")
+        .ok()
+        .and_then(|val| val.parse().ok())
+        .unwrap_or_else(|| {
+            error!("Invalid or missing VETO_WINDOW_CLOSE_HOURS env var, using fallback 2");
+            2
         })
 });
 
@@ -164,13 +185,71 @@ pub static UPLOAD_DIR: LazyLock<String> = LazyLock::new(|| {
     })
 });
 
-pub static ALLOWED_GRADES: LazyLock<Vec<&'static str>> = LazyLock::new(|| {
-    let allowed_grades_str = env::var("ALLOWED_GRADES")
-        .unwrap_or_else(|_| {
-            error!("Missing ALLOWED_GRADES env var, using fallback 'undergraduate:graduate'");
-            "undergraduate:graduate".to_string()
+/// Number of days after both users confirmed their final match during which they can
+/// give feedback on it, from 1 to 365
+pub static FEEDBACK_WINDOW_DAYS: LazyLock<i32> = LazyLock::new(|| {
+    env::var("FEEDBACK_WINDOW_DAYS")
+        .ok()
+        .and_then(|val| val.parse().ok())
+        .filter(|days| (1..=365).contains(days))
+        .unwrap_or_else(|| {
+            error!("Invalid or missing FEEDBACK_WINDOW_DAYS env var, using fallback 14");
+            14
         })
-        .leak();
+});
+
+/// Reasons users can pick from when rejecting a final match, from the comma-separated
+/// `REJECTION_REASONS` env var
+pub static REJECTION_REASONS: LazyLock<Vec<String>> = LazyLock::new(|| {
+    let reasons: Vec<String> = env::var("REJECTION_REASONS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|reason| !reason.is_empty())
+        .map(String::from)
+        .collect();
+
+    if reasons.is_empty() {
+        error!("Missing REJECTION_REASONS env var, using fallback reasons");
+        return DEFAULT_REJECTION_REASONS.map(String::from).to_vec();
+    }
+    reasons
+});
 
-    allowed_grades_str.split(':').collect()
+/// Version of the matching algorithm and its configuration, recorded with every final
+/// match so that feedback can be compared across versions.
+///
+/// `MATCHING_ALGORITHM_VERSION` if set, otherwise made of [`MATCHING_ALGORITHM_REVISION`]
+/// and a fingerprint of the scorers of the form schema and the scoring weights, e.g.
+/// `v1-3fa2c91d07be`.
+pub static MATCHING_ALGORITHM_VERSION: LazyLock<String> = LazyLock::new(|| {
+    if let Ok(version) = env::var("MATCHING_ALGORITHM_VERSION") {
+        return version;
+    }
+
+    let config = serde_json::json!({
+        "scorers": FORM_SCHEMA.scorers,
+        "tag_score_decay_factor": *TAG_SCORE_DECAY_FACTOR,
+        "complementary_tag_weight": *COMPLEMENTARY_TAG_WEIGHT,
+        "trait_match_points": *TRAIT_MATCH_POINTS,
+        "trait_score_decay_factor": *TRAIT_SCORE_DECAY_FACTOR,
+        "boundary_match_points": *BOUNDARY_MATCH_POINTS,
+    });
+    let mut hasher = Sha256::new();
+    hasher.update(config.to_string().as_bytes());
+    let fingerprint = format!("{:x}", hasher.finalize());
+    format!("v{MATCHING_ALGORITHM_REVISION}-{}", &fingerprint[..12])
+});
+
+/// Number of days a student card photo is kept after an admin verified or rejected it,
+/// not negative
+pub static CARD_PHOTO_RETENTION_DAYS: LazyLock<i32> = LazyLock::new(|| {
+    env::var("CARD_PHOTO_RETENTION_DAYS")
+        .ok()
+        .and_then(|val| val.parse().ok())
+        .filter(|days| *days >= 0)
+        .unwrap_or_else(|| {
+            error!("Invalid or missing CARD_PHOTO_RETENTION_DAYS env var, using fallback 30");
+            30
+        })
 });
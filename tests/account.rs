@@ -0,0 +1,272 @@
+mod common;
+
+use std::path::Path;
+
+use common::*;
+use hilo::models::UserStatus;
+use serde_json::json;
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn test_export_account(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let (male_token, _female_token) =
+        setup_two_matched_users(&client, &address, &mock_emailer).await;
+
+    // The 1x1 test image cannot be thumbnailed, so place a thumbnail manually
+    let user_id = sqlx::query_scalar!(
+        "SELECT id FROM users WHERE email = $1",
+        "male@mails.tsinghua.edu.cn"
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    std::fs::write(
+        Path::new("./uploads_test/profile_photos").join(format!("{user_id}_thumb.png")),
+        create_test_image(),
+    )
+    .unwrap();
+
+    let (mut archive, data) = export_account(&client, &address, &male_token).await;
+
+    assert_eq!(data["user"]["id"], user_id.to_string());
+    assert_eq!(data["user"]["email"], MALE_EMAIL);
+    assert_eq!(data["user"]["status"], "matched");
+    assert_eq!(data["user"]["locale"], "en");
+    assert_eq!(data["form_revisions"].as_array().unwrap().len(), 1);
+    assert_eq!(data["events"].as_array().unwrap().len(), 1);
+    assert_eq!(data["events"][0]["form"]["gender"], "male");
+    assert!(data["events"][0]["final_match"]["partner_id"].is_string());
+
+    // Card photo, profile photo and its thumbnail are all included
+    for name in [
+        format!("card_photos/{user_id}.png"),
+        format!("profile_photos/{user_id}.png"),
+        format!("profile_photos/{user_id}_thumb.png"),
+    ] {
+        assert!(
+            archive.by_name(&name).is_ok(),
+            "Export should contain {name}"
+        );
+    }
+}
+
+#[sqlx::test]
+async fn test_export_includes_match_history(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    let (male_token, female_token) =
+        setup_two_matched_users(&client, &address, &mock_emailer).await;
+    let response = update_preferences(
+        &client,
+        &address,
+        &male_token,
+        &json!({ "match_reminder": false }),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let response = reject_final_match(
+        &client,
+        &address,
+        &male_token,
+        json!({"reason": "not_attracted", "comment": "Not this time"}),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // Matched again, confirmed and rated
+    admin_trigger_final_match(&client, &address).await;
+    accept_final_match(&client, &address, &male_token).await;
+    accept_final_match(&client, &address, &female_token).await;
+    let response = submit_feedback(
+        &client,
+        &address,
+        &male_token,
+        json!({"rating": 5, "met": true, "comment": "Lovely"}),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let (_, data) = export_account(&client, &address, &male_token).await;
+    let event = &data["events"][0];
+    assert_eq!(event["rejections"].as_array().unwrap().len(), 1);
+    assert_eq!(event["rejections"][0]["rejected_by_user"], true);
+    assert_eq!(event["rejections"][0]["reason"], "not_attracted");
+    assert_eq!(event["rejections"][0]["comment"], "Not this time");
+    assert_eq!(event["feedback"][0]["rating"], 5);
+    assert_eq!(event["feedback"][0]["comment"], "Lovely");
+    assert_eq!(data["notification_preferences"]["match_reminder"], false);
+    assert!(data["notification_preferences"]["updated_at"].is_string());
+    let statuses: Vec<&str> = data["user_events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|event| event["kind"] == "status_changed")
+        .map(|event| event["data"]["status"].as_str().unwrap())
+        .collect();
+    assert!(statuses.ends_with(&["matched", "form_completed", "matched", "confirmed"]));
+    let templates: Vec<&str> = data["emails"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|email| email["template"].as_str().unwrap())
+        .collect();
+    assert!(templates.contains(&"final_match_created"));
+    // The code that created the account was sent before it existed
+    assert!(!templates.contains(&"verification_code"));
+
+    // The partner is not told why the match was rejected
+    let (_, data) = export_account(&client, &address, &female_token).await;
+    let rejection = &data["events"][0]["rejections"][0];
+    assert_eq!(rejection["rejected_by_user"], false);
+    assert!(rejection["reason"].is_null());
+    assert!(rejection["comment"].is_null());
+    assert!(data["events"][0]["feedback"].as_array().unwrap().is_empty());
+    assert!(data["notification_preferences"]["updated_at"].is_null());
+}
+
+#[sqlx::test]
+async fn test_delete_account_reverts_partner(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let (male_token, female_token) =
+        setup_two_matched_users(&client, &address, &mock_emailer).await;
+
+    let male_id = sqlx::query_scalar!(
+        "SELECT id FROM users WHERE email = $1",
+        "male@mails.tsinghua.edu.cn"
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    // The 1x1 test image cannot be thumbnailed, so place a thumbnail manually
+    std::fs::write(
+        Path::new("./uploads_test/profile_photos").join(format!("{male_id}_thumb.png")),
+        create_test_image(),
+    )
+    .unwrap();
+    let emails = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM email_outbox WHERE recipient = $1",
+        "male@mails.tsinghua.edu.cn"
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(emails.unwrap() > 0);
+
+    let response = client
+        .delete(format!("{address}/api/account"))
+        .header("Authorization", format!("Bearer {male_token}"))
+        .send()
+        .await
+        .expect("Failed to delete account");
+
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["success"], true);
+    assert_eq!(body["files_removed"], 3); // card, profile photo, thumbnail
+
+    // User and all dependent rows are gone
+    let remaining = sqlx::query_scalar!("SELECT COUNT(*) FROM users WHERE id = $1", male_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, Some(0));
+
+    let final_matches = sqlx::query_scalar!("SELECT COUNT(*) FROM final_matches")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(final_matches, Some(0));
+
+    let emails = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM email_outbox WHERE recipient = $1",
+        "male@mails.tsinghua.edu.cn"
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(emails, Some(0));
+
+    // Partner can be matched again
+    let partner_status = sqlx::query_scalar!(
+        r#"SELECT status as "status: UserStatus" FROM users WHERE email = $1"#,
+        "female@mails.tsinghua.edu.cn"
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(partner_status, UserStatus::FormCompleted);
+
+    let response = client
+        .get(format!("{address}/api/profile"))
+        .header("Authorization", format!("Bearer {female_token}"))
+        .send()
+        .await
+        .unwrap();
+    let profile: serde_json::Value = response.json().await.unwrap();
+    assert!(profile["final_match"].is_null());
+
+    // Files are removed from disk
+    for dir in ["card_photos", "profile_photos"] {
+        let dir_path = Path::new("./uploads_test").join(dir);
+        let leftover = std::fs::read_dir(&dir_path)
+            .unwrap()
+            .filter_map(Result::ok)
+            .any(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(&male_id.to_string())
+            });
+        assert!(!leftover, "User files should be removed from {dir}");
+    }
+
+    // The still-valid access token no longer resolves to a user
+    let response = client
+        .get(format!("{address}/api/profile"))
+        .header("Authorization", format!("Bearer {male_token}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_delete_account_keeps_inactive_partner(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let (male_token, _female_token) =
+        setup_two_matched_users(&client, &address, &mock_emailer).await;
+
+    // A suspended partner has no match status left to revert
+    sqlx::query!(
+        "UPDATE users SET status = 'suspended' WHERE email = 'female@mails.tsinghua.edu.cn'"
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let response = client
+        .delete(format!("{address}/api/account"))
+        .header("Authorization", format!("Bearer {male_token}"))
+        .send()
+        .await
+        .expect("Failed to delete account");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let partner_status = sqlx::query_scalar!(
+        r#"SELECT status as "status: UserStatus" FROM users WHERE email = $1"#,
+        "female@mails.tsinghua.edu.cn"
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(partner_status, UserStatus::Suspended);
+}
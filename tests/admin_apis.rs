@@ -1,4 +1,5 @@
-use serde_json::Value;
+use hilo::models::Gender;
+use serde_json::{Value, json};
 use sqlx::PgPool;
 
 mod common;
@@ -113,8 +114,8 @@ async fn test_admin_user_stats(db_pool: PgPool) {
 
     // Create forms for these users
     sqlx::query!(
-        r#"INSERT INTO forms (user_id, gender, familiar_tags, aspirational_tags, recent_topics, self_traits, ideal_traits, physical_boundary, self_intro)
-           VALUES ($1, 'male', '{}', '{}', 'test topics', '{}', '{}', 2, 'test intro')"#,
+        r#"INSERT INTO forms (user_id, gender, answers)
+           VALUES ($1, 'male', '{"familiar_tags": [], "aspirational_tags": [], "recent_topics": "test topics", "self_traits": [], "ideal_traits": [], "physical_boundary": 2, "self_intro": "test intro"}')"#,
         male_user_id
     )
     .execute(&db_pool)
@@ -122,8 +123,8 @@ async fn test_admin_user_stats(db_pool: PgPool) {
     .unwrap();
 
     sqlx::query!(
-        r#"INSERT INTO forms (user_id, gender, familiar_tags, aspirational_tags, recent_topics, self_traits, ideal_traits, physical_boundary, self_intro)
-           VALUES ($1, 'female', '{}', '{}', 'test topics', '{}', '{}', 2, 'test intro')"#,
+        r#"INSERT INTO forms (user_id, gender, answers)
+           VALUES ($1, 'female', '{"familiar_tags": [], "aspirational_tags": [], "recent_topics": "test topics", "self_traits": [], "ideal_traits": [], "physical_boundary": 2, "self_intro": "test intro"}')"#,
         female_user_id
     )
     .execute(&db_pool)
@@ -151,6 +152,76 @@ async fn test_admin_user_stats(db_pool: PgPool) {
     assert_eq!(body["unmatched_females"], 0); // matched status
 }
 
+#[sqlx::test]
+async fn test_admin_preview_exposure(db_pool: PgPool) {
+    // Create two users in the matching pool
+    let male_email = "male@mails.tsinghua.edu.cn";
+    let female_email = "female@mails.tsinghua.edu.cn";
+
+    let male_user_id = sqlx::query_scalar!(
+        r#"INSERT INTO users (email, status) VALUES ($1, 'form_completed') RETURNING id"#,
+        male_email
+    )
+    .fetch_one(&db_pool)
+    .await
+    .unwrap();
+
+    let female_user_id = sqlx::query_scalar!(
+        r#"INSERT INTO users (email, status) VALUES ($1, 'form_completed') RETURNING id"#,
+        female_email
+    )
+    .fetch_one(&db_pool)
+    .await
+    .unwrap();
+
+    for (user_id, gender) in [
+        (male_user_id, Gender::Male),
+        (female_user_id, Gender::Female),
+    ] {
+        sqlx::query!(
+            r#"INSERT INTO forms (user_id, gender, answers)
+               VALUES ($1, $2, '{"familiar_tags": [], "aspirational_tags": [], "recent_topics": "test topics", "self_traits": [], "ideal_traits": [], "physical_boundary": 2, "self_intro": "test intro"}')"#,
+            user_id,
+            gender as Gender
+        )
+        .execute(&db_pool)
+        .await
+        .unwrap();
+    }
+
+    // Only the female user is shown in a preview
+    sqlx::query!(
+        "INSERT INTO match_previews (user_id, candidate_ids, scores) VALUES ($1, $2, $3)",
+        male_user_id,
+        &[female_user_id],
+        &[1.0]
+    )
+    .execute(&db_pool)
+    .await
+    .unwrap();
+
+    let app = common::spawn_admin_app(db_pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/api/admin/stats/exposure", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+
+    // Most exposed user first
+    let exposures = body["data"].as_array().unwrap();
+    assert_eq!(exposures.len(), 2);
+    assert_eq!(exposures[0]["email"], female_email);
+    assert_eq!(exposures[0]["exposure"], 1);
+    assert_eq!(exposures[1]["email"], male_email);
+    assert_eq!(exposures[1]["exposure"], 0);
+    assert_eq!(body["pagination"]["total"], 2);
+}
+
 #[sqlx::test]
 async fn test_admin_tags_with_stats(db_pool: PgPool) {
     // Create test user with form
@@ -166,11 +237,18 @@ async fn test_admin_tags_with_stats(db_pool: PgPool) {
 
     // Create form with some tags
     sqlx::query!(
-        r#"INSERT INTO forms (user_id, gender, familiar_tags, aspirational_tags, recent_topics, self_traits, ideal_traits, physical_boundary, self_intro)
-           VALUES ($1, 'male', $2, $3, 'test topics', '{}', '{}', 2, 'test intro')"#,
+        r#"INSERT INTO forms (user_id, gender, answers)
+           VALUES ($1, 'male', $2)"#,
         user_id,
-        &vec!["sports".to_string(), "basketball".to_string()],
-        &vec!["music".to_string()]
+        json!({
+            "familiar_tags": ["sports", "basketball"],
+            "aspirational_tags": ["music"],
+            "recent_topics": "test topics",
+            "self_traits": [],
+            "ideal_traits": [],
+            "physical_boundary": 2,
+            "self_intro": "test intro"
+        })
     )
     .execute(&db_pool)
     .await
@@ -231,7 +309,10 @@ async fn test_admin_final_matches(db_pool: PgPool) {
     };
 
     sqlx::query!(
-        r#"INSERT INTO final_matches (user_a_id, user_b_id, score) VALUES ($1, $2, 0.85)"#,
+        r#"
+        INSERT INTO final_matches (user_a_id, user_b_id, score, auto_accept_at, reminder_at)
+        VALUES ($1, $2, 0.85, NOW() + INTERVAL '24 hours', NOW() + INTERVAL '18 hours')
+        "#,
         smaller_id,
         larger_id
     )
@@ -268,3 +349,169 @@ async fn test_admin_final_matches(db_pool: PgPool) {
     assert!(match_record.get("user_b_email").is_some());
     assert_eq!(match_record["score"], 0.85);
 }
+
+#[sqlx::test]
+async fn test_admin_audit_log_records_verification(db_pool: PgPool) {
+    // Create a user waiting for verification
+    let test_email = "audit@mails.tsinghua.edu.cn";
+
+    let user_id = sqlx::query_scalar!(
+        r#"INSERT INTO users (email, status) VALUES ($1, 'verification_pending') RETURNING id"#,
+        test_email
+    )
+    .fetch_one(&db_pool)
+    .await
+    .unwrap();
+
+    let app = common::spawn_admin_app(db_pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    // Verify the user as an identified admin
+    let response = client
+        .post(format!("{}/api/admin/verify-user", app.address))
+        .header("cf-access-authenticated-user-email", "admin@example.com")
+        .json(&serde_json::json!({
+            "user_id": user_id,
+            "status": "verified"
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(response.status(), 200);
+
+    // Query the audit log for this user
+    let response = client
+        .get(format!(
+            "{}/api/admin/audit?target_id={}",
+            app.address, user_id
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+
+    let entries = body["data"].as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(body["pagination"]["total"], 1);
+
+    let entry = &entries[0];
+    assert_eq!(entry["actor"], "admin@example.com");
+    assert_eq!(entry["action"], "verify_user");
+    assert_eq!(entry["target_ids"][0], user_id.to_string());
+    assert_eq!(entry["status_before"], "verification_pending");
+    assert_eq!(entry["status_after"], "verified");
+    assert_eq!(entry["payload"]["status"], "verified");
+
+    // Filtering by another actor returns nothing
+    let response = client
+        .get(format!(
+            "{}/api/admin/audit?actor=someone-else",
+            app.address
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["data"].as_array().unwrap().is_empty());
+}
+
+#[sqlx::test]
+async fn test_admin_delete_final_match_audits_status_before(db_pool: PgPool) {
+    let matched_id = sqlx::query_scalar!(
+        r#"INSERT INTO users (email, status) VALUES ('user1@mails.tsinghua.edu.cn', 'matched') RETURNING id"#
+    )
+    .fetch_one(&db_pool)
+    .await
+    .unwrap();
+    let confirmed_id = sqlx::query_scalar!(
+        r#"INSERT INTO users (email, status) VALUES ('user2@mails.tsinghua.edu.cn', 'confirmed') RETURNING id"#
+    )
+    .fetch_one(&db_pool)
+    .await
+    .unwrap();
+    let match_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO final_matches (user_a_id, user_b_id, score, auto_accept_at, reminder_at)
+        VALUES ($1, $2, 0.85, NOW() + INTERVAL '24 hours', NOW() + INTERVAL '18 hours')
+        RETURNING id
+        "#,
+        matched_id.min(confirmed_id),
+        matched_id.max(confirmed_id)
+    )
+    .fetch_one(&db_pool)
+    .await
+    .unwrap();
+
+    let app = common::spawn_admin_app(db_pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .delete(format!(
+            "{}/api/admin/final-matches/{match_id}",
+            app.address
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status(), 200);
+
+    // One entry per user, each with the status the user had
+    for (user_id, status_before) in [(matched_id, "matched"), (confirmed_id, "confirmed")] {
+        let response = client
+            .get(format!(
+                "{}/api/admin/audit?target_id={user_id}",
+                app.address
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request");
+        let body: Value = response.json().await.unwrap();
+        let entries = body["data"].as_array().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["action"], "delete_final_match");
+        assert_eq!(entries[0]["actor"], common::ADMIN_EMAIL);
+        assert_eq!(entries[0]["target_ids"][0], match_id.to_string());
+        assert_eq!(entries[0]["status_before"], status_before);
+        assert_eq!(entries[0]["status_after"], "form_completed");
+    }
+}
+
+#[sqlx::test]
+async fn test_admin_actions_require_identity(db_pool: PgPool) {
+    let client = reqwest::Client::new();
+
+    let app = common::spawn_unauthenticated_admin_app(db_pool.clone()).await;
+    let response = client
+        .post(format!("{}/api/admin/update-previews", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status(), 401);
+
+    let app = common::spawn_admin_app(db_pool).await;
+    let response = client
+        .post(format!("{}/api/admin/update-previews", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status(), 200);
+
+    let response = client
+        .get(format!(
+            "{}/api/admin/audit?action=update_match_previews",
+            app.address
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["pagination"]["total"], 1);
+    assert_eq!(body["data"][0]["actor"], common::ADMIN_EMAIL);
+    assert_eq!(body["data"][0]["target_ids"], json!([]));
+    assert!(body["data"][0]["payload"]["event_id"].is_string());
+}
@@ -0,0 +1,341 @@
+mod common;
+
+use common::*;
+use reqwest::multipart;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+async fn send_code(client: &reqwest::Client, address: &str, email: &str) -> reqwest::StatusCode {
+    client
+        .post(format!("{address}/api/auth/send-code"))
+        .json(&json!({ "email": email }))
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+/// Sends a code until the response has the expected status, giving running servers
+/// time to reload the allowed domains after a change.
+async fn send_code_until(
+    client: &reqwest::Client,
+    address: &str,
+    email: &str,
+    expected: reqwest::StatusCode,
+) -> reqwest::StatusCode {
+    let mut status = send_code(client, address, email).await;
+    for _ in 0..40 {
+        if status == expected {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        status = send_code(client, address, email).await;
+    }
+    status
+}
+
+async fn create_domain(client: &reqwest::Client, address: &str, body: &Value) -> reqwest::Response {
+    client
+        .post(format!("{address}/api/admin/domains"))
+        .json(body)
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn upload_card_with_grade(
+    client: &reqwest::Client,
+    address: &str,
+    token: &str,
+    grade: &str,
+) -> reqwest::StatusCode {
+    let form = multipart::Form::new()
+        .part(
+            "card",
+            multipart::Part::bytes(create_test_image())
+                .file_name("card.png")
+                .mime_str("image/png")
+                .unwrap(),
+        )
+        .text("grade", grade.to_string());
+
+    client
+        .post(format!("{address}/api/upload/card"))
+        .header("Authorization", format!("Bearer {token}"))
+        .multipart(form)
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+#[sqlx::test]
+async fn test_admin_manages_allowed_domains(pool: PgPool) {
+    let (address, _mock_emailer) = spawn_app(pool).await;
+    let client = reqwest::Client::new();
+
+    let domains: Value = client
+        .get(format!("{address}/api/admin/domains"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let seeded: Vec<&str> = domains
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|domain| domain["domain"].as_str().unwrap())
+        .collect();
+    assert_eq!(seeded, ["mails.tsinghua.edu.cn", "stu.pku.edu.cn"]);
+    assert_eq!(
+        domains[0]["allowed_grades"],
+        json!(["undergraduate", "graduate"])
+    );
+
+    assert_eq!(
+        send_code(&client, &address, "a@cs.example.edu").await,
+        reqwest::StatusCode::BAD_REQUEST
+    );
+
+    let response = create_domain(
+        &client,
+        &address,
+        &json!({
+            "domain": " Example.EDU ",
+            "display_name": "Example University",
+            "include_subdomains": true,
+            "allowed_grades": ["phd"],
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::CREATED);
+    let domain: Value = response.json().await.unwrap();
+    assert_eq!(domain["domain"], "example.edu");
+    let domain_id = domain["id"].as_str().unwrap().to_string();
+
+    // Picked up without a restart, including subdomains
+    assert_eq!(
+        send_code_until(
+            &client,
+            &address,
+            "a@cs.example.edu",
+            reqwest::StatusCode::ACCEPTED
+        )
+        .await,
+        reqwest::StatusCode::ACCEPTED
+    );
+    assert_eq!(
+        send_code(&client, &address, "b@Example.edu").await,
+        reqwest::StatusCode::ACCEPTED
+    );
+    assert_eq!(
+        send_code(&client, &address, "c@notexample.edu").await,
+        reqwest::StatusCode::BAD_REQUEST
+    );
+
+    // Without subdomains only the exact domain is allowed
+    let response = client
+        .put(format!("{address}/api/admin/domains/{domain_id}"))
+        .json(&json!({
+            "domain": "example.edu",
+            "display_name": "Example University",
+            "allowed_grades": ["phd"],
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        send_code_until(
+            &client,
+            &address,
+            "d@cs.example.edu",
+            reqwest::StatusCode::BAD_REQUEST
+        )
+        .await,
+        reqwest::StatusCode::BAD_REQUEST
+    );
+
+    let response = client
+        .delete(format!("{address}/api/admin/domains/{domain_id}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        send_code_until(
+            &client,
+            &address,
+            "e@example.edu",
+            reqwest::StatusCode::BAD_REQUEST
+        )
+        .await,
+        reqwest::StatusCode::BAD_REQUEST
+    );
+
+    let audit: Value = client
+        .get(format!("{address}/api/admin/audit?target_id={domain_id}"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let actions: Vec<&str> = audit["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["action"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        actions,
+        [
+            "delete_allowed_domain",
+            "update_allowed_domain",
+            "create_allowed_domain"
+        ]
+    );
+}
+
+#[sqlx::test]
+async fn test_allowed_domain_validation(pool: PgPool) {
+    let app = spawn_admin_app(pool).await;
+    let client = reqwest::Client::new();
+
+    let valid = json!({
+        "domain": "example.edu",
+        "display_name": "Example University",
+        "allowed_grades": ["undergraduate"],
+    });
+
+    for invalid in [
+        json!({ "domain": "not a domain" }),
+        json!({ "domain": "user@example.edu" }),
+        json!({ "domain": "localhost" }),
+        json!({ "display_name": " " }),
+        json!({ "allowed_grades": [] }),
+    ] {
+        let mut body = valid.clone();
+        body.as_object_mut()
+            .unwrap()
+            .extend(invalid.as_object().unwrap().clone());
+        let response = create_domain(&client, &app.address, &body).await;
+        assert_eq!(
+            response.status(),
+            reqwest::StatusCode::BAD_REQUEST,
+            "{body}"
+        );
+    }
+
+    let response = create_domain(&client, &app.address, &valid).await;
+    assert_eq!(response.status(), reqwest::StatusCode::CREATED);
+    let response = create_domain(&client, &app.address, &valid).await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    // Renaming onto an existing domain is rejected
+    let existing: Value = client
+        .get(format!("{}/api/admin/domains", app.address))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let example_id = existing
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|domain| domain["domain"] == "example.edu")
+        .unwrap()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let mut renamed = valid.clone();
+    renamed["domain"] = json!("stu.pku.edu.cn");
+    let response = client
+        .put(format!("{}/api/admin/domains/{example_id}", app.address))
+        .json(&renamed)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let unknown = uuid::Uuid::new_v4();
+    let response = client
+        .put(format!("{}/api/admin/domains/{unknown}", app.address))
+        .json(&valid)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    let response = client
+        .delete(format!("{}/api/admin/domains/{unknown}", app.address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_domain_grades_and_removal(pool: PgPool) {
+    let email = "student@example.edu";
+    sqlx::query!(
+        "INSERT INTO allowed_domains (domain, display_name, allowed_grades)
+         VALUES ('example.edu', 'Example University', ARRAY['phd'])",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    // ALLOWED_DOMAINS is only seeded while no domain is allowed
+    let domains = sqlx::query_scalar!("SELECT domain FROM allowed_domains")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(domains, ["example.edu"]);
+
+    let token = get_access_token(&client, &address, &mock_emailer, email).await;
+
+    // Grades are allowed per domain
+    assert_eq!(
+        upload_card_with_grade(&client, &address, &token, "undergraduate").await,
+        reqwest::StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        upload_card_with_grade(&client, &address, &token, "phd").await,
+        reqwest::StatusCode::OK
+    );
+
+    sqlx::query!("INSERT INTO users (email) VALUES ('existing@example.edu')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query!("DELETE FROM allowed_domains WHERE domain = 'example.edu'")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // New accounts are rejected
+    assert_eq!(
+        send_code_until(
+            &client,
+            &address,
+            "newcomer@example.edu",
+            reqwest::StatusCode::BAD_REQUEST
+        )
+        .await,
+        reqwest::StatusCode::BAD_REQUEST
+    );
+
+    // Existing users can still log in, but no longer upload a card
+    let token = get_access_token(&client, &address, &mock_emailer, "existing@example.edu").await;
+    assert_eq!(
+        upload_card_with_grade(&client, &address, &token, "phd").await,
+        reqwest::StatusCode::FORBIDDEN
+    );
+}
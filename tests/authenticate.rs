@@ -10,7 +10,11 @@ use sqlx::PgPool;
 // Helper function to extract verification code from email body
 fn extract_verification_code(email_body: &str) -> &str {
     // Extract 6-digit code from "Your verification code is: 123456"
-    email_body.trim_start_matches("Your verification code is: ")
+    email_body
+        .trim_start_matches("Your verification code is: ")
+        .lines()
+        .next()
+        .unwrap_or_default()
 }
 
 /// Helper function to complete auth flow
@@ -35,8 +39,10 @@ async fn complete_auth_flow(
     assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
 
     // Extract code from email
-    let sent_email = mock_emailer.last_sent_email().expect("No email sent");
-    let code = extract_verification_code(&sent_email.body_html);
+    let sent_email = mock_emailer
+        .wait_for_email(|email| email.recipient == email_addr)
+        .await;
+    let code = extract_verification_code(&sent_email.text);
 
     // Verify code and get tokens
     let response = client
@@ -121,8 +127,10 @@ async fn test_verify_code_wrong_code(pool: PgPool) {
     assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
 
     // Extract code from email
-    let sent_email = mock_emailer.last_sent_email().expect("No email sent");
-    let code = extract_verification_code(&sent_email.body_html);
+    let sent_email = mock_emailer
+        .wait_for_email(|email| email.recipient == test_email)
+        .await;
+    let code = extract_verification_code(&sent_email.text);
 
     // Try with wrong code
     let response = client
@@ -218,6 +226,54 @@ async fn test_refresh_token_rotation(pool: PgPool) {
     assert!(body.contains("Invalid refresh token"));
 }
 
+#[sqlx::test]
+async fn test_emails_are_sent_in_the_negotiated_locale(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    let email_addr = "test@mails.tsinghua.edu.cn";
+    // French has no templates, so the next best language is picked
+    let accept_language = "fr-FR, zh-CN;q=0.9, en;q=0.8";
+
+    let response = client
+        .post(format!("{address}/api/auth/send-code"))
+        .header("Accept-Language", accept_language)
+        .json(&json!({"email": email_addr}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+
+    let sent_email = mock_emailer
+        .wait_for_email(|email| email.recipient == email_addr)
+        .await;
+    assert_eq!(sent_email.locale, "zh");
+    assert_eq!(sent_email.subject, "你的 Project Contigo 登录验证码");
+    let code = sent_email
+        .text
+        .trim_start_matches("你的验证码是：")
+        .lines()
+        .next()
+        .unwrap();
+    // The preheader previews the code
+    assert!(sent_email.html.contains(&format!("你的登录验证码：{code}")));
+
+    let response = client
+        .post(format!("{address}/api/auth/verify-code"))
+        .header("Accept-Language", accept_language)
+        .json(&json!({"email": email_addr, "code": code}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // Later emails are sent in the locale stored on the account
+    let locale = sqlx::query_scalar!("SELECT locale FROM users WHERE email = $1", email_addr)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(locale, "zh");
+}
+
 #[sqlx::test]
 async fn test_refresh_token_invalid(pool: PgPool) {
     let (address, _) = spawn_app(pool).await;
@@ -260,16 +316,18 @@ async fn test_multiple_users_different_codes(pool: PgPool) {
         assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
     }
 
-    assert_eq!(mock_emailer.sent_count(), users.len());
+    assert_eq!(mock_emailer.wait_for_count(users.len()).await, users.len());
 
     // Verify each user can authenticate with their respective codes
     let sent_emails = mock_emailer.get_sent_emails();
 
-    for (i, email) in users.iter().enumerate() {
-        let sent_email = &sent_emails[i];
-        assert_eq!(&sent_email.recipient, email);
+    for email in &users {
+        let sent_email = sent_emails
+            .iter()
+            .find(|sent| &sent.recipient == email)
+            .expect("No email sent to user");
 
-        let code = extract_verification_code(&sent_email.body_html);
+        let code = extract_verification_code(&sent_email.text);
 
         let response = client
             .post(format!("{}/api/auth/verify-code", &address))
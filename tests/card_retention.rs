@@ -0,0 +1,222 @@
+mod common;
+
+use std::path::Path;
+
+use hilo::{models::UserStatus, services::retention::RetentionService};
+use serde_json::Value;
+use sqlx::PgPool;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use uuid::Uuid;
+
+/// Inserts a user with a card photo on disk whose review happened `reviewed_days_ago` days ago.
+async fn insert_user_with_card(
+    pool: &PgPool,
+    email: &str,
+    status: UserStatus,
+    reviewed_days_ago: Option<i32>,
+) -> (Uuid, std::path::PathBuf) {
+    let user_id = sqlx::query_scalar!(
+        r#"INSERT INTO users (email, status, card_reviewed_at)
+           VALUES ($1, $2, NOW() - make_interval(days => $3))
+           RETURNING id"#,
+        email,
+        status as UserStatus,
+        reviewed_days_ago
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap();
+
+    let filename = format!("{user_id}.png");
+    sqlx::query!(
+        "UPDATE users SET card_photo_filename = $1 WHERE id = $2",
+        filename,
+        user_id
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+
+    let dir = Path::new("./uploads_test/card_photos");
+    std::fs::create_dir_all(dir).unwrap();
+    let file_path = dir.join(&filename);
+    std::fs::write(&file_path, common::create_test_image()).unwrap();
+
+    (user_id, file_path)
+}
+
+#[sqlx::test]
+async fn test_purge_expired_card_photos(pool: PgPool) {
+    dotenvy::from_filename_override("tests/data/.test.env").unwrap();
+
+    let (expired_id, expired_path) = insert_user_with_card(
+        &pool,
+        "expired@mails.tsinghua.edu.cn",
+        UserStatus::Verified,
+        Some(31),
+    )
+    .await;
+    let (rejected_id, rejected_path) = insert_user_with_card(
+        &pool,
+        "rejected@mails.tsinghua.edu.cn",
+        UserStatus::Unverified,
+        Some(40),
+    )
+    .await;
+    let (recent_id, recent_path) = insert_user_with_card(
+        &pool,
+        "recent@mails.tsinghua.edu.cn",
+        UserStatus::FormCompleted,
+        Some(1),
+    )
+    .await;
+    let (pending_id, pending_path) = insert_user_with_card(
+        &pool,
+        "pending@mails.tsinghua.edu.cn",
+        UserStatus::VerificationPending,
+        None,
+    )
+    .await;
+
+    let purged = RetentionService::purge_expired_card_photos(&pool, 30)
+        .await
+        .unwrap();
+    assert_eq!(purged, 2);
+
+    // Expired photos are gone from disk and database, status is unchanged
+    for (user_id, path, status) in [
+        (expired_id, &expired_path, UserStatus::Verified),
+        (rejected_id, &rejected_path, UserStatus::Unverified),
+    ] {
+        assert!(!path.exists(), "Expired card photo should be deleted");
+        let user = sqlx::query!(
+            r#"SELECT status as "status: UserStatus", card_photo_filename, card_purged_at
+               FROM users WHERE id = $1"#,
+            user_id
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(user.status, status);
+        assert!(user.card_photo_filename.is_none());
+        assert!(user.card_purged_at.is_some());
+        assert_eq!(
+            user.status.is_card_verified(),
+            status == UserStatus::Verified
+        );
+    }
+
+    // Recently reviewed and pending photos are kept
+    for (user_id, path) in [(recent_id, &recent_path), (pending_id, &pending_path)] {
+        assert!(path.exists(), "Card photo should be kept");
+        let filename = sqlx::query_scalar!(
+            "SELECT card_photo_filename FROM users WHERE id = $1",
+            user_id
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert!(filename.is_some());
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+#[sqlx::test]
+async fn test_admin_sees_card_purge_schedule(pool: PgPool) {
+    let (user_id, file_path) = insert_user_with_card(
+        &pool,
+        "schedule@mails.tsinghua.edu.cn",
+        UserStatus::VerificationPending,
+        None,
+    )
+    .await;
+
+    let app = common::spawn_admin_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    // Not reviewed yet, so nothing is scheduled
+    let body: Value = client
+        .get(format!("{}/api/admin/user/{}", app.address, user_id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(body["card_purge_at"].is_null());
+    assert!(body["card_verified_at"].is_null());
+
+    assert!(
+        common::admin_verify_user(
+            &client,
+            &app.address,
+            "schedule@mails.tsinghua.edu.cn",
+            "verified"
+        )
+        .await
+    );
+
+    let body: Value = client
+        .get(format!("{}/api/admin/user/{}", app.address, user_id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(body["card_verified_at"].is_string());
+    assert!(body["card_purge_at"].is_string());
+    assert!(body["card_purged_at"].is_null());
+
+    let verified_at =
+        OffsetDateTime::parse(body["card_verified_at"].as_str().unwrap(), &Rfc3339).unwrap();
+    let purge_at =
+        OffsetDateTime::parse(body["card_purge_at"].as_str().unwrap(), &Rfc3339).unwrap();
+    assert_eq!((purge_at - verified_at).whole_days(), 30);
+
+    std::fs::remove_file(file_path).unwrap();
+}
+
+#[sqlx::test]
+async fn test_purge_retries_undeletable_card_photos(pool: PgPool) {
+    dotenvy::from_filename_override("tests/data/.test.env").unwrap();
+
+    let (user_id, file_path) = insert_user_with_card(
+        &pool,
+        "undeletable@mails.tsinghua.edu.cn",
+        UserStatus::Verified,
+        Some(31),
+    )
+    .await;
+    // A directory in place of the file cannot be removed as a file
+    std::fs::remove_file(&file_path).unwrap();
+    std::fs::create_dir(&file_path).unwrap();
+
+    let purged = RetentionService::purge_expired_card_photos(&pool, 30)
+        .await
+        .unwrap();
+    assert_eq!(purged, 0);
+    let user = sqlx::query!(
+        "SELECT card_photo_filename, card_purged_at FROM users WHERE id = $1",
+        user_id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(user.card_photo_filename.is_some());
+    assert!(user.card_purged_at.is_none());
+
+    // Retried on the next run once the file can be deleted
+    std::fs::remove_dir(&file_path).unwrap();
+    std::fs::write(&file_path, common::create_test_image()).unwrap();
+    let purged = RetentionService::purge_expired_card_photos(&pool, 30)
+        .await
+        .unwrap();
+    assert_eq!(purged, 1);
+    assert!(!file_path.exists());
+    let purged_at = sqlx::query_scalar!("SELECT card_purged_at FROM users WHERE id = $1", user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(purged_at.is_some());
+}
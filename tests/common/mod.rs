@@ -1,16 +1,29 @@
 #![allow(dead_code)]
 
-use std::sync::{Arc, Mutex, Once};
+use std::{
+    io::{Cursor, Read},
+    sync::{
+        Arc, LazyLock, Mutex, Once,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
 
 use async_trait::async_trait;
+use axum::{Router, extract::Request, http::HeaderValue, middleware};
 use hilo::{
     handlers::AuthResponse,
-    services::email::{EmailError, EmailService},
+    models::{Answer, EmailMessage, Form, FormSchema, Gender, TemplateId},
+    services::{
+        domain::DomainService,
+        email::{EmailError, EmailService},
+    },
 };
 use reqwest::multipart;
 use serde_json::{Value, json};
-use sqlx::PgPool;
+use sqlx::{PgPool, types::Json};
 use tokio::net::TcpListener;
+use uuid::Uuid;
 
 pub fn init_tracing_once() {
     static INIT: Once = Once::new();
@@ -24,23 +37,37 @@ pub fn init_tracing_once() {
 
 /// A mock email service that stores sent emails for testing purposes.
 /// This is ideal for integration tests as it doesn't produce console output.
+/// Emails are delivered by a background worker, so tests wait for them to arrive.
 #[derive(Debug, Default)]
 pub struct MockEmailer {
     sent_emails: Mutex<Vec<SentEmail>>,
+    failing: AtomicBool,
+    rejecting: AtomicBool,
 }
 
 #[derive(Debug, Clone)]
 pub struct SentEmail {
     pub recipient: String,
+    pub template: TemplateId,
+    pub locale: String,
     pub subject: String,
-    pub body_html: String,
+    pub text: String,
+    pub html: String,
 }
 
 impl MockEmailer {
     pub fn new() -> Self {
-        Self {
-            sent_emails: Mutex::new(Vec::new()),
-        }
+        Self::default()
+    }
+
+    /// Simulate a provider outage: while failing, every send returns an error
+    pub fn set_failing(&self, failing: bool) {
+        self.failing.store(failing, Ordering::SeqCst);
+    }
+
+    /// Simulate a permanent rejection: while rejecting, every send is refused for good
+    pub fn set_rejecting(&self, rejecting: bool) {
+        self.rejecting.store(rejecting, Ordering::SeqCst);
     }
 
     /// Get all sent emails for testing verification
@@ -62,24 +89,62 @@ impl MockEmailer {
     pub fn last_sent_email(&self) -> Option<SentEmail> {
         self.sent_emails.lock().unwrap().last().cloned()
     }
+
+    /// Wait for the last sent email matching the predicate
+    pub async fn wait_for_email(&self, predicate: impl Fn(&SentEmail) -> bool) -> SentEmail {
+        for _ in 0..200 {
+            let found = self
+                .sent_emails
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .find(|email| predicate(email))
+                .cloned();
+            if let Some(email) = found {
+                return email;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        panic!("Expected email was not sent");
+    }
+
+    /// Wait until at least `count` emails were sent, returning the actual count
+    pub async fn wait_for_count(&self, count: usize) -> usize {
+        for _ in 0..200 {
+            if self.sent_count() >= count {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        self.sent_count()
+    }
+
+    fn record(&self, email: SentEmail) -> Result<String, EmailError> {
+        if self.failing.load(Ordering::SeqCst) {
+            return Err(EmailError::SendFailed("Mock provider outage".to_string()));
+        }
+        if self.rejecting.load(Ordering::SeqCst) {
+            return Err(EmailError::Rejected("Mock recipient unknown".to_string()));
+        }
+        self.sent_emails.lock().unwrap().push(email);
+        Ok("mock".to_string())
+    }
 }
 
 #[async_trait]
 impl EmailService for MockEmailer {
-    async fn send_email(
-        &self,
-        recipient: &str,
-        subject: &str,
-        code: &str,
-    ) -> Result<(), EmailError> {
+    async fn send(&self, message: &EmailMessage) -> Result<String, EmailError> {
         let email = SentEmail {
-            recipient: recipient.to_string(),
-            subject: subject.to_string(),
-            body_html: format!("Your verification code is: {code}"),
+            recipient: message.recipient.clone(),
+            template: message.template,
+            locale: message.locale.clone(),
+            subject: message.subject.clone(),
+            text: message.text.clone(),
+            html: message.html.clone(),
         };
 
-        self.sent_emails.lock().unwrap().push(email);
-        Ok(())
+        self.record(email)
     }
 }
 
@@ -90,6 +155,7 @@ impl EmailService for MockEmailer {
 pub async fn spawn_app(test_db_pool: PgPool) -> (String, Arc<MockEmailer>) {
     dotenvy::from_filename_override("tests/data/.test.env").unwrap();
     init_tracing_once();
+    DomainService::seed_from_env(&test_db_pool).await.unwrap();
 
     let mock_emailer = Arc::new(MockEmailer::new());
     let mock_cloned = Arc::clone(&mock_emailer);
@@ -102,9 +168,9 @@ pub async fn spawn_app(test_db_pool: PgPool) -> (String, Arc<MockEmailer>) {
 
     tokio::spawn(async move {
         // Create the main app with admin routes merged in
-        let main_app = hilo::app_with_email_service(test_db_pool.clone(), mock_cloned);
-        let admin_router = hilo::handlers::admin_router(test_db_pool);
-        let combined_app = main_app.merge(admin_router);
+        let main_app = hilo::app_with_email_service(test_db_pool.clone(), mock_cloned.clone());
+        let admin_router = hilo::handlers::admin_router(test_db_pool, mock_cloned);
+        let combined_app = main_app.merge(with_admin_identity(admin_router));
 
         axum::serve(listener, combined_app).await.unwrap();
     });
@@ -128,6 +194,24 @@ pub async fn spawn_app(test_db_pool: PgPool) -> (String, Arc<MockEmailer>) {
     (address, mock_emailer)
 }
 
+/// Identity of the admin performing requests that do not name one
+pub const ADMIN_EMAIL: &str = "admin@example.com";
+
+const ADMIN_IDENTITY_HEADER: &str = "cf-access-authenticated-user-email";
+
+/// Stands in for Cloudflare Access, attributing requests without an identity to
+/// [`ADMIN_EMAIL`]
+fn with_admin_identity(router: Router) -> Router {
+    router.layer(middleware::map_request(|mut request: Request| async move {
+        if !request.headers().contains_key(ADMIN_IDENTITY_HEADER) {
+            request
+                .headers_mut()
+                .insert(ADMIN_IDENTITY_HEADER, HeaderValue::from_static(ADMIN_EMAIL));
+        }
+        request
+    }))
+}
+
 /// Test structure for admin API responses
 pub struct TestApp {
     pub address: String,
@@ -135,8 +219,18 @@ pub struct TestApp {
 
 /// Spawns only the admin app for testing admin-specific functionality
 pub async fn spawn_admin_app(test_db_pool: PgPool) -> TestApp {
+    spawn_admin_router(test_db_pool, true).await
+}
+
+/// Spawns only the admin app without an identity provider in front of it
+pub async fn spawn_unauthenticated_admin_app(test_db_pool: PgPool) -> TestApp {
+    spawn_admin_router(test_db_pool, false).await
+}
+
+async fn spawn_admin_router(test_db_pool: PgPool, identified: bool) -> TestApp {
     dotenvy::from_filename_override("tests/data/.test.env").unwrap();
     init_tracing_once();
+    DomainService::seed_from_env(&test_db_pool).await.unwrap();
 
     // Randomly choose an available port
     let listener = TcpListener::bind("127.0.0.1:0")
@@ -145,7 +239,12 @@ pub async fn spawn_admin_app(test_db_pool: PgPool) -> TestApp {
     let port = listener.local_addr().unwrap().port();
 
     tokio::spawn(async move {
-        let admin_router = hilo::handlers::admin_router(test_db_pool);
+        let admin_router = hilo::handlers::admin_router(test_db_pool, Arc::new(MockEmailer::new()));
+        let admin_router = if identified {
+            with_admin_identity(admin_router)
+        } else {
+            admin_router
+        };
         axum::serve(listener, admin_router).await.unwrap();
     });
 
@@ -171,7 +270,11 @@ pub async fn spawn_admin_app(test_db_pool: PgPool) -> TestApp {
 // Helper function to extract verification code from email body
 pub fn extract_verification_code(email_body: &str) -> &str {
     // Extract 6-digit code from "Your verification code is: 123456"
-    email_body.trim_start_matches("Your verification code is: ")
+    email_body
+        .trim_start_matches("Your verification code is: ")
+        .lines()
+        .next()
+        .unwrap_or_default()
 }
 
 /// Helper function to complete auth flow and return access token
@@ -193,8 +296,10 @@ pub async fn get_access_token(
     assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
 
     // Extract code from email
-    let sent_email = mock_emailer.last_sent_email().expect("No email sent");
-    let code = extract_verification_code(&sent_email.body_html);
+    let sent_email = mock_emailer
+        .wait_for_email(|email| email.recipient == email_addr)
+        .await;
+    let code = extract_verification_code(&sent_email.text);
 
     // Verify code and get tokens
     let response = client
@@ -231,6 +336,33 @@ pub fn create_test_image() -> Vec<u8> {
     ]
 }
 
+/// The form schema of `form_schema.json`, for tests that score forms without the app
+pub static FORM_SCHEMA: LazyLock<FormSchema> = LazyLock::new(|| {
+    let schema_json =
+        std::fs::read_to_string("form_schema.json").expect("Failed to read form_schema.json");
+
+    FormSchema::from_json(&schema_json).expect("Failed to load form schema")
+});
+
+/// Builds a form with the given answers, keyed by question id
+pub fn create_form<const N: usize>(
+    user_id: Uuid,
+    gender: Gender,
+    answers: [(&str, Answer); N],
+) -> Form {
+    Form {
+        user_id,
+        gender,
+        answers: Json(
+            answers
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+        ),
+        profile_photo_filename: None,
+    }
+}
+
 pub fn create_male_form_submission() -> serde_json::Value {
     json!({
         "wechat_id": "test_wechat_123",
@@ -309,6 +441,31 @@ pub async fn create_form_with_profile_photo(
     })
 }
 
+/// Registers a user and brings them to `form_completed`, returning their access token.
+pub async fn complete_form(
+    client: &reqwest::Client,
+    address: &str,
+    mock_emailer: &MockEmailer,
+    email: &str,
+    mut form: Value,
+) -> String {
+    let token = get_access_token(client, address, mock_emailer, email).await;
+    upload_card(client, address, &token).await;
+    assert!(admin_verify_user(client, address, email, "verified").await);
+
+    form["profile_photo_filename"] = json!(upload_profile_photo(client, address, &token).await);
+    let response = client
+        .post(format!("{address}/api/form"))
+        .header("Authorization", format!("Bearer {token}"))
+        .json(&form)
+        .send()
+        .await
+        .expect("Failed to submit form");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    token
+}
+
 /// Admin helper to verify a user via email
 pub async fn admin_verify_user(
     client: &reqwest::Client,
@@ -441,8 +598,8 @@ pub async fn setup_two_matched_users(
     address: &str,
     mock_emailer: &MockEmailer,
 ) -> (String, String) {
-    let male_email = "male@mails.tsinghua.edu.cn";
-    let female_email = "female@mails.tsinghua.edu.cn";
+    let male_email = MALE_EMAIL;
+    let female_email = FEMALE_EMAIL;
 
     // 1. Get access tokens for both users
     let male_token = get_access_token(client, address, mock_emailer, male_email).await;
@@ -490,3 +647,174 @@ pub async fn setup_two_matched_users(
 
     (male_token, female_token)
 }
+
+/// Accepts the final match of the user
+pub async fn accept_final_match(client: &reqwest::Client, address: &str, token: &str) {
+    let response = client
+        .post(format!("{address}/api/final-match/accept"))
+        .header("Authorization", format!("Bearer {token}"))
+        .send()
+        .await
+        .expect("Failed to accept final match");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+}
+
+/// Rejects the final match of the user, `body` optionally giving a reason and comment
+pub async fn reject_final_match(
+    client: &reqwest::Client,
+    address: &str,
+    token: &str,
+    body: Value,
+) -> reqwest::Response {
+    client
+        .post(format!("{address}/api/final-match/reject"))
+        .header("Authorization", format!("Bearer {token}"))
+        .json(&body)
+        .send()
+        .await
+        .expect("Failed to reject final match")
+}
+
+/// Submits feedback on the confirmed final match of the user
+pub async fn submit_feedback(
+    client: &reqwest::Client,
+    address: &str,
+    token: &str,
+    feedback: Value,
+) -> reqwest::Response {
+    client
+        .post(format!("{address}/api/final-match/feedback"))
+        .header("Authorization", format!("Bearer {token}"))
+        .json(&feedback)
+        .send()
+        .await
+        .expect("Failed to submit feedback")
+}
+
+/// Changes the notification preferences of the user, omitted fields are kept
+pub async fn update_preferences(
+    client: &reqwest::Client,
+    address: &str,
+    token: &str,
+    body: &Value,
+) -> reqwest::Response {
+    client
+        .patch(format!("{address}/api/notifications/preferences"))
+        .header("Authorization", format!("Bearer {token}"))
+        .json(body)
+        .send()
+        .await
+        .expect("Failed to update notification preferences")
+}
+
+/// Sorted recipients of the sent emails rendered from `template`
+pub fn recipients(mock_emailer: &MockEmailer, template: TemplateId) -> Vec<String> {
+    let mut recipients: Vec<String> = mock_emailer
+        .get_sent_emails()
+        .into_iter()
+        .filter(|email| email.template == template)
+        .map(|email| email.recipient)
+        .collect();
+    recipients.sort();
+    recipients
+}
+
+/// Waits until an email rendered from `template` was sent to each of the users
+pub async fn wait_for_recipients(mock_emailer: &MockEmailer, template: TemplateId, users: &[&str]) {
+    for user in users {
+        mock_emailer
+            .wait_for_email(|email| email.template == template && email.recipient == *user)
+            .await;
+    }
+}
+
+/// Asserts that no email rendered from `template` was sent or is waiting in the outbox
+///
+/// Emails are enqueued before the response, so nothing can show up later.
+pub async fn assert_not_notified(pool: &PgPool, mock_emailer: &MockEmailer, template: TemplateId) {
+    let enqueued = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM email_outbox WHERE template = $1",
+        template as TemplateId
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    assert_eq!(enqueued, Some(0));
+    assert!(recipients(mock_emailer, template).is_empty());
+}
+
+/// Email of the male test user
+pub const MALE_EMAIL: &str = "male@mails.tsinghua.edu.cn";
+
+/// Email of the female test user
+pub const FEMALE_EMAIL: &str = "female@mails.tsinghua.edu.cn";
+
+/// Looks up the ID of the user with `email`
+pub async fn user_id(pool: &PgPool, email: &str) -> Uuid {
+    sqlx::query_scalar!("SELECT id FROM users WHERE email = $1", email)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+/// Sends an unauthenticated GET request and returns the JSON body of the `200 OK` response
+pub async fn get_json(client: &reqwest::Client, url: String) -> Value {
+    let response = client.get(url).send().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    response.json().await.unwrap()
+}
+
+/// Creates an event through the admin API
+pub async fn create_event(
+    client: &reqwest::Client,
+    address: &str,
+    body: &Value,
+) -> reqwest::Response {
+    client
+        .post(format!("{address}/api/admin/events"))
+        .json(body)
+        .send()
+        .await
+        .unwrap()
+}
+
+/// Makes an event current through the admin API
+pub async fn activate_event(
+    client: &reqwest::Client,
+    address: &str,
+    event_id: &str,
+) -> reqwest::Response {
+    client
+        .post(format!("{address}/api/admin/events/{event_id}/activate"))
+        .send()
+        .await
+        .unwrap()
+}
+
+/// Downloads the account export and returns the archive with its parsed `data.json`
+pub async fn export_account(
+    client: &reqwest::Client,
+    address: &str,
+    token: &str,
+) -> (zip::ZipArchive<Cursor<Vec<u8>>>, Value) {
+    let response = client
+        .get(format!("{address}/api/account/export"))
+        .header("Authorization", format!("Bearer {token}"))
+        .send()
+        .await
+        .expect("Failed to export account");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "application/zip");
+
+    let bytes = response.bytes().await.unwrap().to_vec();
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).expect("Export should be a ZIP");
+    let mut data_json = String::new();
+    archive
+        .by_name("data.json")
+        .expect("Export should contain data.json")
+        .read_to_string(&mut data_json)
+        .unwrap();
+    let data = serde_json::from_str(&data_json).unwrap();
+
+    (archive, data)
+}
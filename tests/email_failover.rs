@@ -0,0 +1,199 @@
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::*;
+use hilo::{
+    models::{CircuitState, EmailMessage, NewEmail, TemplateId},
+    services::{
+        email::{EmailService, FailoverEmailer},
+        outbox::EmailOutboxService,
+    },
+    utils::static_object::EMAIL_TEMPLATES,
+};
+use serde_json::Value;
+use sqlx::PgPool;
+use tokio::net::TcpListener;
+
+const TEST_EMAIL: &str = "test@mails.tsinghua.edu.cn";
+
+fn test_message() -> EmailMessage {
+    EMAIL_TEMPLATES
+        .render(
+            TEST_EMAIL,
+            TemplateId::PartnerAccepted,
+            "en",
+            Default::default(),
+        )
+        .unwrap()
+}
+
+/// A failover emailer over a primary and a secondary mock provider
+fn failover(
+    failure_threshold: u32,
+    open_duration: Duration,
+) -> (FailoverEmailer, Arc<MockEmailer>, Arc<MockEmailer>) {
+    let primary = Arc::new(MockEmailer::new());
+    let secondary = Arc::new(MockEmailer::new());
+    let emailer = FailoverEmailer::new(vec![
+        (
+            "primary".to_string(),
+            primary.clone() as Arc<dyn EmailService>,
+        ),
+        (
+            "secondary".to_string(),
+            secondary.clone() as Arc<dyn EmailService>,
+        ),
+    ])
+    .circuit_breaker(failure_threshold, open_duration);
+    (emailer, primary, secondary)
+}
+
+#[tokio::test]
+async fn test_failover_to_next_provider() {
+    let (emailer, primary, secondary) = failover(3, Duration::from_secs(60));
+
+    // The provider that accepted the email is reported
+    let provider = emailer.send(&test_message()).await.unwrap();
+    assert_eq!(provider, "primary");
+    assert_eq!(primary.sent_count(), 1);
+    assert_eq!(secondary.sent_count(), 0);
+
+    primary.set_failing(true);
+    let provider = emailer.send(&test_message()).await.unwrap();
+    assert_eq!(provider, "secondary");
+    assert_eq!(primary.sent_count(), 1);
+    assert_eq!(secondary.sent_count(), 1);
+
+    let health = emailer.health();
+    assert_eq!(health.len(), 2);
+    assert_eq!(health[0].name, "primary");
+    assert_eq!(health[0].state, CircuitState::Closed);
+    assert_eq!(health[0].delivered, 1);
+    assert_eq!(health[0].failed, 1);
+    assert_eq!(health[0].consecutive_failures, 1);
+    assert!(
+        health[0]
+            .last_error
+            .as_deref()
+            .unwrap()
+            .contains("Mock provider outage")
+    );
+    assert_eq!(health[1].name, "secondary");
+    assert_eq!(health[1].delivered, 1);
+    assert_eq!(health[1].failed, 0);
+}
+
+#[tokio::test]
+async fn test_circuit_opens_after_consecutive_failures() {
+    let (emailer, primary, secondary) = failover(2, Duration::from_secs(60));
+    primary.set_failing(true);
+
+    for _ in 0..2 {
+        emailer.send(&test_message()).await.unwrap();
+    }
+    let health = emailer.health();
+    assert_eq!(health[0].state, CircuitState::Open);
+    assert!(health[0].open_until.is_some());
+
+    // The open circuit skips the primary even after it recovered
+    primary.set_failing(false);
+    emailer.send(&test_message()).await.unwrap();
+    assert_eq!(primary.sent_count(), 0);
+    assert_eq!(secondary.sent_count(), 3);
+
+    let health = emailer.health();
+    assert_eq!(health[0].failed, 2);
+    assert_eq!(health[0].skipped, 1);
+}
+
+#[tokio::test]
+async fn test_half_open_probe_recovers_provider() {
+    let (emailer, primary, secondary) = failover(1, Duration::from_millis(100));
+    primary.set_failing(true);
+
+    emailer.send(&test_message()).await.unwrap();
+    assert_eq!(emailer.health()[0].state, CircuitState::Open);
+
+    // A failed probe opens the circuit again
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    emailer.send(&test_message()).await.unwrap();
+    let health = emailer.health();
+    assert_eq!(health[0].state, CircuitState::Open);
+    assert_eq!(health[0].failed, 2);
+    assert_eq!(secondary.sent_count(), 2);
+
+    // A successful probe closes it
+    primary.set_failing(false);
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    emailer.send(&test_message()).await.unwrap();
+    let health = emailer.health();
+    assert_eq!(health[0].state, CircuitState::Closed);
+    assert_eq!(health[0].consecutive_failures, 0);
+    assert!(health[0].open_until.is_none());
+    assert_eq!(primary.sent_count(), 1);
+    assert_eq!(secondary.sent_count(), 2);
+}
+
+#[tokio::test]
+async fn test_all_providers_failing() {
+    let (emailer, primary, secondary) = failover(3, Duration::from_secs(60));
+    primary.set_failing(true);
+    secondary.set_failing(true);
+
+    let error = emailer.send(&test_message()).await.unwrap_err().to_string();
+    assert!(error.contains("All email providers failed"), "{error}");
+    assert!(error.contains("primary: "), "{error}");
+    assert!(error.contains("secondary: "), "{error}");
+}
+
+#[sqlx::test]
+async fn test_outbox_records_delivering_provider(pool: PgPool) {
+    let (emailer, primary, _) = failover(3, Duration::from_secs(60));
+    primary.set_failing(true);
+    let mut conn = pool.acquire().await.unwrap();
+    EmailOutboxService::enqueue(
+        &mut conn,
+        &NewEmail::new(TEST_EMAIL, TemplateId::PartnerAccepted, []),
+    )
+    .await
+    .unwrap();
+
+    EmailOutboxService::deliver_due(&pool, &emailer)
+        .await
+        .unwrap();
+
+    let provider = sqlx::query_scalar!("SELECT provider FROM email_outbox")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(provider.as_deref(), Some("secondary"));
+}
+
+#[sqlx::test]
+async fn test_admin_lists_provider_health(pool: PgPool) {
+    let (emailer, primary, _) = failover(1, Duration::from_secs(60));
+    primary.set_failing(true);
+    emailer.send(&test_message()).await.unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let router = hilo::handlers::admin_router(pool, Arc::new(emailer));
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+    let response = reqwest::get(format!(
+        "http://127.0.0.1:{port}/api/admin/emails/providers"
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body[0]["name"], "primary");
+    assert_eq!(body[0]["state"], "open");
+    assert_eq!(body[0]["failed"], 1);
+    assert!(body[0]["open_until"].is_string());
+    assert_eq!(body[1]["name"], "secondary");
+    assert_eq!(body[1]["state"], "closed");
+    assert_eq!(body[1]["delivered"], 1);
+    assert!(body[1]["last_delivered_at"].is_string());
+}
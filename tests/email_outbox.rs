@@ -0,0 +1,346 @@
+mod common;
+
+use common::*;
+use hilo::{
+    models::{EmailStatus, NewEmail, TemplateId},
+    services::outbox::EmailOutboxService,
+    utils::constant::{EMAIL_MAX_ATTEMPTS, EMAIL_RETRY_BASE_DELAY},
+};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use time::{Duration, OffsetDateTime};
+
+const TEST_EMAIL: &str = "test@mails.tsinghua.edu.cn";
+
+fn test_email() -> NewEmail {
+    NewEmail::new(
+        TEST_EMAIL,
+        TemplateId::Notification,
+        [
+            ("subject", "Test subject".to_string()),
+            ("message", "Test message".to_string()),
+        ],
+    )
+}
+
+async fn enqueue(pool: &PgPool, email: &NewEmail) {
+    let mut conn = pool.acquire().await.unwrap();
+    EmailOutboxService::enqueue(&mut conn, email).await.unwrap();
+}
+
+#[sqlx::test]
+async fn test_failed_delivery_is_retried_with_backoff(pool: PgPool) {
+    let mock_emailer = MockEmailer::new();
+    enqueue(&pool, &test_email()).await;
+
+    mock_emailer.set_failing(true);
+    let attempted = EmailOutboxService::deliver_due(&pool, &mock_emailer)
+        .await
+        .unwrap();
+    assert_eq!(attempted, 1);
+
+    let email = sqlx::query!("SELECT attempts, next_attempt_at, last_error FROM email_outbox")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(email.attempts, 1);
+    assert!(email.last_error.unwrap().contains("Mock provider outage"));
+    let base_delay = Duration::try_from(EMAIL_RETRY_BASE_DELAY).unwrap();
+    let delay = email.next_attempt_at - OffsetDateTime::now_utc();
+    assert!(delay > base_delay - Duration::seconds(5));
+    assert!(delay <= base_delay);
+
+    // Not due yet
+    let attempted = EmailOutboxService::deliver_due(&pool, &mock_emailer)
+        .await
+        .unwrap();
+    assert_eq!(attempted, 0);
+
+    // Backoff doubles with each failed attempt
+    assert_eq!(
+        EmailOutboxService::retry_delay(2),
+        EMAIL_RETRY_BASE_DELAY * 2
+    );
+    assert_eq!(
+        EmailOutboxService::retry_delay(3),
+        EMAIL_RETRY_BASE_DELAY * 4
+    );
+
+    // Recovered provider delivers the email and marks it sent
+    mock_emailer.set_failing(false);
+    sqlx::query!("UPDATE email_outbox SET next_attempt_at = NOW()")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let attempted = EmailOutboxService::deliver_due(&pool, &mock_emailer)
+        .await
+        .unwrap();
+    assert_eq!(attempted, 1);
+    assert_eq!(mock_emailer.sent_count(), 1);
+    assert_eq!(
+        mock_emailer.last_sent_email().unwrap().recipient,
+        TEST_EMAIL
+    );
+
+    // The email is kept with the provider that accepted it
+    let email = sqlx::query!(
+        r#"SELECT status as "status: EmailStatus", provider, sent_at FROM email_outbox"#
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(email.status, EmailStatus::Sent);
+    assert_eq!(email.provider.as_deref(), Some("mock"));
+    assert!(email.sent_at.is_some());
+
+    // Sent emails are not delivered again
+    sqlx::query!("UPDATE email_outbox SET next_attempt_at = NOW()")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let attempted = EmailOutboxService::deliver_due(&pool, &mock_emailer)
+        .await
+        .unwrap();
+    assert_eq!(attempted, 0);
+}
+
+#[sqlx::test]
+async fn test_email_out_of_attempts_becomes_dead_letter(pool: PgPool) {
+    let app = spawn_admin_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    let mock_emailer = MockEmailer::new();
+    mock_emailer.set_failing(true);
+
+    enqueue(&pool, &test_email()).await;
+    sqlx::query!(
+        "UPDATE email_outbox SET attempts = $1 - 1",
+        EMAIL_MAX_ATTEMPTS
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let attempted = EmailOutboxService::deliver_due(&pool, &mock_emailer)
+        .await
+        .unwrap();
+    assert_eq!(attempted, 1);
+
+    // Dead letters are no longer retried
+    sqlx::query!("UPDATE email_outbox SET next_attempt_at = NOW()")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let attempted = EmailOutboxService::deliver_due(&pool, &mock_emailer)
+        .await
+        .unwrap();
+    assert_eq!(attempted, 0);
+
+    let response = client
+        .get(format!("{}/api/admin/emails?status=failed", app.address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["pagination"]["total"], 1);
+    let email = &body["data"][0];
+    assert_eq!(email["recipient"], TEST_EMAIL);
+    assert_eq!(email["template"], "notification");
+    assert_eq!(email["locale"], "en");
+    assert_eq!(email["subject"], "Test subject");
+    assert_eq!(email["status"], "failed");
+    assert_eq!(email["attempts"], EMAIL_MAX_ATTEMPTS);
+    assert_eq!(
+        email["last_error"],
+        "Failed to send email: Mock provider outage"
+    );
+    assert!(email.get("body").is_none());
+
+    let response = client
+        .get(format!("{}/api/admin/emails?status=pending", app.address))
+        .send()
+        .await
+        .unwrap();
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["pagination"]["total"], 0);
+}
+
+#[sqlx::test]
+async fn test_email_failing_to_render_becomes_dead_letter(pool: PgPool) {
+    let mock_emailer = MockEmailer::new();
+    enqueue(&pool, &test_email()).await;
+    sqlx::query!("UPDATE email_outbox SET variables = '{}'")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let attempted = EmailOutboxService::deliver_due(&pool, &mock_emailer)
+        .await
+        .unwrap();
+    assert_eq!(attempted, 1);
+    assert_eq!(mock_emailer.sent_count(), 0);
+
+    let email =
+        sqlx::query!(r#"SELECT status as "status: EmailStatus", last_error FROM email_outbox"#)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(email.status, EmailStatus::Failed);
+    assert!(
+        email
+            .last_error
+            .unwrap()
+            .contains("variables of template 'notification'")
+    );
+}
+
+#[sqlx::test]
+async fn test_expired_verification_code_is_not_sent(pool: PgPool) {
+    let mock_emailer = MockEmailer::new();
+    enqueue(
+        &pool,
+        &NewEmail::verification_code(
+            TEST_EMAIL,
+            "123456",
+            OffsetDateTime::now_utc() - Duration::seconds(1),
+        ),
+    )
+    .await;
+
+    let attempted = EmailOutboxService::deliver_due(&pool, &mock_emailer)
+        .await
+        .unwrap();
+    assert_eq!(attempted, 0);
+    assert_eq!(mock_emailer.sent_count(), 0);
+
+    let email = sqlx::query!(
+        r#"SELECT status as "status: EmailStatus", last_error, variables FROM email_outbox"#
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(email.status, EmailStatus::Failed);
+    assert_eq!(email.last_error.as_deref(), Some("Expired before delivery"));
+    // The dead letter does not keep the code
+    assert!(email.variables.get("code").is_none());
+    assert!(email.variables.get("expiry_minutes").is_some());
+}
+
+#[sqlx::test]
+async fn test_rejected_email_is_not_retried(pool: PgPool) {
+    let mock_emailer = MockEmailer::new();
+    mock_emailer.set_rejecting(true);
+    enqueue(
+        &pool,
+        &NewEmail::verification_code(
+            TEST_EMAIL,
+            "123456",
+            OffsetDateTime::now_utc() + Duration::minutes(5),
+        ),
+    )
+    .await;
+
+    let attempted = EmailOutboxService::deliver_due(&pool, &mock_emailer)
+        .await
+        .unwrap();
+    assert_eq!(attempted, 1);
+
+    let email = sqlx::query!(
+        r#"SELECT status as "status: EmailStatus", attempts, last_error, variables FROM email_outbox"#
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(email.status, EmailStatus::Failed);
+    assert_eq!(email.attempts, 1);
+    assert_eq!(
+        email.last_error.as_deref(),
+        Some("Email rejected: Mock recipient unknown")
+    );
+    assert!(email.variables.get("code").is_none());
+}
+
+#[sqlx::test]
+async fn test_old_emails_are_purged(pool: PgPool) {
+    let mock_emailer = MockEmailer::new();
+    mock_emailer.set_rejecting(true);
+    enqueue(&pool, &test_email()).await;
+    enqueue(&pool, &test_email()).await;
+    EmailOutboxService::deliver_due(&pool, &mock_emailer)
+        .await
+        .unwrap();
+    mock_emailer.set_rejecting(false);
+    enqueue(&pool, &test_email()).await;
+    EmailOutboxService::deliver_due(&pool, &mock_emailer)
+        .await
+        .unwrap();
+
+    // The sent email and one dead letter are past the retention period
+    sqlx::query!(
+        "UPDATE email_outbox SET created_at = NOW() - INTERVAL '31 days'
+         WHERE status = 'sent'
+            OR id = (SELECT id FROM email_outbox WHERE status = 'failed' LIMIT 1)"
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    EmailOutboxService::deliver_due(&pool, &mock_emailer)
+        .await
+        .unwrap();
+
+    let remaining =
+        sqlx::query_scalar!(r#"SELECT status as "status: EmailStatus" FROM email_outbox"#)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(remaining, [EmailStatus::Failed]);
+}
+
+#[sqlx::test]
+async fn test_send_code_survives_provider_outage(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    mock_emailer.set_failing(true);
+
+    let response = client
+        .post(format!("{address}/api/auth/send-code"))
+        .json(&json!({ "email": TEST_EMAIL }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+
+    // The worker attempts delivery and schedules a retry
+    let mut last_error: Option<String> = None;
+    for _ in 0..100 {
+        last_error = sqlx::query_scalar!("SELECT last_error FROM email_outbox")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        if last_error.is_some() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    assert!(last_error.unwrap().contains("Mock provider outage"));
+    assert_eq!(mock_emailer.sent_count(), 0);
+
+    // Once the provider recovers, the retry delivers the code
+    mock_emailer.set_failing(false);
+    sqlx::query!("UPDATE email_outbox SET next_attempt_at = NOW()")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let email = mock_emailer
+        .wait_for_email(|email| email.recipient == TEST_EMAIL)
+        .await;
+    let code = extract_verification_code(&email.text);
+
+    let response = client
+        .post(format!("{address}/api/auth/verify-code"))
+        .json(&json!({ "email": TEST_EMAIL, "code": code }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+}
@@ -0,0 +1,39 @@
+//! `email_service_from_env` reads the environment, so it is tested in its own
+//! binary where no other test runs concurrently.
+
+use std::{env, panic};
+
+use hilo::email_service_from_env;
+
+fn set_env(pairs: &[(&str, &str)]) {
+    for (name, value) in pairs {
+        // SAFETY: this is the only test of the binary, so no other thread
+        // reads or writes the environment
+        unsafe { env::set_var(name, value) };
+    }
+}
+
+#[test]
+fn test_email_provider_configuration() {
+    // A typo does not silently fall back to logging emails
+    set_env(&[("EMAIL_PROVIDER", "log,smpt")]);
+    assert!(panic::catch_unwind(email_service_from_env).is_err());
+
+    set_env(&[
+        ("EMAIL_PROVIDER", " log , log "),
+        ("EMAIL_CIRCUIT_FAILURE_THRESHOLD", "0"),
+    ]);
+    assert!(panic::catch_unwind(email_service_from_env).is_err());
+
+    set_env(&[
+        ("EMAIL_CIRCUIT_FAILURE_THRESHOLD", "5"),
+        ("EMAIL_CIRCUIT_OPEN_SECONDS", "a minute"),
+    ]);
+    assert!(panic::catch_unwind(email_service_from_env).is_err());
+
+    set_env(&[("EMAIL_CIRCUIT_OPEN_SECONDS", "120")]);
+    let service = email_service_from_env();
+    let health = service.health();
+    assert_eq!(health.len(), 2);
+    assert!(health.iter().all(|provider| provider.name == "log"));
+}
@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+
+use hilo::models::{EmailTemplates, TemplateError, TemplateId};
+use serde_json::{Value, json};
+
+const RECIPIENT_EMAIL: &str = "user@mails.tsinghua.edu.cn";
+
+fn shipped_templates() -> Value {
+    let raw = std::fs::read_to_string("email_templates.json").unwrap();
+    serde_json::from_str(&raw).unwrap()
+}
+
+fn load(templates: &Value) -> Result<EmailTemplates, TemplateError> {
+    EmailTemplates::from_json(&templates.to_string())
+}
+
+fn variables(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_shipped_templates_are_valid() {
+    let templates = load(&shipped_templates()).unwrap();
+
+    let message = templates
+        .render(
+            RECIPIENT_EMAIL,
+            TemplateId::VerificationCode,
+            "en",
+            variables(&[("code", "123456"), ("expiry_minutes", "5")]),
+        )
+        .unwrap();
+    assert_eq!(message.recipient, RECIPIENT_EMAIL);
+    assert_eq!(message.subject, "Your login code to Project Contigo");
+    assert!(
+        message
+            .text
+            .starts_with("Your verification code is: 123456")
+    );
+    assert!(message.text.contains("expire in 5 minutes"));
+    assert!(message.html.contains("123456"));
+    assert!(message.html.starts_with("<!DOCTYPE html>"));
+    assert!(!message.html.contains("{{"));
+    // Hidden preview shown by email clients
+    assert!(message.html.contains("Your login code: 123456"));
+
+    // Templates without a preheader have no hidden preview
+    let message = templates
+        .render(
+            RECIPIENT_EMAIL,
+            TemplateId::PartnerAccepted,
+            "en",
+            BTreeMap::new(),
+        )
+        .unwrap();
+    assert!(!message.html.contains("preheader"));
+
+    // Every template but the free-form notification is translated to Chinese
+    for id in TemplateId::ALL {
+        let pairs = id
+            .variables()
+            .iter()
+            .map(|name| (*name, "1"))
+            .collect::<Vec<_>>();
+        let message = templates
+            .render(RECIPIENT_EMAIL, id, "zh", variables(&pairs))
+            .unwrap();
+        let expected = if id == TemplateId::Notification {
+            "en"
+        } else {
+            "zh"
+        };
+        assert_eq!(message.locale, expected, "{id}");
+    }
+}
+
+#[test]
+fn test_negotiate_locale() {
+    let templates = load(&shipped_templates()).unwrap();
+
+    assert_eq!(templates.negotiate("zh-CN,zh;q=0.9,en;q=0.8"), Some("zh"));
+    assert_eq!(templates.negotiate("en-US,en;q=0.9,zh;q=0.8"), Some("en"));
+    // Ranked by quality rather than by order
+    assert_eq!(templates.negotiate("en;q=0.5, zh-TW"), Some("zh"));
+    assert_eq!(templates.negotiate("ZH-cn"), Some("zh"));
+    // Languages without templates and excluded languages are skipped
+    assert_eq!(templates.negotiate("fr, zh;q=0.1"), Some("zh"));
+    assert_eq!(templates.negotiate("zh;q=0, en;q=0.1"), Some("en"));
+    assert_eq!(templates.negotiate("fr-FR, de"), None);
+    assert_eq!(templates.negotiate("*"), None);
+    assert_eq!(templates.negotiate(""), None);
+    assert_eq!(templates.negotiate("zh;q=high"), None);
+}
+
+#[test]
+fn test_render_escapes_html_and_checks_variables() {
+    let templates = load(&shipped_templates()).unwrap();
+
+    let message = templates
+        .render(
+            RECIPIENT_EMAIL,
+            TemplateId::Notification,
+            "en",
+            variables(&[("subject", "Hello"), ("message", "<b>Tom & Jerry</b>\nBye")]),
+        )
+        .unwrap();
+    assert_eq!(message.subject, "Hello");
+    assert_eq!(message.text, "<b>Tom & Jerry</b>\nBye");
+    assert!(
+        message
+            .html
+            .contains("&lt;b&gt;Tom &amp; Jerry&lt;/b&gt;<br>Bye")
+    );
+
+    // Missing and unexpected variables are rejected
+    let result = templates.render(
+        RECIPIENT_EMAIL,
+        TemplateId::Notification,
+        "en",
+        variables(&[("subject", "Hello")]),
+    );
+    assert!(matches!(result, Err(TemplateError::Variables(..))));
+    let result = templates.render(
+        RECIPIENT_EMAIL,
+        TemplateId::PartnerAccepted,
+        "en",
+        variables(&[("name", "Tom")]),
+    );
+    assert!(matches!(result, Err(TemplateError::Variables(..))));
+}
+
+#[test]
+fn test_render_falls_back_to_default_locale() {
+    let mut raw = shipped_templates();
+    raw["partner_accepted"]["de"] = json!({
+        "subject": "Dein Partner hat angenommen",
+        "text": "Bitte melde dich an.",
+        "html": "<p>Bitte melde dich an.</p>",
+    });
+    let templates = load(&raw).unwrap();
+
+    let message = templates
+        .render(
+            RECIPIENT_EMAIL,
+            TemplateId::PartnerAccepted,
+            "de",
+            BTreeMap::new(),
+        )
+        .unwrap();
+    assert_eq!(message.locale, "de");
+    assert_eq!(message.subject, "Dein Partner hat angenommen");
+    assert!(message.html.contains(r#"<html lang="de">"#));
+
+    let message = templates
+        .render(
+            RECIPIENT_EMAIL,
+            TemplateId::MatchRejected,
+            "de",
+            BTreeMap::new(),
+        )
+        .unwrap();
+    assert_eq!(message.locale, "en");
+    assert_eq!(
+        message.subject,
+        "Your match on Project Contigo has been cancelled"
+    );
+}
+
+#[test]
+fn test_invalid_templates_are_rejected() {
+    let invalid = |mutate: fn(&mut Value)| {
+        let mut raw = shipped_templates();
+        mutate(&mut raw);
+        load(&raw).unwrap_err()
+    };
+
+    // Every template must exist in the default locale
+    let error = invalid(|raw| {
+        raw.as_object_mut().unwrap().remove("match_reminder");
+    });
+    assert!(error.to_string().contains("match_reminder"), "{error}");
+    let error = invalid(|raw| {
+        raw["match_reminder"]["de"] = raw["match_reminder"]["en"].take();
+        raw["match_reminder"].as_object_mut().unwrap().remove("en");
+    });
+    assert!(
+        error.to_string().contains("missing in locale 'en'"),
+        "{error}"
+    );
+
+    // Templates may only reference their own variables
+    let error = invalid(|raw| {
+        raw["partner_accepted"]["en"]["text"] = json!("Hello {{name}}");
+    });
+    assert!(
+        error.to_string().contains("unknown variable 'name'"),
+        "{error}"
+    );
+    let error = invalid(|raw| {
+        raw["final_match_created"]["en"]["html"] = json!("<p>{{auto_accept_hours</p>");
+    });
+    assert!(
+        error.to_string().contains("unclosed placeholder"),
+        "{error}"
+    );
+
+    // Unknown templates and parts are rejected
+    let error = invalid(|raw| {
+        raw["welcome"] = raw["partner_accepted"].clone();
+    });
+    assert!(matches!(error, TemplateError::Json(_)));
+    let error = invalid(|raw| {
+        raw["partner_accepted"]["en"]
+            .as_object_mut()
+            .unwrap()
+            .remove("html");
+    });
+    assert!(matches!(error, TemplateError::Json(_)));
+
+    // Subjects are a single line
+    let error = invalid(|raw| {
+        raw["partner_accepted"]["en"]["subject"] = json!("Two\nlines");
+    });
+    assert!(error.to_string().contains("several lines"), "{error}");
+}
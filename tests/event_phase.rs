@@ -0,0 +1,258 @@
+mod common;
+
+use common::*;
+use hilo::services::event::EventService;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Inserts a phase that started `minutes_ago` minutes ago, bypassing the admin API
+/// which only accepts future start times.
+async fn start_phase(pool: &PgPool, phase: &str, minutes_ago: i32) {
+    sqlx::query(
+        "INSERT INTO event_phases (phase, starts_at)
+         VALUES ($1::event_phase, NOW() - make_interval(mins => $2))",
+    )
+    .bind(phase)
+    .bind(minutes_ago)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+async fn get_phase(client: &reqwest::Client, address: &str) -> Value {
+    let response = client
+        .get(format!("{address}/api/event/phase"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    response.json().await.unwrap()
+}
+
+async fn send_code(client: &reqwest::Client, address: &str, email: &str) -> reqwest::StatusCode {
+    client
+        .post(format!("{address}/api/auth/send-code"))
+        .json(&json!({ "email": email }))
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+async fn post_with_token(
+    client: &reqwest::Client,
+    url: String,
+    token: &str,
+    body: &Value,
+) -> reqwest::StatusCode {
+    client
+        .post(url)
+        .header("Authorization", format!("Bearer {token}"))
+        .json(body)
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+#[sqlx::test]
+async fn test_admin_event_phase_timeline(pool: PgPool) {
+    let (address, _) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    // Without a timeline nothing has started
+    let phase = get_phase(&client, &address).await;
+    assert!(phase["phase"].is_null());
+    assert!(phase["next_phase"].is_null());
+
+    let now = time::OffsetDateTime::now_utc();
+    let rfc3339 = |time: time::OffsetDateTime| {
+        time.format(&time::format_description::well_known::Rfc3339)
+            .unwrap()
+    };
+
+    let response = client
+        .post(format!("{address}/api/admin/event-phases"))
+        .json(&json!({ "phases": [
+            { "phase": "registration", "starts_at": rfc3339(now + time::Duration::hours(1)) },
+            { "phase": "form_filling", "starts_at": rfc3339(now + time::Duration::hours(2)) },
+        ]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::CREATED);
+    let created: Vec<Value> = response.json().await.unwrap();
+    assert_eq!(created.len(), 2);
+
+    // Start times in the past are rejected
+    let response = client
+        .post(format!("{address}/api/admin/event-phases"))
+        .json(&json!({ "phases": [
+            { "phase": "preview_veto", "starts_at": rfc3339(now - time::Duration::hours(1)) },
+        ]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let phase = get_phase(&client, &address).await;
+    assert!(phase["phase"].is_null());
+    assert_eq!(phase["next_phase"], "registration");
+
+    // Cancelling a phase removes it from the timeline
+    let registration_id: Uuid = created[0]["id"].as_str().unwrap().parse().unwrap();
+    let url = format!("{address}/api/admin/event-phases/{registration_id}");
+    let response = client.delete(&url).send().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let response = client.delete(&url).send().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    let response = client
+        .get(format!("{address}/api/admin/event-phases"))
+        .send()
+        .await
+        .unwrap();
+    let phases: Vec<Value> = response.json().await.unwrap();
+    assert_eq!(phases.len(), 1);
+    assert_eq!(phases[0]["phase"], "form_filling");
+
+    let audited = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM admin_audit_log
+         WHERE action IN ('create_event_phases', 'cancel_event_phase')"
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(audited, Some(2));
+}
+
+#[sqlx::test]
+async fn test_event_phase_gates_endpoints(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let male_token = complete_form(
+        &client,
+        &address,
+        &mock_emailer,
+        MALE_EMAIL,
+        create_male_form_submission(),
+    )
+    .await;
+    let female_token = complete_form(
+        &client,
+        &address,
+        &mock_emailer,
+        FEMALE_EMAIL,
+        create_female_form_submission(),
+    )
+    .await;
+    let male_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = $1", MALE_EMAIL)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    // Form filling: only existing users can get a login code, but the response is the same
+    start_phase(&pool, "form_filling", 10).await;
+    assert_eq!(get_phase(&client, &address).await["phase"], "form_filling");
+
+    sqlx::query!("INSERT INTO users (email) VALUES ('existing@mails.tsinghua.edu.cn')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    mock_emailer.clear();
+    for email in [
+        "newcomer@mails.tsinghua.edu.cn",
+        "existing@mails.tsinghua.edu.cn",
+    ] {
+        assert_eq!(
+            send_code(&client, &address, email).await,
+            reqwest::StatusCode::ACCEPTED
+        );
+    }
+    for email in [
+        "newcomer@mails.tsinghua.edu.cn",
+        "existing@mails.tsinghua.edu.cn",
+    ] {
+        assert_eq!(
+            send_code(&client, &address, email).await,
+            reqwest::StatusCode::TOO_MANY_REQUESTS
+        );
+    }
+    mock_emailer
+        .wait_for_email(|email| email.recipient == "existing@mails.tsinghua.edu.cn")
+        .await;
+    assert!(
+        mock_emailer
+            .get_sent_emails()
+            .iter()
+            .all(|email| email.recipient != "newcomer@mails.tsinghua.edu.cn")
+    );
+
+    let form_url = format!("{address}/api/form");
+    let veto_url = format!("{address}/api/veto");
+    let veto = json!({ "vetoed_id": male_id });
+    assert_eq!(
+        post_with_token(
+            &client,
+            form_url.clone(),
+            &male_token,
+            &create_male_form_submission()
+        )
+        .await,
+        reqwest::StatusCode::OK
+    );
+    assert_eq!(
+        post_with_token(&client, veto_url.clone(), &female_token, &veto).await,
+        reqwest::StatusCode::FORBIDDEN
+    );
+
+    // Preview and veto: the scheduler publishes previews when the phase starts
+    start_phase(&pool, "preview_veto", 5).await;
+    sqlx::query!("DELETE FROM match_previews")
+        .execute(&pool)
+        .await
+        .unwrap();
+    EventService::advance_phases(&pool).await.unwrap();
+
+    let entered =
+        sqlx::query_scalar!("SELECT COUNT(*) FROM event_phases WHERE entered_at IS NOT NULL")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(entered, Some(2));
+    let previews = sqlx::query_scalar!("SELECT COUNT(*) FROM match_previews")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(previews, Some(2));
+
+    assert_eq!(
+        post_with_token(
+            &client,
+            form_url,
+            &male_token,
+            &create_male_form_submission()
+        )
+        .await,
+        reqwest::StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        post_with_token(&client, veto_url, &female_token, &veto).await,
+        reqwest::StatusCode::CREATED
+    );
+
+    let accept_url = format!("{address}/api/final-match/accept");
+    assert_eq!(
+        post_with_token(&client, accept_url.clone(), &male_token, &json!({})).await,
+        reqwest::StatusCode::FORBIDDEN
+    );
+
+    // Confirmation: the accept route is open, but the user has no final match
+    start_phase(&pool, "confirmation", 1).await;
+    assert_eq!(
+        post_with_token(&client, accept_url, &male_token, &json!({})).await,
+        reqwest::StatusCode::BAD_REQUEST
+    );
+}
@@ -0,0 +1,347 @@
+mod common;
+
+use std::time::Duration;
+
+use common::*;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use uuid::Uuid;
+
+/// An event received from the stream
+#[derive(Debug)]
+struct StreamEvent {
+    id: i64,
+    kind: String,
+    data: Value,
+}
+
+/// Reads the events of an open event stream
+struct EventStreamReader {
+    response: reqwest::Response,
+    buffer: String,
+}
+
+impl EventStreamReader {
+    async fn open(client: &reqwest::Client, address: &str, token: &str) -> Self {
+        Self::open_with(client.get(format!("{address}/api/events/stream")), token).await
+    }
+
+    async fn open_with(request: reqwest::RequestBuilder, token: &str) -> Self {
+        let response = request
+            .header("Authorization", format!("Bearer {token}"))
+            .send()
+            .await
+            .expect("Failed to open event stream");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(
+            response.headers()["content-type"].to_str().unwrap(),
+            "text/event-stream"
+        );
+        Self {
+            response,
+            buffer: String::new(),
+        }
+    }
+
+    /// Wait for the next event, skipping heartbeats
+    async fn next_event(&mut self) -> StreamEvent {
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                if let Some(end) = self.buffer.find("\n\n") {
+                    let block = self.buffer[..end].to_string();
+                    self.buffer.drain(..end + 2);
+                    if let Some(event) = parse_event(&block) {
+                        return event;
+                    }
+                    continue;
+                }
+                let chunk = self
+                    .response
+                    .chunk()
+                    .await
+                    .expect("Failed to read event stream")
+                    .expect("Event stream ended");
+                self.buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+            }
+        })
+        .await
+        .expect("Timed out waiting for an event")
+    }
+
+    /// Assert that no event arrives shortly
+    async fn assert_no_event(&mut self) {
+        let result = tokio::time::timeout(Duration::from_millis(500), async {
+            self.next_event().await
+        })
+        .await;
+        assert!(result.is_err(), "Unexpected event: {result:?}");
+    }
+}
+
+/// Parse an SSE block, `None` for comments such as heartbeats
+fn parse_event(block: &str) -> Option<StreamEvent> {
+    let (mut id, mut kind, mut data) = (None, None, None);
+    for line in block.lines() {
+        if let Some(value) = line.strip_prefix("id:") {
+            id = Some(value.trim().parse().unwrap());
+        } else if let Some(value) = line.strip_prefix("event:") {
+            kind = Some(value.trim().to_string());
+        } else if let Some(value) = line.strip_prefix("data:") {
+            data = Some(serde_json::from_str(value.trim()).unwrap());
+        }
+    }
+    Some(StreamEvent {
+        id: id?,
+        kind: kind?,
+        data: data?,
+    })
+}
+
+async fn schedule_final_match(client: &reqwest::Client, address: &str, time: OffsetDateTime) {
+    let response = client
+        .post(format!("{address}/api/admin/scheduled-matches"))
+        .json(&json!({"scheduled_times": [{"scheduled_time": time.format(&Rfc3339).unwrap()}]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::CREATED);
+}
+
+/// Stores the match previews of a user like the periodic refresh
+async fn refresh_previews(pool: &PgPool, user_id: Uuid, candidate_ids: &[Uuid], score: f64) {
+    sqlx::query!(
+        r#"
+        INSERT INTO match_previews (user_id, candidate_ids, scores)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (event_id, user_id)
+        DO UPDATE SET candidate_ids = EXCLUDED.candidate_ids, scores = EXCLUDED.scores
+        "#,
+        user_id,
+        candidate_ids,
+        &vec![score; candidate_ids.len()]
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[sqlx::test]
+async fn test_stream_requires_authentication(pool: PgPool) {
+    let (address, _) = spawn_app(pool).await;
+
+    let response = reqwest::get(format!("{address}/api/events/stream"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test]
+async fn test_stream_rejects_invalid_last_event_id(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool).await;
+    let client = reqwest::Client::new();
+    let token = get_access_token(
+        &client,
+        &address,
+        &mock_emailer,
+        "user@mails.tsinghua.edu.cn",
+    )
+    .await;
+
+    let response = client
+        .get(format!("{address}/api/events/stream"))
+        .header("Authorization", format!("Bearer {token}"))
+        .header("Last-Event-ID", "not-a-number")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_stream_pushes_status_changes(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool).await;
+    let client = reqwest::Client::new();
+    let email = "user@mails.tsinghua.edu.cn";
+    let token = get_access_token(&client, &address, &mock_emailer, email).await;
+    upload_card(&client, &address, &token).await;
+
+    let mut stream = EventStreamReader::open(&client, &address, &token).await;
+    // Events recorded before connecting are not replayed without a last event id
+    stream.assert_no_event().await;
+
+    assert!(admin_verify_user(&client, &address, email, "verified").await);
+    let event = stream.next_event().await;
+    assert_eq!(event.kind, "status_changed");
+    assert_eq!(event.data, json!({"status": "verified"}));
+
+    // Events of other users are not pushed
+    let other_token = get_access_token(
+        &client,
+        &address,
+        &mock_emailer,
+        "other@mails.tsinghua.edu.cn",
+    )
+    .await;
+    upload_card(&client, &address, &other_token).await;
+    assert!(admin_verify_user(&client, &address, "other@mails.tsinghua.edu.cn", "verified").await);
+    stream.assert_no_event().await;
+}
+
+#[sqlx::test]
+async fn test_stream_pushes_partner_acceptance(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool).await;
+    let client = reqwest::Client::new();
+    let (male_token, female_token) =
+        setup_two_matched_users(&client, &address, &mock_emailer).await;
+
+    let mut stream = EventStreamReader::open(&client, &address, &female_token).await;
+
+    accept_final_match(&client, &address, &male_token).await;
+
+    let event = stream.next_event().await;
+    assert_eq!(event.kind, "partner_accepted");
+    assert_eq!(event.data, json!({}));
+}
+
+#[sqlx::test]
+async fn test_stream_pushes_next_match_time(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool).await;
+    let client = reqwest::Client::new();
+    let token = get_access_token(
+        &client,
+        &address,
+        &mock_emailer,
+        "user@mails.tsinghua.edu.cn",
+    )
+    .await;
+
+    let mut stream = EventStreamReader::open(&client, &address, &token).await;
+
+    let later = OffsetDateTime::now_utc().replace_nanosecond(0).unwrap() + time::Duration::hours(2);
+    schedule_final_match(&client, &address, later).await;
+    let event = stream.next_event().await;
+    assert_eq!(event.kind, "next_match_time_changed");
+    let next = OffsetDateTime::parse(event.data["next"].as_str().unwrap(), &Rfc3339).unwrap();
+    assert_eq!(next, later);
+
+    // Scheduling a later match leaves the next time unchanged
+    schedule_final_match(&client, &address, later + time::Duration::hours(1)).await;
+    stream.assert_no_event().await;
+
+    let sooner = later - time::Duration::hours(1);
+    schedule_final_match(&client, &address, sooner).await;
+    let event = stream.next_event().await;
+    assert_eq!(event.kind, "next_match_time_changed");
+    let next = OffsetDateTime::parse(event.data["next"].as_str().unwrap(), &Rfc3339).unwrap();
+    assert_eq!(next, sooner);
+}
+
+#[sqlx::test]
+async fn test_stream_resumes_after_last_event_id(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool).await;
+    let client = reqwest::Client::new();
+    let email = "user@mails.tsinghua.edu.cn";
+    let token = get_access_token(&client, &address, &mock_emailer, email).await;
+    upload_card(&client, &address, &token).await;
+
+    let mut stream = EventStreamReader::open(&client, &address, &token).await;
+    assert!(admin_verify_user(&client, &address, email, "verified").await);
+    let verified = stream.next_event().await;
+    drop(stream);
+
+    // Missed while disconnected
+    let later = OffsetDateTime::now_utc() + time::Duration::hours(2);
+    schedule_final_match(&client, &address, later).await;
+
+    let request = client
+        .get(format!("{address}/api/events/stream"))
+        .header("Last-Event-ID", verified.id.to_string());
+    let mut stream = EventStreamReader::open_with(request, &token).await;
+    let missed = stream.next_event().await;
+    assert_eq!(missed.kind, "next_match_time_changed");
+    assert!(missed.id > verified.id);
+    stream.assert_no_event().await;
+
+    // The query parameter works alike
+    let request = client.get(format!(
+        "{address}/api/events/stream?last_event_id={}",
+        verified.id - 1
+    ));
+    let mut stream = EventStreamReader::open_with(request, &token).await;
+    let event = stream.next_event().await;
+    assert_eq!(event.id, verified.id);
+    assert_eq!(event.kind, "status_changed");
+    assert_eq!(stream.next_event().await.id, missed.id);
+}
+
+#[sqlx::test]
+async fn test_stream_pushes_changed_previews_only(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    let token = get_access_token(&client, &address, &mock_emailer, MALE_EMAIL).await;
+    let user_id = user_id(&pool, MALE_EMAIL).await;
+
+    let mut stream = EventStreamReader::open(&client, &address, &token).await;
+    let candidates = [Uuid::new_v4(), Uuid::new_v4()];
+    refresh_previews(&pool, user_id, &candidates, 0.5).await;
+    assert_eq!(stream.next_event().await.kind, "previews_updated");
+
+    // Refreshing to the same candidates is not pushed, even if their scores changed
+    refresh_previews(&pool, user_id, &candidates, 0.7).await;
+    stream.assert_no_event().await;
+
+    refresh_previews(&pool, user_id, &candidates[..1], 0.7).await;
+    assert_eq!(stream.next_event().await.kind, "previews_updated");
+}
+
+#[sqlx::test]
+async fn test_stream_never_skips_events_committed_late(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    let token = get_access_token(&client, &address, &mock_emailer, MALE_EMAIL).await;
+    let user_id = user_id(&pool, MALE_EMAIL).await;
+
+    let mut stream = EventStreamReader::open(&client, &address, &token).await;
+
+    // The transaction starts first, but records its event after another one commits
+    let mut tx = pool.begin().await.unwrap();
+    sqlx::query!("SELECT pg_current_xact_id()::text")
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+    let committed_first = sqlx::query_scalar!(
+        "INSERT INTO user_events (user_id, kind) VALUES ($1, 'previews_updated') RETURNING id",
+        user_id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    // Held back while the older transaction may still record events
+    stream.assert_no_event().await;
+
+    let committed_last = sqlx::query_scalar!(
+        "INSERT INTO user_events (user_id, kind) VALUES ($1, 'partner_accepted') RETURNING id",
+        user_id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .unwrap();
+    tx.commit().await.unwrap();
+    assert!(committed_last > committed_first);
+
+    // Sent in the order of their transactions, not of their ids
+    let first = stream.next_event().await;
+    assert_eq!(first.id, committed_last);
+    assert_eq!(first.kind, "partner_accepted");
+    assert_eq!(stream.next_event().await.id, committed_first);
+    drop(stream);
+
+    // Resuming after the event with the higher id still sends the other one
+    let request = client
+        .get(format!("{address}/api/events/stream"))
+        .header("Last-Event-ID", committed_last.to_string());
+    let mut stream = EventStreamReader::open_with(request, &token).await;
+    assert_eq!(stream.next_event().await.id, committed_first);
+    stream.assert_no_event().await;
+}
@@ -0,0 +1,296 @@
+mod common;
+
+use common::*;
+use hilo::models::UserStatus;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn test_activating_event_starts_over(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let token = complete_form(
+        &client,
+        &address,
+        &mock_emailer,
+        MALE_EMAIL,
+        create_male_form_submission(),
+    )
+    .await;
+    complete_form(
+        &client,
+        &address,
+        &mock_emailer,
+        FEMALE_EMAIL,
+        create_female_form_submission(),
+    )
+    .await;
+    let female_id = user_id(&pool, FEMALE_EMAIL).await;
+    let user_id = user_id(&pool, MALE_EMAIL).await;
+    let previous_event_id = sqlx::query_scalar!("SELECT id FROM events WHERE is_current")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    sqlx::query!(
+        "INSERT INTO vetoes (vetoer_id, vetoed_id) VALUES ($1, $2)",
+        female_id,
+        user_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let response = create_event(
+        &client,
+        &address,
+        &json!({ "slug": "spring", "name": "Spring event" }),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::CREATED);
+    let event: Value = response.json().await.unwrap();
+    assert_eq!(event["is_current"], false);
+    let event_id = event["id"].as_str().unwrap().to_string();
+
+    // Nothing changes until the event is activated
+    let status = UserStatus::query(&pool, &user_id).await.unwrap();
+    assert_eq!(status, UserStatus::FormCompleted);
+
+    let response = activate_event(&client, &address, &event_id).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["users_reset"], 2);
+
+    let status = UserStatus::query(&pool, &user_id).await.unwrap();
+    assert_eq!(status, UserStatus::Verified);
+
+    // Previews and vetoes of the previous round do not carry over
+    let previews = sqlx::query_scalar!("SELECT COUNT(*) FROM match_previews")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(previews, Some(0));
+    let vetoes = sqlx::query_scalar!("SELECT COUNT(*) FROM vetoes")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(vetoes, Some(0));
+
+    // The form of the previous event is kept but no longer visible to the user
+    let response = client
+        .get(format!("{address}/api/form"))
+        .header("Authorization", format!("Bearer {token}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    let detail = get_json(&client, format!("{address}/api/admin/user/{user_id}")).await;
+    assert!(detail["form"].is_null());
+    let detail = get_json(
+        &client,
+        format!("{address}/api/admin/user/{user_id}?event_id={previous_event_id}"),
+    )
+    .await;
+    assert_eq!(detail["form"]["gender"], "male");
+
+    // The user can take part again with a new form
+    let mut form = create_male_form_submission();
+    form["profile_photo_filename"] = json!(upload_profile_photo(&client, &address, &token).await);
+    let response = client
+        .post(format!("{address}/api/form"))
+        .header("Authorization", format!("Bearer {token}"))
+        .json(&form)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let forms = sqlx::query_scalar!("SELECT COUNT(*) FROM forms WHERE user_id = $1", user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(forms, Some(2));
+
+    // The export contains the forms of both events
+    let (_, data) = export_account(&client, &address, &token).await;
+    let exported_events = data["events"].as_array().unwrap();
+    assert_eq!(exported_events.len(), 2);
+    assert_eq!(exported_events[0]["event_id"], event_id);
+    assert_eq!(
+        exported_events[1]["event_id"],
+        previous_event_id.to_string()
+    );
+    for exported in exported_events {
+        assert_eq!(exported["form"]["gender"], "male");
+    }
+
+    let revisions = get_json(
+        &client,
+        format!("{address}/api/admin/user/{user_id}/form-revisions"),
+    )
+    .await;
+    assert_eq!(revisions.as_array().unwrap().len(), 1);
+    assert_eq!(revisions[0]["event_id"], event_id);
+
+    let events = get_json(&client, format!("{address}/api/admin/events")).await;
+    let events = events.as_array().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["slug"], "spring");
+    assert_eq!(events[0]["is_current"], true);
+    assert_eq!(events[1]["is_current"], false);
+
+    let audit = get_json(
+        &client,
+        format!("{address}/api/admin/audit?target_id={event_id}"),
+    )
+    .await;
+    let actions: Vec<&str> = audit["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["action"].as_str().unwrap())
+        .collect();
+    assert_eq!(actions, ["activate_event", "create_event"]);
+}
+
+#[sqlx::test]
+async fn test_event_allowed_domains(pool: PgPool) {
+    let (address, _mock_emailer) = spawn_app(pool).await;
+    let client = reqwest::Client::new();
+
+    let response = create_event(
+        &client,
+        &address,
+        &json!({
+            "slug": "pku",
+            "name": "PKU event",
+            "allowed_domains": ["stu.pku.edu.cn"],
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::CREATED);
+    let event: Value = response.json().await.unwrap();
+
+    let send_code = |email: &'static str| {
+        let client = client.clone();
+        let address = address.clone();
+        async move {
+            client
+                .post(format!("{address}/api/auth/send-code"))
+                .json(&json!({ "email": email }))
+                .send()
+                .await
+                .unwrap()
+                .status()
+        }
+    };
+
+    // Before activation the deployment defaults apply
+    assert_eq!(send_code(MALE_EMAIL).await, reqwest::StatusCode::ACCEPTED);
+
+    let response = activate_event(&client, &address, event["id"].as_str().unwrap()).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    assert_eq!(
+        send_code("someone@mails.tsinghua.edu.cn").await,
+        reqwest::StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        send_code("someone@stu.pku.edu.cn").await,
+        reqwest::StatusCode::ACCEPTED
+    );
+}
+
+#[sqlx::test]
+async fn test_event_validation(pool: PgPool) {
+    let app = spawn_admin_app(pool).await;
+    let client = reqwest::Client::new();
+
+    let response = create_event(&client, &app.address, &json!({ "slug": "", "name": "x" })).await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let response = create_event(
+        &client,
+        &app.address,
+        &json!({ "slug": "fall", "name": "Fall", "allowed_grades": [] }),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let response = create_event(
+        &client,
+        &app.address,
+        &json!({ "slug": "fall", "name": "Fall" }),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::CREATED);
+    let response = create_event(
+        &client,
+        &app.address,
+        &json!({ "slug": "fall", "name": "Again" }),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let current = get_json(&client, format!("{}/api/admin/events", app.address)).await;
+    let current_id = current
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|event| event["is_current"] == true)
+        .unwrap()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let response = activate_event(&client, &app.address, &current_id).await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let unknown = uuid::Uuid::new_v4();
+    let response = activate_event(&client, &app.address, &unknown.to_string()).await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    let response = client
+        .get(format!(
+            "{}/api/admin/matches?event_id={unknown}",
+            app.address
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_concurrent_activations(pool: PgPool) {
+    let app = spawn_admin_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let response = create_event(
+        &client,
+        &app.address,
+        &json!({ "slug": "spring", "name": "Spring event" }),
+    )
+    .await;
+    let event: Value = response.json().await.unwrap();
+    let event_id = event["id"].as_str().unwrap();
+
+    // Only one of the activations resets the users, the other finds it current
+    let (first, second) = tokio::join!(
+        activate_event(&client, &app.address, event_id),
+        activate_event(&client, &app.address, event_id)
+    );
+    let mut statuses = [first.status(), second.status()];
+    statuses.sort();
+    assert_eq!(
+        statuses,
+        [reqwest::StatusCode::OK, reqwest::StatusCode::BAD_REQUEST]
+    );
+
+    let activations =
+        sqlx::query_scalar!("SELECT COUNT(*) FROM admin_audit_log WHERE action = 'activate_event'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(activations, Some(1));
+}
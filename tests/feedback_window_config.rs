@@ -0,0 +1,16 @@
+//! `FEEDBACK_WINDOW_DAYS` is read from the environment once, so it is tested in its
+//! own binary where no other test runs concurrently.
+
+use std::env;
+
+use hilo::utils::static_object::FEEDBACK_WINDOW_DAYS;
+
+#[test]
+fn test_out_of_range_feedback_window_falls_back() {
+    // SAFETY: this is the only test of the binary, so no other thread
+    // reads or writes the environment
+    unsafe { env::set_var("FEEDBACK_WINDOW_DAYS", "10000000") };
+
+    // A window this long would overflow the closing time of every window
+    assert_eq!(*FEEDBACK_WINDOW_DAYS, 14);
+}
@@ -68,6 +68,16 @@ async fn test_view_profiles(
         final_match["self_intro"].is_string(),
         "Should have partner self intro"
     );
+    // The visible answers are also listed by question id, without private ones
+    assert_eq!(
+        final_match["answers"]["self_intro"],
+        final_match["self_intro"]
+    );
+    assert_eq!(
+        final_match["answers"]["familiar_tags"],
+        final_match["familiar_tags"]
+    );
+    assert!(final_match["answers"].get("physical_boundary").is_none());
     assert!(
         final_match["photo_url"].is_string(),
         "Should have partner photo URL"
@@ -100,7 +100,7 @@ async fn test_submit_form_upsert_behavior(pool: PgPool) {
         .send()
         .await
         .expect("Failed to submit updated form");
-    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
 
     // Verify the form was not duplicated
     let form_count = sqlx::query_scalar!(
@@ -112,15 +112,24 @@ async fn test_submit_form_upsert_behavior(pool: PgPool) {
     .expect("Failed to count forms");
     assert_eq!(form_count, Some(1));
 
-    // Verify the content was not updated
+    // Verify the content was updated and the status is unchanged
     let form = sqlx::query!(
-        r#"SELECT recent_topics, gender as "gender: Gender" FROM forms WHERE user_id = (SELECT id FROM users WHERE email = $1)"#,
+        r#"SELECT answers->>'recent_topics' as recent_topics, gender as "gender: Gender" FROM forms WHERE user_id = (SELECT id FROM users WHERE email = $1)"#,
         test_email
     )
     .fetch_one(&pool)
     .await
     .expect("Failed to fetch form");
-    assert_eq!(form.gender, Gender::Male);
+    assert_eq!(form.gender, Gender::Female);
+
+    let user_status = sqlx::query!(
+        r#"SELECT status as "status: UserStatus" FROM users WHERE email = $1"#,
+        test_email
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("Failed to fetch user status");
+    assert_eq!(user_status.status, UserStatus::FormCompleted);
 }
 
 #[sqlx::test]
@@ -156,14 +165,22 @@ async fn test_get_form_success(pool: PgPool) {
     let retrieved_form: Form = response.json().await.expect("Failed to parse form");
     assert_eq!(retrieved_form.gender, Gender::Male);
     assert_eq!(
-        retrieved_form.familiar_tags,
-        vec!["basketball", "pc_fps", "japanese"]
+        retrieved_form.answers.choices("familiar_tags"),
+        ["basketball", "pc_fps", "japanese"]
     );
-    assert_eq!(retrieved_form.physical_boundary, 2);
-    assert!(retrieved_form.recent_topics.contains("machine learning"));
+    assert_eq!(retrieved_form.answers.scale("physical_boundary"), Some(2));
     assert!(
         retrieved_form
-            .self_intro
+            .answers
+            .text("recent_topics")
+            .unwrap()
+            .contains("machine learning")
+    );
+    assert!(
+        retrieved_form
+            .answers
+            .text("self_intro")
+            .unwrap()
             .contains("computer science student")
     );
 }
@@ -296,7 +313,7 @@ async fn test_submit_form_invalid_tags(pool: PgPool) {
 
     assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
     let body = response.text().await.expect("Failed to read response");
-    assert!(body.contains("Invalid familiar tag"));
+    assert!(body.contains("Invalid choice"));
 
     // Test with non-matchable tags
     form_data["familiar_tags"] = json!(["desktop"]);
@@ -311,7 +328,7 @@ async fn test_submit_form_invalid_tags(pool: PgPool) {
 
     assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
     let body = response.text().await.expect("Failed to read response");
-    assert!(body.contains("Invalid familiar tag"));
+    assert!(body.contains("Invalid choice"));
 
     // Test with internal duplicate tags
     form_data["familiar_tags"] = json!(["pc_fps", "pc_fps"]);
@@ -326,11 +343,11 @@ async fn test_submit_form_invalid_tags(pool: PgPool) {
 
     assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
     let body = response.text().await.expect("Failed to read response");
-    assert!(body.contains("Duplicate tag"));
+    assert!(body.contains("Duplicate choice"));
 
     // Test with duplicate tags between familiar and aspirational
     form_data["familiar_tags"] = json!(["pc_fps"]);
-    form_data["aspirational_tags"] = json!(["pc_fps", "crafts"]);
+    form_data["aspirational_tags"] = json!(["pc_fps", "tennis"]);
 
     let response = client
         .post(format!("{}/api/form", &address))
@@ -342,7 +359,7 @@ async fn test_submit_form_invalid_tags(pool: PgPool) {
 
     assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
     let body = response.text().await.expect("Failed to read response");
-    assert!(body.contains("Duplicate tag"));
+    assert!(body.contains("Duplicate choice"));
 }
 
 #[sqlx::test]
@@ -452,3 +469,409 @@ async fn test_submit_form_updates_wechat_id_in_users_table(pool: PgPool) {
 
     assert_eq!(user_data.wechat_id, Some("test_wechat_123".to_string()));
 }
+
+#[sqlx::test]
+async fn test_edit_completed_form_records_revisions(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    let male_email = "male@mails.tsinghua.edu.cn";
+    let female_email = "female@mails.tsinghua.edu.cn";
+
+    let male_token = setup_verified_user(&client, &address, &mock_emailer, &pool, male_email).await;
+    let female_token =
+        setup_verified_user(&client, &address, &mock_emailer, &pool, female_email).await;
+
+    for (token, form) in [
+        (&male_token, create_male_form_submission()),
+        (&female_token, create_female_form_submission()),
+    ] {
+        let response = client
+            .post(format!("{address}/api/form"))
+            .header("Authorization", format!("Bearer {token}"))
+            .json(&form)
+            .send()
+            .await
+            .expect("Failed to submit form");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    let male_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = $1", male_email)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    // The female user vetoes the male user based on his preview
+    let response = client
+        .post(format!("{address}/api/veto"))
+        .header("Authorization", format!("Bearer {female_token}"))
+        .json(&json!({ "vetoed_id": male_id }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::CREATED);
+
+    let veto_count = || async {
+        sqlx::query_scalar!("SELECT COUNT(*) FROM vetoes WHERE vetoed_id = $1", male_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+    };
+
+    // Fixing a typo in the self introduction keeps the veto, which was based on the preview
+    let mut form = create_male_form_submission();
+    form["self_intro"] = json!("Hi! I'm a computer science student who loves sports and games.");
+    let response = client
+        .post(format!("{address}/api/form"))
+        .header("Authorization", format!("Bearer {male_token}"))
+        .json(&form)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(veto_count().await, Some(1));
+
+    // Changing tags shown in the preview makes the veto stale
+    form["familiar_tags"] = json!(["basketball", "pc_fps"]);
+    let response = client
+        .post(format!("{address}/api/form"))
+        .header("Authorization", format!("Bearer {male_token}"))
+        .json(&form)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(veto_count().await, Some(0));
+
+    // Previews are regenerated for the edited form
+    let candidates = sqlx::query_scalar!(
+        "SELECT candidate_ids FROM match_previews WHERE user_id = $1",
+        male_id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(candidates.len(), 1);
+
+    // Every submission is recorded as a revision
+    let response = client
+        .get(format!("{address}/api/admin/user/{male_id}/form-revisions"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let revisions: Vec<serde_json::Value> = response.json().await.unwrap();
+    assert_eq!(revisions.len(), 3);
+    assert_eq!(revisions[0]["revision"], 1);
+    assert_eq!(
+        revisions[0]["familiar_tags"],
+        json!(["basketball", "pc_fps", "japanese"])
+    );
+    assert_eq!(revisions[2]["revision"], 3);
+    assert_eq!(
+        revisions[2]["familiar_tags"],
+        json!(["basketball", "pc_fps"])
+    );
+}
+
+#[sqlx::test]
+async fn test_edit_form_rejected_with_final_match(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let (male_token, _female_token) =
+        setup_two_matched_users(&client, &address, &mock_emailer).await;
+
+    let submit = |token: String| {
+        let client = client.clone();
+        let address = address.clone();
+        async move {
+            client
+                .post(format!("{address}/api/form"))
+                .header("Authorization", format!("Bearer {token}"))
+                .json(&create_male_form_submission())
+                .send()
+                .await
+                .unwrap()
+                .status()
+        }
+    };
+
+    // Pending final match
+    assert_eq!(
+        submit(male_token.clone()).await,
+        reqwest::StatusCode::FORBIDDEN
+    );
+
+    // Confirmed final match
+    let response = client
+        .post(format!("{address}/api/final-match/accept"))
+        .header("Authorization", format!("Bearer {male_token}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(submit(male_token).await, reqwest::StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test]
+async fn test_form_draft_save_restore_and_promote(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    let test_email = "test@mails.tsinghua.edu.cn";
+
+    let access_token =
+        setup_verified_user(&client, &address, &mock_emailer, &pool, test_email).await;
+
+    // No draft yet
+    let response = client
+        .get(format!("{address}/api/form/draft"))
+        .header("Authorization", format!("Bearer {access_token}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    // Partially filled drafts with unknown tags are accepted
+    let draft = json!({
+        "gender": "male",
+        "familiar_tags": ["not_a_real_tag"],
+        "self_intro": "A long self introduction that is still being written"
+    });
+    let response = client
+        .put(format!("{address}/api/form/draft"))
+        .header("Authorization", format!("Bearer {access_token}"))
+        .json(&draft)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // Saving a draft does not change the status
+    let status = sqlx::query_scalar!(
+        r#"SELECT status as "status: UserStatus" FROM users WHERE email = $1"#,
+        test_email
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(status, UserStatus::Verified);
+
+    let response = client
+        .get(format!("{address}/api/form/draft"))
+        .header("Authorization", format!("Bearer {access_token}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let restored: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(restored["gender"], "male");
+    assert_eq!(restored["familiar_tags"], json!(["not_a_real_tag"]));
+    assert_eq!(
+        restored["self_intro"],
+        "A long self introduction that is still being written"
+    );
+    assert!(restored["wechat_id"].is_null());
+    assert!(restored["updated_at"].is_string());
+
+    // Final submission still runs the full validation
+    let mut form = create_male_form_submission();
+    form["familiar_tags"] = json!(["not_a_real_tag"]);
+    let response = client
+        .post(format!("{address}/api/form"))
+        .header("Authorization", format!("Bearer {access_token}"))
+        .json(&form)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    // Fields missing from both the submission and the draft are still required
+    let mut form = create_male_form_submission();
+    form.as_object_mut().unwrap().remove("wechat_id");
+    let response = client
+        .post(format!("{address}/api/form"))
+        .header("Authorization", format!("Bearer {access_token}"))
+        .json(&form)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    // The draft completes the submission, and the submitted fields take precedence
+    let mut form = create_male_form_submission();
+    form.as_object_mut().unwrap().remove("gender");
+    form.as_object_mut().unwrap().remove("self_intro");
+    let response = client
+        .post(format!("{address}/api/form"))
+        .header("Authorization", format!("Bearer {access_token}"))
+        .json(&form)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let submitted: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(submitted["gender"], "male");
+    assert_eq!(
+        submitted["self_intro"],
+        "A long self introduction that is still being written"
+    );
+    assert_eq!(submitted["familiar_tags"], form["familiar_tags"]);
+
+    let response = client
+        .get(format!("{address}/api/form/draft"))
+        .header("Authorization", format!("Bearer {access_token}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_form_draft_limits_and_status(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    let test_email = "test@mails.tsinghua.edu.cn";
+
+    let access_token = get_access_token(&client, &address, &mock_emailer, test_email).await;
+
+    // Unverified users cannot save drafts
+    let response = client
+        .put(format!("{address}/api/form/draft"))
+        .header("Authorization", format!("Bearer {access_token}"))
+        .json(&json!({ "gender": "male" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+
+    sqlx::query!(
+        "UPDATE users SET status = 'verified' WHERE email = $1",
+        test_email
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let too_many_tags: Vec<String> = (0..20).map(|i| format!("tag{i}")).collect();
+    for draft in [
+        json!({ "self_intro": "a".repeat(10_000) }),
+        json!({ "wechat_id": "a".repeat(1_000) }),
+        json!({ "familiar_tags": too_many_tags }),
+        json!({ "self_traits": ["a", "b", "c", "d"] }),
+    ] {
+        let response = client
+            .put(format!("{address}/api/form/draft"))
+            .header("Authorization", format!("Bearer {access_token}"))
+            .json(&draft)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+}
+
+#[sqlx::test]
+async fn test_form_schema_drives_validation(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    let test_email = "test@mails.tsinghua.edu.cn";
+
+    // The schema is public so clients can render the form
+    let response = client
+        .get(format!("{address}/api/form/schema"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let schema: serde_json::Value = response.json().await.unwrap();
+    let questions = schema["questions"].as_array().unwrap();
+    let boundary = questions
+        .iter()
+        .find(|q| q["id"] == "physical_boundary")
+        .unwrap();
+    assert_eq!(boundary["type"], "scale");
+    assert_eq!(boundary["max"], 4);
+    assert!(
+        schema["scorers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|s| s["type"] == "tag_overlap" && s["familiar"] == "familiar_tags")
+    );
+
+    let access_token =
+        setup_verified_user(&client, &address, &mock_emailer, &pool, test_email).await;
+
+    let cases = [
+        ("favorite_color", json!("blue"), "Unknown question"),
+        (
+            "self_intro",
+            json!(["not", "text"]),
+            "Answer has wrong type",
+        ),
+        ("self_traits", json!(["humor", "humor"]), "Duplicate choice"),
+        (
+            "recent_topics",
+            json!("a".repeat(801)),
+            "Text answer too long",
+        ),
+    ];
+    for (question, answer, message) in cases {
+        let mut form = create_male_form_submission();
+        form[question] = answer;
+        let response = client
+            .post(format!("{address}/api/form"))
+            .header("Authorization", format!("Bearer {access_token}"))
+            .json(&form)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            response.status(),
+            reqwest::StatusCode::BAD_REQUEST,
+            "{question}"
+        );
+        assert!(
+            response.text().await.unwrap().contains(message),
+            "{question}"
+        );
+    }
+
+    // Required questions must be answered
+    let mut form = create_male_form_submission();
+    form.as_object_mut().unwrap().remove("physical_boundary");
+    let response = client
+        .post(format!("{address}/api/form"))
+        .header("Authorization", format!("Bearer {access_token}"))
+        .json(&form)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    assert!(
+        response
+            .text()
+            .await
+            .unwrap()
+            .contains("Missing required answer")
+    );
+
+    // Answers are stored as JSONB keyed by question id
+    let response = client
+        .post(format!("{address}/api/form"))
+        .header("Authorization", format!("Bearer {access_token}"))
+        .json(&create_male_form_submission())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let answers = sqlx::query_scalar!(
+        "SELECT answers FROM forms WHERE user_id = (SELECT id FROM users WHERE email = $1)",
+        test_email
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(answers["physical_boundary"], 2);
+    assert!(answers.get("wechat_id").is_none());
+}
@@ -0,0 +1,211 @@
+mod common;
+
+use common::*;
+use hilo::{
+    services::scheduler::SchedulerService, utils::static_object::MATCHING_ALGORITHM_VERSION,
+};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn test_feedback_after_both_users_confirmed(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    let (male_token, female_token) =
+        setup_two_matched_users(&client, &address, &mock_emailer).await;
+    let feedback = json!({"rating": 4, "met": true, "comment": "  Had a great coffee  "});
+
+    // Not before the pair is confirmed
+    let response = submit_feedback(&client, &address, &male_token, feedback.clone()).await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    accept_final_match(&client, &address, &male_token).await;
+    let response = submit_feedback(&client, &address, &male_token, feedback.clone()).await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    accept_final_match(&client, &address, &female_token).await;
+    let response = submit_feedback(&client, &address, &male_token, feedback).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["rating"], 4);
+    assert_eq!(body["met"], true);
+    assert_eq!(body["comment"], "Had a great coffee");
+
+    // Submitting again replaces the feedback
+    let response = submit_feedback(
+        &client,
+        &address,
+        &male_token,
+        json!({"rating": 2, "met": false, "comment": " "}),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["rating"], 2);
+    assert!(body["comment"].is_null());
+
+    let rows = sqlx::query!("SELECT rating, met FROM match_feedback")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].rating, 2);
+    assert!(!rows[0].met);
+}
+
+#[sqlx::test]
+async fn test_feedback_validation(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool).await;
+    let client = reqwest::Client::new();
+    let (male_token, female_token) =
+        setup_two_matched_users(&client, &address, &mock_emailer).await;
+    accept_final_match(&client, &address, &male_token).await;
+    accept_final_match(&client, &address, &female_token).await;
+
+    for feedback in [
+        json!({"rating": 0, "met": true}),
+        json!({"rating": 6, "met": true}),
+        json!({"rating": 3, "met": true, "comment": "a".repeat(2001)}),
+    ] {
+        let response = submit_feedback(&client, &address, &male_token, feedback).await;
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+
+    let response = submit_feedback(
+        &client,
+        &address,
+        &male_token,
+        json!({"rating": 3, "met": true, "comment": "a".repeat(2000)}),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn test_feedback_window(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    let (male_token, female_token) =
+        setup_two_matched_users(&client, &address, &mock_emailer).await;
+
+    // Auto-acceptance confirms the pair too
+    sqlx::query!("UPDATE final_matches SET auto_accept_at = NOW() - INTERVAL '1 minute'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    SchedulerService::auto_accept_expired_matches(&pool)
+        .await
+        .unwrap();
+    let response = submit_feedback(
+        &client,
+        &address,
+        &female_token,
+        json!({"rating": 5, "met": true}),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // The window closes after FEEDBACK_WINDOW_DAYS
+    sqlx::query!("UPDATE final_matches SET confirmed_at = NOW() - INTERVAL '15 days'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let response = submit_feedback(
+        &client,
+        &address,
+        &male_token,
+        json!({"rating": 5, "met": true}),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test]
+async fn test_admin_feedback_summary(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    let (male_token, female_token) =
+        setup_two_matched_users(&client, &address, &mock_emailer).await;
+
+    let response = client
+        .get(format!("{address}/api/admin/feedback"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["overall"]["matches"], 0);
+    assert!(body["overall"]["average_rating"].is_null());
+
+    accept_final_match(&client, &address, &male_token).await;
+    accept_final_match(&client, &address, &female_token).await;
+    for (token, rating, met) in [(&male_token, 4, true), (&female_token, 5, false)] {
+        let response = submit_feedback(
+            &client,
+            &address,
+            token,
+            json!({"rating": rating, "met": met, "comment": "Nice"}),
+        )
+        .await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    let score = sqlx::query_scalar!("SELECT score FROM final_matches")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    let response = client
+        .get(format!("{address}/api/admin/feedback?bucket_width=2"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+
+    let overall = &body["overall"];
+    assert_eq!(overall["matches"], 1);
+    assert_eq!(overall["responses"], 2);
+    assert_eq!(overall["average_rating"], 4.5);
+    assert_eq!(overall["met_rate"], 0.5);
+    assert_eq!(overall["rating_counts"], json!([0, 0, 0, 1, 1]));
+
+    let buckets = body["by_score_bucket"].as_array().unwrap();
+    assert_eq!(buckets.len(), 1);
+    let min_score = buckets[0]["min_score"].as_f64().unwrap();
+    assert_eq!(buckets[0]["max_score"].as_f64().unwrap(), min_score + 2.0);
+    assert!(min_score <= score && score < min_score + 2.0);
+    assert_eq!(buckets[0]["responses"], 2);
+
+    // The test forms share no tags
+    assert_eq!(body["by_tag_overlap"][0]["tag_overlap"], 0);
+    assert_eq!(body["by_tag_overlap"][0]["responses"], 2);
+    assert_eq!(
+        body["by_algorithm_version"][0]["algorithm_version"],
+        MATCHING_ALGORITHM_VERSION.as_str()
+    );
+    assert!(
+        MATCHING_ALGORITHM_VERSION.starts_with("v1-"),
+        "{}",
+        *MATCHING_ALGORITHM_VERSION
+    );
+
+    // The comments are listed with the match
+    let response = client
+        .get(format!("{address}/api/admin/matches"))
+        .send()
+        .await
+        .unwrap();
+    let body: Value = response.json().await.unwrap();
+    let final_match = &body["data"][0];
+    assert_eq!(final_match["tag_overlap"], 0);
+    assert!(final_match["confirmed_at"].is_string());
+    assert_eq!(final_match["feedback"].as_array().unwrap().len(), 2);
+    assert_eq!(final_match["feedback"][0]["comment"], "Nice");
+
+    let response = client
+        .get(format!("{address}/api/admin/feedback?bucket_width=0"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+}
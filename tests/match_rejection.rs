@@ -0,0 +1,235 @@
+mod common;
+
+use common::*;
+use hilo::models::TemplateId;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+const COMMENT: &str = "We have nothing in common";
+
+#[sqlx::test]
+async fn test_get_rejection_reasons(pool: PgPool) {
+    let (address, _) = spawn_app(pool).await;
+
+    let response = reqwest::get(format!("{address}/api/final-match/rejection-reasons"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(
+        body,
+        json!({"reasons": ["no_shared_interests", "not_attracted", "other"]})
+    );
+}
+
+#[sqlx::test]
+async fn test_rejection_reason_is_kept_from_partner(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    let (male_token, female_token) =
+        setup_two_matched_users(&client, &address, &mock_emailer).await;
+    let final_match = sqlx::query!("SELECT id, score FROM final_matches")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    mock_emailer.clear();
+
+    let response = reject_final_match(
+        &client,
+        &address,
+        &female_token,
+        json!({"reason": "no_shared_interests", "comment": format!(" {COMMENT} ")}),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // The match is moved to the rejected matches
+    let remaining = sqlx::query_scalar!("SELECT COUNT(*) FROM final_matches")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, Some(0));
+    let rejection = sqlx::query!(
+        "SELECT final_match_id, rejected_by, partner_id, score, reason, comment FROM match_rejections"
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(rejection.final_match_id, final_match.id);
+    assert_eq!(rejection.score, final_match.score);
+    assert_eq!(rejection.reason.as_deref(), Some("no_shared_interests"));
+    assert_eq!(rejection.comment.as_deref(), Some(COMMENT));
+    let female_id = user_id(&pool, FEMALE_EMAIL).await;
+    assert_eq!(rejection.rejected_by, Some(female_id));
+    assert_ne!(rejection.partner_id, Some(female_id));
+
+    // Neither the notification nor the profile of the partner mention the reason
+    let email = mock_emailer
+        .wait_for_email(|email| email.template == TemplateId::MatchRejected)
+        .await;
+    assert_eq!(email.recipient, MALE_EMAIL);
+    for text in [&email.text, &email.html] {
+        assert!(!text.contains(COMMENT));
+        assert!(!text.contains("no_shared_interests"));
+    }
+    let profile = client
+        .get(format!("{address}/api/profile"))
+        .header("Authorization", format!("Bearer {male_token}"))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(!profile.contains(COMMENT));
+    assert!(!profile.contains("no_shared_interests"));
+}
+
+#[sqlx::test]
+async fn test_invalid_rejection_is_refused(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    let (male_token, _) = setup_two_matched_users(&client, &address, &mock_emailer).await;
+
+    for body in [
+        json!({"reason": "too_tall"}),
+        json!({"reason": "other", "comment": "a".repeat(2001)}),
+    ] {
+        let response = reject_final_match(&client, &address, &male_token, body).await;
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+    let remaining = sqlx::query_scalar!("SELECT COUNT(*) FROM final_matches")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, Some(1));
+
+    // Both fields are optional
+    let response = reject_final_match(&client, &address, &male_token, json!({})).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let rejection = sqlx::query!("SELECT reason, comment FROM match_rejections")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(rejection.reason.is_none());
+    assert!(rejection.comment.is_none());
+}
+
+#[sqlx::test]
+async fn test_admin_rejection_summary(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    let (male_token, _) = setup_two_matched_users(&client, &address, &mock_emailer).await;
+    let score = sqlx::query_scalar!("SELECT score FROM final_matches")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    let response = reject_final_match(
+        &client,
+        &address,
+        &male_token,
+        json!({"reason": "not_attracted", "comment": COMMENT}),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // The users are matched again in the next round
+    admin_trigger_final_match(&client, &address).await;
+
+    let response = client
+        .get(format!("{address}/api/admin/rejections"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["pagination"]["total"], 1);
+    let rejection = &body["data"][0];
+    assert_eq!(rejection["rejected_by_email"], MALE_EMAIL);
+    assert_eq!(rejection["partner_email"], FEMALE_EMAIL);
+    assert_eq!(rejection["reason"], "not_attracted");
+    assert_eq!(rejection["comment"], COMMENT);
+    assert_eq!(rejection["tag_overlap"], 0);
+    assert!(rejection["algorithm_version"].is_string());
+
+    let response = client
+        .get(format!(
+            "{address}/api/admin/rejections/summary?bucket_width=1000"
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+
+    let overall = &body["overall"];
+    assert_eq!(overall["matches"], 2);
+    assert_eq!(overall["rejections"], 1);
+    assert_eq!(overall["rejection_rate"], 0.5);
+    assert_eq!(overall["reasons"], json!({"not_attracted": 1}));
+    assert_eq!(overall["without_reason"], 0);
+
+    assert_eq!(body["by_reason"].as_array().unwrap().len(), 1);
+    assert_eq!(body["by_reason"][0]["reason"], "not_attracted");
+    assert_eq!(body["by_reason"][0]["rejections"], 1);
+    assert_eq!(body["by_reason"][0]["average_score"].as_f64(), Some(score));
+    assert_eq!(body["by_reason"][0]["average_tag_overlap"], 0.0);
+
+    assert_eq!(body["by_score_bucket"][0]["min_score"], 0.0);
+    assert_eq!(body["by_score_bucket"][0]["rejection_rate"], 0.5);
+    assert_eq!(body["by_tag_overlap"][0]["tag_overlap"], 0);
+    assert_eq!(body["by_algorithm_version"][0]["rejections"], 1);
+
+    let response = client
+        .get(format!(
+            "{address}/api/admin/rejections/summary?bucket_width=-1"
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_admin_deleted_matches_are_counted(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    setup_two_matched_users(&client, &address, &mock_emailer).await;
+    let match_id = sqlx::query_scalar!("SELECT id FROM final_matches")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    let response = client
+        .delete(format!("{address}/api/admin/final-matches/{match_id}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let response = client
+        .get(format!("{address}/api/admin/rejections"))
+        .send()
+        .await
+        .unwrap();
+    let body: Value = response.json().await.unwrap();
+    let rejection = &body["data"][0];
+    assert_eq!(rejection["final_match_id"], match_id.to_string());
+    assert_eq!(rejection["deleted_by"], ADMIN_EMAIL);
+    assert!(rejection["rejected_by"].is_null());
+
+    // Still counted as a match, but not as a rejection
+    let response = client
+        .get(format!("{address}/api/admin/rejections/summary"))
+        .send()
+        .await
+        .unwrap();
+    let body: Value = response.json().await.unwrap();
+    let overall = &body["overall"];
+    assert_eq!(overall["matches"], 1);
+    assert_eq!(overall["rejections"], 0);
+    assert_eq!(overall["rejection_rate"], 0.0);
+    assert_eq!(overall["deleted_by_admin"], 1);
+    assert!(body["by_reason"].as_array().unwrap().is_empty());
+}
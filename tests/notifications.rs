@@ -0,0 +1,357 @@
+mod common;
+
+use common::*;
+use hilo::{
+    models::{RoundDeadlines, TemplateId},
+    services::scheduler::SchedulerService,
+    utils::constant::{DEFAULT_AUTO_ACCEPT_HOURS, DEFAULT_REMINDER_HOURS},
+};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use time::OffsetDateTime;
+
+#[sqlx::test]
+async fn test_notification_preferences(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool).await;
+    let client = reqwest::Client::new();
+    let token = get_access_token(&client, &address, &mock_emailer, MALE_EMAIL).await;
+
+    let response = client
+        .get(format!("{address}/api/notifications/preferences"))
+        .header("Authorization", format!("Bearer {token}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let preferences: Value = response.json().await.unwrap();
+    assert_eq!(
+        preferences,
+        json!({
+            "final_match_created": true,
+            "partner_accepted": true,
+            "match_rejected": true,
+            "match_dissolved": true,
+            "match_auto_confirmed": true,
+            "match_reminder": true,
+        })
+    );
+
+    let response = update_preferences(
+        &client,
+        &address,
+        &token,
+        &json!({ "partner_accepted": false }),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // Omitted fields are kept
+    let response = update_preferences(
+        &client,
+        &address,
+        &token,
+        &json!({ "match_rejected": false }),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let preferences: Value = response.json().await.unwrap();
+    assert_eq!(preferences["partner_accepted"], false);
+    assert_eq!(preferences["match_rejected"], false);
+    assert_eq!(preferences["final_match_created"], true);
+
+    let response = client
+        .get(format!("{address}/api/notifications/preferences"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test]
+async fn test_notifications_use_the_user_locale(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    let (_, female_token) = setup_two_matched_users(&client, &address, &mock_emailer).await;
+    sqlx::query!(
+        "UPDATE users SET locale = 'zh' WHERE email = $1",
+        MALE_EMAIL
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    accept_final_match(&client, &address, &female_token).await;
+
+    let email = mock_emailer
+        .wait_for_email(|email| email.template == TemplateId::PartnerAccepted)
+        .await;
+    assert_eq!(email.recipient, MALE_EMAIL);
+    assert_eq!(email.locale, "zh");
+    assert_eq!(email.subject, "你的对象在 Project Contigo 接受了匹配");
+    assert!(email.html.contains(r#"<html lang="zh">"#));
+}
+
+#[sqlx::test]
+async fn test_match_lifecycle_notifications(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let (male_token, female_token) =
+        setup_two_matched_users(&client, &address, &mock_emailer).await;
+    wait_for_recipients(
+        &mock_emailer,
+        TemplateId::FinalMatchCreated,
+        &[FEMALE_EMAIL, MALE_EMAIL],
+    )
+    .await;
+    assert_eq!(
+        recipients(&mock_emailer, TemplateId::FinalMatchCreated),
+        [FEMALE_EMAIL, MALE_EMAIL]
+    );
+
+    // The partner learns about the acceptance
+    accept_final_match(&client, &address, &male_token).await;
+    wait_for_recipients(&mock_emailer, TemplateId::PartnerAccepted, &[FEMALE_EMAIL]).await;
+    assert_eq!(
+        recipients(&mock_emailer, TemplateId::PartnerAccepted),
+        [FEMALE_EMAIL]
+    );
+
+    // Opted out of rejections, so the male user is not notified
+    let response = update_preferences(
+        &client,
+        &address,
+        &male_token,
+        &json!({ "match_rejected": false }),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let response = reject_final_match(&client, &address, &female_token, json!({})).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_not_notified(&pool, &mock_emailer, TemplateId::MatchRejected).await;
+}
+
+#[sqlx::test]
+async fn test_auto_confirmation_notifications(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let (_, female_token) = setup_two_matched_users(&client, &address, &mock_emailer).await;
+    let response = update_preferences(
+        &client,
+        &address,
+        &female_token,
+        &json!({ "match_auto_confirmed": false }),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    sqlx::query!("UPDATE final_matches SET auto_accept_at = NOW() - INTERVAL '1 minute'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    SchedulerService::auto_accept_expired_matches(&pool)
+        .await
+        .unwrap();
+
+    let statuses =
+        sqlx::query_scalar!(r#"SELECT status::text AS "status!" FROM users ORDER BY email"#)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(statuses, ["confirmed", "confirmed"]);
+    wait_for_recipients(&mock_emailer, TemplateId::MatchAutoConfirmed, &[MALE_EMAIL]).await;
+    // Opted out, so nothing was enqueued for the female user
+    let enqueued = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM email_outbox WHERE recipient = $1 AND template = 'match_auto_confirmed'",
+        FEMALE_EMAIL
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(enqueued, Some(0));
+    assert_eq!(
+        recipients(&mock_emailer, TemplateId::MatchAutoConfirmed),
+        [MALE_EMAIL]
+    );
+}
+
+#[sqlx::test]
+async fn test_auto_confirmation_skips_confirmed_users(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let (_, female_token) = setup_two_matched_users(&client, &address, &mock_emailer).await;
+    accept_final_match(&client, &address, &female_token).await;
+
+    sqlx::query!("UPDATE final_matches SET auto_accept_at = NOW() - INTERVAL '1 minute'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    SchedulerService::auto_accept_expired_matches(&pool)
+        .await
+        .unwrap();
+
+    // Only the user who had not responded is told about the auto-confirmation
+    wait_for_recipients(&mock_emailer, TemplateId::MatchAutoConfirmed, &[MALE_EMAIL]).await;
+    let enqueued = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM email_outbox WHERE recipient = $1 AND template = 'match_auto_confirmed'",
+        FEMALE_EMAIL
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(enqueued, Some(0));
+    assert_eq!(
+        recipients(&mock_emailer, TemplateId::MatchAutoConfirmed),
+        [MALE_EMAIL]
+    );
+}
+
+#[sqlx::test]
+async fn test_reminder_before_auto_confirmation(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let (male_token, _) = setup_two_matched_users(&client, &address, &mock_emailer).await;
+    accept_final_match(&client, &address, &male_token).await;
+
+    // Deadlines of the round are fixed on the match
+    let final_match =
+        sqlx::query!("SELECT created_at, auto_accept_at, reminder_at FROM final_matches")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    let expected = time::Duration::hours(DEFAULT_AUTO_ACCEPT_HOURS.into());
+    let auto_accept_after = final_match.auto_accept_at - final_match.created_at;
+    assert!((auto_accept_after - expected).abs() < time::Duration::seconds(5));
+    assert_eq!(
+        final_match.auto_accept_at - final_match.reminder_at,
+        time::Duration::hours(DEFAULT_REMINDER_HOURS.into())
+    );
+
+    // Not due yet
+    SchedulerService::send_match_reminders(&pool).await.unwrap();
+    assert_not_notified(&pool, &mock_emailer, TemplateId::MatchReminder).await;
+
+    sqlx::query!(
+        "UPDATE final_matches SET reminder_at = NOW(), auto_accept_at = NOW() + INTERVAL '5 hours 30 minutes'",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    SchedulerService::send_match_reminders(&pool).await.unwrap();
+    SchedulerService::send_match_reminders(&pool).await.unwrap();
+
+    // Only the user who has not responded is reminded, and only once
+    wait_for_recipients(&mock_emailer, TemplateId::MatchReminder, &[FEMALE_EMAIL]).await;
+    let reminders =
+        sqlx::query_scalar!("SELECT COUNT(*) FROM email_outbox WHERE template = 'match_reminder'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(reminders, Some(1));
+    assert_eq!(
+        recipients(&mock_emailer, TemplateId::MatchReminder),
+        [FEMALE_EMAIL]
+    );
+    let reminder = mock_emailer
+        .wait_for_email(|email| email.template == TemplateId::MatchReminder)
+        .await;
+    assert!(
+        reminder.text.contains("within 6 hours"),
+        "{}",
+        reminder.text
+    );
+
+    let reminded = sqlx::query_scalar!("SELECT reminded_at FROM final_matches")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(reminded.is_some());
+}
+
+#[sqlx::test]
+async fn test_reminder_opt_out_and_expired_matches(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let (male_token, _) = setup_two_matched_users(&client, &address, &mock_emailer).await;
+    let response = update_preferences(
+        &client,
+        &address,
+        &male_token,
+        &json!({ "match_reminder": false }),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // Expired matches are auto-accepted instead of reminded
+    sqlx::query!("UPDATE final_matches SET reminder_at = NOW(), auto_accept_at = NOW()")
+        .execute(&pool)
+        .await
+        .unwrap();
+    SchedulerService::send_match_reminders(&pool).await.unwrap();
+    assert_not_notified(&pool, &mock_emailer, TemplateId::MatchReminder).await;
+
+    // The male user opted out, so only the female user is reminded
+    sqlx::query!("UPDATE final_matches SET auto_accept_at = NOW() + INTERVAL '1 hour'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    SchedulerService::send_match_reminders(&pool).await.unwrap();
+    wait_for_recipients(&mock_emailer, TemplateId::MatchReminder, &[FEMALE_EMAIL]).await;
+    let enqueued = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM email_outbox WHERE recipient = $1 AND template = 'match_reminder'",
+        MALE_EMAIL
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(enqueued, Some(0));
+    assert_eq!(
+        recipients(&mock_emailer, TemplateId::MatchReminder),
+        [FEMALE_EMAIL]
+    );
+}
+
+#[sqlx::test]
+async fn test_round_deadlines(pool: PgPool) {
+    let app = spawn_admin_app(pool).await;
+    let client = reqwest::Client::new();
+
+    let deadlines = RoundDeadlines {
+        auto_accept_hours: Some(12),
+        reminder_hours: Some(2),
+    };
+    assert!(deadlines.validate().is_ok());
+    let now = OffsetDateTime::now_utc();
+    assert_eq!(
+        deadlines.schedule(now),
+        (
+            now + time::Duration::hours(12),
+            now + time::Duration::hours(10)
+        )
+    );
+
+    // The reminder must be sent before the auto-confirmation
+    for query in [
+        "auto_accept_hours=0",
+        "reminder_hours=-1",
+        "auto_accept_hours=4",
+        "auto_accept_hours=6&reminder_hours=6",
+        "auto_accept_hours=721",
+        "auto_accept_hours=2147483647",
+    ] {
+        let response = client
+            .post(format!("{}/api/admin/trigger-match?{query}", app.address))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            response.status(),
+            reqwest::StatusCode::BAD_REQUEST,
+            "{query}"
+        );
+    }
+}
@@ -2,16 +2,23 @@
 //!
 //! NOTE: Only this test uses `tests/data/tags.test.json` to load a simplified tag system.
 //! Other tests use the full tag system from `tags.json` to ensure comprehensive coverage.
+//! Traits always come from `traits.json`.
 
-use std::{collections::HashMap, sync::LazyLock};
+mod common;
 
+use std::{
+    collections::{HashMap, HashSet},
+    sync::LazyLock,
+};
+
+use common::{FORM_SCHEMA, create_form};
 use hilo::{
-    models::{Form, Gender, TagSystem},
+    models::{Answer, Catalogs, Form, FormSchema, Gender, TagSystem},
     services::matching::MatchingService,
 };
 use uuid::Uuid;
 
-static TAG_SYSTEM: LazyLock<TagSystem> = LazyLock::new(|| {
+static CATALOGS: LazyLock<Catalogs> = LazyLock::new(|| {
     dotenvy::from_filename_override("tests/data/.test.env").unwrap();
     tracing_subscriber::fmt()
         .with_env_filter("trace")
@@ -20,12 +27,21 @@ static TAG_SYSTEM: LazyLock<TagSystem> = LazyLock::new(|| {
 
     let test_tags_json = std::fs::read_to_string("tests/data/tags.test.json")
         .expect("Failed to read test_tags.json");
+    let traits_json = std::fs::read_to_string("traits.json").expect("Failed to read traits.json");
 
-    TagSystem::from_json(&test_tags_json).expect("Failed to load test tag system")
+    Catalogs::from_json(&test_tags_json, &traits_json).expect("Failed to load test catalogs")
 });
 
-fn get_test_tag_system() -> &'static TagSystem {
-    &TAG_SYSTEM
+fn get_test_catalogs() -> &'static Catalogs {
+    &CATALOGS
+}
+
+fn trait_system() -> &'static TagSystem {
+    &CATALOGS.traits
+}
+
+fn schema() -> &'static FormSchema {
+    &FORM_SCHEMA
 }
 
 fn create_test_form(
@@ -35,25 +51,26 @@ fn create_test_form(
     aspirational_tags: Vec<String>,
     self_traits: Vec<String>,
     ideal_traits: Vec<String>,
-    physical_boundary: i16,
+    physical_boundary: i64,
 ) -> Form {
-    Form {
+    create_form(
         user_id,
         gender,
-        familiar_tags,
-        aspirational_tags,
-        recent_topics: "Test topic".to_string(),
-        self_traits,
-        ideal_traits,
-        physical_boundary,
-        self_intro: "Test intro".to_string(),
-        profile_photo_filename: None,
-    }
+        [
+            ("familiar_tags", Answer::Choices(familiar_tags)),
+            ("aspirational_tags", Answer::Choices(aspirational_tags)),
+            ("recent_topics", Answer::Text("Test topic".to_string())),
+            ("self_traits", Answer::Choices(self_traits)),
+            ("ideal_traits", Answer::Choices(ideal_traits)),
+            ("physical_boundary", Answer::Scale(physical_boundary)),
+            ("self_intro", Answer::Text("Test intro".to_string())),
+        ],
+    )
 }
 
 #[test]
 fn test_gender_compatibility_filter() {
-    let tag_system = get_test_tag_system();
+    let catalogs = get_test_catalogs();
     let tag_frequencies = HashMap::new();
 
     // Same gender users should get -1 score
@@ -77,8 +94,14 @@ fn test_gender_compatibility_filter() {
         3,
     );
 
-    let score =
-        MatchingService::calculate_match_score(&user1, &user2, tag_system, &tag_frequencies, 20);
+    let score = MatchingService::calculate_match_score(
+        &user1,
+        &user2,
+        schema(),
+        catalogs,
+        &tag_frequencies,
+        20,
+    );
 
     assert_eq!(
         score, -1.0,
@@ -88,7 +111,7 @@ fn test_gender_compatibility_filter() {
 
 #[test]
 fn test_physical_boundary() {
-    let tag_system = get_test_tag_system();
+    let catalogs = get_test_catalogs();
     let tag_frequencies = HashMap::new();
 
     // Incompatible physical boundaries should get -1 score
@@ -112,8 +135,14 @@ fn test_physical_boundary() {
         3, // High intimacy (difference > 1)
     );
 
-    let score =
-        MatchingService::calculate_match_score(&user1, &user2, tag_system, &tag_frequencies, 20);
+    let score = MatchingService::calculate_match_score(
+        &user1,
+        &user2,
+        schema(),
+        catalogs,
+        &tag_frequencies,
+        20,
+    );
 
     assert_eq!(
         score, -1.0,
@@ -130,8 +159,14 @@ fn test_physical_boundary() {
         2, // Acceptable intimacy (difference = 1)
     );
 
-    let score =
-        MatchingService::calculate_match_score(&user1, &user2, tag_system, &tag_frequencies, 20);
+    let score = MatchingService::calculate_match_score(
+        &user1,
+        &user2,
+        schema(),
+        catalogs,
+        &tag_frequencies,
+        20,
+    );
 
     assert_eq!(
         score, 0.0,
@@ -148,8 +183,14 @@ fn test_physical_boundary() {
         1, // Exact same intimacy
     );
 
-    let score =
-        MatchingService::calculate_match_score(&user1, &user2, tag_system, &tag_frequencies, 20);
+    let score = MatchingService::calculate_match_score(
+        &user1,
+        &user2,
+        schema(),
+        catalogs,
+        &tag_frequencies,
+        20,
+    );
 
     assert_eq!(
         score, 1.0,
@@ -159,7 +200,7 @@ fn test_physical_boundary() {
 
 #[test]
 fn test_hierarchical_tag_matching() {
-    let tag_system = get_test_tag_system();
+    let catalogs = get_test_catalogs();
     let tag_frequencies = HashMap::from([
         // -- Sports tags
         ("sports".to_string(), 15),
@@ -200,7 +241,8 @@ fn test_hierarchical_tag_matching() {
     let score_diff = MatchingService::calculate_match_score(
         &primary_user,
         &user1,
-        tag_system,
+        schema(),
+        catalogs,
         &tag_frequencies,
         20,
     );
@@ -224,7 +266,8 @@ fn test_hierarchical_tag_matching() {
     let score_exact = MatchingService::calculate_match_score(
         &primary_user,
         &user2,
-        tag_system,
+        schema(),
+        catalogs,
         &tag_frequencies,
         20,
     );
@@ -247,7 +290,8 @@ fn test_hierarchical_tag_matching() {
     let zero_score = MatchingService::calculate_match_score(
         &primary_user,
         &user3,
-        tag_system,
+        schema(),
+        catalogs,
         &tag_frequencies,
         20,
     );
@@ -260,7 +304,7 @@ fn test_hierarchical_tag_matching() {
 
 #[test]
 fn test_idf_scoring() {
-    let tag_system = get_test_tag_system();
+    let catalogs = get_test_catalogs();
     let tag_frequencies = HashMap::from([
         // -- Sports tags
         ("soccer".to_string(), 10),    // common
@@ -290,8 +334,14 @@ fn test_idf_scoring() {
         2,
     );
 
-    let score_common =
-        MatchingService::calculate_match_score(&user1, &user2, tag_system, &tag_frequencies, 20);
+    let score_common = MatchingService::calculate_match_score(
+        &user1,
+        &user2,
+        schema(),
+        catalogs,
+        &tag_frequencies,
+        20,
+    );
 
     let user1 = create_test_form(
         Uuid::new_v4(),
@@ -313,8 +363,14 @@ fn test_idf_scoring() {
         2,
     );
 
-    let score_rare =
-        MatchingService::calculate_match_score(&user1, &user2, tag_system, &tag_frequencies, 20);
+    let score_rare = MatchingService::calculate_match_score(
+        &user1,
+        &user2,
+        schema(),
+        catalogs,
+        &tag_frequencies,
+        20,
+    );
 
     assert!(
         score_rare > score_common,
@@ -324,7 +380,7 @@ fn test_idf_scoring() {
 
 #[test]
 fn test_trait_matching() {
-    let tag_system = get_test_tag_system();
+    let catalogs = get_test_catalogs();
     let tag_frequencies = HashMap::new();
 
     // Perfect trait compatibility
@@ -348,8 +404,14 @@ fn test_trait_matching() {
         3,
     );
 
-    let score_perfect =
-        MatchingService::calculate_match_score(&user1, &user2, tag_system, &tag_frequencies, 20);
+    let score_perfect = MatchingService::calculate_match_score(
+        &user1,
+        &user2,
+        schema(),
+        catalogs,
+        &tag_frequencies,
+        20,
+    );
 
     // No trait compatibility
     let user3 = create_test_form(
@@ -362,8 +424,14 @@ fn test_trait_matching() {
         3,
     );
 
-    let score_no_match =
-        MatchingService::calculate_match_score(&user1, &user3, tag_system, &tag_frequencies, 20);
+    let score_no_match = MatchingService::calculate_match_score(
+        &user1,
+        &user3,
+        schema(),
+        catalogs,
+        &tag_frequencies,
+        20,
+    );
 
     assert!(
         score_perfect > score_no_match,
@@ -371,9 +439,155 @@ fn test_trait_matching() {
     );
 }
 
+#[test]
+fn test_trait_partial_credit() {
+    // Exact matches get full credit, traits in the same group of traits.json get a
+    // decayed share, unrelated traits get nothing
+    assert_eq!(
+        MatchingService::calculate_trait_credit("humor", "humor", trait_system()),
+        1.0
+    );
+    assert_eq!(
+        MatchingService::calculate_trait_credit("humor", "optimistic", trait_system()),
+        0.5
+    );
+    assert_eq!(
+        MatchingService::calculate_trait_credit("explorer", "sports_lover", trait_system()),
+        0.5
+    );
+    assert_eq!(
+        MatchingService::calculate_trait_credit("humor", "sports_lover", trait_system()),
+        0.0
+    );
+
+    let to_vec = |traits: &[&str]| traits.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+    let ideal = to_vec(&["humor", "explorer"]);
+
+    let exact = MatchingService::calculate_trait_compatibility(
+        &ideal,
+        &to_vec(&["humor", "explorer"]),
+        trait_system(),
+        0.0,
+        0.0,
+    );
+    let related = MatchingService::calculate_trait_compatibility(
+        &ideal,
+        &to_vec(&["optimistic", "sports_lover"]),
+        trait_system(),
+        0.0,
+        0.0,
+    );
+    let unrelated = MatchingService::calculate_trait_compatibility(
+        &ideal,
+        &to_vec(&["bookworm", "reliable"]),
+        trait_system(),
+        0.0,
+        0.0,
+    );
+    assert_eq!(exact, 4.0);
+    assert_eq!(related, 2.0);
+    assert_eq!(unrelated, 0.0);
+}
+
+#[test]
+fn test_trait_asymmetric_weighting() {
+    let to_vec = |traits: &[&str]| traits.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+    let ideal = to_vec(&["humor", "reliable"]);
+
+    // One desired trait missing
+    let missing = MatchingService::calculate_trait_compatibility(
+        &ideal,
+        &to_vec(&["humor"]),
+        trait_system(),
+        1.0,
+        0.25,
+    );
+    // All desired traits present, plus an extra one
+    let extra = MatchingService::calculate_trait_compatibility(
+        &ideal,
+        &to_vec(&["humor", "reliable", "bookworm"]),
+        trait_system(),
+        1.0,
+        0.25,
+    );
+    let exact = MatchingService::calculate_trait_compatibility(
+        &ideal,
+        &to_vec(&["humor", "reliable"]),
+        trait_system(),
+        1.0,
+        0.25,
+    );
+
+    assert_eq!(missing, (1.0 - 1.0) * 2.0);
+    assert_eq!(extra, (2.0 - 0.25) * 2.0);
+    assert_eq!(exact, 4.0);
+    assert!(
+        missing < extra,
+        "A missing desired trait should cost more than an extra one"
+    );
+
+    // Without ideal traits there is nothing to satisfy or penalize
+    assert_eq!(
+        MatchingService::calculate_trait_compatibility(
+            &[],
+            &to_vec(&["humor"]),
+            trait_system(),
+            1.0,
+            0.25
+        ),
+        0.0
+    );
+}
+
+#[test]
+fn test_diverse_candidate_selection() {
+    let popular = Uuid::new_v4();
+    let lookalike = Uuid::new_v4();
+    let different = Uuid::new_v4();
+
+    let tag_sets: HashMap<Uuid, HashSet<&str>> = [
+        (popular, HashSet::from(["volleyball", "tennis"])),
+        (lookalike, HashSet::from(["volleyball", "tennis"])),
+        (different, HashSet::from(["reading"])),
+    ]
+    .into_iter()
+    .collect();
+    let candidates = vec![(different, 8.0), (lookalike, 9.5), (popular, 10.0)];
+    let ids =
+        |selected: Vec<(Uuid, f64)>| selected.into_iter().map(|(id, _)| id).collect::<Vec<_>>();
+
+    // Without diversity the top scores win
+    let by_score = MatchingService::select_diverse_candidates(
+        candidates.clone(),
+        &tag_sets,
+        &HashMap::new(),
+        2,
+        0.0,
+        usize::MAX,
+    );
+    assert_eq!(ids(by_score), vec![popular, lookalike]);
+
+    // With diversity a candidate with the same tags as an already picked one loses
+    let diverse = MatchingService::select_diverse_candidates(
+        candidates.clone(),
+        &tag_sets,
+        &HashMap::new(),
+        2,
+        0.5,
+        usize::MAX,
+    );
+    assert_eq!(ids(diverse), vec![popular, different]);
+
+    // Candidates already at the exposure cap are skipped
+    let exposure = HashMap::from([(popular, 3)]);
+    let capped =
+        MatchingService::select_diverse_candidates(candidates, &tag_sets, &exposure, 2, 0.0, 3);
+    assert_eq!(ids(capped), vec![lookalike, different]);
+}
+
 #[test]
 fn test_asymmetric_tag_matching() {
-    let tag_system = get_test_tag_system();
+    let catalogs = get_test_catalogs();
     let tag_frequencies = HashMap::from([
         // -- Sports tags
         ("soccer".to_string(), 10),    // common
@@ -403,8 +617,14 @@ fn test_asymmetric_tag_matching() {
         2,
     );
 
-    let score_common =
-        MatchingService::calculate_match_score(&user1, &user2, tag_system, &tag_frequencies, 20);
+    let score_common = MatchingService::calculate_match_score(
+        &user1,
+        &user2,
+        schema(),
+        catalogs,
+        &tag_frequencies,
+        20,
+    );
 
     assert!(
         score_common > 0.0,
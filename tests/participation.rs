@@ -0,0 +1,265 @@
+mod common;
+
+use common::*;
+use hilo::models::UserStatus;
+use serde_json::{Value, json};
+use sqlx::{Connection, PgConnection, PgPool};
+
+async fn post_participation(
+    client: &reqwest::Client,
+    address: &str,
+    token: &str,
+    action: &str,
+) -> reqwest::Response {
+    client
+        .post(format!("{address}/api/participation/{action}"))
+        .header("Authorization", format!("Bearer {token}"))
+        .send()
+        .await
+        .expect("Failed to send participation request")
+}
+
+async fn preview_count(client: &reqwest::Client, address: &str, token: &str) -> usize {
+    let response = client
+        .get(format!("{address}/api/veto/previews"))
+        .header("Authorization", format!("Bearer {token}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let previews: Vec<Value> = response.json().await.unwrap();
+    previews.len()
+}
+
+#[sqlx::test]
+async fn test_pause_and_resume(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let male_token = complete_form(
+        &client,
+        &address,
+        &mock_emailer,
+        MALE_EMAIL,
+        create_male_form_submission(),
+    )
+    .await;
+    let female_token = complete_form(
+        &client,
+        &address,
+        &mock_emailer,
+        FEMALE_EMAIL,
+        create_female_form_submission(),
+    )
+    .await;
+    assert_eq!(preview_count(&client, &address, &female_token).await, 1);
+
+    let response = post_participation(&client, &address, &male_token, "pause").await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let profile: Value = response.json().await.unwrap();
+    assert_eq!(profile["status"], "paused");
+
+    // Paused user no longer appears in previews and has none of their own
+    assert_eq!(preview_count(&client, &address, &female_token).await, 0);
+    assert_eq!(preview_count(&client, &address, &male_token).await, 0);
+
+    // Paused user can edit the form again without re-entering the pool
+    let mut form = create_male_form_submission();
+    form["self_intro"] = json!("An updated introduction written while paused.");
+    let response = client
+        .post(format!("{address}/api/form"))
+        .header("Authorization", format!("Bearer {male_token}"))
+        .json(&form)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let status = sqlx::query_scalar!(
+        r#"SELECT status as "status: UserStatus" FROM users WHERE email = $1"#,
+        MALE_EMAIL
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(status, UserStatus::Paused);
+
+    // Pausing twice is rejected
+    let response = post_participation(&client, &address, &male_token, "pause").await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let response = post_participation(&client, &address, &male_token, "resume").await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let profile: Value = response.json().await.unwrap();
+    assert_eq!(profile["status"], "form_completed");
+
+    // Previews are regenerated on resume
+    assert_eq!(preview_count(&client, &address, &female_token).await, 1);
+    assert_eq!(preview_count(&client, &address, &male_token).await, 1);
+}
+
+#[sqlx::test]
+async fn test_withdraw_excludes_from_final_matching(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let male_token = complete_form(
+        &client,
+        &address,
+        &mock_emailer,
+        MALE_EMAIL,
+        create_male_form_submission(),
+    )
+    .await;
+    complete_form(
+        &client,
+        &address,
+        &mock_emailer,
+        FEMALE_EMAIL,
+        create_female_form_submission(),
+    )
+    .await;
+
+    let response = post_participation(&client, &address, &male_token, "withdraw").await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let profile: Value = response.json().await.unwrap();
+    assert_eq!(profile["status"], "withdrawn");
+
+    // Form is kept but locked
+    let response = client
+        .get(format!("{address}/api/form"))
+        .header("Authorization", format!("Bearer {male_token}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let response = client
+        .post(format!("{address}/api/form"))
+        .header("Authorization", format!("Bearer {male_token}"))
+        .json(&create_male_form_submission())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+
+    let result = admin_trigger_final_match(&client, &address).await;
+    assert_eq!(result["matches_created"], 0);
+
+    // Withdrawn users can rejoin and are matched again
+    let response = post_participation(&client, &address, &male_token, "resume").await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let result = admin_trigger_final_match(&client, &address).await;
+    assert_eq!(result["matches_created"], 1);
+}
+
+#[sqlx::test]
+async fn test_pause_during_final_matching(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    complete_form(
+        &client,
+        &address,
+        &mock_emailer,
+        MALE_EMAIL,
+        create_male_form_submission(),
+    )
+    .await;
+    complete_form(
+        &client,
+        &address,
+        &mock_emailer,
+        FEMALE_EMAIL,
+        create_female_form_submission(),
+    )
+    .await;
+
+    // Pause the user in a transaction that stays open while the pool is fetched
+    let mut tx = pool.begin().await.unwrap();
+    sqlx::query!(
+        "UPDATE users SET status = 'paused' WHERE email = $1",
+        MALE_EMAIL
+    )
+    .execute(tx.as_mut())
+    .await
+    .unwrap();
+
+    let matching = tokio::spawn({
+        let client = client.clone();
+        let address = address.clone();
+        async move { admin_trigger_final_match(&client, &address).await }
+    });
+
+    // Commit the pause once matching waits to persist the pair. The app may hold
+    // every pooled connection meanwhile, so watch on a connection of our own.
+    let mut watcher = PgConnection::connect_with(&pool.connect_options())
+        .await
+        .unwrap();
+    tokio::time::timeout(std::time::Duration::from_secs(10), async {
+        loop {
+            let waiting = sqlx::query_scalar!(
+                r#"SELECT COUNT(*) as "count!" FROM pg_locks WHERE NOT granted"#
+            )
+            .fetch_one(&mut watcher)
+            .await
+            .unwrap();
+            if waiting > 0 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("Final matching never waited for the paused user");
+    tx.commit().await.unwrap();
+
+    let result = matching.await.unwrap();
+    assert_eq!(result["matches_created"], 0);
+    let final_matches = sqlx::query_scalar!("SELECT COUNT(*) FROM final_matches")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(final_matches, Some(0));
+    for (email, status) in [
+        (MALE_EMAIL, UserStatus::Paused),
+        (FEMALE_EMAIL, UserStatus::FormCompleted),
+    ] {
+        let current = sqlx::query_scalar!(
+            r#"SELECT status as "status: UserStatus" FROM users WHERE email = $1"#,
+            email
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(current, status);
+    }
+}
+
+#[sqlx::test]
+async fn test_participation_requires_completed_form(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let token = get_access_token(
+        &client,
+        &address,
+        &mock_emailer,
+        "unverified@mails.tsinghua.edu.cn",
+    )
+    .await;
+
+    for action in ["pause", "withdraw", "resume"] {
+        let response = post_participation(&client, &address, &token, action).await;
+        assert_eq!(
+            response.status(),
+            reqwest::StatusCode::BAD_REQUEST,
+            "{action}"
+        );
+    }
+
+    // Matched users cannot leave the pool either
+    let (male_token, _female_token) =
+        setup_two_matched_users(&client, &address, &mock_emailer).await;
+    let response = post_participation(&client, &address, &male_token, "pause").await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+}
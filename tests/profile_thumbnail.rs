@@ -79,8 +79,8 @@ async fn test_fetch_thumbnail_success(pool: PgPool) {
 
     // Create a form entry for the user
     sqlx::query!(
-        r#"INSERT INTO forms (user_id, profile_photo_filename, gender, familiar_tags, aspirational_tags, recent_topics, self_traits, ideal_traits, physical_boundary, self_intro)
-         VALUES ($1, $2, 'male', '{}', '{}', 'test topics', '{}', '{}', 2, 'test intro')"#,
+        r#"INSERT INTO forms (user_id, profile_photo_filename, gender, answers)
+         VALUES ($1, $2, 'male', '{"familiar_tags": [], "aspirational_tags": [], "recent_topics": "test topics", "self_traits": [], "ideal_traits": [], "physical_boundary": 2, "self_intro": "test intro"}')"#,
         user_id,
         filename
     )
@@ -223,8 +223,8 @@ async fn test_fetch_thumbnail_not_found_no_photo(pool: PgPool) {
 
     // Create form without profile photo
     sqlx::query!(
-        r#"INSERT INTO forms (user_id, profile_photo_filename, gender, familiar_tags, aspirational_tags, recent_topics, self_traits, ideal_traits, physical_boundary, self_intro)
-         VALUES ($1, $2, 'male', '{}', '{}', 'test topics', '{}', '{}', 2, 'test intro')"#,
+        r#"INSERT INTO forms (user_id, profile_photo_filename, gender, answers)
+         VALUES ($1, $2, 'male', '{"familiar_tags": [], "aspirational_tags": [], "recent_topics": "test topics", "self_traits": [], "ideal_traits": [], "physical_boundary": 2, "self_intro": "test intro"}')"#,
         user_id,
         None::<String>
     )
@@ -1,6 +1,6 @@
 use hilo::models::{
     CreateScheduledMatchRequest, CreateScheduledMatchesRequest, NextMatchTimeResponse,
-    ScheduledFinalMatch,
+    RoundDeadlines, ScheduledFinalMatch,
 };
 use time::OffsetDateTime;
 
@@ -22,9 +22,14 @@ async fn test_create_and_get_scheduled_matches(pool: sqlx::PgPool) {
         scheduled_times: vec![
             CreateScheduledMatchRequest {
                 scheduled_time: future_time_1,
+                deadlines: RoundDeadlines::default(),
             },
             CreateScheduledMatchRequest {
                 scheduled_time: future_time_2,
+                deadlines: RoundDeadlines {
+                    auto_accept_hours: Some(12),
+                    reminder_hours: Some(2),
+                },
             },
         ],
     };
@@ -52,6 +57,9 @@ async fn test_create_and_get_scheduled_matches(pool: sqlx::PgPool) {
         created_matches[1].status,
         hilo::models::ScheduleStatus::Pending
     );
+    assert_eq!(created_matches[0].auto_accept_hours, None);
+    assert_eq!(created_matches[1].auto_accept_hours, Some(12));
+    assert_eq!(created_matches[1].reminder_hours, Some(2));
 
     // Test getting all scheduled matches
     let response = client
@@ -135,6 +143,7 @@ async fn test_get_next_match_time(pool: sqlx::PgPool) {
     let create_request = CreateScheduledMatchesRequest {
         scheduled_times: vec![CreateScheduledMatchRequest {
             scheduled_time: future_time,
+            deadlines: RoundDeadlines::default(),
         }],
     };
 
@@ -181,6 +190,7 @@ async fn test_create_scheduled_match_past_time_fails(pool: sqlx::PgPool) {
     let create_request = CreateScheduledMatchesRequest {
         scheduled_times: vec![CreateScheduledMatchRequest {
             scheduled_time: past_time,
+            deadlines: RoundDeadlines::default(),
         }],
     };
 
@@ -194,6 +204,36 @@ async fn test_create_scheduled_match_past_time_fails(pool: sqlx::PgPool) {
     assert_eq!(response.status().as_u16(), 400);
 }
 
+#[sqlx::test]
+async fn test_create_scheduled_match_invalid_deadlines_fails(pool: sqlx::PgPool) {
+    let (address, _) = spawn_app(pool).await;
+    let client = reqwest::Client::new();
+    let future_time = OffsetDateTime::now_utc() + time::Duration::hours(1);
+
+    for (auto_accept_hours, reminder_hours) in
+        [(Some(0), Some(1)), (Some(4), None), (Some(6), Some(6))]
+    {
+        let create_request = CreateScheduledMatchesRequest {
+            scheduled_times: vec![CreateScheduledMatchRequest {
+                scheduled_time: future_time,
+                deadlines: RoundDeadlines {
+                    auto_accept_hours,
+                    reminder_hours,
+                },
+            }],
+        };
+
+        let response = client
+            .post(format!("{}/api/admin/scheduled-matches", &address))
+            .json(&create_request)
+            .send()
+            .await
+            .expect("Failed to execute request.");
+
+        assert_eq!(response.status().as_u16(), 400);
+    }
+}
+
 #[sqlx::test]
 async fn test_cancel_nonexistent_scheduled_match(pool: sqlx::PgPool) {
     let (address, _) = spawn_app(pool).await;
@@ -1,6 +1,7 @@
 mod common;
 
-use common::spawn_app;
+use common::{extract_verification_code, spawn_app};
+use hilo::models::TemplateId;
 use serde_json::json;
 use sqlx::PgPool;
 
@@ -24,11 +25,20 @@ async fn send_verification_code_works(pool: PgPool) {
     }
 
     // Verify that email was sent through MockEmailer
-    assert_eq!(mock_emailer.sent_count(), 2);
+    assert_eq!(mock_emailer.wait_for_count(2).await, 2);
 
-    let sent_email = mock_emailer.last_sent_email().expect("No email was sent");
-    assert_eq!(&sent_email.recipient, available_emails.last().unwrap());
-    assert!(sent_email.body_html.contains("Your verification code is:"));
+    for test_email in available_emails {
+        let sent_email = mock_emailer
+            .wait_for_email(|email| email.recipient == test_email)
+            .await;
+        assert_eq!(sent_email.template, TemplateId::VerificationCode);
+        assert!(sent_email.text.starts_with("Your verification code is:"));
+        assert!(
+            sent_email
+                .html
+                .contains(extract_verification_code(&sent_email.text))
+        );
+    }
 }
 
 #[sqlx::test]
@@ -69,7 +79,7 @@ async fn send_verification_code_rate_limit(pool: PgPool) {
         .expect("Failed to execute first request");
 
     assert_eq!(response1.status(), reqwest::StatusCode::ACCEPTED);
-    assert_eq!(mock_emailer.sent_count(), 1);
+    assert_eq!(mock_emailer.wait_for_count(1).await, 1);
 
     // Send second request immediately (should be rate limited)
     let response2 = client
@@ -0,0 +1,401 @@
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use hilo::{
+    models::{EmailMessage, TemplateId},
+    services::email::{EmailError, EmailService, SmtpConfig, SmtpEmailer, SmtpSecurity},
+    utils::static_object::EMAIL_TEMPLATES,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+};
+
+const SENDER_EMAIL: &str = "noreply@example.com";
+const RECIPIENT_EMAIL: &str = "user@mails.tsinghua.edu.cn";
+const REJECTED_EMAIL: &str = "unknown@mails.tsinghua.edu.cn";
+
+/// `AUTH PLAIN` initial response for `smtp-user` / `smtp-pass`
+const EXPECTED_AUTH: &str = "AUTH PLAIN AHNtdHAtdXNlcgBzbXRwLXBhc3M=";
+
+/// An email received by the SMTP sink
+#[derive(Debug, Clone)]
+struct ReceivedEmail {
+    auth: Option<String>,
+    from: String,
+    to: Vec<String>,
+    data: String,
+}
+
+/// A local SMTP server accepting every email except those to `REJECTED_EMAIL`
+///
+/// When advertising `STARTTLS`, the sink hangs up instead of completing the TLS
+/// handshake, as the emailer only trusts certificates of public authorities.
+#[derive(Default)]
+struct SmtpSink {
+    starttls: bool,
+    commands: Mutex<Vec<String>>,
+    received: Mutex<Vec<ReceivedEmail>>,
+    connections: AtomicUsize,
+}
+
+impl SmtpSink {
+    /// Starts the sink on a random port, returning it along with the port
+    async fn spawn() -> (Arc<Self>, u16) {
+        Self::listen(Self::default()).await
+    }
+
+    /// Starts a sink advertising `STARTTLS`
+    async fn spawn_starttls() -> (Arc<Self>, u16) {
+        Self::listen(Self {
+            starttls: true,
+            ..Self::default()
+        })
+        .await
+    }
+
+    async fn listen(sink: Self) -> (Arc<Self>, u16) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let sink = Arc::new(sink);
+
+        let server = Arc::clone(&sink);
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                server.connections.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(Arc::clone(&server).handle(stream));
+            }
+        });
+
+        (sink, port)
+    }
+
+    async fn handle(self: Arc<Self>, stream: tokio::net::TcpStream) {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        let mut reply = async |line: &str| {
+            writer
+                .write_all(format!("{line}\r\n").as_bytes())
+                .await
+                .unwrap();
+        };
+
+        reply("220 sink ESMTP ready").await;
+        let mut auth = None;
+        let mut from = String::new();
+        let mut to = Vec::new();
+
+        while let Ok(Some(line)) = lines.next_line().await {
+            let command = line.to_ascii_uppercase();
+            self.commands.lock().unwrap().push(command.clone());
+            if command.starts_with("EHLO") {
+                reply("250-sink").await;
+                if self.starttls {
+                    reply("250-STARTTLS").await;
+                }
+                reply("250-AUTH PLAIN LOGIN").await;
+                reply("250 8BITMIME").await;
+            } else if command == "STARTTLS" {
+                reply("220 2.0.0 Ready to start TLS").await;
+                break;
+            } else if command.starts_with("AUTH") {
+                auth = Some(line);
+                reply("235 2.7.0 Authentication successful").await;
+            } else if command.starts_with("MAIL FROM:") {
+                from = line[10..].trim().to_string();
+                to.clear();
+                reply("250 2.1.0 Ok").await;
+            } else if command.starts_with("RCPT TO:") {
+                let recipient = line[8..].trim().to_string();
+                if recipient.contains(REJECTED_EMAIL) {
+                    reply("550 5.1.1 Mailbox unavailable").await;
+                } else {
+                    to.push(recipient);
+                    reply("250 2.1.5 Ok").await;
+                }
+            } else if command == "DATA" {
+                reply("354 End data with <CR><LF>.<CR><LF>").await;
+                let mut data = String::new();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if line == "." {
+                        break;
+                    }
+                    data.push_str(&line);
+                    data.push('\n');
+                }
+                self.received.lock().unwrap().push(ReceivedEmail {
+                    auth: auth.clone(),
+                    from: from.clone(),
+                    to: to.clone(),
+                    data,
+                });
+                reply("250 2.0.0 Ok: queued").await;
+            } else if command == "QUIT" {
+                reply("221 2.0.0 Bye").await;
+                break;
+            } else {
+                // RSET, NOOP
+                reply("250 2.0.0 Ok").await;
+            }
+        }
+    }
+
+    fn received(&self) -> Vec<ReceivedEmail> {
+        self.received.lock().unwrap().clone()
+    }
+
+    fn commands(&self) -> Vec<String> {
+        self.commands.lock().unwrap().clone()
+    }
+}
+
+fn smtp_emailer(port: u16, credentials: Option<(String, String)>) -> SmtpEmailer {
+    secure_smtp_emailer(port, SmtpSecurity::None, credentials)
+}
+
+fn secure_smtp_emailer(
+    port: u16,
+    security: SmtpSecurity,
+    credentials: Option<(String, String)>,
+) -> SmtpEmailer {
+    SmtpEmailer::new(SmtpConfig {
+        host: "127.0.0.1".to_string(),
+        port: Some(port),
+        security,
+        credentials,
+        pool_size: 2,
+        sender_email: SENDER_EMAIL.to_string(),
+    })
+    .unwrap()
+}
+
+fn verification_email(recipient: &str, code: &str) -> EmailMessage {
+    let variables = [("code", code), ("expiry_minutes", "5")]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    EMAIL_TEMPLATES
+        .render(recipient, TemplateId::VerificationCode, "en", variables)
+        .unwrap()
+}
+
+fn notification_email(recipient: &str) -> EmailMessage {
+    EMAIL_TEMPLATES
+        .render(
+            recipient,
+            TemplateId::PartnerAccepted,
+            "en",
+            Default::default(),
+        )
+        .unwrap()
+}
+
+/// Undo quoted-printable soft line breaks so the body can be searched
+fn unfold(data: &str) -> String {
+    data.replace("=\n", "")
+}
+
+#[tokio::test]
+async fn test_smtp_emailer_sends_through_relay() {
+    let (sink, port) = SmtpSink::spawn().await;
+    let emailer = smtp_emailer(
+        port,
+        Some(("smtp-user".to_string(), "smtp-pass".to_string())),
+    );
+
+    emailer
+        .send(&verification_email(RECIPIENT_EMAIL, "123456"))
+        .await
+        .unwrap();
+    emailer
+        .send(&notification_email(RECIPIENT_EMAIL))
+        .await
+        .unwrap();
+
+    let received = sink.received();
+    assert_eq!(received.len(), 2);
+    for email in &received {
+        assert_eq!(email.auth.as_deref(), Some(EXPECTED_AUTH));
+        assert_eq!(email.from, format!("<{SENDER_EMAIL}>"));
+        assert_eq!(email.to, [format!("<{RECIPIENT_EMAIL}>")]);
+        // Plain-text and HTML alternatives
+        assert!(email.data.contains("Content-Type: multipart/alternative"));
+        assert!(email.data.contains("Content-Type: text/plain"));
+        assert!(email.data.contains("Content-Type: text/html"));
+    }
+    assert!(
+        received[0]
+            .data
+            .contains("Subject: Your login code to Project Contigo")
+    );
+    assert!(unfold(&received[0].data).contains("123456"));
+    assert!(unfold(&received[1].data).contains("Your partner has accepted your match."));
+
+    // Connections are returned to the pool in the background, so keep sending
+    // until a pooled connection is checked with `NOOP` and reused
+    let mut sent = received.len();
+    while !sink.commands().iter().any(|command| command == "NOOP") {
+        assert!(sent < 20, "No pooled connection was reused");
+        emailer
+            .send(&notification_email(RECIPIENT_EMAIL))
+            .await
+            .unwrap();
+        sent += 1;
+    }
+    assert_eq!(sink.received().len(), sent);
+    assert!(sink.connections.load(Ordering::SeqCst) < sent);
+}
+
+#[tokio::test]
+async fn test_smtp_emailer_upgrades_with_starttls() {
+    let (sink, port) = SmtpSink::spawn_starttls().await;
+    let emailer = secure_smtp_emailer(
+        port,
+        SmtpSecurity::StartTls,
+        Some(("smtp-user".to_string(), "smtp-pass".to_string())),
+    );
+
+    // The sink hangs up instead of completing the handshake
+    let result = emailer
+        .send(&verification_email(RECIPIENT_EMAIL, "123456"))
+        .await;
+    assert!(
+        matches!(result, Err(EmailError::SendFailed(_))),
+        "{result:?}"
+    );
+
+    // Nothing but the greeting is sent before the connection is upgraded
+    let commands = sink.commands();
+    assert_eq!(commands.len(), 2, "{commands:?}");
+    assert!(commands[0].starts_with("EHLO"));
+    assert_eq!(commands[1], "STARTTLS");
+    assert!(sink.received().is_empty());
+}
+
+#[tokio::test]
+async fn test_smtp_emailer_requires_starttls() {
+    let (sink, port) = SmtpSink::spawn().await;
+    let emailer = secure_smtp_emailer(
+        port,
+        SmtpSecurity::StartTls,
+        Some(("smtp-user".to_string(), "smtp-pass".to_string())),
+    );
+
+    // A relay not offering `STARTTLS` never sees the credentials or the email
+    let result = emailer
+        .send(&verification_email(RECIPIENT_EMAIL, "123456"))
+        .await;
+    assert!(result.is_err());
+    let commands = sink.commands();
+    assert!(
+        commands
+            .iter()
+            .all(|command| !command.starts_with("AUTH") && !command.starts_with("MAIL")),
+        "{commands:?}"
+    );
+    assert!(sink.received().is_empty());
+}
+
+#[tokio::test]
+async fn test_smtp_emailer_connects_with_tls() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handshake = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut record = [0; 3];
+        stream.read_exact(&mut record).await.unwrap();
+        record
+    });
+    let emailer = secure_smtp_emailer(port, SmtpSecurity::Tls, None);
+
+    // The relay hangs up instead of completing the handshake
+    let result = emailer
+        .send(&verification_email(RECIPIENT_EMAIL, "123456"))
+        .await;
+    assert!(
+        matches!(result, Err(EmailError::SendFailed(_))),
+        "{result:?}"
+    );
+
+    // The client opens with a TLS handshake record rather than waiting for a greeting
+    let record = handshake.await.unwrap();
+    assert_eq!(record[0], 0x16);
+    assert_eq!(record[1], 0x03);
+}
+
+#[tokio::test]
+async fn test_smtp_emailer_without_authentication() {
+    let (sink, port) = SmtpSink::spawn().await;
+    let emailer = smtp_emailer(port, None);
+
+    emailer
+        .send(&verification_email(RECIPIENT_EMAIL, "654321"))
+        .await
+        .unwrap();
+
+    let received = sink.received();
+    assert_eq!(received.len(), 1);
+    assert!(received[0].auth.is_none());
+}
+
+#[tokio::test]
+async fn test_smtp_emailer_reports_rejection() {
+    let (sink, port) = SmtpSink::spawn().await;
+    let emailer = smtp_emailer(port, None);
+
+    let result = emailer.send(&notification_email(REJECTED_EMAIL)).await;
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("SMTP error"), "{error}");
+    assert!(sink.received().is_empty());
+
+    // Invalid addresses are rejected before contacting the relay
+    let result = emailer.send(&notification_email("not an email")).await;
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid recipient")
+    );
+
+    // The connection stays usable after a rejection
+    emailer
+        .send(&notification_email(RECIPIENT_EMAIL))
+        .await
+        .unwrap();
+    assert_eq!(sink.received().len(), 1);
+}
+
+#[test]
+fn test_smtp_configuration() {
+    assert_eq!(
+        "starttls".parse::<SmtpSecurity>().unwrap(),
+        SmtpSecurity::StartTls
+    );
+    assert_eq!("tls".parse::<SmtpSecurity>().unwrap(), SmtpSecurity::Tls);
+    assert_eq!("none".parse::<SmtpSecurity>().unwrap(), SmtpSecurity::None);
+    assert!("ssl".parse::<SmtpSecurity>().is_err());
+
+    let result = SmtpEmailer::new(SmtpConfig {
+        host: "127.0.0.1".to_string(),
+        port: None,
+        security: SmtpSecurity::StartTls,
+        credentials: None,
+        pool_size: 1,
+        sender_email: "not an email".to_string(),
+    });
+    assert!(matches!(result, Err(EmailError::InvalidConfig(_))));
+
+    // A pool without connections could never send anything
+    let result = SmtpEmailer::new(SmtpConfig {
+        host: "127.0.0.1".to_string(),
+        port: None,
+        security: SmtpSecurity::StartTls,
+        credentials: None,
+        pool_size: 0,
+        sender_email: SENDER_EMAIL.to_string(),
+    });
+    assert!(matches!(result, Err(EmailError::InvalidConfig(_))));
+}
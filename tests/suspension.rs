@@ -0,0 +1,441 @@
+mod common;
+
+use common::*;
+use hilo::{
+    handlers::AuthResponse,
+    models::{TemplateId, UserStatus},
+    services::moderation::ModerationService,
+};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+
+async fn user_status(pool: &PgPool, email: &str) -> UserStatus {
+    sqlx::query_scalar!(
+        r#"SELECT status as "status: UserStatus" FROM users WHERE email = $1"#,
+        email
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[sqlx::test]
+async fn test_suspend_matched_user_dissolves_match(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let (male_token, female_token) =
+        setup_two_matched_users(&client, &address, &mock_emailer).await;
+
+    let response = client
+        .post(format!("{address}/api/admin/suspend-user"))
+        .header("cf-access-authenticated-user-email", "mod@example.com")
+        .json(&json!({
+            "email": MALE_EMAIL,
+            "reason": "Harassment reported by another participant"
+        }))
+        .send()
+        .await
+        .expect("Failed to suspend user");
+
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["status"], "suspended");
+
+    // Final match is dissolved and the partner is back in the pool
+    let final_matches = sqlx::query_scalar!("SELECT COUNT(*) FROM final_matches")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(final_matches, Some(0));
+    assert_eq!(
+        user_status(&pool, FEMALE_EMAIL).await,
+        UserStatus::FormCompleted
+    );
+
+    // The dissolved match still counts in the rejection statistics
+    let deleted_by = sqlx::query_scalar!("SELECT deleted_by FROM match_rejections")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(deleted_by.as_deref(), Some("mod@example.com"));
+
+    // Partner is notified
+    let email = mock_emailer
+        .wait_for_email(|email| email.subject.contains("cancelled"))
+        .await;
+    assert_eq!(email.recipient, FEMALE_EMAIL);
+
+    // Suspended user is rejected with the reason
+    let response = client
+        .get(format!("{address}/api/profile"))
+        .header("Authorization", format!("Bearer {male_token}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["reason"], "Harassment reported by another participant");
+    assert!(body["suspended_until"].is_null());
+
+    // Partner can still use the API
+    let response = client
+        .get(format!("{address}/api/profile"))
+        .header("Authorization", format!("Bearer {female_token}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // Suspended user is excluded from matching
+    let form_completed = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM users u JOIN forms f ON f.user_id = u.id WHERE u.status = 'form_completed'"
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(form_completed, Some(1));
+
+    // Suspension is audited
+    let response = client
+        .get(format!("{address}/api/admin/audit?action=suspend_user"))
+        .send()
+        .await
+        .unwrap();
+    let audit: Value = response.json().await.unwrap();
+    let entry = &audit["data"][0];
+    assert_eq!(entry["actor"], "mod@example.com");
+    assert_eq!(entry["status_before"], "matched");
+    assert_eq!(entry["status_after"], "suspended");
+}
+
+#[sqlx::test]
+async fn test_suspend_user_with_inactive_partner(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    setup_two_matched_users(&client, &address, &mock_emailer).await;
+    mock_emailer.clear();
+
+    // The partner left the match without it being deleted
+    sqlx::query!(
+        "UPDATE users SET status = 'verified' WHERE email = $1",
+        FEMALE_EMAIL
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let response = client
+        .post(format!("{address}/api/admin/suspend-user"))
+        .json(&json!({ "email": MALE_EMAIL, "reason": "Spam" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // The match is dissolved, the partner is left unchanged and not notified
+    let final_matches = sqlx::query_scalar!("SELECT COUNT(*) FROM final_matches")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(final_matches, Some(0));
+    assert_eq!(user_status(&pool, FEMALE_EMAIL).await, UserStatus::Verified);
+    assert_eq!(user_status(&pool, MALE_EMAIL).await, UserStatus::Suspended);
+    assert_not_notified(&pool, &mock_emailer, TemplateId::MatchDissolved).await;
+}
+
+#[sqlx::test]
+async fn test_suspension_respects_partner_preferences(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+    let (_, female_token) = setup_two_matched_users(&client, &address, &mock_emailer).await;
+    let response = update_preferences(
+        &client,
+        &address,
+        &female_token,
+        &json!({ "match_dissolved": false }),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    mock_emailer.clear();
+
+    let response = client
+        .post(format!("{address}/api/admin/suspend-user"))
+        .json(&json!({ "email": MALE_EMAIL, "reason": "Spam" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    assert_eq!(
+        user_status(&pool, FEMALE_EMAIL).await,
+        UserStatus::FormCompleted
+    );
+    assert_not_notified(&pool, &mock_emailer, TemplateId::MatchDissolved).await;
+}
+
+#[sqlx::test]
+async fn test_lift_suspension_restores_access(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let (male_token, _female_token) =
+        setup_two_matched_users(&client, &address, &mock_emailer).await;
+
+    let response = client
+        .post(format!("{address}/api/admin/suspend-user"))
+        .json(&json!({ "email": MALE_EMAIL, "reason": "Spam" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // Suspending twice is rejected
+    let response = client
+        .post(format!("{address}/api/admin/suspend-user"))
+        .json(&json!({ "email": MALE_EMAIL, "reason": "Spam" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    // Verification status cannot be changed while suspended
+    assert!(!admin_verify_user(&client, &address, MALE_EMAIL, "verified").await);
+
+    let response = client
+        .post(format!("{address}/api/admin/lift-suspension"))
+        .json(&json!({ "email": MALE_EMAIL }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    // The match was dissolved, so the user returns to the matching pool
+    assert_eq!(body["status"], "form_completed");
+
+    let response = client
+        .get(format!("{address}/api/profile"))
+        .header("Authorization", format!("Bearer {male_token}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let suspension = sqlx::query!(
+        "SELECT suspension_reason, suspended_until, status_before_suspension::text FROM users WHERE email = $1",
+        MALE_EMAIL
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(suspension.suspension_reason.is_none());
+    assert!(suspension.suspended_until.is_none());
+    assert!(suspension.status_before_suspension.is_none());
+
+    // Lifting again is rejected
+    let response = client
+        .post(format!("{address}/api/admin/lift-suspension"))
+        .json(&json!({ "email": MALE_EMAIL }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_suspend_user_validation(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    get_access_token(&client, &address, &mock_emailer, MALE_EMAIL).await;
+
+    let cases = [
+        json!({ "email": MALE_EMAIL, "reason": "  " }),
+        json!({ "email": MALE_EMAIL, "reason": "Spam", "suspended_until": "2000-01-01T00:00:00Z" }),
+        json!({ "reason": "Spam" }),
+    ];
+    for case in cases {
+        let response = client
+            .post(format!("{address}/api/admin/suspend-user"))
+            .json(&case)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            response.status(),
+            reqwest::StatusCode::BAD_REQUEST,
+            "{case}"
+        );
+    }
+
+    let response = client
+        .post(format!("{address}/api/admin/suspend-user"))
+        .json(&json!({ "email": "nobody@mails.tsinghua.edu.cn", "reason": "Spam" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_expired_suspension_is_lifted(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let token = get_access_token(&client, &address, &mock_emailer, MALE_EMAIL).await;
+
+    let response = client
+        .post(format!("{address}/api/admin/suspend-user"))
+        .json(&json!({
+            "email": MALE_EMAIL,
+            "reason": "Cooling off",
+            "suspended_until": "2099-01-01T00:00:00Z"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let response = client
+        .get(format!("{address}/api/profile"))
+        .header("Authorization", format!("Bearer {token}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["suspended_until"], "2099-01-01T00:00:00Z");
+
+    // Let the suspension expire
+    sqlx::query!(
+        "UPDATE users SET suspended_until = NOW() - INTERVAL '1 minute' WHERE email = $1",
+        MALE_EMAIL
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let lifted = ModerationService::lift_expired_suspensions(&pool)
+        .await
+        .unwrap();
+    assert_eq!(lifted, 1);
+    assert_eq!(user_status(&pool, MALE_EMAIL).await, UserStatus::Unverified);
+
+    let audit_actor =
+        sqlx::query_scalar!("SELECT actor FROM admin_audit_log WHERE action = 'lift_suspension'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(audit_actor, "scheduler");
+
+    let response = client
+        .get(format!("{address}/api/profile"))
+        .header("Authorization", format!("Bearer {token}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn test_expired_suspension_is_lifted_on_request(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    let token = get_access_token(&client, &address, &mock_emailer, MALE_EMAIL).await;
+
+    sqlx::query!(
+        r#"UPDATE users
+           SET status = 'suspended', suspension_reason = 'Spam',
+               suspended_until = NOW() - INTERVAL '1 minute',
+               status_before_suspension = 'unverified'
+           WHERE email = $1"#,
+        MALE_EMAIL
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // The middleware lifts the expired suspension without waiting for the background task
+    let response = client
+        .get(format!("{address}/api/profile"))
+        .header("Authorization", format!("Bearer {token}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(user_status(&pool, MALE_EMAIL).await, UserStatus::Unverified);
+}
+
+#[sqlx::test]
+async fn test_suspended_user_cannot_log_in(pool: PgPool) {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+    let client = reqwest::Client::new();
+
+    // Log in before the suspension
+    client
+        .post(format!("{address}/api/auth/send-code"))
+        .json(&json!({ "email": MALE_EMAIL }))
+        .send()
+        .await
+        .unwrap();
+    let email = mock_emailer
+        .wait_for_email(|email| email.recipient == MALE_EMAIL)
+        .await;
+    let response = client
+        .post(format!("{address}/api/auth/verify-code"))
+        .json(&json!({ "email": MALE_EMAIL, "code": extract_verification_code(&email.text) }))
+        .send()
+        .await
+        .unwrap();
+    let AuthResponse { refresh_token, .. } = response.json().await.unwrap();
+
+    // Request a code before the suspension and use it after
+    client
+        .post(format!("{address}/api/auth/send-code"))
+        .json(&json!({ "email": FEMALE_EMAIL }))
+        .send()
+        .await
+        .unwrap();
+    let email = mock_emailer
+        .wait_for_email(|email| email.recipient == FEMALE_EMAIL)
+        .await;
+    let female_code = extract_verification_code(&email.text);
+    sqlx::query!(
+        "INSERT INTO users (email, status) VALUES ($1, 'unverified')",
+        FEMALE_EMAIL
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    for email in [MALE_EMAIL, FEMALE_EMAIL] {
+        let response = client
+            .post(format!("{address}/api/admin/suspend-user"))
+            .json(&json!({ "email": email, "reason": "Spam" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    // The refresh token was revoked
+    let response = client
+        .post(format!("{address}/api/auth/refresh"))
+        .json(&json!({ "refresh_token": refresh_token }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    // No new tokens are issued
+    let response = client
+        .post(format!("{address}/api/auth/verify-code"))
+        .json(&json!({ "email": FEMALE_EMAIL, "code": female_code }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["reason"], "Spam");
+}
@@ -0,0 +1,81 @@
+//! Tests for the text similarity scorer in isolation, without database dependencies.
+
+mod common;
+
+use common::{FORM_SCHEMA, create_form};
+use hilo::{
+    models::{Answer, Form, FormSchema, Gender},
+    services::text_similarity::{TextCorpus, tokenize},
+};
+use uuid::Uuid;
+
+fn create_text_form(recent_topics: &str, self_intro: &str) -> Form {
+    create_form(
+        Uuid::new_v4(),
+        Gender::Male,
+        [
+            ("recent_topics", Answer::Text(recent_topics.to_string())),
+            ("self_intro", Answer::Text(self_intro.to_string())),
+        ],
+    )
+}
+
+#[test]
+fn test_tokenize_cjk_and_latin() {
+    assert_eq!(tokenize("机器学习"), vec!["机器", "器学", "学习"]);
+    assert_eq!(tokenize("猫"), vec!["猫"]);
+    assert_eq!(
+        tokenize("最近在学Rust和LLM, a lot!"),
+        vec!["最近", "近在", "在学", "rust", "和", "llm", "lot"]
+    );
+    assert!(tokenize("  ,. ").is_empty());
+}
+
+#[test]
+fn test_similarity_prefers_shared_topics() {
+    let forms = vec![
+        create_text_form("最近在研究机器学习和大模型", "喜欢跑步"),
+        create_text_form("机器学习的论文读了不少", "周末去爬山"),
+        create_text_form("在学做川菜和烘焙", "喜欢看电影"),
+        create_text_form("Rust and distributed systems", "I like hiking"),
+    ];
+    let corpus = TextCorpus::build(&forms, &FORM_SCHEMA);
+
+    let related = corpus.similarity(forms[0].user_id, forms[1].user_id);
+    let unrelated = corpus.similarity(forms[0].user_id, forms[3].user_id);
+    assert!(related > 0.0, "shared bigrams should give a positive score");
+    assert!(related > unrelated);
+    assert_eq!(unrelated, 0.0);
+
+    // Symmetric, bounded and 1 for identical answers
+    let reverse = corpus.similarity(forms[1].user_id, forms[0].user_id);
+    assert!((related - reverse).abs() < 1e-9);
+    assert!(related <= 1.0);
+    assert!((corpus.similarity(forms[2].user_id, forms[2].user_id) - 1.0).abs() < 1e-9);
+
+    // Users outside the corpus get no text score
+    assert_eq!(corpus.similarity(forms[0].user_id, Uuid::new_v4()), 0.0);
+}
+
+#[test]
+fn test_corpus_empty_without_text_scorer() {
+    let schema = FormSchema::from_json(
+        r#"{"questions": [{"id": "self_intro", "type": "text", "max_length": 100}]}"#,
+    )
+    .unwrap();
+    let forms = vec![
+        create_text_form("same", "same text"),
+        create_text_form("same", "same text"),
+    ];
+    let corpus = TextCorpus::build(&forms, &schema);
+    assert_eq!(corpus.similarity(forms[0].user_id, forms[1].user_id), 0.0);
+
+    // Text scorers can only consume text questions
+    let invalid = FormSchema::from_json(
+        r#"{
+            "questions": [{"id": "level", "type": "scale", "min": 1, "max": 3}],
+            "scorers": [{"type": "text_similarity", "questions": ["level"], "weight": 1.0}]
+        }"#,
+    );
+    assert!(invalid.is_err());
+}
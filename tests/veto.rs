@@ -0,0 +1,293 @@
+mod common;
+
+use common::*;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const OTHER_MALE_EMAIL: &str = "other.male@mails.tsinghua.edu.cn";
+
+async fn preview_ids(client: &reqwest::Client, address: &str, token: &str) -> Vec<Uuid> {
+    let response = client
+        .get(format!("{address}/api/veto/previews"))
+        .header("Authorization", format!("Bearer {token}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let previews: Vec<Value> = response.json().await.unwrap();
+    previews
+        .iter()
+        .map(|preview| preview["candidate_id"].as_str().unwrap().parse().unwrap())
+        .collect()
+}
+
+/// Users in the matching pool, with match previews generated for all of them
+struct PoolUsers {
+    address: String,
+    male_token: String,
+    female_token: String,
+    male_id: Uuid,
+    other_male_id: Uuid,
+    female_id: Uuid,
+}
+
+/// Brings two male users and one female user to `form_completed`.
+async fn setup_pool(pool: &PgPool, client: &reqwest::Client) -> PoolUsers {
+    let (address, mock_emailer) = spawn_app(pool.clone()).await;
+
+    let male_token = complete_form(
+        client,
+        &address,
+        &mock_emailer,
+        MALE_EMAIL,
+        create_male_form_submission(),
+    )
+    .await;
+    let mut other_male_form = create_male_form_submission();
+    other_male_form["wechat_id"] = json!("test_wechat_789");
+    complete_form(
+        client,
+        &address,
+        &mock_emailer,
+        OTHER_MALE_EMAIL,
+        other_male_form,
+    )
+    .await;
+    let female_token = complete_form(
+        client,
+        &address,
+        &mock_emailer,
+        FEMALE_EMAIL,
+        create_female_form_submission(),
+    )
+    .await;
+
+    PoolUsers {
+        address,
+        male_token,
+        female_token,
+        male_id: user_id(pool, MALE_EMAIL).await,
+        other_male_id: user_id(pool, OTHER_MALE_EMAIL).await,
+        female_id: user_id(pool, FEMALE_EMAIL).await,
+    }
+}
+
+async fn post_veto(
+    client: &reqwest::Client,
+    address: &str,
+    token: &str,
+    vetoed_id: Uuid,
+) -> reqwest::StatusCode {
+    client
+        .post(format!("{address}/api/veto"))
+        .header("Authorization", format!("Bearer {token}"))
+        .json(&json!({ "vetoed_id": vetoed_id }))
+        .send()
+        .await
+        .expect("Failed to send veto request")
+        .status()
+}
+
+async fn delete_veto(
+    client: &reqwest::Client,
+    address: &str,
+    token: &str,
+    vetoed_id: Uuid,
+) -> reqwest::StatusCode {
+    client
+        .delete(format!("{address}/api/veto"))
+        .header("Authorization", format!("Bearer {token}"))
+        .json(&json!({ "vetoed_id": vetoed_id }))
+        .send()
+        .await
+        .expect("Failed to send veto removal request")
+        .status()
+}
+
+/// Fetches `path`, e.g. `/api/vetoes` or `/api/veto/quota`
+async fn get_vetoes(client: &reqwest::Client, address: &str, token: &str, path: &str) -> Value {
+    let response = client
+        .get(format!("{address}{path}"))
+        .header("Authorization", format!("Bearer {token}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    response.json().await.unwrap()
+}
+
+#[sqlx::test]
+async fn test_veto_excludes_both_directions_from_previews(pool: PgPool) {
+    let client = reqwest::Client::new();
+    let PoolUsers {
+        address,
+        male_token,
+        female_token,
+        male_id,
+        other_male_id,
+        female_id,
+    } = setup_pool(&pool, &client).await;
+
+    let mut previews = preview_ids(&client, &address, &female_token).await;
+    previews.sort();
+    let mut expected = vec![male_id, other_male_id];
+    expected.sort();
+    assert_eq!(previews, expected);
+    assert_eq!(
+        preview_ids(&client, &address, &male_token).await,
+        vec![female_id]
+    );
+
+    assert_eq!(
+        post_veto(&client, &address, &female_token, male_id).await,
+        reqwest::StatusCode::CREATED
+    );
+
+    // The vetoer's stored previews are regenerated right away, without the vetoed user
+    let candidate_ids = sqlx::query_scalar!(
+        "SELECT candidate_ids FROM match_previews WHERE user_id = $1",
+        female_id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(candidate_ids, vec![other_male_id]);
+    assert_eq!(
+        preview_ids(&client, &address, &female_token).await,
+        vec![other_male_id]
+    );
+
+    // The vetoed user no longer sees the vetoer either
+    assert!(preview_ids(&client, &address, &male_token).await.is_empty());
+}
+
+#[sqlx::test]
+async fn test_veto_backfill_skips_users_who_left_the_pool(pool: PgPool) {
+    let client = reqwest::Client::new();
+    let PoolUsers {
+        address,
+        female_token,
+        male_id,
+        other_male_id,
+        female_id,
+        ..
+    } = setup_pool(&pool, &client).await;
+
+    // Leaves the pool after the last preview generation
+    sqlx::query!(
+        "UPDATE users SET status = 'paused' WHERE id = $1",
+        other_male_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    assert_eq!(
+        post_veto(&client, &address, &female_token, male_id).await,
+        reqwest::StatusCode::CREATED
+    );
+    let candidate_ids = sqlx::query_scalar!(
+        "SELECT candidate_ids FROM match_previews WHERE user_id = $1",
+        female_id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(candidate_ids.is_empty());
+}
+
+#[sqlx::test]
+async fn test_veto_quota_and_window(pool: PgPool) {
+    let client = reqwest::Client::new();
+    let PoolUsers {
+        address,
+        male_token,
+        female_token,
+        male_id,
+        other_male_id,
+        ..
+    } = setup_pool(&pool, &client).await;
+
+    let vetoes = get_vetoes(&client, &address, &female_token, "/api/vetoes").await;
+    assert_eq!(vetoes, json!([]));
+    let quota = get_vetoes(&client, &address, &female_token, "/api/veto/quota").await;
+    assert_eq!(quota["remaining_quota"], 1);
+    assert!(quota["window_closes_at"].is_null());
+
+    // The window closes VETO_WINDOW_CLOSE_HOURS (2) before the next final match
+    sqlx::query!(
+        "INSERT INTO scheduled_final_matches (scheduled_time) VALUES (NOW() + INTERVAL '1 hour')"
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        post_veto(&client, &address, &female_token, male_id).await,
+        reqwest::StatusCode::FORBIDDEN
+    );
+
+    sqlx::query!("UPDATE scheduled_final_matches SET scheduled_time = NOW() + INTERVAL '3 hours'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        post_veto(&client, &address, &female_token, male_id).await,
+        reqwest::StatusCode::CREATED
+    );
+
+    // VETO_QUOTA is 1, but re-adding the same veto stays idempotent
+    assert_eq!(
+        post_veto(&client, &address, &female_token, other_male_id).await,
+        reqwest::StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        post_veto(&client, &address, &female_token, male_id).await,
+        reqwest::StatusCode::OK
+    );
+
+    let vetoes = get_vetoes(&client, &address, &female_token, "/api/vetoes").await;
+    assert_eq!(vetoes, json!([male_id]));
+    let quota = get_vetoes(&client, &address, &female_token, "/api/veto/quota").await;
+    assert_eq!(quota["remaining_quota"], 0);
+    assert!(quota["window_closes_at"].is_string());
+
+    // Concurrent vetoes cannot exceed the quota together
+    assert_eq!(
+        delete_veto(&client, &address, &female_token, male_id).await,
+        reqwest::StatusCode::OK
+    );
+    let (first, second) = tokio::join!(
+        post_veto(&client, &address, &female_token, male_id),
+        post_veto(&client, &address, &female_token, other_male_id),
+    );
+    let mut statuses = [first, second];
+    statuses.sort();
+    assert_eq!(
+        statuses,
+        [reqwest::StatusCode::CREATED, reqwest::StatusCode::FORBIDDEN]
+    );
+    let vetoes = get_vetoes(&client, &address, &female_token, "/api/vetoes").await;
+    assert_eq!(vetoes.as_array().unwrap().len(), 1);
+    let vetoed_id: Uuid = vetoes[0].as_str().unwrap().parse().unwrap();
+
+    // Vetoes cannot be removed after the window closed either
+    sqlx::query!("UPDATE scheduled_final_matches SET scheduled_time = NOW() + INTERVAL '1 hour'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        delete_veto(&client, &address, &female_token, vetoed_id).await,
+        reqwest::StatusCode::FORBIDDEN
+    );
+
+    // The window does not open before the user's previews are published
+    sqlx::query!("DELETE FROM match_previews WHERE user_id = $1", male_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        post_veto(&client, &address, &male_token, other_male_id).await,
+        reqwest::StatusCode::FORBIDDEN
+    );
+}